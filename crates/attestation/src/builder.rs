@@ -9,9 +9,11 @@ use tlsn_core::{
 };
 
 use crate::{
-    Attestation, AttestationConfig, Body, CryptoProvider, Extension, FieldId, Header,
-    ServerCertCommitment, VERSION, request::Request, serialize::CanonicalSerialize,
-    signing::SignatureAlgId,
+    Attestation, AttestationConfig, Body, CryptoProvider, EVM_VERSION, Extension, FieldId, Header,
+    ServerCertCommitment, VERSION, evm,
+    request::Request,
+    serialize::EncodingMode,
+    signing::{SignatureAlgId, VerifyingKey},
 };
 
 /// Attestation builder state for accepting a request.
@@ -22,6 +24,7 @@ pub struct Accept {}
 pub struct Sign {
     signature_alg: SignatureAlgId,
     hash_alg: HashAlgId,
+    encoding: EncodingMode,
     connection_info: Option<ConnectionInfo>,
     server_ephemeral_key: Option<ServerEphemKey>,
     cert_commitment: ServerCertCommitment,
@@ -59,6 +62,7 @@ impl<'a> AttestationBuilder<'a, Accept> {
             server_cert_commitment: cert_commitment,
             extensions,
         } = request;
+        let encoding = *config.encoding();
 
         if !config.supported_signature_algs().contains(&signature_alg) {
             return Err(AttestationBuilderError::new(
@@ -74,9 +78,27 @@ impl<'a> AttestationBuilder<'a, Accept> {
             ));
         }
 
+        if encoding == EncodingMode::Evm {
+            evm::check_algs(&signature_alg, &hash_alg)
+                .map_err(|err| AttestationBuilderError::new(ErrorKind::Request, err))?;
+        }
+
+        config
+            .extension_registry()
+            .validate(&extensions)
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Extension, err))?;
+
         if let Some(validator) = config.extension_validator() {
             validator(&extensions)
                 .map_err(|err| AttestationBuilderError::new(ErrorKind::Extension, err))?;
+        } else if let Some(extension) = config.extension_registry().unknown(&extensions).next() {
+            return Err(AttestationBuilderError::new(
+                ErrorKind::Extension,
+                format!(
+                    "unknown extension: {:?}",
+                    String::from_utf8_lossy(&extension.id)
+                ),
+            ));
         }
 
         Ok(AttestationBuilder {
@@ -84,6 +106,7 @@ impl<'a> AttestationBuilder<'a, Accept> {
             state: Sign {
                 signature_alg,
                 hash_alg,
+                encoding,
                 connection_info: None,
                 server_ephemeral_key: None,
                 cert_commitment,
@@ -131,15 +154,69 @@ impl AttestationBuilder<'_, Sign> {
 
     /// Builds the attestation.
     pub fn build(self, provider: &CryptoProvider) -> Result<Attestation, AttestationBuilderError> {
+        let signature_alg = self.state.signature_alg;
+        let signer = provider
+            .signer
+            .get(&signature_alg)
+            .map_err(|_| missing_signer(&signature_alg))?;
+
+        let (header, body) = self.into_parts(provider, signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&header.signing_message())
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header,
+            body,
+        })
+    }
+
+    /// Builds the attestation, signing it with an asynchronous signer.
+    ///
+    /// Uses the [`AsyncSigner`](crate::signing::AsyncSigner) configured for
+    /// the accepted signature algorithm, falling back to the synchronous
+    /// signer if there is none.
+    pub async fn build_async(
+        self,
+        provider: &CryptoProvider,
+    ) -> Result<Attestation, AttestationBuilderError> {
+        let signature_alg = self.state.signature_alg;
+        let Ok(signer) = provider.signer.get_async(&signature_alg) else {
+            return self.build(provider);
+        };
+
+        let (header, body) = self.into_parts(provider, signer.verifying_key())?;
+
+        let signature = signer
+            .sign(&header.signing_message())
+            .await
+            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
+
+        Ok(Attestation {
+            signature,
+            header,
+            body,
+        })
+    }
+
+    /// Returns the header and body of the attestation to be signed.
+    fn into_parts(
+        self,
+        provider: &CryptoProvider,
+        verifying_key: VerifyingKey,
+    ) -> Result<(Header, Body), AttestationBuilderError> {
         let Sign {
-            signature_alg,
             hash_alg,
+            encoding,
             connection_info,
             server_ephemeral_key,
             cert_commitment,
             encoder_secret,
             extensions,
             transcript_commitments,
+            ..
         } = self.state;
 
         let hasher = provider.hash.get(&hash_alg).map_err(|_| {
@@ -148,19 +225,11 @@ impl AttestationBuilder<'_, Sign> {
                 format!("accepted hash algorithm {hash_alg} but it's missing in the provider"),
             )
         })?;
-        let signer = provider.signer.get(&signature_alg).map_err(|_| {
-            AttestationBuilderError::new(
-                ErrorKind::Config,
-                format!(
-                    "accepted signature algorithm {signature_alg} but it's missing in the provider"
-                ),
-            )
-        })?;
 
         let mut field_id = FieldId::default();
 
         let body = Body {
-            verifying_key: field_id.next(signer.verifying_key()),
+            verifying_key: field_id.next(verifying_key),
             connection_info: field_id.next(connection_info.ok_or_else(|| {
                 AttestationBuilderError::new(ErrorKind::Field, "connection info was not set")
             })?),
@@ -181,22 +250,24 @@ impl AttestationBuilder<'_, Sign> {
 
         let header = Header {
             id: rng().random(),
-            version: VERSION,
-            root: body.root(hasher),
+            version: match encoding {
+                EncodingMode::Bcs => VERSION,
+                EncodingMode::Evm => EVM_VERSION,
+            },
+            root: body.root(hasher, encoding),
         };
 
-        let signature = signer
-            .sign(&CanonicalSerialize::serialize(&header))
-            .map_err(|err| AttestationBuilderError::new(ErrorKind::Signature, err))?;
-
-        Ok(Attestation {
-            signature,
-            header,
-            body,
-        })
+        Ok((header, body))
     }
 }
 
+fn missing_signer(signature_alg: &SignatureAlgId) -> AttestationBuilderError {
+    AttestationBuilderError::new(
+        ErrorKind::Config,
+        format!("accepted signature algorithm {signature_alg} but it's missing in the provider"),
+    )
+}
+
 /// Error for [`AttestationBuilder`].
 #[derive(Debug, thiserror::Error)]
 pub struct AttestationBuilderError {
@@ -259,7 +330,14 @@ mod test {
     };
     use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
 
-    use crate::fixtures::{RequestFixture, request_fixture};
+    use crate::{
+        InvalidExtension, TypedExtension,
+        fixtures::{RequestFixture, request_fixture},
+        signing::{
+            AsyncSigner, Secp256k1Signer, Secp256k1Verifier, Signature, SignatureError,
+            SignatureVerifier, Signer,
+        },
+    };
 
     use super::*;
 
@@ -496,4 +574,145 @@ mod test {
 
         assert_eq!(attestation.body.extensions().count(), 1);
     }
+
+    struct Nonce(u64);
+
+    impl TypedExtension for Nonce {
+        const ID: &'static [u8] = b"test.nonce";
+
+        fn encode(&self) -> Vec<u8> {
+            self.0.to_be_bytes().to_vec()
+        }
+
+        fn decode(value: &[u8]) -> Result<Self, InvalidExtension> {
+            let bytes = value
+                .try_into()
+                .map_err(|_| InvalidExtension::new("nonce must be 8 bytes"))?;
+
+            Ok(Self(u64::from_be_bytes(bytes)))
+        }
+    }
+
+    fn extension(id: &[u8], value: &[u8]) -> Extension {
+        Extension {
+            id: id.to_vec(),
+            value: value.to_vec(),
+        }
+    }
+
+    #[rstest]
+    #[case::known(vec![Nonce(1).to_extension()], true)]
+    #[case::invalid(vec![extension(Nonce::ID, &[0; 4])], false)]
+    #[case::unknown(vec![Nonce(1).to_extension(), extension(b"foo", b"bar")], false)]
+    fn test_attestation_builder_typed_extension(
+        #[case] extensions: Vec<Extension>,
+        #[case] accepted: bool,
+    ) {
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1])
+            .register_extension::<Nonce>()
+            .build()
+            .unwrap();
+
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } = request_fixture(
+            transcript,
+            encoding_provider(GET_WITH_HEADER, OK_JSON),
+            connection,
+            Blake3::default(),
+            extensions,
+        );
+
+        let result = Attestation::builder(&attestation_config).accept_request(request);
+
+        match result {
+            Ok(_) => assert!(accepted),
+            Err(err) => {
+                assert!(!accepted);
+                assert!(matches!(err.kind, ErrorKind::Extension));
+            }
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_attestation_builder_build_async(attestation_config: &AttestationConfig) {
+        struct TestSigner(Secp256k1Signer);
+
+        #[async_trait::async_trait]
+        impl AsyncSigner for TestSigner {
+            fn alg_id(&self) -> SignatureAlgId {
+                self.0.alg_id()
+            }
+
+            async fn sign(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
+                self.0.sign(msg)
+            }
+
+            fn verifying_key(&self) -> VerifyingKey {
+                self.0.verifying_key()
+            }
+        }
+
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } = request_fixture(
+            transcript,
+            encoding_provider(GET_WITH_HEADER, OK_JSON),
+            connection.clone(),
+            Blake3::default(),
+            Vec::new(),
+        );
+
+        let mut attestation_builder = Attestation::builder(attestation_config)
+            .accept_request(request)
+            .unwrap();
+
+        let ConnectionFixture {
+            server_cert_data,
+            connection_info,
+            ..
+        } = connection;
+
+        let CertBinding::V1_2(CertBindingV1_2 {
+            server_ephemeral_key,
+            ..
+        }) = server_cert_data.binding
+        else {
+            panic!("expected v1.2 handshake data");
+        };
+
+        attestation_builder
+            .connection_info(connection_info)
+            .server_ephemeral_key(server_ephemeral_key);
+
+        let signer = Secp256k1Signer::new(&[42u8; 32]).unwrap();
+        let verifying_key = signer.verifying_key();
+
+        let mut provider = CryptoProvider::default();
+        provider
+            .signer
+            .set_async_signer(Box::new(TestSigner(signer)));
+
+        // The synchronous `build` can not use the signer.
+        assert_eq!(provider.signer.supported_algs().count(), 0);
+        assert_eq!(
+            provider.signer.supported_async_algs().collect::<Vec<_>>(),
+            vec![SignatureAlgId::SECP256K1]
+        );
+
+        let attestation = attestation_builder.build_async(&provider).await.unwrap();
+
+        assert_eq!(attestation.body.verifying_key(), &verifying_key);
+        Secp256k1Verifier
+            .verify(
+                &verifying_key,
+                &attestation.header.signing_message(),
+                &attestation.signature.data,
+            )
+            .unwrap();
+    }
 }
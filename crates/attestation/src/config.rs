@@ -3,7 +3,8 @@ use std::{fmt::Debug, sync::Arc};
 use tlsn_core::hash::HashAlgId;
 
 use crate::{
-    Extension, InvalidExtension, hash::DEFAULT_SUPPORTED_HASH_ALGS, signing::SignatureAlgId,
+    EncodingMode, Extension, ExtensionRegistry, InvalidExtension, TypedExtension,
+    hash::DEFAULT_SUPPORTED_HASH_ALGS, signing::SignatureAlgId,
 };
 
 type ExtensionValidator = Arc<dyn Fn(&[Extension]) -> Result<(), InvalidExtension> + Send + Sync>;
@@ -45,6 +46,8 @@ impl AttestationConfigError {
 pub struct AttestationConfig {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
+    encoding: EncodingMode,
+    extension_registry: ExtensionRegistry,
     extension_validator: Option<ExtensionValidator>,
 }
 
@@ -62,6 +65,14 @@ impl AttestationConfig {
         &self.supported_hash_algs
     }
 
+    pub(crate) fn encoding(&self) -> &EncodingMode {
+        &self.encoding
+    }
+
+    pub(crate) fn extension_registry(&self) -> &ExtensionRegistry {
+        &self.extension_registry
+    }
+
     pub(crate) fn extension_validator(&self) -> Option<&ExtensionValidator> {
         self.extension_validator.as_ref()
     }
@@ -72,6 +83,8 @@ impl Debug for AttestationConfig {
         f.debug_struct("AttestationConfig")
             .field("supported_signature_algs", &self.supported_signature_algs)
             .field("supported_hash_algs", &self.supported_hash_algs)
+            .field("encoding", &self.encoding)
+            .field("extension_registry", &self.extension_registry)
             .finish_non_exhaustive()
     }
 }
@@ -80,6 +93,8 @@ impl Debug for AttestationConfig {
 pub struct AttestationConfigBuilder {
     supported_signature_algs: Vec<SignatureAlgId>,
     supported_hash_algs: Vec<HashAlgId>,
+    encoding: EncodingMode,
+    extension_registry: ExtensionRegistry,
     extension_validator: Option<ExtensionValidator>,
 }
 
@@ -88,15 +103,9 @@ impl Default for AttestationConfigBuilder {
         Self {
             supported_signature_algs: Vec::default(),
             supported_hash_algs: DEFAULT_SUPPORTED_HASH_ALGS.to_vec(),
-            extension_validator: Some(Arc::new(|e| {
-                if !e.is_empty() {
-                    Err(InvalidExtension::new(
-                        "all extensions are disallowed by default",
-                    ))
-                } else {
-                    Ok(())
-                }
-            })),
+            encoding: EncodingMode::default(),
+            extension_registry: ExtensionRegistry::default(),
+            extension_validator: None,
         }
     }
 }
@@ -120,8 +129,46 @@ impl AttestationConfigBuilder {
         self
     }
 
+    /// Sets the encoding mode of the attestations.
+    ///
+    /// The encoding mode is not part of the attestation request, the Prover
+    /// and Notary must agree on it out of band. Defaults to
+    /// [`EncodingMode::Bcs`].
+    pub fn encoding(&mut self, encoding: EncodingMode) -> &mut Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Registers a typed extension.
+    ///
+    /// Requested extensions of this type are decoded and validated before
+    /// being accepted.
+    ///
+    /// # Example
+    /// ```
+    /// # use tlsn_attestation::{AttestationConfig, InvalidExtension, TypedExtension};
+    /// # struct Nonce(u64);
+    /// # impl TypedExtension for Nonce {
+    /// #     const ID: &'static [u8] = b"example.nonce";
+    /// #     fn encode(&self) -> Vec<u8> { self.0.to_be_bytes().to_vec() }
+    /// #     fn decode(value: &[u8]) -> Result<Self, InvalidExtension> {
+    /// #         Ok(Self(u64::from_be_bytes(value.try_into().map_err(|_| InvalidExtension::new("invalid nonce"))?)))
+    /// #     }
+    /// # }
+    /// # let mut builder = AttestationConfig::builder();
+    /// builder.register_extension::<Nonce>();
+    /// ```
+    pub fn register_extension<T: TypedExtension>(&mut self) -> &mut Self {
+        self.extension_registry.register::<T>();
+        self
+    }
+
     /// Sets the extension validator.
     ///
+    /// The validator is called with all the requested extensions, after any
+    /// [registered](Self::register_extension) extensions have been validated.
+    /// Without a validator, extensions which are not registered are rejected.
+    ///
     /// # Example
     /// ```
     /// # use tlsn_attestation::{AttestationConfig, InvalidExtension};
@@ -148,6 +195,8 @@ impl AttestationConfigBuilder {
         Ok(AttestationConfig {
             supported_signature_algs: self.supported_signature_algs.clone(),
             supported_hash_algs: self.supported_hash_algs.clone(),
+            encoding: self.encoding,
+            extension_registry: self.extension_registry.clone(),
             extension_validator: self.extension_validator.clone(),
         })
     }
@@ -158,6 +207,8 @@ impl Debug for AttestationConfigBuilder {
         f.debug_struct("AttestationConfigBuilder")
             .field("supported_signature_algs", &self.supported_signature_algs)
             .field("supported_hash_algs", &self.supported_hash_algs)
+            .field("encoding", &self.encoding)
+            .field("extension_registry", &self.extension_registry)
             .finish_non_exhaustive()
     }
 }
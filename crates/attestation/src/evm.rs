@@ -0,0 +1,434 @@
+//! EVM compatible attestations.
+//!
+//! Attestations built with [`EncodingMode::Evm`] can be verified by a smart
+//! contract. This module documents the encoding and provides
+//! [`verify_attestation`], a reference verifier which mirrors the on-chain
+//! algorithm.
+//!
+//! The encoding mode is not part of the attestation request, the Prover and
+//! Notary agree on it out of band. The Notary selects it with
+//! [`AttestationConfigBuilder::encoding`](crate::AttestationConfigBuilder::encoding).
+//!
+//! # Fields
+//!
+//! Each field of the attestation body is ABI encoded as Solidity's
+//! `abi.encode(...)` would encode the members listed below, and hashed as
+//! `keccak256(domain || encoding)` where `domain` is the 16 byte domain
+//! separator of the field type.
+//!
+//! | Field                    | Encoding                                                           |
+//! |--------------------------|--------------------------------------------------------------------|
+//! | Verifying key            | `(uint8 alg, bytes key)`                                           |
+//! | Connection info          | `(uint64 time, uint16 version, uint32 sent, uint32 received)`      |
+//! | Server ephemeral key     | `(uint16 group, bytes key)`                                        |
+//! | Server cert commitment   | `(uint8 alg, bytes hash)`                                          |
+//! | Encoder secret           | `(bytes32 seed, bytes16 delta)`                                    |
+//! | Extension                | `(bytes id, bytes value)`                                          |
+//! | Encoding commitment      | `(uint8 0, uint8 alg, bytes root)`                                 |
+//! | Plaintext hash           | `(uint8 1, uint8 direction, (uint64, uint64)[] ranges, uint8 alg, bytes hash)` |
+//!
+//! The TLS version and key group use their TLS protocol values, eg. `0x0303`
+//! for TLS 1.2. Other kinds of transcript commitments are encoded as
+//! `(uint8 255, bytes bcs)`.
+//!
+//! # Merkle tree
+//!
+//! The field hashes, ordered by field id, are the leaves of a keccak256 Merkle
+//! tree. Each parent node is `keccak256(left || right)`, and a node without a
+//! sibling is promoted to the next layer unchanged. The root of the tree is
+//! the `root` of the attestation header.
+//!
+//! # Signature
+//!
+//! The header is signed following EIP-712 with the `SECP256K1ETH` algorithm,
+//! ie. the signature `r || s || v` can be checked with `ecrecover` against
+//! the digest
+//!
+//! ```text
+//! keccak256(0x1901 || domainSeparator || keccak256(abi.encode(HEADER_TYPEHASH, id, version, root)))
+//! ```
+//!
+//! where `HEADER_TYPEHASH` is the hash of
+//! `AttestationHeader(bytes16 id,uint32 version,bytes32 root)` and the domain
+//! separator is derived from `EIP712Domain(string name,string version)` with
+//! the name `TLSNotary` and version `1`. The `version` of the header is
+//! [`EVM_VERSION`](crate::EVM_VERSION), which distinguishes EVM attestations
+//! from BCS ones.
+
+mod abi;
+
+use tiny_keccak::{Hasher, Keccak};
+use tlsn_core::hash::{Hash, HashAlgId, HashAlgorithm, Keccak256};
+
+use crate::{
+    Attestation, Body, EncodingMode, Header,
+    presentation::Presentation,
+    signing::{KeyAlgId, Signature, SignatureAlgId, VerifyingKey},
+};
+
+pub(crate) use abi::AbiEncode;
+use abi::Token;
+
+/// EIP-712 domain type.
+const EIP712_DOMAIN_TYPE: &[u8] = b"EIP712Domain(string name,string version)";
+/// EIP-712 domain name.
+const EIP712_DOMAIN_NAME: &[u8] = b"TLSNotary";
+/// EIP-712 domain version.
+const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+/// EIP-712 type of the attestation header.
+const HEADER_TYPE: &[u8] = b"AttestationHeader(bytes16 id,uint32 version,bytes32 root)";
+
+/// An Ethereum address.
+pub type Address = [u8; 20];
+
+/// Checks that the algorithms are compatible with [`EncodingMode::Evm`].
+pub(crate) fn check_algs(
+    signature_alg: &SignatureAlgId,
+    hash_alg: &HashAlgId,
+) -> Result<(), String> {
+    if *signature_alg != SignatureAlgId::SECP256K1ETH {
+        return Err(format!(
+            "evm encoding requires the secp256k1eth signature algorithm, got {signature_alg}"
+        ));
+    }
+
+    if *hash_alg != HashAlgId::KECCAK256 {
+        return Err(format!(
+            "evm encoding requires the keccak-256 hash algorithm, got {hash_alg}"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the EIP-712 message of the header, ie. the preimage of the signed
+/// digest.
+pub(crate) fn eip712_message(header: &Header) -> [u8; 66] {
+    let domain_separator = keccak256(&abi::encode(&[
+        Token::FixedBytes(keccak256(EIP712_DOMAIN_TYPE).to_vec()),
+        Token::FixedBytes(keccak256(EIP712_DOMAIN_NAME).to_vec()),
+        Token::FixedBytes(keccak256(EIP712_DOMAIN_VERSION).to_vec()),
+    ]));
+
+    // The root is 32 bytes as this mode requires keccak-256, longer roots are
+    // rejected before signing or verifying.
+    let root = header.root.value.as_bytes();
+    let struct_hash = keccak256(&abi::encode(&[
+        Token::FixedBytes(keccak256(HEADER_TYPE).to_vec()),
+        Token::FixedBytes(header.id.0.to_vec()),
+        Token::Uint(header.version.0 as u64),
+        Token::FixedBytes(root[..root.len().min(32)].to_vec()),
+    ]));
+
+    let mut message = [0u8; 66];
+    message[..2].copy_from_slice(&[0x19, 0x01]);
+    message[2..34].copy_from_slice(&domain_separator);
+    message[34..].copy_from_slice(&struct_hash);
+    message
+}
+
+/// Returns the Ethereum address of a secp256k1 verifying key.
+pub fn address(key: &VerifyingKey) -> Result<Address, EvmVerificationError> {
+    if key.alg != KeyAlgId::K256 {
+        return Err(EvmVerificationError(format!(
+            "expected a k256 key, got {}",
+            key.alg
+        )));
+    }
+
+    let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&key.data)
+        .map_err(|_| EvmVerificationError("invalid k256 key".to_string()))?;
+
+    Ok(key_address(&key))
+}
+
+fn key_address(key: &k256::ecdsa::VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    // Skip the SEC1 tag of the uncompressed point.
+    let hash = keccak256(&point.as_bytes()[1..]);
+
+    hash[12..].try_into().unwrap()
+}
+
+/// Verifies an attestation the same way a smart contract would.
+///
+/// # Arguments
+///
+/// * `attestation` - The attestation to verify.
+/// * `signer` - Address of the trusted Notary.
+pub fn verify_attestation(
+    attestation: &Attestation,
+    signer: &Address,
+) -> Result<(), EvmVerificationError> {
+    verify(
+        &attestation.signature,
+        &attestation.header,
+        &attestation.body,
+        signer,
+    )
+}
+
+/// Verifies the attestation of a presentation the same way a smart contract
+/// would.
+///
+/// Only the attestation is verified, the server identity and transcript
+/// proofs of the presentation are not.
+///
+/// # Arguments
+///
+/// * `presentation` - The presentation to verify.
+/// * `signer` - Address of the trusted Notary.
+pub fn verify_presentation(
+    presentation: &Presentation,
+    signer: &Address,
+) -> Result<(), EvmVerificationError> {
+    let (signature, header, body) = presentation.attestation_proof().parts();
+
+    verify(signature, header, body, signer)
+}
+
+fn verify(
+    signature: &Signature,
+    header: &Header,
+    body: &Body,
+    signer: &Address,
+) -> Result<(), EvmVerificationError> {
+    if header.encoding() != Some(EncodingMode::Evm) {
+        return Err(EvmVerificationError(format!(
+            "expected evm encoding, got version {:?}",
+            header.version
+        )));
+    }
+
+    check_algs(&signature.alg, &header.root.alg).map_err(EvmVerificationError)?;
+
+    // Compute the Merkle root of the fields.
+    let hasher = Keccak256::default();
+    let mut leaves = Vec::new();
+    for (idx, (id, hash)) in body
+        .hash_fields(&hasher, EncodingMode::Evm)
+        .into_iter()
+        .enumerate()
+    {
+        if id.0 as usize != idx {
+            return Err(EvmVerificationError(
+                "field ids are not contiguous".to_string(),
+            ));
+        }
+
+        leaves.push(hash);
+    }
+
+    if merkle_root(&hasher, leaves).as_bytes() != header.root.value.as_bytes() {
+        return Err(EvmVerificationError(
+            "merkle root does not match the header".to_string(),
+        ));
+    }
+
+    // Recover the signer of the EIP-712 digest.
+    if signature.data.len() != 65 {
+        return Err(EvmVerificationError(
+            "signature must be 65 bytes".to_string(),
+        ));
+    }
+
+    let digest = keccak256(&eip712_message(header));
+    let sig = k256::ecdsa::Signature::from_slice(&signature.data[..64])
+        .map_err(|_| EvmVerificationError("invalid signature".to_string()))?;
+    let recid = signature.data[64]
+        .checked_sub(27)
+        .and_then(k256::ecdsa::RecoveryId::from_byte)
+        .ok_or_else(|| EvmVerificationError("invalid recovery id".to_string()))?;
+
+    let key = k256::ecdsa::VerifyingKey::recover_from_prehash(&digest, &sig, recid)
+        .map_err(|_| EvmVerificationError("failed to recover signer".to_string()))?;
+
+    if &key_address(&key) != signer {
+        return Err(EvmVerificationError(
+            "attestation is not signed by the expected signer".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Computes the root of a keccak-256 Merkle tree.
+fn merkle_root(hasher: &Keccak256, mut layer: Vec<Hash>) -> Hash {
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hasher.hash(&[left.as_bytes(), right.as_bytes()].concat()),
+                [node] => *node,
+                _ => unreachable!("chunks are non-empty and at most 2 long"),
+            })
+            .collect();
+    }
+
+    layer[0]
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Error for EVM verification.
+#[derive(Debug, thiserror::Error)]
+#[error("evm verification error: {0}")]
+pub struct EvmVerificationError(String);
+
+#[cfg(test)]
+mod test {
+    use alloy_signer_local::PrivateKeySigner;
+    use tlsn_core::{
+        connection::{CertBinding, CertBindingV1_2},
+        fixtures::ConnectionFixture,
+        merkle::MerkleTree,
+        transcript::Transcript,
+    };
+    use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
+
+    use super::*;
+    use crate::{
+        AttestationConfig, CryptoProvider, EVM_VERSION,
+        request::{Request, RequestConfig},
+    };
+
+    const SIGNING_KEY: [u8; 32] = [1u8; 32];
+
+    fn signer_address() -> Address {
+        PrivateKeySigner::from_slice(&SIGNING_KEY)
+            .unwrap()
+            .address()
+            .into()
+    }
+
+    fn attestation(provider: &CryptoProvider) -> (Request, Attestation) {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let ConnectionFixture {
+            server_name,
+            connection_info,
+            server_cert_data,
+        } = ConnectionFixture::tlsnotary(transcript.length());
+
+        let mut request_config = RequestConfig::builder();
+        request_config
+            .signature_alg(SignatureAlgId::SECP256K1ETH)
+            .hash_alg(HashAlgId::KECCAK256)
+            .encoding(EncodingMode::Evm);
+        let request_config = request_config.build().unwrap();
+
+        let mut request_builder = Request::builder(&request_config);
+        request_builder
+            .server_name(server_name)
+            .handshake_data(server_cert_data.clone())
+            .transcript(transcript);
+        let (request, _) = request_builder.build(provider).unwrap();
+
+        let attestation_config = AttestationConfig::builder()
+            .supported_signature_algs([SignatureAlgId::SECP256K1ETH])
+            .encoding(EncodingMode::Evm)
+            .build()
+            .unwrap();
+
+        let CertBinding::V1_2(CertBindingV1_2 {
+            server_ephemeral_key,
+            ..
+        }) = server_cert_data.binding
+        else {
+            panic!("expected v1.2 binding data");
+        };
+
+        let mut attestation_builder = Attestation::builder(&attestation_config)
+            .accept_request(request.clone())
+            .unwrap();
+        attestation_builder
+            .connection_info(connection_info)
+            .server_ephemeral_key(server_ephemeral_key);
+
+        (request, attestation_builder.build(provider).unwrap())
+    }
+
+    fn provider() -> CryptoProvider {
+        let mut provider = CryptoProvider::default();
+        provider.signer.set_secp256k1eth(&SIGNING_KEY).unwrap();
+        provider
+    }
+
+    #[test]
+    fn test_verify_attestation() {
+        let provider = provider();
+        let (request, attestation) = attestation(&provider);
+
+        assert_eq!(attestation.header.version, EVM_VERSION);
+        assert_eq!(attestation.header.encoding(), Some(EncodingMode::Evm));
+        assert!(
+            request
+                .validate_with_encoding(&attestation, EncodingMode::Evm)
+                .is_ok()
+        );
+        assert!(
+            request
+                .validate_with_encoding(&attestation, EncodingMode::Bcs)
+                .is_err()
+        );
+        assert_eq!(
+            address(attestation.body.verifying_key()).unwrap(),
+            signer_address()
+        );
+
+        verify_attestation(&attestation, &signer_address()).unwrap();
+
+        let presentation = attestation.presentation_builder(&provider).build().unwrap();
+        verify_presentation(&presentation, &signer_address()).unwrap();
+
+        // The presentation also verifies with the regular verifier.
+        presentation.verify(&provider).unwrap();
+    }
+
+    #[test]
+    fn test_verify_attestation_wrong_signer() {
+        let provider = provider();
+        let (_, attestation) = attestation(&provider);
+
+        let err = verify_attestation(&attestation, &[0u8; 20]).unwrap_err();
+        assert!(err.0.contains("expected signer"));
+    }
+
+    #[test]
+    fn test_verify_attestation_tampered_body() {
+        let provider = provider();
+        let (_, mut attestation) = attestation(&provider);
+
+        attestation.body.connection_info.data.time += 1;
+
+        let err = verify_attestation(&attestation, &signer_address()).unwrap_err();
+        assert!(err.0.contains("merkle root"));
+    }
+
+    #[test]
+    fn test_request_config_requires_evm_algs() {
+        let mut builder = RequestConfig::builder();
+        builder.encoding(EncodingMode::Evm);
+
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_merkle_root() {
+        let hasher = Keccak256::default();
+
+        for count in 1..10u8 {
+            let leaves = (0..count).map(|i| hasher.hash(&[i])).collect::<Vec<_>>();
+
+            let mut tree = MerkleTree::new(HashAlgId::KECCAK256);
+            tree.insert(&hasher, leaves.clone());
+
+            assert_eq!(merkle_root(&hasher, leaves), tree.root().value);
+        }
+    }
+}
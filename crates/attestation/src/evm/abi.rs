@@ -0,0 +1,283 @@
+//! Solidity ABI encoding.
+
+use tls_core::msgs::enums::NamedGroup;
+use tlsn_core::{
+    connection::{ConnectionInfo, ServerEphemKey, TlsVersion},
+    hash::TypedHash,
+    transcript::{Direction, TranscriptCommitment, encoding::EncoderSecret},
+};
+
+use crate::{Extension, connection::ServerCertCommitment, signing::VerifyingKey};
+
+/// A value which can be ABI encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token {
+    /// Unsigned integer, eg. `uint8` or `uint64`.
+    Uint(u64),
+    /// Fixed size byte array of at most 32 bytes, eg. `bytes16` or `bytes32`.
+    FixedBytes(Vec<u8>),
+    /// Dynamically sized byte array, `bytes`.
+    Bytes(Vec<u8>),
+    /// Dynamically sized array, eg. `uint64[]`.
+    Array(Vec<Token>),
+    /// Tuple or struct.
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Uint(_) | Token::FixedBytes(_) => false,
+            Token::Bytes(_) | Token::Array(_) => true,
+            Token::Tuple(tokens) => tokens.iter().any(Token::is_dynamic),
+        }
+    }
+
+    /// Returns the size of the token in the head of an enclosing tuple.
+    fn head_len(&self) -> usize {
+        match self {
+            Token::Tuple(tokens) if !self.is_dynamic() => tokens.iter().map(Token::head_len).sum(),
+            _ => 32,
+        }
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Token::Uint(value) => out.extend_from_slice(&word(*value)),
+            Token::FixedBytes(bytes) => {
+                assert!(bytes.len() <= 32, "fixed bytes must be at most 32 bytes");
+                pad_right(out, bytes);
+            }
+            Token::Bytes(bytes) => {
+                out.extend_from_slice(&word(bytes.len() as u64));
+                pad_right(out, bytes);
+            }
+            Token::Array(tokens) => {
+                out.extend_from_slice(&word(tokens.len() as u64));
+                encode_tuple(tokens, out);
+            }
+            Token::Tuple(tokens) => encode_tuple(tokens, out),
+        }
+    }
+}
+
+/// Encodes the tokens as Solidity's `abi.encode(...)` would.
+pub(crate) fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_tuple(tokens, &mut out);
+    out
+}
+
+fn encode_tuple(tokens: &[Token], out: &mut Vec<u8>) {
+    let head_len: usize = tokens.iter().map(Token::head_len).sum();
+
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&word((head_len + tail.len()) as u64));
+            token.encode_into(&mut tail);
+        } else {
+            token.encode_into(&mut head);
+        }
+    }
+
+    out.extend_from_slice(&head);
+    out.extend_from_slice(&tail);
+}
+
+/// Returns the value as a big-endian 32 byte word.
+fn word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Appends the bytes right-padded with zeros to a multiple of 32 bytes.
+fn pad_right(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(bytes);
+    out.resize(out.len() + (32 - bytes.len() % 32) % 32, 0);
+}
+
+/// ABI encoding of attestation fields.
+pub(crate) trait AbiEncode {
+    /// Returns the tokens of the type.
+    fn abi_tokens(&self) -> Vec<Token>;
+
+    /// Returns the ABI encoding of the type.
+    fn abi_encode(&self) -> Vec<u8> {
+        encode(&self.abi_tokens())
+    }
+}
+
+fn hash_tokens(hash: &TypedHash) -> [Token; 2] {
+    [
+        Token::Uint(hash.alg.as_u8() as u64),
+        Token::Bytes(hash.value.as_bytes().to_vec()),
+    ]
+}
+
+impl AbiEncode for VerifyingKey {
+    fn abi_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::Uint(self.alg.as_u8() as u64),
+            Token::Bytes(self.data.clone()),
+        ]
+    }
+}
+
+impl AbiEncode for ConnectionInfo {
+    fn abi_tokens(&self) -> Vec<Token> {
+        let version = match self.version {
+            TlsVersion::V1_2 => 0x0303,
+            TlsVersion::V1_3 => 0x0304,
+        };
+
+        vec![
+            Token::Uint(self.time),
+            Token::Uint(version),
+            Token::Uint(self.transcript_length.sent as u64),
+            Token::Uint(self.transcript_length.received as u64),
+        ]
+    }
+}
+
+impl AbiEncode for ServerEphemKey {
+    fn abi_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::Uint(NamedGroup::from(self.typ).get_u16() as u64),
+            Token::Bytes(self.key.clone()),
+        ]
+    }
+}
+
+impl AbiEncode for ServerCertCommitment {
+    fn abi_tokens(&self) -> Vec<Token> {
+        hash_tokens(&self.0).to_vec()
+    }
+}
+
+impl AbiEncode for EncoderSecret {
+    fn abi_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::FixedBytes(self.seed().to_vec()),
+            Token::FixedBytes(self.delta().to_vec()),
+        ]
+    }
+}
+
+impl AbiEncode for Extension {
+    fn abi_tokens(&self) -> Vec<Token> {
+        vec![
+            Token::Bytes(self.id.clone()),
+            Token::Bytes(self.value.clone()),
+        ]
+    }
+}
+
+impl AbiEncode for TranscriptCommitment {
+    fn abi_tokens(&self) -> Vec<Token> {
+        match self {
+            TranscriptCommitment::Encoding(commitment) => {
+                let mut tokens = vec![Token::Uint(0)];
+                tokens.extend(hash_tokens(&commitment.root));
+                tokens
+            }
+            TranscriptCommitment::Hash(hash) => {
+                let direction = match hash.direction {
+                    Direction::Sent => 0,
+                    Direction::Received => 1,
+                };
+                let ranges = hash
+                    .idx
+                    .iter_ranges()
+                    .map(|range| {
+                        Token::Tuple(vec![
+                            Token::Uint(range.start as u64),
+                            Token::Uint(range.end as u64),
+                        ])
+                    })
+                    .collect();
+
+                let mut tokens = vec![Token::Uint(1), Token::Uint(direction), Token::Array(ranges)];
+                tokens.extend(hash_tokens(&hash.hash));
+                tokens
+            }
+            // Commitments without a dedicated layout fall back to their
+            // canonical serialization.
+            commitment => vec![
+                Token::Uint(0xff),
+                Token::Bytes(bcs::to_bytes(commitment).unwrap()),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn words(words: &[[u8; 32]]) -> Vec<u8> {
+        words.concat()
+    }
+
+    fn bytes_word(bytes: &[u8]) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[..bytes.len()].copy_from_slice(bytes);
+        word
+    }
+
+    #[test]
+    fn test_encode_static() {
+        let encoded = encode(&[
+            Token::Uint(1),
+            Token::FixedBytes(vec![0xaa; 16]),
+            Token::Tuple(vec![Token::Uint(2), Token::Uint(3)]),
+        ]);
+
+        assert_eq!(
+            encoded,
+            words(&[word(1), bytes_word(&[0xaa; 16]), word(2), word(3)])
+        );
+    }
+
+    #[test]
+    fn test_encode_bytes() {
+        // abi.encode(uint256(1), bytes("abc"))
+        let encoded = encode(&[Token::Uint(1), Token::Bytes(b"abc".to_vec())]);
+
+        assert_eq!(
+            encoded,
+            words(&[word(1), word(0x40), word(3), bytes_word(b"abc")])
+        );
+    }
+
+    #[test]
+    fn test_encode_nested() {
+        // abi.encode(bytes(""), [(1, 2)], bytes(33 bytes))
+        let encoded = encode(&[
+            Token::Bytes(Vec::new()),
+            Token::Array(vec![Token::Tuple(vec![Token::Uint(1), Token::Uint(2)])]),
+            Token::Bytes(vec![0xbb; 33]),
+        ]);
+
+        assert_eq!(
+            encoded,
+            words(&[
+                word(0x60),
+                word(0x80),
+                word(0xe0),
+                // bytes("")
+                word(0),
+                // (uint256, uint256)[]
+                word(1),
+                word(1),
+                word(2),
+                // bytes(33 bytes)
+                word(33),
+                [0xbb; 32],
+                bytes_word(&[0xbb]),
+            ])
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::error::Error;
+use std::{collections::HashMap, error::Error};
 
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +15,148 @@ pub struct Extension {
 
 impl_domain_separator!(Extension);
 
+/// A typed attestation extension.
+///
+/// Implementors define how the extension is encoded into, and decoded from,
+/// the value of an [`Extension`] with the identifier [`ID`](Self::ID).
+///
+/// # Example
+///
+/// ```
+/// # use tlsn_attestation::{InvalidExtension, TypedExtension};
+/// struct Nonce(u64);
+///
+/// impl TypedExtension for Nonce {
+///     const ID: &'static [u8] = b"example.nonce";
+///
+///     fn encode(&self) -> Vec<u8> {
+///         self.0.to_be_bytes().to_vec()
+///     }
+///
+///     fn decode(value: &[u8]) -> Result<Self, InvalidExtension> {
+///         let bytes = value
+///             .try_into()
+///             .map_err(|_| InvalidExtension::new("nonce must be 8 bytes"))?;
+///
+///         Ok(Self(u64::from_be_bytes(bytes)))
+///     }
+/// }
+/// ```
+pub trait TypedExtension: Sized {
+    /// Extension identifier.
+    const ID: &'static [u8];
+
+    /// Encodes the extension value.
+    fn encode(&self) -> Vec<u8>;
+
+    /// Decodes the extension value.
+    fn decode(value: &[u8]) -> Result<Self, InvalidExtension>;
+
+    /// Validates the decoded extension.
+    ///
+    /// Accepts any value by default.
+    fn validate(&self) -> Result<(), InvalidExtension> {
+        Ok(())
+    }
+
+    /// Converts the extension into an [`Extension`].
+    fn to_extension(&self) -> Extension {
+        Extension {
+            id: Self::ID.to_vec(),
+            value: self.encode(),
+        }
+    }
+
+    /// Decodes and validates an [`Extension`].
+    fn from_extension(extension: &Extension) -> Result<Self, InvalidExtension> {
+        if extension.id != Self::ID {
+            return Err(InvalidExtension::new(format!(
+                "expected extension id {:?}, got {:?}",
+                String::from_utf8_lossy(Self::ID),
+                String::from_utf8_lossy(&extension.id)
+            )));
+        }
+
+        let typed = Self::decode(&extension.value)?;
+        typed.validate()?;
+
+        Ok(typed)
+    }
+}
+
+type ValidateFn = fn(&Extension) -> Result<(), InvalidExtension>;
+
+/// Registry of [typed extensions](TypedExtension).
+///
+/// Extensions with an identifier which has been registered are known, and are
+/// decoded and validated using their typed implementation. All other
+/// extensions are unknown.
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionRegistry {
+    extensions: HashMap<Vec<u8>, ValidateFn>,
+}
+
+impl ExtensionRegistry {
+    /// Creates a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a typed extension.
+    ///
+    /// Replaces any extension previously registered with the same identifier.
+    pub fn register<T: TypedExtension>(&mut self) -> &mut Self {
+        self.extensions
+            .insert(T::ID.to_vec(), |extension: &Extension| {
+                T::from_extension(extension).map(|_| ())
+            });
+        self
+    }
+
+    /// Returns `true` if no extensions are registered.
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty()
+    }
+
+    /// Returns `true` if an extension with the given identifier is registered.
+    pub fn is_known(&self, id: &[u8]) -> bool {
+        self.extensions.contains_key(id)
+    }
+
+    /// Validates the known extensions, ignoring unknown extensions.
+    pub fn validate<'a>(
+        &self,
+        extensions: impl IntoIterator<Item = &'a Extension>,
+    ) -> Result<(), InvalidExtension> {
+        for extension in extensions {
+            if let Some(validate) = self.extensions.get(&extension.id) {
+                validate(extension)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the extensions which are not registered.
+    pub fn unknown<'a>(
+        &'a self,
+        extensions: impl IntoIterator<Item = &'a Extension> + 'a,
+    ) -> impl Iterator<Item = &'a Extension> + 'a {
+        extensions
+            .into_iter()
+            .filter(|extension| !self.is_known(&extension.id))
+    }
+}
+
+/// Returns the first extension of type `T`, if any.
+pub(crate) fn find<'a, T: TypedExtension>(
+    mut extensions: impl Iterator<Item = &'a Extension>,
+) -> Option<Result<T, InvalidExtension>> {
+    extensions
+        .find(|extension| extension.id == T::ID)
+        .map(T::from_extension)
+}
+
 /// Invalid extension error.
 #[derive(Debug, thiserror::Error)]
 #[error("invalid extension: {reason}")]
@@ -33,3 +175,83 @@ impl InvalidExtension {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Nonce(u64);
+
+    impl TypedExtension for Nonce {
+        const ID: &'static [u8] = b"test.nonce";
+
+        fn encode(&self) -> Vec<u8> {
+            self.0.to_be_bytes().to_vec()
+        }
+
+        fn decode(value: &[u8]) -> Result<Self, InvalidExtension> {
+            let bytes = value
+                .try_into()
+                .map_err(|_| InvalidExtension::new("nonce must be 8 bytes"))?;
+
+            Ok(Self(u64::from_be_bytes(bytes)))
+        }
+
+        fn validate(&self) -> Result<(), InvalidExtension> {
+            if self.0 == 0 {
+                return Err(InvalidExtension::new("nonce must not be zero"));
+            }
+
+            Ok(())
+        }
+    }
+
+    fn unknown() -> Extension {
+        Extension {
+            id: b"test.unknown".to_vec(),
+            value: b"foo".to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_typed_extension_roundtrip() {
+        let extension = Nonce(42).to_extension();
+
+        assert_eq!(extension.id, b"test.nonce");
+        assert_eq!(Nonce::from_extension(&extension).unwrap(), Nonce(42));
+        assert!(Nonce::from_extension(&unknown()).is_err());
+        assert!(Nonce::from_extension(&Nonce(0).to_extension()).is_err());
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = ExtensionRegistry::new();
+        registry.register::<Nonce>();
+
+        assert!(registry.is_known(Nonce::ID));
+        assert!(!registry.is_known(b"test.unknown"));
+
+        let extensions = [Nonce(1).to_extension(), unknown()];
+        assert!(registry.validate(&extensions).is_ok());
+        assert_eq!(
+            registry.unknown(&extensions).collect::<Vec<_>>(),
+            vec![&extensions[1]]
+        );
+
+        let invalid = Extension {
+            id: Nonce::ID.to_vec(),
+            value: vec![0; 4],
+        };
+        assert!(registry.validate([&invalid]).is_err());
+        assert!(registry.validate([&Nonce(0).to_extension()]).is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let extensions = [unknown(), Nonce(1).to_extension(), Nonce(2).to_extension()];
+
+        assert_eq!(find::<Nonce>(extensions.iter()).unwrap().unwrap(), Nonce(1));
+        assert!(find::<Nonce>([unknown()].iter()).is_none());
+    }
+}
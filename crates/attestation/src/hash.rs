@@ -1,6 +1,9 @@
 use tlsn_core::hash::{Hash, HashAlgId, HashAlgorithm};
 
-use crate::serialize::{CanonicalSerialize, DomainSeparator};
+use crate::{
+    evm::AbiEncode,
+    serialize::{CanonicalSerialize, DomainSeparator, EncodingMode},
+};
 
 pub(crate) const DEFAULT_SUPPORTED_HASH_ALGS: &[HashAlgId] =
     &[HashAlgId::SHA256, HashAlgId::BLAKE3, HashAlgId::KECCAK256];
@@ -14,6 +17,18 @@ pub(crate) trait HashAlgorithmExt: HashAlgorithm {
     fn hash_separated<T: DomainSeparator + CanonicalSerialize>(&self, data: &T) -> Hash {
         self.hash_prefixed(data.domain(), &data.serialize())
     }
+
+    /// Hashes an attestation field using the given encoding mode.
+    fn hash_field<T: DomainSeparator + CanonicalSerialize + AbiEncode>(
+        &self,
+        data: &T,
+        mode: EncodingMode,
+    ) -> Hash {
+        match mode {
+            EncodingMode::Bcs => self.hash_separated(data),
+            EncodingMode::Evm => self.hash_prefixed(data.domain(), &data.abi_encode()),
+        }
+    }
 }
 
 impl<T: HashAlgorithm + ?Sized> HashAlgorithmExt for T {}
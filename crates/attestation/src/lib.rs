@@ -54,6 +54,15 @@
 //! [include](crate::AttestationBuilder::extension)
 //! their own extensions.
 //!
+//! Applications may define their extensions as a [`TypedExtension`], which
+//! takes care of encoding, decoding and validating the extension value. Typed
+//! extensions can be
+//! [registered](crate::AttestationConfigBuilder::register_extension)
+//! with a Notary, and decoded from the [`Body`] or a verified
+//! [presentation](crate::presentation::PresentationOutput::extension). An
+//! [`ExtensionRegistry`] can also be used to find the extensions which an
+//! application does not know about.
+//!
 //! # Committing to the transcript
 //!
 //! The TLS commitment protocol produces commitments to the entire transcript of
@@ -121,6 +130,10 @@
 //! The Notary's [`CryptoProvider`] must be configured with an appropriate
 //! signing key for attestations. See
 //! [`SignerProvider`](crate::signing::SignerProvider) for more information.
+//! Keys held by an external service, such as a KMS, can be used by configuring
+//! an [`AsyncSigner`](crate::signing::AsyncSigner) and building the
+//! attestation with
+//! [`AttestationBuilder::build_async`](crate::AttestationBuilder::build_async).
 //!
 //! # Constructing a presentation
 //!
@@ -190,6 +203,14 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! If the attestation was co-signed by several Notaries, a verifier can instead
+//! require a quorum of trusted Notaries using
+//! [`Presentation::verify_quorum`](crate::presentation::Presentation::verify_quorum).
+//! See the [`quorum`] module for more information.
+//!
+//! Attestations which need to be verified by a smart contract can be requested
+//! with [`EncodingMode::Evm`], see the [`evm`] module.
 
 #![deny(missing_docs, unreachable_pub, unused_must_use)]
 #![deny(clippy::all)]
@@ -198,6 +219,7 @@
 mod builder;
 mod config;
 pub mod connection;
+pub mod evm;
 mod extension;
 #[cfg(any(test, feature = "fixtures"))]
 pub mod fixtures;
@@ -205,6 +227,7 @@ pub(crate) mod hash;
 pub mod presentation;
 mod proof;
 mod provider;
+pub mod quorum;
 pub mod request;
 mod secrets;
 pub(crate) mod serialize;
@@ -226,18 +249,23 @@ use crate::{
     connection::ServerCertCommitment,
     hash::HashAlgorithmExt,
     presentation::PresentationBuilder,
-    serialize::impl_domain_separator,
+    serialize::{CanonicalSerialize, impl_domain_separator},
     signing::{Signature, VerifyingKey},
 };
 
 pub use builder::{AttestationBuilder, AttestationBuilderError};
 pub use config::{AttestationConfig, AttestationConfigBuilder, AttestationConfigError};
-pub use extension::{Extension, InvalidExtension};
+pub use extension::{Extension, ExtensionRegistry, InvalidExtension, TypedExtension};
 pub use proof::{AttestationError, AttestationProof};
 pub use provider::CryptoProvider;
 pub use secrets::Secrets;
+pub use serialize::EncodingMode;
 /// Current version of attestations.
+///
+/// Attestations using [`EncodingMode::Bcs`] have this version.
 pub const VERSION: Version = Version(0);
+/// Version of attestations using [`EncodingMode::Evm`].
+pub const EVM_VERSION: Version = Version(1);
 
 /// Unique identifier for an attestation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -318,6 +346,29 @@ pub struct Header {
     pub root: TypedHash,
 }
 
+impl Header {
+    /// Returns the encoding mode of the attestation, or `None` if the version
+    /// is not supported.
+    pub fn encoding(&self) -> Option<EncodingMode> {
+        match self.version {
+            VERSION => Some(EncodingMode::Bcs),
+            EVM_VERSION => Some(EncodingMode::Evm),
+            _ => None,
+        }
+    }
+
+    /// Returns the message which is signed by the Notary.
+    ///
+    /// Headers with an unsupported version are rejected before their
+    /// signature is checked, see [`Header::encoding`].
+    pub(crate) fn signing_message(&self) -> Vec<u8> {
+        match self.encoding() {
+            Some(EncodingMode::Evm) => evm::eip712_message(self).to_vec(),
+            _ => CanonicalSerialize::serialize(self),
+        }
+    }
+}
+
 impl_domain_separator!(Header);
 
 /// Attestation body.
@@ -338,6 +389,22 @@ impl Body {
         self.extensions.iter().map(|field| &field.data)
     }
 
+    /// Returns the first extension of type `T`, decoded and validated.
+    ///
+    /// Returns `None` if the body contains no extension with the identifier of
+    /// `T`.
+    pub fn extension<T: TypedExtension>(&self) -> Option<Result<T, InvalidExtension>> {
+        extension::find(self.extensions())
+    }
+
+    /// Returns the extensions which are not registered in the registry.
+    pub fn unknown_extensions<'a>(
+        &'a self,
+        registry: &'a ExtensionRegistry,
+    ) -> impl Iterator<Item = &'a Extension> + 'a {
+        registry.unknown(self.extensions())
+    }
+
     /// Returns the attestation verifying key.
     pub fn verifying_key(&self) -> &VerifyingKey {
         &self.verifying_key.data
@@ -346,10 +413,10 @@ impl Body {
     /// Computes the Merkle root of the attestation fields.
     ///
     /// This is only used when building an attestation.
-    pub(crate) fn root(&self, hasher: &dyn HashAlgorithm) -> TypedHash {
+    pub(crate) fn root(&self, hasher: &dyn HashAlgorithm, mode: EncodingMode) -> TypedHash {
         let mut tree = MerkleTree::new(hasher.id());
         let fields = self
-            .hash_fields(hasher)
+            .hash_fields(hasher, mode)
             .into_iter()
             .map(|(_, hash)| hash)
             .collect::<Vec<_>>();
@@ -359,13 +426,17 @@ impl Body {
 
     /// Returns the fields of the body hashed and sorted by id.
     ///
-    /// Each field is hashed with a domain separator to mitigate type confusion
-    /// attacks.
+    /// Each field is encoded according to the encoding mode and hashed with a
+    /// domain separator to mitigate type confusion attacks.
     ///
     /// # Note
     ///
     /// The order of fields is not stable across versions.
-    pub(crate) fn hash_fields(&self, hasher: &dyn HashAlgorithm) -> Vec<(FieldId, Hash)> {
+    pub(crate) fn hash_fields(
+        &self,
+        hasher: &dyn HashAlgorithm,
+        mode: EncodingMode,
+    ) -> Vec<(FieldId, Hash)> {
         // CRITICAL: ensure all fields are included! If a new field is added to the
         // struct without including it here, it will not be included in the attestation.
         let Self {
@@ -379,31 +450,34 @@ impl Body {
         } = self;
 
         let mut fields: Vec<(FieldId, Hash)> = vec![
-            (verifying_key.id, hasher.hash_separated(&verifying_key.data)),
-            (conn_info.id, hasher.hash_separated(&conn_info.data)),
+            (
+                verifying_key.id,
+                hasher.hash_field(&verifying_key.data, mode),
+            ),
+            (conn_info.id, hasher.hash_field(&conn_info.data, mode)),
             (
                 server_ephemeral_key.id,
-                hasher.hash_separated(&server_ephemeral_key.data),
+                hasher.hash_field(&server_ephemeral_key.data, mode),
             ),
             (
                 cert_commitment.id,
-                hasher.hash_separated(&cert_commitment.data),
+                hasher.hash_field(&cert_commitment.data, mode),
             ),
         ];
 
         if let Some(encoder_secret) = encoder_secret {
             fields.push((
                 encoder_secret.id,
-                hasher.hash_separated(&encoder_secret.data),
+                hasher.hash_field(&encoder_secret.data, mode),
             ));
         }
 
         for field in extensions.iter() {
-            fields.push((field.id, hasher.hash_separated(&field.data)));
+            fields.push((field.id, hasher.hash_field(&field.data, mode)));
         }
 
         for field in transcript_commitments.iter() {
-            fields.push((field.id, hasher.hash_separated(&field.data)));
+            fields.push((field.id, hasher.hash_field(&field.data, mode)));
         }
 
         fields.sort_by_key(|(id, _)| *id);
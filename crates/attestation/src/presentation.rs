@@ -32,8 +32,10 @@ use tlsn_core::{
 };
 
 use crate::{
-    Attestation, AttestationError, AttestationProof, CryptoProvider, Extension,
+    Attestation, AttestationError, AttestationProof, CryptoProvider, Extension, ExtensionRegistry,
+    InvalidExtension, TypedExtension,
     connection::{ServerIdentityProof, ServerIdentityProofError},
+    quorum::{Cosignature, QuorumError, QuorumPolicy},
     signing::VerifyingKey,
 };
 
@@ -62,7 +64,16 @@ impl Presentation {
         self.attestation.verifying_key()
     }
 
+    /// Returns the attestation proof.
+    pub(crate) fn attestation_proof(&self) -> &AttestationProof {
+        &self.attestation
+    }
+
     /// Verifies the presentation.
+    ///
+    /// Extensions registered in the provider's
+    /// [extension registry](CryptoProvider::extension) are validated, other
+    /// extensions are returned as is.
     pub fn verify(
         self,
         provider: &CryptoProvider,
@@ -74,6 +85,7 @@ impl Presentation {
         } = self;
 
         let attestation = attestation.verify(provider)?;
+        let signers = vec![attestation.body.verifying_key().clone()];
 
         let server_name = identity
             .map(|identity| {
@@ -103,16 +115,56 @@ impl Presentation {
 
         let connection_info = attestation.body.connection_info().clone();
 
+        provider.extension.validate(attestation.body.extensions())?;
+
         let extensions = attestation.body.extensions().cloned().collect();
 
         Ok(PresentationOutput {
             attestation,
+            signers,
             server_name,
             connection_info,
             transcript,
             extensions,
         })
     }
+
+    /// Verifies the presentation, requiring the attestation to be signed by a
+    /// quorum of trusted Notaries.
+    ///
+    /// The cosignatures are not part of the presentation, they are
+    /// transmitted alongside it. All of them must be valid, and the Notaries
+    /// which signed the attestation are reported in
+    /// [`PresentationOutput::signers`].
+    ///
+    /// See [`verify`](Self::verify) for details.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Cryptography provider.
+    /// * `cosignatures` - Cosignatures of the attestation by additional
+    ///   Notaries.
+    /// * `policy` - Quorum policy over the trusted Notaries.
+    pub fn verify_quorum(
+        self,
+        provider: &CryptoProvider,
+        cosignatures: &[Cosignature],
+        policy: &QuorumPolicy,
+    ) -> Result<PresentationOutput, PresentationError> {
+        let mut output = self.verify(provider)?;
+
+        for cosignature in cosignatures {
+            cosignature.verify(provider, &output.attestation.header)?;
+
+            if !output.signers.contains(&cosignature.verifying_key) {
+                output.signers.push(cosignature.verifying_key.clone());
+            }
+        }
+
+        policy.check(&output.signers)?;
+
+        Ok(output)
+    }
 }
 
 /// Output of a verified [`Presentation`].
@@ -121,6 +173,12 @@ impl Presentation {
 pub struct PresentationOutput {
     /// Verified attestation.
     pub attestation: Attestation,
+    /// Verifying keys of the Notaries which signed the attestation.
+    ///
+    /// The first key is the key of the Notary which issued the attestation.
+    /// Co-signing Notaries are only included by
+    /// [`Presentation::verify_quorum`].
+    pub signers: Vec<VerifyingKey>,
     /// Authenticated server name.
     pub server_name: Option<ServerName>,
     /// Connection information.
@@ -131,6 +189,24 @@ pub struct PresentationOutput {
     pub extensions: Vec<Extension>,
 }
 
+impl PresentationOutput {
+    /// Returns the first extension of type `T`, decoded and validated.
+    ///
+    /// Returns `None` if the attestation contains no extension with the
+    /// identifier of `T`.
+    pub fn extension<T: TypedExtension>(&self) -> Option<Result<T, InvalidExtension>> {
+        crate::extension::find(self.extensions.iter())
+    }
+
+    /// Returns the extensions which are not registered in the registry.
+    pub fn unknown_extensions<'a>(
+        &'a self,
+        registry: &'a ExtensionRegistry,
+    ) -> impl Iterator<Item = &'a Extension> + 'a {
+        registry.unknown(&self.extensions)
+    }
+}
+
 /// Builder for [`Presentation`].
 pub struct PresentationBuilder<'a> {
     provider: &'a CryptoProvider,
@@ -222,6 +298,8 @@ enum ErrorKind {
     Attestation,
     Identity,
     Transcript,
+    Quorum,
+    Extension,
 }
 
 impl fmt::Display for PresentationError {
@@ -232,6 +310,8 @@ impl fmt::Display for PresentationError {
             ErrorKind::Attestation => f.write_str("attestation error")?,
             ErrorKind::Identity => f.write_str("server identity error")?,
             ErrorKind::Transcript => f.write_str("transcript error")?,
+            ErrorKind::Quorum => f.write_str("quorum error")?,
+            ErrorKind::Extension => f.write_str("extension error")?,
         }
 
         if let Some(source) = &self.source {
@@ -260,6 +340,15 @@ impl From<ServerIdentityProofError> for PresentationError {
     }
 }
 
+impl From<InvalidExtension> for PresentationError {
+    fn from(error: InvalidExtension) -> Self {
+        Self {
+            kind: ErrorKind::Extension,
+            source: Some(Box::new(error)),
+        }
+    }
+}
+
 impl From<TranscriptProofError> for PresentationError {
     fn from(error: TranscriptProofError) -> Self {
         Self {
@@ -268,3 +357,12 @@ impl From<TranscriptProofError> for PresentationError {
         }
     }
 }
+
+impl From<QuorumError> for PresentationError {
+    fn from(error: QuorumError) -> Self {
+        Self {
+            kind: ErrorKind::Quorum,
+            source: Some(Box::new(error)),
+        }
+    }
+}
@@ -8,8 +8,7 @@ use tlsn_core::{
 };
 
 use crate::{
-    Attestation, Body, CryptoProvider, Header,
-    serialize::CanonicalSerialize,
+    Attestation, Body, CryptoProvider, EncodingMode, Header, evm,
     signing::{Signature, VerifyingKey},
 };
 
@@ -31,7 +30,14 @@ impl AttestationProof {
             .get(&attestation.header.root.alg)
             .map_err(|e| AttestationError::new(ErrorKind::Provider, e))?;
 
-        let body = BodyProof::new(hasher, attestation.body.clone())?;
+        let encoding = attestation.header.encoding().ok_or_else(|| {
+            AttestationError::new(
+                ErrorKind::Version,
+                format!("unsupported version: {:?}", attestation.header.version),
+            )
+        })?;
+
+        let body = BodyProof::new(hasher, encoding, attestation.body.clone())?;
 
         Ok(Self {
             signature: attestation.signature.clone(),
@@ -45,6 +51,12 @@ impl AttestationProof {
         self.body.verifying_key()
     }
 
+    /// Returns the signature, header and body of the proof, without verifying
+    /// them.
+    pub(crate) fn parts(&self) -> (&Signature, &Header, &Body) {
+        (&self.signature, &self.header, &self.body.body)
+    }
+
     /// Verifies the attestation proof.
     ///
     /// # Arguments
@@ -57,14 +69,28 @@ impl AttestationProof {
             .get(&self.signature.alg)
             .map_err(|e| AttestationError::new(ErrorKind::Provider, e))?;
 
+        let encoding = self.header.encoding().ok_or_else(|| {
+            AttestationError::new(
+                ErrorKind::Version,
+                format!("unsupported version: {:?}", self.header.version),
+            )
+        })?;
+
+        if encoding == EncodingMode::Evm {
+            evm::check_algs(&self.signature.alg, &self.header.root.alg)
+                .map_err(|e| AttestationError::new(ErrorKind::Signature, e))?;
+        }
+
         // Verify that the body is corresponding to the header.
-        let body = self.body.verify_with_provider(provider, &self.header)?;
+        let body = self
+            .body
+            .verify_with_provider(provider, &self.header, encoding)?;
 
         // Verify signature of the header.
         signature_verifier
             .verify(
                 &body.verifying_key.data,
-                &CanonicalSerialize::serialize(&self.header),
+                &self.header.signing_message(),
                 &self.signature.data,
             )
             .map_err(|e| AttestationError::new(ErrorKind::Signature, e))?;
@@ -92,10 +118,11 @@ impl BodyProof {
     // body.
     pub(crate) fn new(
         hasher: &dyn HashAlgorithm,
+        encoding: EncodingMode,
         body: Body,
     ) -> Result<BodyProof, AttestationError> {
         let (indices, leaves): (Vec<_>, Vec<_>) = body
-            .hash_fields(hasher)
+            .hash_fields(hasher, encoding)
             .into_iter()
             .map(|(id, hash)| (id.0 as usize, hash))
             .unzip();
@@ -117,6 +144,7 @@ impl BodyProof {
         self,
         provider: &CryptoProvider,
         header: &Header,
+        encoding: EncodingMode,
     ) -> Result<Body, AttestationError> {
         let hasher = provider
             .hash
@@ -125,7 +153,7 @@ impl BodyProof {
 
         let fields = self
             .body
-            .hash_fields(hasher)
+            .hash_fields(hasher, encoding)
             .into_iter()
             .map(|(id, hash)| (id.0 as usize, hash));
 
@@ -164,6 +192,7 @@ impl fmt::Display for AttestationError {
             ErrorKind::Provider => f.write_str("provider error")?,
             ErrorKind::Signature => f.write_str("signature error")?,
             ErrorKind::Body => f.write_str("body proof error")?,
+            ErrorKind::Version => f.write_str("version error")?,
         }
 
         if let Some(source) = &self.source {
@@ -179,4 +208,5 @@ enum ErrorKind {
     Provider,
     Signature,
     Body,
+    Version,
 }
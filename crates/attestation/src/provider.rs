@@ -1,6 +1,9 @@
 use tlsn_core::{hash::HashProvider, webpki::ServerCertVerifier};
 
-use crate::signing::{SignatureVerifierProvider, SignerProvider};
+use crate::{
+    ExtensionRegistry,
+    signing::{SignatureVerifierProvider, SignerProvider},
+};
 
 /// Cryptography provider.
 ///
@@ -33,6 +36,10 @@ pub struct CryptoProvider {
     ///
     /// This is used for verifying signatures of attestations.
     pub signature: SignatureVerifierProvider,
+    /// Extension registry.
+    ///
+    /// Registered extensions are validated when verifying presentations.
+    pub extension: ExtensionRegistry,
 }
 
 opaque_debug::implement!(CryptoProvider);
@@ -44,6 +51,7 @@ impl Default for CryptoProvider {
             cert: ServerCertVerifier::mozilla(),
             signer: Default::default(),
             signature: Default::default(),
+            extension: Default::default(),
         }
     }
 }
@@ -0,0 +1,414 @@
+//! Multi-notary attestations.
+//!
+//! An [`Attestation`] is signed by the Notary which took part in the TLS
+//! session. For claims which should not rely on a single Notary, the
+//! attestation [`Header`] can additionally be co-signed by other Notaries,
+//! each producing a [`Cosignature`]. A co-signing Notary checks that the
+//! header commits to the attestation body before signing it, so all
+//! signatures vouch for the same set of fields.
+//!
+//! Cosignatures are not part of a
+//! [`Presentation`](crate::presentation::Presentation), the Prover sends them
+//! alongside it. A Verifier can then require that at least `k` out of a set of
+//! `n` trusted Notaries signed the attestation by verifying the presentation
+//! and the cosignatures with
+//! [`Presentation::verify_quorum`](crate::presentation::Presentation::verify_quorum)
+//! and a [`QuorumPolicy`].
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use tlsn_core::hash::HashAlgId;
+
+use crate::{
+    Attestation, CryptoProvider, EncodingMode, Header,
+    signing::{Signature, SignatureAlgId, VerifyingKey},
+};
+
+/// A signature over an attestation [`Header`] by an additional Notary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cosignature {
+    /// The key of the co-signing Notary.
+    pub verifying_key: VerifyingKey,
+    /// The signature of the header.
+    pub signature: Signature,
+}
+
+impl Cosignature {
+    /// Verifies the cosignature of the header.
+    pub(crate) fn verify(
+        &self,
+        provider: &CryptoProvider,
+        header: &Header,
+    ) -> Result<(), QuorumError> {
+        if header.encoding().is_none() {
+            return Err(QuorumError::new(
+                ErrorKind::Signature,
+                format!("unsupported version: {:?}", header.version),
+            ));
+        }
+
+        let verifier = provider
+            .signature
+            .get(&self.signature.alg)
+            .map_err(|e| QuorumError::new(ErrorKind::Provider, e))?;
+
+        verifier
+            .verify(
+                &self.verifying_key,
+                &header.signing_message(),
+                &self.signature.data,
+            )
+            .map_err(|e| QuorumError::new(ErrorKind::Signature, e))
+    }
+}
+
+impl Attestation {
+    /// Co-signs the attestation header.
+    ///
+    /// The header is only signed if it commits to the attestation body.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Cryptography provider holding the co-signing Notary's
+    ///   signer.
+    /// * `signature_alg` - Signature algorithm to use.
+    pub fn cosign(
+        &self,
+        provider: &CryptoProvider,
+        signature_alg: &SignatureAlgId,
+    ) -> Result<Cosignature, QuorumError> {
+        let encoding = self.header.encoding().ok_or_else(|| {
+            QuorumError::new(
+                ErrorKind::Body,
+                format!("unsupported version: {:?}", self.header.version),
+            )
+        })?;
+
+        if encoding == EncodingMode::Evm && self.header.root.alg != HashAlgId::KECCAK256 {
+            return Err(QuorumError::new(
+                ErrorKind::Body,
+                "evm encoding requires the keccak-256 hash algorithm",
+            ));
+        }
+
+        let hasher = provider
+            .hash
+            .get(&self.header.root.alg)
+            .map_err(|e| QuorumError::new(ErrorKind::Provider, e))?;
+
+        if self.body.root(hasher, encoding) != self.header.root {
+            return Err(QuorumError::new(
+                ErrorKind::Body,
+                "header root does not match the attestation body",
+            ));
+        }
+
+        let signer = provider
+            .signer
+            .get(signature_alg)
+            .map_err(|e| QuorumError::new(ErrorKind::Provider, e))?;
+
+        let signature = signer
+            .sign(&self.header.signing_message())
+            .map_err(|e| QuorumError::new(ErrorKind::Signature, e))?;
+
+        Ok(Cosignature {
+            verifying_key: signer.verifying_key(),
+            signature,
+        })
+    }
+}
+
+/// Policy requiring an attestation to be signed by a quorum of trusted
+/// Notaries.
+#[derive(Debug, Clone)]
+pub struct QuorumPolicy {
+    threshold: usize,
+    trusted: Vec<VerifyingKey>,
+}
+
+impl QuorumPolicy {
+    /// Creates a new quorum policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Minimum number of distinct trusted Notaries which must
+    ///   have signed.
+    /// * `trusted` - Verifying keys of the trusted Notaries.
+    pub fn new(
+        threshold: usize,
+        trusted: impl IntoIterator<Item = VerifyingKey>,
+    ) -> Result<Self, QuorumError> {
+        let mut keys: Vec<VerifyingKey> = Vec::new();
+        for key in trusted {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        if threshold == 0 {
+            return Err(QuorumError::new(
+                ErrorKind::Policy,
+                "threshold must be at least 1",
+            ));
+        }
+
+        if threshold > keys.len() {
+            return Err(QuorumError::new(
+                ErrorKind::Policy,
+                format!(
+                    "threshold {threshold} exceeds the number of trusted keys {}",
+                    keys.len()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            threshold,
+            trusted: keys,
+        })
+    }
+
+    /// Returns the minimum number of trusted Notaries which must have signed.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the verifying keys of the trusted Notaries.
+    pub fn trusted(&self) -> &[VerifyingKey] {
+        &self.trusted
+    }
+
+    /// Checks that the signers satisfy the policy, returning the trusted
+    /// signers.
+    pub(crate) fn check<'a>(
+        &self,
+        signers: &'a [VerifyingKey],
+    ) -> Result<Vec<&'a VerifyingKey>, QuorumError> {
+        let trusted = signers
+            .iter()
+            .filter(|key| self.trusted.contains(key))
+            .collect::<Vec<_>>();
+
+        if trusted.len() < self.threshold {
+            return Err(QuorumError::new(
+                ErrorKind::Quorum,
+                format!(
+                    "signed by {} trusted notaries, required {}",
+                    trusted.len(),
+                    self.threshold
+                ),
+            ));
+        }
+
+        Ok(trusted)
+    }
+}
+
+/// Error for multi-notary attestations.
+#[derive(Debug, thiserror::Error)]
+pub struct QuorumError {
+    kind: ErrorKind,
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl QuorumError {
+    fn new<E>(kind: ErrorKind, source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self {
+            kind,
+            source: Some(source.into()),
+        }
+    }
+
+    /// Returns whether the error is due to the quorum not being reached.
+    pub fn is_quorum(&self) -> bool {
+        matches!(self.kind, ErrorKind::Quorum)
+    }
+}
+
+impl fmt::Display for QuorumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("quorum error: ")?;
+
+        match self.kind {
+            ErrorKind::Provider => f.write_str("provider error")?,
+            ErrorKind::Body => f.write_str("body error")?,
+            ErrorKind::Signature => f.write_str("signature error")?,
+            ErrorKind::Policy => f.write_str("invalid policy")?,
+            ErrorKind::Quorum => f.write_str("quorum not reached")?,
+        }
+
+        if let Some(source) = &self.source {
+            write!(f, " caused by: {source}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    Provider,
+    Body,
+    Signature,
+    Policy,
+    Quorum,
+}
+
+#[cfg(test)]
+mod test {
+    use tlsn_core::{
+        fixtures::{ConnectionFixture, encoding_provider},
+        hash::Blake3,
+        transcript::Transcript,
+    };
+    use tlsn_data_fixtures::http::{request::GET_WITH_HEADER, response::OK_JSON};
+
+    use super::*;
+    use crate::{
+        Version,
+        fixtures::{RequestFixture, attestation_fixture, request_fixture},
+        signing::KeyAlgId,
+    };
+
+    fn fixture() -> Attestation {
+        let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+        let connection = ConnectionFixture::tlsnotary(transcript.length());
+
+        let RequestFixture { request, .. } = request_fixture(
+            transcript,
+            encoding_provider(GET_WITH_HEADER, OK_JSON),
+            connection.clone(),
+            Blake3::default(),
+            Vec::new(),
+        );
+
+        attestation_fixture(request, connection, SignatureAlgId::SECP256K1, &[])
+    }
+
+    fn notary(key: u8) -> CryptoProvider {
+        let mut provider = CryptoProvider::default();
+        provider.signer.set_secp256r1(&[key; 32]).unwrap();
+        provider
+    }
+
+    fn verifying_key(provider: &CryptoProvider) -> VerifyingKey {
+        provider
+            .signer
+            .get(&SignatureAlgId::SECP256R1)
+            .unwrap()
+            .verifying_key()
+    }
+
+    #[test]
+    fn test_cosign() {
+        let attestation = fixture();
+        let provider = notary(1);
+
+        let cosignature = attestation
+            .cosign(&provider, &SignatureAlgId::SECP256R1)
+            .unwrap();
+
+        assert_eq!(cosignature.verifying_key, verifying_key(&provider));
+        assert!(cosignature.verify(&provider, &attestation.header).is_ok());
+    }
+
+    #[test]
+    fn test_cosign_tampered_body() {
+        let mut attestation = fixture();
+        attestation.body.connection_info.data.time += 1;
+
+        let err = attestation
+            .cosign(&notary(1), &SignatureAlgId::SECP256R1)
+            .unwrap_err();
+
+        assert!(matches!(err.kind, ErrorKind::Body));
+    }
+
+    #[test]
+    fn test_cosignature_wrong_header() {
+        let attestation = fixture();
+        let other = fixture();
+        let provider = notary(1);
+
+        let cosignature = attestation
+            .cosign(&provider, &SignatureAlgId::SECP256R1)
+            .unwrap();
+
+        assert!(cosignature.verify(&provider, &other.header).is_err());
+    }
+
+    /// BCS encoded header from before the encoding mode was introduced.
+    const HEADER_FIXTURE: [u8; 54] = [
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x02, 0x20, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab,
+        0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab,
+        0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab, 0xab,
+    ];
+
+    /// Secp256k1 key and signature of [`HEADER_FIXTURE`].
+    const KEY_FIXTURE: [u8; 33] = [
+        0x03, 0x1b, 0x84, 0xc5, 0x56, 0x7b, 0x12, 0x64, 0x40, 0x99, 0x5d, 0x3e, 0xd5, 0xaa, 0xba,
+        0x05, 0x65, 0xd7, 0x1e, 0x18, 0x34, 0x60, 0x48, 0x19, 0xff, 0x9c, 0x17, 0xf5, 0xe9, 0xd5,
+        0xdd, 0x07, 0x8f,
+    ];
+    const SIGNATURE_FIXTURE: [u8; 64] = [
+        0x57, 0x33, 0x92, 0x88, 0x3c, 0x3e, 0xd4, 0x71, 0x1e, 0x23, 0x18, 0x61, 0x79, 0x64, 0xe6,
+        0x0f, 0x6f, 0x12, 0x9b, 0xab, 0xfb, 0xf4, 0x63, 0xd8, 0x4e, 0xf5, 0x43, 0xc8, 0xf1, 0xe2,
+        0xd9, 0x2e, 0x5a, 0x42, 0xa2, 0xb8, 0xe6, 0x00, 0x7d, 0xfe, 0x26, 0x69, 0x6a, 0x11, 0x84,
+        0x77, 0x68, 0xa7, 0xc9, 0xeb, 0x9b, 0x42, 0x7d, 0x25, 0xc2, 0x2b, 0xb8, 0xaf, 0x7e, 0x48,
+        0xd1, 0xc0, 0xcb, 0x35,
+    ];
+
+    #[test]
+    fn test_cosignature_header_fixture() {
+        let mut header: Header = bcs::from_bytes(&HEADER_FIXTURE).unwrap();
+        assert_eq!(header.version, crate::VERSION);
+        assert_eq!(header.encoding(), Some(EncodingMode::Bcs));
+        assert_eq!(bcs::to_bytes(&header).unwrap(), HEADER_FIXTURE);
+
+        let cosignature = Cosignature {
+            verifying_key: VerifyingKey {
+                alg: KeyAlgId::K256,
+                data: KEY_FIXTURE.to_vec(),
+            },
+            signature: Signature {
+                alg: SignatureAlgId::SECP256K1,
+                data: SIGNATURE_FIXTURE.to_vec(),
+            },
+        };
+
+        let provider = CryptoProvider::default();
+        cosignature.verify(&provider, &header).unwrap();
+
+        // Headers of an unknown version are rejected.
+        header.version = Version(2);
+        assert!(cosignature.verify(&provider, &header).is_err());
+    }
+
+    #[test]
+    fn test_policy() {
+        let keys = (1..=3)
+            .map(|key| verifying_key(&notary(key)))
+            .collect::<Vec<_>>();
+        let untrusted = verifying_key(&notary(4));
+
+        assert!(QuorumPolicy::new(0, keys.clone()).is_err());
+        assert!(QuorumPolicy::new(4, keys.clone()).is_err());
+        // Duplicate keys are only counted once.
+        assert!(QuorumPolicy::new(2, [keys[0].clone(), keys[0].clone()]).is_err());
+
+        let policy = QuorumPolicy::new(2, keys.clone()).unwrap();
+
+        let signers = [keys[0].clone(), untrusted.clone(), keys[2].clone()];
+        assert_eq!(policy.check(&signers).unwrap(), vec![&keys[0], &keys[2]]);
+
+        let signers = [keys[1].clone(), untrusted];
+        assert!(policy.check(&signers).unwrap_err().is_quorum());
+    }
+}
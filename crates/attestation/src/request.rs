@@ -20,7 +20,9 @@ use serde::{Deserialize, Serialize};
 
 use tlsn_core::hash::HashAlgId;
 
-use crate::{Attestation, Extension, connection::ServerCertCommitment, signing::SignatureAlgId};
+use crate::{
+    Attestation, EncodingMode, Extension, connection::ServerCertCommitment, signing::SignatureAlgId,
+};
 
 pub use builder::{RequestBuilder, RequestBuilderError};
 pub use config::{RequestConfig, RequestConfigBuilder, RequestConfigBuilderError};
@@ -73,9 +75,31 @@ impl Request {
 
         Ok(())
     }
+
+    /// Validates the content of the attestation against this request and
+    /// checks that it was built with the given encoding mode.
+    ///
+    /// The encoding mode is not part of the request, the Prover and Notary
+    /// agree on it out of band.
+    pub fn validate_with_encoding(
+        &self,
+        attestation: &Attestation,
+        encoding: EncodingMode,
+    ) -> Result<(), InconsistentAttestation> {
+        self.validate(attestation)?;
+
+        if attestation.header.encoding() != Some(encoding) {
+            return Err(InconsistentAttestation(format!(
+                "encoding mode: expected {}, got version {:?}",
+                encoding, attestation.header.version
+            )));
+        }
+
+        Ok(())
+    }
 }
 
-/// Error for [`Request::validate`].
+/// Error for [`Request::validate`] and [`Request::validate_with_encoding`].
 #[derive(Debug, thiserror::Error)]
 #[error("inconsistent attestation: {0}")]
 pub struct InconsistentAttestation(String);
@@ -1,12 +1,13 @@
 use tlsn_core::{hash::HashAlgId, transcript::TranscriptCommitConfig};
 
-use crate::{Extension, signing::SignatureAlgId};
+use crate::{EncodingMode, Extension, evm, signing::SignatureAlgId};
 
 /// Request configuration.
 #[derive(Debug, Clone)]
 pub struct RequestConfig {
     signature_alg: SignatureAlgId,
     hash_alg: HashAlgId,
+    encoding: EncodingMode,
     extensions: Vec<Extension>,
     transcript_commit: Option<TranscriptCommitConfig>,
 }
@@ -33,6 +34,11 @@ impl RequestConfig {
         &self.hash_alg
     }
 
+    /// Returns the encoding mode.
+    pub fn encoding(&self) -> &EncodingMode {
+        &self.encoding
+    }
+
     /// Returns the extensions.
     pub fn extensions(&self) -> &[Extension] {
         &self.extensions
@@ -49,6 +55,7 @@ impl RequestConfig {
 pub struct RequestConfigBuilder {
     signature_alg: SignatureAlgId,
     hash_alg: HashAlgId,
+    encoding: EncodingMode,
     extensions: Vec<Extension>,
     transcript_commit: Option<TranscriptCommitConfig>,
 }
@@ -58,6 +65,7 @@ impl Default for RequestConfigBuilder {
         Self {
             signature_alg: SignatureAlgId::SECP256K1,
             hash_alg: HashAlgId::BLAKE3,
+            encoding: EncodingMode::default(),
             extensions: Vec::new(),
             transcript_commit: None,
         }
@@ -77,6 +85,22 @@ impl RequestConfigBuilder {
         self
     }
 
+    /// Sets the encoding mode.
+    ///
+    /// [`EncodingMode::Evm`] requires the
+    /// [`SECP256K1ETH`](SignatureAlgId::SECP256K1ETH) signature algorithm and
+    /// the [`KECCAK256`](HashAlgId::KECCAK256) hash algorithm.
+    ///
+    /// The encoding mode is not sent to the Notary, it must be agreed on out
+    /// of band, see
+    /// [`AttestationConfigBuilder::encoding`](crate::AttestationConfigBuilder::encoding).
+    /// The Prover checks the attestation with
+    /// [`Request::validate_with_encoding`](crate::request::Request::validate_with_encoding).
+    pub fn encoding(&mut self, encoding: EncodingMode) -> &mut Self {
+        self.encoding = encoding;
+        self
+    }
+
     /// Adds an extension to the request.
     pub fn extension(&mut self, extension: Extension) -> &mut Self {
         self.extensions.push(extension);
@@ -91,9 +115,15 @@ impl RequestConfigBuilder {
 
     /// Builds the config.
     pub fn build(self) -> Result<RequestConfig, RequestConfigBuilderError> {
+        if self.encoding == EncodingMode::Evm {
+            evm::check_algs(&self.signature_alg, &self.hash_alg)
+                .map_err(|message| RequestConfigBuilderError { message })?;
+        }
+
         Ok(RequestConfig {
             signature_alg: self.signature_alg,
             hash_alg: self.hash_alg,
+            encoding: self.encoding,
             extensions: self.extensions,
             transcript_commit: self.transcript_commit,
         })
@@ -1,3 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Canonical encoding of attestation data.
+///
+/// The encoding mode determines how attestation fields are encoded for hashing
+/// and how the attestation header is encoded for signing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EncodingMode {
+    /// BCS encoding.
+    #[default]
+    Bcs,
+    /// EVM compatible encoding.
+    ///
+    /// Fields are ABI encoded and the header is signed using an EIP-712 style
+    /// digest, so that attestations can be verified by a smart contract. This
+    /// mode requires the
+    /// [`SECP256K1ETH`](crate::signing::SignatureAlgId::SECP256K1ETH)
+    /// signature algorithm and the
+    /// [`KECCAK256`](tlsn_core::hash::HashAlgId::KECCAK256) hash algorithm.
+    /// See the [`evm`](crate::evm) module for more information.
+    Evm,
+}
+
+impl std::fmt::Display for EncodingMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodingMode::Bcs => write!(f, "bcs"),
+            EncodingMode::Evm => write!(f, "evm"),
+        }
+    }
+}
+
 /// Canonical serialization of TLSNotary types.
 ///
 /// This trait is used to serialize types into a canonical byte representation.
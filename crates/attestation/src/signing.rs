@@ -1,7 +1,10 @@
 //! Cryptographic signatures.
 
+pub mod remote;
+
 use std::collections::HashMap;
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::serialize::impl_domain_separator;
@@ -15,6 +18,8 @@ impl KeyAlgId {
     pub const K256: Self = Self(1);
     /// NIST P-256 elliptic curve key algorithm.
     pub const P256: Self = Self(2);
+    /// Edwards25519 key algorithm.
+    pub const ED25519: Self = Self(3);
 
     /// Creates a new key algorithm identifier.
     ///
@@ -42,6 +47,7 @@ impl std::fmt::Display for KeyAlgId {
         match *self {
             KeyAlgId::K256 => write!(f, "k256"),
             KeyAlgId::P256 => write!(f, "p256"),
+            KeyAlgId::ED25519 => write!(f, "ed25519"),
             _ => write!(f, "custom({:02x})", self.0),
         }
     }
@@ -61,6 +67,8 @@ impl SignatureAlgId {
     /// Uses secp256k1 with Keccak-256 hashing. The signature is a concatenation
     /// of `r || s || v` as defined in Solidity's ecrecover().
     pub const SECP256K1ETH: Self = Self(3);
+    /// Ed25519 signature algorithm as defined in RFC 8032.
+    pub const ED25519: Self = Self(4);
 
     /// Creates a new signature algorithm identifier.
     ///
@@ -89,6 +97,7 @@ impl std::fmt::Display for SignatureAlgId {
             SignatureAlgId::SECP256K1 => write!(f, "secp256k1"),
             SignatureAlgId::SECP256R1 => write!(f, "secp256r1"),
             SignatureAlgId::SECP256K1ETH => write!(f, "secp256k1eth"),
+            SignatureAlgId::ED25519 => write!(f, "ed25519"),
             _ => write!(f, "custom({:02x})", self.0),
         }
     }
@@ -103,19 +112,38 @@ pub struct UnknownSignatureAlgId(SignatureAlgId);
 #[derive(Default)]
 pub struct SignerProvider {
     signers: HashMap<SignatureAlgId, Box<dyn Signer + Send + Sync>>,
+    async_signers: HashMap<SignatureAlgId, Box<dyn AsyncSigner + Send + Sync>>,
 }
 
 impl SignerProvider {
     /// Returns the supported signature algorithms.
+    ///
+    /// Only includes algorithms of signers configured with
+    /// [`set_signer`](Self::set_signer), see
+    /// [`supported_async_algs`](Self::supported_async_algs) for asynchronous
+    /// signers.
     pub fn supported_algs(&self) -> impl Iterator<Item = SignatureAlgId> + '_ {
         self.signers.keys().copied()
     }
 
+    /// Returns the signature algorithms supported by asynchronous signers.
+    pub fn supported_async_algs(&self) -> impl Iterator<Item = SignatureAlgId> + '_ {
+        self.async_signers.keys().copied()
+    }
+
     /// Configures a signer.
     pub fn set_signer(&mut self, signer: Box<dyn Signer + Send + Sync>) {
         self.signers.insert(signer.alg_id(), signer);
     }
 
+    /// Configures an asynchronous signer.
+    ///
+    /// Asynchronous signers are only used by
+    /// [`AttestationBuilder::build_async`](crate::AttestationBuilder::build_async).
+    pub fn set_async_signer(&mut self, signer: Box<dyn AsyncSigner + Send + Sync>) {
+        self.async_signers.insert(signer.alg_id(), signer);
+    }
+
     /// Configures a secp256k1 signer with the provided signing key.
     pub fn set_secp256k1(&mut self, key: &[u8]) -> Result<&mut Self, SignerError> {
         self.set_signer(Box::new(Secp256k1Signer::new(key)?));
@@ -137,6 +165,13 @@ impl SignerProvider {
         Ok(self)
     }
 
+    /// Configures an ed25519 signer with the provided signing key.
+    pub fn set_ed25519(&mut self, key: &[u8]) -> Result<&mut Self, SignerError> {
+        self.set_signer(Box::new(Ed25519Signer::new(key)?));
+
+        Ok(self)
+    }
+
     /// Returns a signer for the given algorithm.
     pub(crate) fn get(
         &self,
@@ -147,6 +182,17 @@ impl SignerProvider {
             .map(|s| &**s)
             .ok_or(UnknownSignatureAlgId(*alg))
     }
+
+    /// Returns an asynchronous signer for the given algorithm.
+    pub(crate) fn get_async(
+        &self,
+        alg: &SignatureAlgId,
+    ) -> Result<&(dyn AsyncSigner + Send + Sync), UnknownSignatureAlgId> {
+        self.async_signers
+            .get(alg)
+            .map(|s| &**s)
+            .ok_or(UnknownSignatureAlgId(*alg))
+    }
 }
 
 /// Error for [`Signer`].
@@ -166,6 +212,23 @@ pub trait Signer {
     fn verifying_key(&self) -> VerifyingKey;
 }
 
+/// Asynchronous cryptographic signer.
+///
+/// This is intended for keys which are held by an external service, such as a
+/// KMS or HSM. See [`RemoteSigner`](remote::RemoteSigner) for a reference
+/// implementation.
+#[async_trait]
+pub trait AsyncSigner {
+    /// Returns the algorithm used by this signer.
+    fn alg_id(&self) -> SignatureAlgId;
+
+    /// Signs the message.
+    async fn sign(&self, msg: &[u8]) -> Result<Signature, SignatureError>;
+
+    /// Returns the verifying key for this signer.
+    fn verifying_key(&self) -> VerifyingKey;
+}
+
 /// Provider of signature verifiers.
 pub struct SignatureVerifierProvider {
     verifiers: HashMap<SignatureAlgId, Box<dyn SignatureVerifier + Send + Sync>>,
@@ -181,6 +244,7 @@ impl Default for SignatureVerifierProvider {
             SignatureAlgId::SECP256K1ETH,
             Box::new(Secp256k1EthVerifier) as _,
         );
+        verifiers.insert(SignatureAlgId::ED25519, Box::new(Ed25519Verifier) as _);
 
         Self { verifiers }
     }
@@ -503,6 +567,76 @@ mod secp256k1eth {
 
 pub use secp256k1eth::{Secp256k1EthSigner, Secp256k1EthVerifier};
 
+mod ed25519 {
+    use ed25519_dalek::{Signature as Ed25519Signature, Signer as _, SigningKey, Verifier};
+
+    use super::*;
+
+    /// ed25519 signer.
+    pub struct Ed25519Signer(SigningKey);
+
+    impl Ed25519Signer {
+        /// Creates a new ed25519 signer with the provided 32-byte secret key.
+        pub fn new(key: &[u8]) -> Result<Self, SignerError> {
+            SigningKey::try_from(key)
+                .map(Self)
+                .map_err(|_| SignerError("invalid key".to_string()))
+        }
+    }
+
+    impl Signer for Ed25519Signer {
+        fn alg_id(&self) -> SignatureAlgId {
+            SignatureAlgId::ED25519
+        }
+
+        fn sign(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
+            let sig: Ed25519Signature = self.0.sign(msg);
+
+            Ok(Signature {
+                alg: SignatureAlgId::ED25519,
+                data: sig.to_vec(),
+            })
+        }
+
+        fn verifying_key(&self) -> VerifyingKey {
+            let key = self.0.verifying_key().to_bytes();
+
+            VerifyingKey {
+                alg: KeyAlgId::ED25519,
+                data: key.to_vec(),
+            }
+        }
+    }
+
+    /// ed25519 verifier.
+    pub struct Ed25519Verifier;
+
+    impl SignatureVerifier for Ed25519Verifier {
+        fn alg_id(&self) -> SignatureAlgId {
+            SignatureAlgId::ED25519
+        }
+
+        fn verify(&self, key: &VerifyingKey, msg: &[u8], sig: &[u8]) -> Result<(), SignatureError> {
+            if key.alg != KeyAlgId::ED25519 {
+                return Err(SignatureError("key algorithm is not ed25519".to_string()));
+            }
+
+            let key = ed25519_dalek::VerifyingKey::try_from(key.data.as_slice())
+                .map_err(|_| SignatureError("invalid ed25519 key".to_string()))?;
+
+            let sig = Ed25519Signature::from_slice(sig)
+                .map_err(|_| SignatureError("invalid ed25519 signature".to_string()))?;
+
+            key.verify_strict(msg, &sig)
+                .map_err(|_| SignatureError("ed25519 signature verification failed".to_string()))?;
+
+            Ok(())
+        }
+    }
+}
+
+pub use ed25519::{Ed25519Signer, Ed25519Verifier};
+
 #[cfg(test)]
 mod test {
     use alloy_primitives::utils::eip191_message;
@@ -543,10 +677,21 @@ mod test {
         )
     }
 
+    #[fixture]
+    #[once]
+    fn ed25519_pair() -> (Box<dyn Signer>, Box<dyn SignatureVerifier>) {
+        let secret: [u8; 32] = rand::random();
+        (
+            Box::new(Ed25519Signer::new(&secret).unwrap()),
+            Box::new(Ed25519Verifier {}),
+        )
+    }
+
     #[rstest]
     #[case::r1(secp256r1_pair(), SignatureAlgId::SECP256R1)]
     #[case::k1(secp256k1_pair(), SignatureAlgId::SECP256K1)]
     #[case::k1eth(secp256k1eth_pair(), SignatureAlgId::SECP256K1ETH)]
+    #[case::ed25519(ed25519_pair(), SignatureAlgId::ED25519)]
     fn test_success(
         #[case] pair: (Box<dyn Signer>, Box<dyn SignatureVerifier>),
         #[case] alg: SignatureAlgId,
@@ -566,6 +711,7 @@ mod test {
     #[rstest]
     #[case::r1(secp256r1_pair())]
     #[case::k1eth(secp256k1eth_pair())]
+    #[case::ed25519(ed25519_pair())]
     fn test_wrong_signer(#[case] pair: (Box<dyn Signer>, Box<dyn SignatureVerifier>)) {
         let (signer, _) = pair;
 
@@ -582,9 +728,11 @@ mod test {
     #[case::corrupted_signature_r1(secp256r1_pair(), true, false)]
     #[case::corrupted_signature_k1(secp256k1_pair(), true, false)]
     #[case::corrupted_signature_k1eth(secp256k1eth_pair(), true, false)]
+    #[case::corrupted_signature_ed25519(ed25519_pair(), true, false)]
     #[case::wrong_signature_r1(secp256r1_pair(), false, true)]
     #[case::wrong_signature_k1(secp256k1_pair(), false, true)]
     #[case::wrong_signature_k1eth(secp256k1eth_pair(), false, true)]
+    #[case::wrong_signature_ed25519(ed25519_pair(), false, true)]
     fn test_failure(
         #[case] pair: (Box<dyn Signer>, Box<dyn SignatureVerifier>),
         #[case] corrupted_signature: bool,
@@ -0,0 +1,250 @@
+//! Remote signing.
+//!
+//! [`RemoteSigner`] forwards signing requests to a signing service over a byte
+//! stream, eg. a local socket to a process which holds the Notary key in a KMS
+//! or HSM. [`serve`] implements the service side of the protocol for any
+//! [`Signer`], which can be used as a local stand-in for testing.
+//!
+//! # Protocol
+//!
+//! Every message is BCS serialized and prefixed with its length encoded as a
+//! 4 byte big-endian integer. The client sends a [`SignerRequest`] and waits
+//! for the corresponding [`SignerResponse`] before sending the next request.
+
+use async_trait::async_trait;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, lock::Mutex};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::signing::{
+    AsyncSigner, Signature, SignatureAlgId, SignatureError, Signer, SignerError, VerifyingKey,
+};
+
+/// Maximum length of a message in bytes.
+const MAX_MESSAGE_LEN: usize = 1 << 16;
+
+/// Request sent to a remote signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SignerRequest {
+    /// Request for the signature algorithm and verifying key.
+    Info,
+    /// Request to sign a message.
+    Sign(Vec<u8>),
+}
+
+/// Response of a remote signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SignerResponse {
+    /// Signature algorithm and verifying key of the signer.
+    Info {
+        /// Signature algorithm.
+        alg: SignatureAlgId,
+        /// Verifying key.
+        key: VerifyingKey,
+    },
+    /// Signature of a message.
+    Signature(Signature),
+    /// The request failed.
+    Error(String),
+}
+
+/// Signer which forwards signing requests to a remote signing service.
+pub struct RemoteSigner<Io> {
+    alg: SignatureAlgId,
+    key: VerifyingKey,
+    io: Mutex<Io>,
+}
+
+opaque_debug::implement!(RemoteSigner<Io>);
+
+impl<Io> RemoteSigner<Io>
+where
+    Io: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Connects to a remote signer, retrieving its signature algorithm and
+    /// verifying key.
+    ///
+    /// # Arguments
+    ///
+    /// * `io` - Connection to the signing service.
+    pub async fn connect(mut io: Io) -> Result<Self, SignerError> {
+        write_message(&mut io, &SignerRequest::Info)
+            .await
+            .map_err(|e| SignerError(format!("failed to send request: {e}")))?;
+
+        let response = read_message(&mut io)
+            .await
+            .map_err(|e| SignerError(format!("failed to receive response: {e}")))?;
+
+        match response {
+            Some(SignerResponse::Info { alg, key }) => Ok(Self {
+                alg,
+                key,
+                io: Mutex::new(io),
+            }),
+            Some(SignerResponse::Error(e)) => Err(SignerError(format!("remote signer error: {e}"))),
+            Some(_) => Err(SignerError("unexpected response".to_string())),
+            None => Err(SignerError("connection closed".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl<Io> AsyncSigner for RemoteSigner<Io>
+where
+    Io: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    fn alg_id(&self) -> SignatureAlgId {
+        self.alg
+    }
+
+    async fn sign(&self, msg: &[u8]) -> Result<Signature, SignatureError> {
+        let mut io = self.io.lock().await;
+
+        write_message(&mut *io, &SignerRequest::Sign(msg.to_vec()))
+            .await
+            .map_err(|e| SignatureError(format!("failed to send request: {e}")))?;
+
+        let response = read_message(&mut *io)
+            .await
+            .map_err(|e| SignatureError(format!("failed to receive response: {e}")))?;
+
+        match response {
+            Some(SignerResponse::Signature(sig)) if sig.alg == self.alg => Ok(sig),
+            Some(SignerResponse::Signature(sig)) => Err(SignatureError(format!(
+                "expected {} signature, got {}",
+                self.alg, sig.alg
+            ))),
+            Some(SignerResponse::Error(e)) => {
+                Err(SignatureError(format!("remote signer error: {e}")))
+            }
+            Some(_) => Err(SignatureError("unexpected response".to_string())),
+            None => Err(SignatureError("connection closed".to_string())),
+        }
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        self.key.clone()
+    }
+}
+
+/// Serves signing requests from a [`RemoteSigner`] until the connection is
+/// closed.
+///
+/// # Arguments
+///
+/// * `signer` - Signer used to sign messages.
+/// * `io` - Connection to the remote signer client.
+pub async fn serve<Io>(signer: &(dyn Signer + Send + Sync), mut io: Io) -> Result<(), SignerError>
+where
+    Io: AsyncRead + AsyncWrite + Unpin,
+{
+    while let Some(request) = read_message(&mut io)
+        .await
+        .map_err(|e| SignerError(format!("failed to receive request: {e}")))?
+    {
+        let response = match request {
+            SignerRequest::Info => SignerResponse::Info {
+                alg: signer.alg_id(),
+                key: signer.verifying_key(),
+            },
+            SignerRequest::Sign(msg) => match signer.sign(&msg) {
+                Ok(sig) => SignerResponse::Signature(sig),
+                Err(e) => SignerResponse::Error(e.to_string()),
+            },
+        };
+
+        write_message(&mut io, &response)
+            .await
+            .map_err(|e| SignerError(format!("failed to send response: {e}")))?;
+    }
+
+    Ok(())
+}
+
+async fn write_message<Io, T>(io: &mut Io, msg: &T) -> std::io::Result<()>
+where
+    Io: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let bytes =
+        bcs::to_bytes(msg).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+    if bytes.len() > MAX_MESSAGE_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "message too long",
+        ));
+    }
+
+    io.write_all(&(bytes.len() as u32).to_be_bytes()).await?;
+    io.write_all(&bytes).await?;
+    io.flush().await
+}
+
+/// Reads a message, returning `None` if the connection was closed.
+async fn read_message<Io, T>(io: &mut Io) -> std::io::Result<Option<T>>
+where
+    Io: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut len = [0u8; 4];
+    match io.read_exact(&mut len).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "message too long",
+        ));
+    }
+
+    let mut bytes = vec![0u8; len];
+    io.read_exact(&mut bytes).await?;
+
+    bcs::from_bytes(&bytes)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    use super::*;
+    use crate::signing::{Secp256k1Signer, Secp256k1Verifier, SignatureVerifier};
+
+    #[tokio::test]
+    async fn test_remote_signer() {
+        let signer = Secp256k1Signer::new(&[42u8; 32]).unwrap();
+        let expected_key = signer.verifying_key();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            serve(&signer, socket.compat()).await
+        });
+
+        let socket = TcpStream::connect(addr).await.unwrap();
+        let remote = RemoteSigner::connect(socket.compat()).await.unwrap();
+
+        assert_eq!(remote.alg_id(), SignatureAlgId::SECP256K1);
+        assert_eq!(remote.verifying_key(), expected_key);
+
+        for msg in [b"foo".as_slice(), b"bar".as_slice()] {
+            let sig = remote.sign(msg).await.unwrap();
+            Secp256k1Verifier
+                .verify(&expected_key, msg, &sig.data)
+                .unwrap();
+        }
+
+        drop(remote);
+        server.await.unwrap().unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use tlsn_attestation::{
     Attestation, AttestationConfig, CryptoProvider,
     presentation::PresentationOutput,
+    quorum::QuorumPolicy,
     request::{Request, RequestConfig},
     signing::SignatureAlgId,
 };
@@ -143,3 +144,78 @@ fn test_api() {
         secrets.transcript().received()
     );
 }
+
+/// Tests that an attestation co-signed by several Notaries can be verified
+/// against a quorum policy.
+#[test]
+fn test_api_quorum() {
+    let mut provider = CryptoProvider::default();
+    provider.signer.set_secp256k1(&[42u8; 32]).unwrap();
+
+    let transcript = Transcript::new(GET_WITH_HEADER, OK_JSON);
+    let ConnectionFixture {
+        server_name,
+        connection_info,
+        server_cert_data,
+    } = ConnectionFixture::tlsnotary(transcript.length());
+
+    let CertBinding::V1_2(CertBindingV1_2 {
+        server_ephemeral_key,
+        ..
+    }) = server_cert_data.binding.clone()
+    else {
+        unreachable!()
+    };
+
+    let request_config = RequestConfig::default();
+    let mut request_builder = Request::builder(&request_config);
+    request_builder
+        .server_name(server_name.clone())
+        .handshake_data(server_cert_data)
+        .transcript(transcript);
+
+    let (request, secrets) = request_builder.build(&provider).unwrap();
+
+    let attestation_config = AttestationConfig::builder()
+        .supported_signature_algs([SignatureAlgId::SECP256K1])
+        .build()
+        .unwrap();
+
+    let mut attestation_builder = Attestation::builder(&attestation_config)
+        .accept_request(request)
+        .unwrap();
+    attestation_builder
+        .connection_info(connection_info)
+        .server_ephemeral_key(server_ephemeral_key);
+
+    let attestation = attestation_builder.build(&provider).unwrap();
+
+    // A second Notary co-signs the attestation.
+    let mut cosigner_provider = CryptoProvider::default();
+    cosigner_provider.signer.set_secp256r1(&[7u8; 32]).unwrap();
+    let cosignature = attestation
+        .cosign(&cosigner_provider, &SignatureAlgId::SECP256R1)
+        .unwrap();
+    let cosigner_key = cosignature.verifying_key.clone();
+
+    let mut builder = attestation.presentation_builder(&provider);
+    builder.identity_proof(secrets.identity_proof());
+    let presentation = builder.build().unwrap();
+    let notary_key = presentation.verifying_key().clone();
+
+    // Verifier requires both Notaries to have signed.
+    let policy = QuorumPolicy::new(2, [notary_key.clone(), cosigner_key.clone()]).unwrap();
+
+    let err = presentation
+        .clone()
+        .verify_quorum(&provider, &[], &policy)
+        .unwrap_err();
+    assert!(err.to_string().contains("quorum error"));
+
+    let output = presentation
+        .verify_quorum(&provider, &[cosignature], &policy)
+        .unwrap();
+
+    assert_eq!(output.signers, vec![notary_key, cosigner_key]);
+    assert_eq!(output.server_name.unwrap(), server_name);
+}
@@ -0,0 +1,287 @@
+//! AES-256 circuit.
+
+use std::sync::{Arc, LazyLock};
+
+use mpz_circuits::{Circuit, CircuitBuilder, Feed, Node};
+
+/// AES-256 block cipher circuit.
+///
+/// # Inputs
+///
+/// 0. KEY: 32 bytes
+/// 1. MSG: 16 bytes
+///
+/// # Outputs
+///
+/// 0. CIPHERTEXT: 16 bytes
+pub static AES256: LazyLock<Arc<Circuit>> = LazyLock::new(|| Arc::new(build_aes256()));
+
+/// Number of rounds of AES-256.
+const ROUNDS: usize = 14;
+
+/// A byte of wires in LSB0 bit order.
+type Byte = [Node<Feed>; 8];
+
+fn build_aes256() -> Circuit {
+    let mut builder = CircuitBuilder::new();
+
+    let key: Vec<Byte> = (0..32)
+        .map(|_| std::array::from_fn(|_| builder.add_input()))
+        .collect();
+    let msg: [Byte; 16] = std::array::from_fn(|_| std::array::from_fn(|_| builder.add_input()));
+
+    let round_keys = expand_key(&mut builder, &key);
+
+    let mut state = add_round_key(&mut builder, msg, &round_keys[0]);
+    for round_key in &round_keys[1..ROUNDS] {
+        state = state.map(|byte| sbox(&mut builder, byte));
+        state = shift_rows(state);
+        state = mix_columns(&mut builder, state);
+        state = add_round_key(&mut builder, state, round_key);
+    }
+    state = state.map(|byte| sbox(&mut builder, byte));
+    state = shift_rows(state);
+    state = add_round_key(&mut builder, state, &round_keys[ROUNDS]);
+
+    for byte in state {
+        for bit in byte {
+            builder.add_output(bit);
+        }
+    }
+
+    builder.build().expect("AES-256 circuit is valid")
+}
+
+/// Expands the 32 byte key into the round keys.
+fn expand_key(builder: &mut CircuitBuilder, key: &[Byte]) -> Vec<[Byte; 16]> {
+    let mut words: Vec<[Byte; 4]> = key
+        .chunks_exact(4)
+        .map(|word| [word[0], word[1], word[2], word[3]])
+        .collect();
+
+    let mut rcon = 1u8;
+    for i in 8..4 * (ROUNDS + 1) {
+        let mut temp = words[i - 1];
+        if i % 8 == 0 {
+            temp.rotate_left(1);
+            temp = temp.map(|byte| sbox(builder, byte));
+            temp[0] = xor_const(builder, temp[0], rcon);
+            rcon <<= 1;
+        } else if i % 8 == 4 {
+            temp = temp.map(|byte| sbox(builder, byte));
+        }
+
+        let prev = words[i - 8];
+        words.push(std::array::from_fn(|j| xor(builder, prev[j], temp[j])));
+    }
+
+    words
+        .chunks_exact(4)
+        .map(|words| std::array::from_fn(|i| words[i / 4][i % 4]))
+        .collect()
+}
+
+fn add_round_key(
+    builder: &mut CircuitBuilder,
+    state: [Byte; 16],
+    round_key: &[Byte; 16],
+) -> [Byte; 16] {
+    std::array::from_fn(|i| xor(builder, state[i], round_key[i]))
+}
+
+/// The state is stored in column-major order.
+fn shift_rows(state: [Byte; 16]) -> [Byte; 16] {
+    std::array::from_fn(|i| {
+        let (row, col) = (i % 4, i / 4);
+        state[row + 4 * ((col + row) % 4)]
+    })
+}
+
+fn mix_columns(builder: &mut CircuitBuilder, state: [Byte; 16]) -> [Byte; 16] {
+    let mut output = state;
+    for (col, out) in state.chunks_exact(4).zip(output.chunks_exact_mut(4)) {
+        let t = xor(builder, col[0], col[1]);
+        let t = xor(builder, t, col[2]);
+        let t = xor(builder, t, col[3]);
+
+        for i in 0..4 {
+            let a = xor(builder, col[i], col[(i + 1) % 4]);
+            let a = xtime(builder, a);
+            let b = xor(builder, col[i], t);
+            out[i] = xor(builder, a, b);
+        }
+    }
+
+    output
+}
+
+/// Multiplication by `x` in GF(2^8).
+fn xtime(builder: &mut CircuitBuilder, byte: Byte) -> Byte {
+    let [b0, b1, b2, b3, b4, b5, b6, b7] = byte;
+    [
+        b7,
+        builder.add_xor_gate(b0, b7),
+        b1,
+        builder.add_xor_gate(b2, b7),
+        builder.add_xor_gate(b3, b7),
+        b4,
+        b5,
+        b6,
+    ]
+}
+
+fn xor(builder: &mut CircuitBuilder, a: Byte, b: Byte) -> Byte {
+    std::array::from_fn(|i| builder.add_xor_gate(a[i], b[i]))
+}
+
+fn xor_const(builder: &mut CircuitBuilder, byte: Byte, value: u8) -> Byte {
+    std::array::from_fn(|i| {
+        if (value >> i) & 1 == 1 {
+            builder.add_inv_gate(byte[i])
+        } else {
+            byte[i]
+        }
+    })
+}
+
+/// The AES S-box circuit of Boyar and Peralta, see
+/// <https://eprint.iacr.org/2011/332>.
+fn sbox(builder: &mut CircuitBuilder, byte: Byte) -> Byte {
+    // The circuit expects the input in MSB0 bit order.
+    let [u7, u6, u5, u4, u3, u2, u1, u0] = byte;
+
+    let t1 = builder.add_xor_gate(u0, u3);
+    let t2 = builder.add_xor_gate(u0, u5);
+    let t3 = builder.add_xor_gate(u0, u6);
+    let t4 = builder.add_xor_gate(u3, u5);
+    let t5 = builder.add_xor_gate(u4, u6);
+    let t6 = builder.add_xor_gate(t1, t5);
+    let t7 = builder.add_xor_gate(u1, u2);
+    let t8 = builder.add_xor_gate(u7, t6);
+    let t9 = builder.add_xor_gate(u7, t7);
+    let t10 = builder.add_xor_gate(t6, t7);
+    let t11 = builder.add_xor_gate(u1, u5);
+    let t12 = builder.add_xor_gate(u2, u5);
+    let t13 = builder.add_xor_gate(t3, t4);
+    let t14 = builder.add_xor_gate(t6, t11);
+    let t15 = builder.add_xor_gate(t5, t11);
+    let t16 = builder.add_xor_gate(t5, t12);
+    let t17 = builder.add_xor_gate(t9, t16);
+    let t18 = builder.add_xor_gate(u3, u7);
+    let t19 = builder.add_xor_gate(t7, t18);
+    let t20 = builder.add_xor_gate(t1, t19);
+    let t21 = builder.add_xor_gate(u6, u7);
+    let t22 = builder.add_xor_gate(t7, t21);
+    let t23 = builder.add_xor_gate(t2, t22);
+    let t24 = builder.add_xor_gate(t2, t10);
+    let t25 = builder.add_xor_gate(t20, t17);
+    let t26 = builder.add_xor_gate(t3, t16);
+    let t27 = builder.add_xor_gate(t1, t12);
+    let m1 = builder.add_and_gate(t13, t6);
+    let m2 = builder.add_and_gate(t23, t8);
+    let m3 = builder.add_xor_gate(t14, m1);
+    let m4 = builder.add_and_gate(t19, u7);
+    let m5 = builder.add_xor_gate(m4, m1);
+    let m6 = builder.add_and_gate(t3, t16);
+    let m7 = builder.add_and_gate(t22, t9);
+    let m8 = builder.add_xor_gate(t26, m6);
+    let m9 = builder.add_and_gate(t20, t17);
+    let m10 = builder.add_xor_gate(m9, m6);
+    let m11 = builder.add_and_gate(t1, t15);
+    let m12 = builder.add_and_gate(t4, t27);
+    let m13 = builder.add_xor_gate(m12, m11);
+    let m14 = builder.add_and_gate(t2, t10);
+    let m15 = builder.add_xor_gate(m14, m11);
+    let m16 = builder.add_xor_gate(m3, m2);
+    let m17 = builder.add_xor_gate(m5, t24);
+    let m18 = builder.add_xor_gate(m8, m7);
+    let m19 = builder.add_xor_gate(m10, m15);
+    let m20 = builder.add_xor_gate(m16, m13);
+    let m21 = builder.add_xor_gate(m17, m15);
+    let m22 = builder.add_xor_gate(m18, m13);
+    let m23 = builder.add_xor_gate(m19, t25);
+    let m24 = builder.add_xor_gate(m22, m23);
+    let m25 = builder.add_and_gate(m22, m20);
+    let m26 = builder.add_xor_gate(m21, m25);
+    let m27 = builder.add_xor_gate(m20, m21);
+    let m28 = builder.add_xor_gate(m23, m25);
+    let m29 = builder.add_and_gate(m28, m27);
+    let m30 = builder.add_and_gate(m26, m24);
+    let m31 = builder.add_and_gate(m20, m23);
+    let m32 = builder.add_and_gate(m27, m31);
+    let m33 = builder.add_xor_gate(m27, m25);
+    let m34 = builder.add_and_gate(m21, m22);
+    let m35 = builder.add_and_gate(m24, m34);
+    let m36 = builder.add_xor_gate(m24, m25);
+    let m37 = builder.add_xor_gate(m21, m29);
+    let m38 = builder.add_xor_gate(m32, m33);
+    let m39 = builder.add_xor_gate(m23, m30);
+    let m40 = builder.add_xor_gate(m35, m36);
+    let m41 = builder.add_xor_gate(m38, m40);
+    let m42 = builder.add_xor_gate(m37, m39);
+    let m43 = builder.add_xor_gate(m37, m38);
+    let m44 = builder.add_xor_gate(m39, m40);
+    let m45 = builder.add_xor_gate(m42, m41);
+    let m46 = builder.add_and_gate(m44, t6);
+    let m47 = builder.add_and_gate(m40, t8);
+    let m48 = builder.add_and_gate(m39, u7);
+    let m49 = builder.add_and_gate(m43, t16);
+    let m50 = builder.add_and_gate(m38, t9);
+    let m51 = builder.add_and_gate(m37, t17);
+    let m52 = builder.add_and_gate(m42, t15);
+    let m53 = builder.add_and_gate(m45, t27);
+    let m54 = builder.add_and_gate(m41, t10);
+    let m55 = builder.add_and_gate(m44, t13);
+    let m56 = builder.add_and_gate(m40, t23);
+    let m57 = builder.add_and_gate(m39, t19);
+    let m58 = builder.add_and_gate(m43, t3);
+    let m59 = builder.add_and_gate(m38, t22);
+    let m60 = builder.add_and_gate(m37, t20);
+    let m61 = builder.add_and_gate(m42, t1);
+    let m62 = builder.add_and_gate(m45, t4);
+    let m63 = builder.add_and_gate(m41, t2);
+    let l0 = builder.add_xor_gate(m61, m62);
+    let l1 = builder.add_xor_gate(m50, m56);
+    let l2 = builder.add_xor_gate(m46, m48);
+    let l3 = builder.add_xor_gate(m47, m55);
+    let l4 = builder.add_xor_gate(m54, m58);
+    let l5 = builder.add_xor_gate(m49, m61);
+    let l6 = builder.add_xor_gate(m62, l5);
+    let l7 = builder.add_xor_gate(m46, l3);
+    let l8 = builder.add_xor_gate(m51, m59);
+    let l9 = builder.add_xor_gate(m52, m53);
+    let l10 = builder.add_xor_gate(m53, l4);
+    let l11 = builder.add_xor_gate(m60, l2);
+    let l12 = builder.add_xor_gate(m48, m51);
+    let l13 = builder.add_xor_gate(m50, l0);
+    let l14 = builder.add_xor_gate(m52, m61);
+    let l15 = builder.add_xor_gate(m55, l1);
+    let l16 = builder.add_xor_gate(m56, l0);
+    let l17 = builder.add_xor_gate(m57, l1);
+    let l18 = builder.add_xor_gate(m58, l8);
+    let l19 = builder.add_xor_gate(m63, l4);
+    let l20 = builder.add_xor_gate(l0, l1);
+    let l21 = builder.add_xor_gate(l1, l7);
+    let l22 = builder.add_xor_gate(l3, l12);
+    let l23 = builder.add_xor_gate(l18, l2);
+    let l24 = builder.add_xor_gate(l15, l9);
+    let l25 = builder.add_xor_gate(l6, l10);
+    let l26 = builder.add_xor_gate(l7, l9);
+    let l27 = builder.add_xor_gate(l8, l10);
+    let l28 = builder.add_xor_gate(l11, l14);
+    let l29 = builder.add_xor_gate(l11, l17);
+    let s0 = builder.add_xor_gate(l6, l24);
+    let s1 = builder.add_xor_gate(l16, l26);
+    let s1 = builder.add_inv_gate(s1);
+    let s2 = builder.add_xor_gate(l19, l28);
+    let s2 = builder.add_inv_gate(s2);
+    let s3 = builder.add_xor_gate(l6, l21);
+    let s4 = builder.add_xor_gate(l20, l22);
+    let s5 = builder.add_xor_gate(l25, l29);
+    let s6 = builder.add_xor_gate(l13, l27);
+    let s6 = builder.add_inv_gate(s6);
+    let s7 = builder.add_xor_gate(l6, l23);
+    let s7 = builder.add_inv_gate(s7);
+
+    [s7, s6, s5, s4, s3, s2, s1, s0]
+}
@@ -1,14 +1,19 @@
-//! The AES-128 block cipher.
+//! The AES-128 and AES-256 block ciphers.
 
 use crate::{Cipher, CtrBlock, Keystream};
 use async_trait::async_trait;
-use mpz_circuits::circuits::AES128;
-use mpz_memory_core::binary::{Binary, U8};
+use mpz_circuits::{circuits::AES128, Circuit};
+use mpz_memory_core::{
+    binary::{Binary, U8},
+    Slice, ToRaw, Vector,
+};
 use mpz_vm_core::{prelude::*, Call, Vm};
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
+mod circuit;
 mod error;
 
+pub use circuit::AES256;
 pub use error::AesError;
 use error::ErrorKind;
 
@@ -53,17 +58,81 @@ impl Cipher for Aes128 {
             .key
             .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
 
-        let output = vm
-            .call(
-                Call::builder(AES128.clone())
-                    .arg(key)
-                    .arg(input)
-                    .build()
-                    .expect("call should be valid"),
-            )
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+        alloc_block(vm, AES128.clone(), key.to_raw(), input)
+    }
+
+    fn alloc_ctr_block(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+    ) -> Result<CtrBlock<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
+
+        alloc_ctr_block(vm, AES128.clone(), key.to_raw(), iv)
+    }
+
+    fn alloc_keystream(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        len: usize,
+    ) -> Result<Keystream<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
+
+        alloc_keystream(vm, AES128.clone(), key.to_raw(), iv, len)
+    }
+}
+
+/// Computes AES-256.
+#[derive(Default, Debug)]
+pub struct Aes256 {
+    key: Option<Array<U8, 32>>,
+    iv: Option<Array<U8, 4>>,
+}
+
+#[async_trait]
+impl Cipher for Aes256 {
+    type Error = AesError;
+    type Key = Array<U8, 32>;
+    type Iv = Array<U8, 4>;
+    type Nonce = Array<U8, 8>;
+    type Counter = Array<U8, 4>;
+    type Block = Array<U8, 16>;
+
+    fn set_key(&mut self, key: Array<U8, 32>) {
+        self.key = Some(key);
+    }
+
+    fn set_iv(&mut self, iv: Array<U8, 4>) {
+        self.iv = Some(iv);
+    }
+
+    fn key(&self) -> Option<&Array<U8, 32>> {
+        self.key.as_ref()
+    }
 
-        Ok(output)
+    fn iv(&self) -> Option<&Array<U8, 4>> {
+        self.iv.as_ref()
+    }
+
+    fn alloc_block(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        input: Array<U8, 16>,
+    ) -> Result<Self::Block, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+
+        alloc_block(vm, AES256.clone(), key.to_raw(), input)
     }
 
     fn alloc_ctr_block(
@@ -77,35 +146,7 @@ impl Cipher for Aes128 {
             .iv
             .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
 
-        let explicit_nonce: Array<U8, 8> = vm
-            .alloc()
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-        vm.mark_public(explicit_nonce)
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-
-        let counter: Array<U8, 4> = vm
-            .alloc()
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-        vm.mark_public(counter)
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-
-        let output = vm
-            .call(
-                Call::builder(AES128.clone())
-                    .arg(key)
-                    .arg(iv)
-                    .arg(explicit_nonce)
-                    .arg(counter)
-                    .build()
-                    .expect("call should be valid"),
-            )
-            .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-
-        Ok(CtrBlock {
-            explicit_nonce,
-            counter,
-            output,
-        })
+        alloc_ctr_block(vm, AES256.clone(), key.to_raw(), iv)
     }
 
     fn alloc_keystream(
@@ -120,53 +161,247 @@ impl Cipher for Aes128 {
             .iv
             .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
 
-        let block_count = len.div_ceil(16);
+        alloc_keystream(vm, AES256.clone(), key.to_raw(), iv, len)
+    }
+}
 
-        let inputs = (0..block_count)
-            .map(|_| {
-                let explicit_nonce: Array<U8, 8> = vm
-                    .alloc()
-                    .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-                let counter: Array<U8, 4> = vm
-                    .alloc()
-                    .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+/// An AES key of either size.
+#[derive(Debug, Clone, Copy)]
+pub enum AesKey {
+    /// AES-128 key.
+    Aes128(Array<U8, 16>),
+    /// AES-256 key.
+    Aes256(Array<U8, 32>),
+}
 
-                vm.mark_public(explicit_nonce)
-                    .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-                vm.mark_public(counter)
-                    .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+impl AesKey {
+    fn circuit(&self) -> Arc<Circuit> {
+        match self {
+            AesKey::Aes128(_) => AES128.clone(),
+            AesKey::Aes256(_) => AES256.clone(),
+        }
+    }
 
-                Ok((explicit_nonce, counter))
-            })
-            .collect::<Result<Vec<_>, AesError>>()?;
-
-        let blocks = inputs
-            .into_iter()
-            .map(|(explicit_nonce, counter)| {
-                let output = vm
-                    .call(
-                        Call::builder(AES128.clone())
-                            .arg(key)
-                            .arg(iv)
-                            .arg(explicit_nonce)
-                            .arg(counter)
-                            .build()
-                            .expect("call should be valid"),
-                    )
-                    .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
-
-                Ok(CtrBlock {
-                    explicit_nonce,
-                    counter,
-                    output,
-                })
-            })
-            .collect::<Result<Vec<_>, AesError>>()?;
+    fn to_raw(self) -> Slice {
+        match self {
+            AesKey::Aes128(key) => key.to_raw(),
+            AesKey::Aes256(key) => key.to_raw(),
+        }
+    }
+}
+
+impl TryFrom<Vector<U8>> for AesKey {
+    type Error = AesError;
 
-        Ok(Keystream::new(&blocks))
+    fn try_from(key: Vector<U8>) -> Result<Self, Self::Error> {
+        match key.len() {
+            16 => Ok(AesKey::Aes128(
+                Array::try_from(key).expect("key length was checked"),
+            )),
+            32 => Ok(AesKey::Aes256(
+                Array::try_from(key).expect("key length was checked"),
+            )),
+            len => Err(AesError::new(
+                ErrorKind::Key,
+                format!("invalid key length: {len}"),
+            )),
+        }
     }
 }
 
+impl From<AesKey> for Vector<U8> {
+    fn from(key: AesKey) -> Self {
+        match key {
+            AesKey::Aes128(key) => key.into(),
+            AesKey::Aes256(key) => key.into(),
+        }
+    }
+}
+
+/// Computes AES with a key size which is only known at runtime, e.g. the one
+/// of a negotiated TLS cipher suite.
+#[derive(Default, Debug)]
+pub struct Aes {
+    key: Option<AesKey>,
+    iv: Option<Array<U8, 4>>,
+}
+
+#[async_trait]
+impl Cipher for Aes {
+    type Error = AesError;
+    type Key = AesKey;
+    type Iv = Array<U8, 4>;
+    type Nonce = Array<U8, 8>;
+    type Counter = Array<U8, 4>;
+    type Block = Array<U8, 16>;
+
+    fn set_key(&mut self, key: AesKey) {
+        self.key = Some(key);
+    }
+
+    fn set_iv(&mut self, iv: Array<U8, 4>) {
+        self.iv = Some(iv);
+    }
+
+    fn key(&self) -> Option<&AesKey> {
+        self.key.as_ref()
+    }
+
+    fn iv(&self) -> Option<&Array<U8, 4>> {
+        self.iv.as_ref()
+    }
+
+    fn alloc_block(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        input: Array<U8, 16>,
+    ) -> Result<Self::Block, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+
+        alloc_block(vm, key.circuit(), key.to_raw(), input)
+    }
+
+    fn alloc_ctr_block(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+    ) -> Result<CtrBlock<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
+
+        alloc_ctr_block(vm, key.circuit(), key.to_raw(), iv)
+    }
+
+    fn alloc_keystream(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        len: usize,
+    ) -> Result<Keystream<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| AesError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| AesError::new(ErrorKind::Iv, "iv not set"))?;
+
+        alloc_keystream(vm, key.circuit(), key.to_raw(), iv, len)
+    }
+}
+
+fn alloc_block(
+    vm: &mut dyn Vm<Binary>,
+    circuit: Arc<Circuit>,
+    key: Slice,
+    input: Array<U8, 16>,
+) -> Result<Array<U8, 16>, AesError> {
+    let output = vm
+        .call(
+            Call::builder(circuit)
+                .arg(key)
+                .arg(input)
+                .build()
+                .expect("call should be valid"),
+        )
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+    Ok(output)
+}
+
+fn alloc_ctr_block(
+    vm: &mut dyn Vm<Binary>,
+    circuit: Arc<Circuit>,
+    key: Slice,
+    iv: Array<U8, 4>,
+) -> Result<CtrBlock<Array<U8, 8>, Array<U8, 4>, Array<U8, 16>>, AesError> {
+    let explicit_nonce: Array<U8, 8> = vm
+        .alloc()
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+    vm.mark_public(explicit_nonce)
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+    let counter: Array<U8, 4> = vm
+        .alloc()
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+    vm.mark_public(counter)
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+    let output = vm
+        .call(
+            Call::builder(circuit)
+                .arg(key)
+                .arg(iv)
+                .arg(explicit_nonce)
+                .arg(counter)
+                .build()
+                .expect("call should be valid"),
+        )
+        .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+    Ok(CtrBlock {
+        explicit_nonce,
+        counter,
+        output,
+    })
+}
+
+fn alloc_keystream(
+    vm: &mut dyn Vm<Binary>,
+    circuit: Arc<Circuit>,
+    key: Slice,
+    iv: Array<U8, 4>,
+    len: usize,
+) -> Result<Keystream<Array<U8, 8>, Array<U8, 4>, Array<U8, 16>>, AesError> {
+    let block_count = len.div_ceil(16);
+
+    let inputs = (0..block_count)
+        .map(|_| {
+            let explicit_nonce: Array<U8, 8> = vm
+                .alloc()
+                .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+            let counter: Array<U8, 4> = vm
+                .alloc()
+                .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+            vm.mark_public(explicit_nonce)
+                .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+            vm.mark_public(counter)
+                .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+            Ok((explicit_nonce, counter))
+        })
+        .collect::<Result<Vec<_>, AesError>>()?;
+
+    let blocks = inputs
+        .into_iter()
+        .map(|(explicit_nonce, counter)| {
+            let output = vm
+                .call(
+                    Call::builder(circuit.clone())
+                        .arg(key)
+                        .arg(iv)
+                        .arg(explicit_nonce)
+                        .arg(counter)
+                        .build()
+                        .expect("call should be valid"),
+                )
+                .map_err(|err| AesError::new(ErrorKind::Vm, err))?;
+
+            Ok(CtrBlock {
+                explicit_nonce,
+                counter,
+                output,
+            })
+        })
+        .collect::<Result<Vec<_>, AesError>>()?;
+
+    Ok(Keystream::new(&blocks))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,6 +529,118 @@ mod tests {
         assert_eq!(ciphertext_gen, expected);
     }
 
+    #[tokio::test]
+    async fn test_aes256_ctr() {
+        let key = [42_u8; 32];
+        let iv = [3_u8; 4];
+        let nonce = [5_u8; 8];
+        let start_counter = 3u32;
+
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut gen, mut ev) = mock_vm();
+
+        let aes_gen = setup_ctr_256(key, iv, &mut gen);
+        let aes_ev = setup_ctr_256(key, iv, &mut ev);
+
+        let msg = vec![42u8; 128];
+
+        let keystream_gen = aes_gen.alloc_keystream(&mut gen, msg.len()).unwrap();
+        let keystream_ev = aes_ev.alloc_keystream(&mut ev, msg.len()).unwrap();
+
+        let msg_ref_gen: Vector<U8> = gen.alloc_vec(msg.len()).unwrap();
+        gen.mark_public(msg_ref_gen).unwrap();
+        gen.assign(msg_ref_gen, msg.clone()).unwrap();
+        gen.commit(msg_ref_gen).unwrap();
+
+        let msg_ref_ev: Vector<U8> = ev.alloc_vec(msg.len()).unwrap();
+        ev.mark_public(msg_ref_ev).unwrap();
+        ev.assign(msg_ref_ev, msg.clone()).unwrap();
+        ev.commit(msg_ref_ev).unwrap();
+
+        let mut ctr = start_counter..;
+        keystream_gen
+            .assign(&mut gen, nonce, move || ctr.next().unwrap().to_be_bytes())
+            .unwrap();
+        let mut ctr = start_counter..;
+        keystream_ev
+            .assign(&mut ev, nonce, move || ctr.next().unwrap().to_be_bytes())
+            .unwrap();
+
+        let cipher_out_gen = keystream_gen.apply(&mut gen, msg_ref_gen).unwrap();
+        let cipher_out_ev = keystream_ev.apply(&mut ev, msg_ref_ev).unwrap();
+
+        let (ct_gen, ct_ev) = tokio::try_join!(
+            async {
+                let out = gen.decode(cipher_out_gen).unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                gen.execute(&mut ctx_a).await.unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                out.await
+            },
+            async {
+                let out = ev.decode(cipher_out_ev).unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                ev.execute(&mut ctx_b).await.unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                out.await
+            }
+        )
+        .unwrap();
+
+        assert_eq!(ct_gen, ct_ev);
+
+        let expected = aes256_apply_keystream(key, iv, nonce, start_counter as usize, msg);
+        assert_eq!(ct_gen, expected);
+    }
+
+    #[tokio::test]
+    async fn test_aes256_ecb() {
+        let key = [1_u8; 32];
+        let input = [5_u8; 16];
+
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut gen, mut ev) = mock_vm();
+
+        let aes_gen = setup_block_256(key, &mut gen);
+        let aes_ev = setup_block_256(key, &mut ev);
+
+        let block_ref_gen: Array<U8, 16> = gen.alloc().unwrap();
+        gen.mark_public(block_ref_gen).unwrap();
+        gen.assign(block_ref_gen, input).unwrap();
+        gen.commit(block_ref_gen).unwrap();
+
+        let block_ref_ev: Array<U8, 16> = ev.alloc().unwrap();
+        ev.mark_public(block_ref_ev).unwrap();
+        ev.assign(block_ref_ev, input).unwrap();
+        ev.commit(block_ref_ev).unwrap();
+
+        let block_gen = aes_gen.alloc_block(&mut gen, block_ref_gen).unwrap();
+        let block_ev = aes_ev.alloc_block(&mut ev, block_ref_ev).unwrap();
+
+        let (ciphertext_gen, ciphetext_ev) = tokio::try_join!(
+            async {
+                let out = gen.decode(block_gen).unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                gen.execute(&mut ctx_a).await.unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                out.await
+            },
+            async {
+                let out = ev.decode(block_ev).unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                ev.execute(&mut ctx_b).await.unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                out.await
+            }
+        )
+        .unwrap();
+
+        assert_eq!(ciphertext_gen, ciphetext_ev);
+
+        let expected = aes256(key, input);
+        assert_eq!(ciphertext_gen, expected);
+    }
+
     fn mock_vm() -> (impl Vm<Binary>, impl Vm<Binary>) {
         let mut rng = StdRng::seed_from_u64(0);
         let delta = Delta::random(&mut rng);
@@ -337,6 +684,37 @@ mod tests {
         aes
     }
 
+    fn setup_ctr_256(key: [u8; 32], iv: [u8; 4], vm: &mut dyn Vm<Binary>) -> Aes {
+        let key_ref: Array<U8, 32> = vm.alloc().unwrap();
+        vm.mark_public(key_ref).unwrap();
+        vm.assign(key_ref, key).unwrap();
+        vm.commit(key_ref).unwrap();
+
+        let iv_ref: Array<U8, 4> = vm.alloc().unwrap();
+        vm.mark_public(iv_ref).unwrap();
+        vm.assign(iv_ref, iv).unwrap();
+        vm.commit(iv_ref).unwrap();
+
+        let mut aes = Aes::default();
+
+        aes.set_key(AesKey::Aes256(key_ref));
+        aes.set_iv(iv_ref);
+
+        aes
+    }
+
+    fn setup_block_256(key: [u8; 32], vm: &mut dyn Vm<Binary>) -> Aes256 {
+        let key_ref: Array<U8, 32> = vm.alloc().unwrap();
+        vm.mark_public(key_ref).unwrap();
+        vm.assign(key_ref, key).unwrap();
+        vm.commit(key_ref).unwrap();
+
+        let mut aes = Aes256::default();
+        aes.set_key(key_ref);
+
+        aes
+    }
+
     fn aes_apply_keystream(
         key: [u8; 16],
         iv: [u8; 4],
@@ -372,4 +750,40 @@ mod tests {
         cipher.encrypt_block(&mut msg);
         msg.into()
     }
+
+    fn aes256_apply_keystream(
+        key: [u8; 32],
+        iv: [u8; 4],
+        explicit_nonce: [u8; 8],
+        start_ctr: usize,
+        msg: Vec<u8>,
+    ) -> Vec<u8> {
+        use ::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+        use aes::Aes256;
+        use ctr::Ctr32BE;
+
+        let mut full_iv = [0u8; 16];
+        full_iv[0..4].copy_from_slice(&iv);
+        full_iv[4..12].copy_from_slice(&explicit_nonce);
+
+        let mut cipher = Ctr32BE::<Aes256>::new(&key.into(), &full_iv.into());
+        let mut out = msg.clone();
+
+        cipher
+            .try_seek(start_ctr * 16)
+            .expect("start counter is less than keystream length");
+        cipher.apply_keystream(&mut out);
+
+        out
+    }
+
+    fn aes256(key: [u8; 32], msg: [u8; 16]) -> [u8; 16] {
+        use ::aes::Aes256 as TestAes256;
+        use ::cipher::{BlockEncrypt, KeyInit};
+
+        let mut msg = msg.into();
+        let cipher = TestAes256::new(&key.into());
+        cipher.encrypt_block(&mut msg);
+        msg.into()
+    }
 }
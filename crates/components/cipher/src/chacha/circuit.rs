@@ -0,0 +1,163 @@
+//! ChaCha20 circuit.
+
+use std::sync::{Arc, LazyLock};
+
+use mpz_circuits::{Circuit, CircuitBuilder, Feed, Node};
+
+/// ChaCha20 block function circuit with a TLS 1.2 nonce, see RFC 7905.
+///
+/// The nonce is computed as `IV ^ (0^32 || EXPLICIT_NONCE)`.
+///
+/// # Inputs
+///
+/// 0. KEY: 32 bytes
+/// 1. IV: 12 bytes
+/// 2. EXPLICIT_NONCE: 8 bytes
+/// 3. COUNTER: 4 bytes, little-endian
+///
+/// # Outputs
+///
+/// 0. KEYSTREAM: 64 bytes
+pub static CHACHA20: LazyLock<Arc<Circuit>> = LazyLock::new(|| Arc::new(build_chacha20()));
+
+/// The constant words "expand 32-byte k".
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// A little-endian word, i.e. bits in LSB0 order.
+type Word = [Node<Feed>; 32];
+
+fn build_chacha20() -> Circuit {
+    let mut builder = CircuitBuilder::new();
+
+    let key: [Word; 8] = std::array::from_fn(|_| input_word(&mut builder));
+    let iv: [Word; 3] = std::array::from_fn(|_| input_word(&mut builder));
+    let explicit_nonce: [Word; 2] = std::array::from_fn(|_| input_word(&mut builder));
+    let counter = input_word(&mut builder);
+
+    let nonce = [
+        iv[0],
+        xor(&mut builder, &iv[1], &explicit_nonce[0]),
+        xor(&mut builder, &iv[2], &explicit_nonce[1]),
+    ];
+
+    // The constant words are not part of the state, they are folded into the
+    // first quarter rounds and the final addition instead.
+    let mut state: [Word; 16] = [
+        key[0], key[1], key[2], key[3], key[0], key[1], key[2], key[3], key[4], key[5], key[6],
+        key[7], counter, nonce[0], nonce[1], nonce[2],
+    ];
+    let initial = state;
+
+    for (i, constant) in CONSTANTS.into_iter().enumerate() {
+        state[i] = add_const(&mut builder, &state[i + 4], constant);
+        quarter_round_tail(&mut builder, &mut state, [i, i + 4, i + 8, i + 12]);
+    }
+    diagonal_round(&mut builder, &mut state);
+
+    for _ in 1..10 {
+        for i in 0..4 {
+            quarter_round(&mut builder, &mut state, [i, i + 4, i + 8, i + 12]);
+        }
+        diagonal_round(&mut builder, &mut state);
+    }
+
+    for (i, word) in state.iter().enumerate() {
+        let word = if i < 4 {
+            add_const(&mut builder, word, CONSTANTS[i])
+        } else {
+            add(&mut builder, word, &initial[i])
+        };
+
+        for bit in word {
+            builder.add_output(bit);
+        }
+    }
+
+    builder.build().expect("ChaCha20 circuit is valid")
+}
+
+fn input_word(builder: &mut CircuitBuilder) -> Word {
+    std::array::from_fn(|_| builder.add_input())
+}
+
+fn diagonal_round(builder: &mut CircuitBuilder, state: &mut [Word; 16]) {
+    quarter_round(builder, state, [0, 5, 10, 15]);
+    quarter_round(builder, state, [1, 6, 11, 12]);
+    quarter_round(builder, state, [2, 7, 8, 13]);
+    quarter_round(builder, state, [3, 4, 9, 14]);
+}
+
+fn quarter_round(builder: &mut CircuitBuilder, state: &mut [Word; 16], idx: [usize; 4]) {
+    let [a, b, ..] = idx;
+    state[a] = add(builder, &state[a], &state[b]);
+    quarter_round_tail(builder, state, idx);
+}
+
+/// The quarter round after the first addition `a += b`.
+fn quarter_round_tail(builder: &mut CircuitBuilder, state: &mut [Word; 16], idx: [usize; 4]) {
+    let [a, b, c, d] = idx;
+    state[d] = rotl(&xor(builder, &state[d], &state[a]), 16);
+    state[c] = add(builder, &state[c], &state[d]);
+    state[b] = rotl(&xor(builder, &state[b], &state[c]), 12);
+    state[a] = add(builder, &state[a], &state[b]);
+    state[d] = rotl(&xor(builder, &state[d], &state[a]), 8);
+    state[c] = add(builder, &state[c], &state[d]);
+    state[b] = rotl(&xor(builder, &state[b], &state[c]), 7);
+}
+
+fn rotl(x: &Word, n: usize) -> Word {
+    std::array::from_fn(|i| x[(i + 32 - n) % 32])
+}
+
+fn xor(builder: &mut CircuitBuilder, x: &Word, y: &Word) -> Word {
+    std::array::from_fn(|i| builder.add_xor_gate(x[i], y[i]))
+}
+
+/// Ripple-carry addition modulo 2^32.
+fn add(builder: &mut CircuitBuilder, x: &Word, y: &Word) -> Word {
+    let mut out = *x;
+    let mut carry = builder.add_and_gate(x[0], y[0]);
+    out[0] = builder.add_xor_gate(x[0], y[0]);
+    for i in 1..32 {
+        let x_c = builder.add_xor_gate(x[i], carry);
+        out[i] = builder.add_xor_gate(x_c, y[i]);
+        if i < 31 {
+            let y_c = builder.add_xor_gate(y[i], carry);
+            let and = builder.add_and_gate(x_c, y_c);
+            carry = builder.add_xor_gate(carry, and);
+        }
+    }
+    out
+}
+
+/// Ripple-carry addition of a constant modulo 2^32.
+fn add_const(builder: &mut CircuitBuilder, x: &Word, value: u32) -> Word {
+    let bit = |i: usize| (value >> i) & 1 == 1;
+
+    // Skip the low zero bits, which do not change the word.
+    let Some(start) = (0..32).find(|&i| bit(i)) else {
+        return *x;
+    };
+
+    let mut out = *x;
+    out[start] = builder.add_inv_gate(x[start]);
+    let mut carry = x[start];
+    for i in start + 1..32 {
+        let x_c = builder.add_xor_gate(x[i], carry);
+        out[i] = if bit(i) {
+            builder.add_inv_gate(x_c)
+        } else {
+            x_c
+        };
+        if i < 31 {
+            let and = builder.add_and_gate(x[i], carry);
+            // With a constant one the carry is `x | carry`.
+            carry = if bit(i) {
+                builder.add_xor_gate(x_c, and)
+            } else {
+                and
+            };
+        }
+    }
+    out
+}
@@ -0,0 +1,263 @@
+//! The ChaCha20 stream cipher.
+
+use crate::{Cipher, CtrBlock, Keystream};
+use async_trait::async_trait;
+use mpz_memory_core::binary::{Binary, U8};
+use mpz_vm_core::{prelude::*, Call, Vm};
+
+mod circuit;
+mod error;
+
+pub use circuit::CHACHA20;
+pub use error::ChaChaError;
+use error::ErrorKind;
+
+/// Computes ChaCha20 as used in TLS 1.2, see RFC 7905.
+///
+/// The 12 byte nonce of each record is the IV XORed with the 8 byte explicit
+/// nonce, i.e. the big-endian record sequence number, left-padded with zeros.
+/// The block counter is little-endian.
+#[derive(Default, Debug)]
+pub struct ChaCha20 {
+    key: Option<Array<U8, 32>>,
+    iv: Option<Array<U8, 12>>,
+}
+
+#[async_trait]
+impl Cipher for ChaCha20 {
+    type Error = ChaChaError;
+    type Key = Array<U8, 32>;
+    type Iv = Array<U8, 12>;
+    type Nonce = Array<U8, 8>;
+    type Counter = Array<U8, 4>;
+    type Block = Array<U8, 64>;
+
+    fn set_key(&mut self, key: Array<U8, 32>) {
+        self.key = Some(key);
+    }
+
+    fn set_iv(&mut self, iv: Array<U8, 12>) {
+        self.iv = Some(iv);
+    }
+
+    fn key(&self) -> Option<&Array<U8, 32>> {
+        self.key.as_ref()
+    }
+
+    fn iv(&self) -> Option<&Array<U8, 12>> {
+        self.iv.as_ref()
+    }
+
+    fn alloc_block(
+        &self,
+        _vm: &mut dyn Vm<Binary>,
+        _input: Array<U8, 64>,
+    ) -> Result<Self::Block, Self::Error> {
+        Err(ChaChaError::new(
+            ErrorKind::Mode,
+            "ChaCha20 is a stream cipher and has no ECB mode",
+        ))
+    }
+
+    fn alloc_ctr_block(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+    ) -> Result<CtrBlock<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| ChaChaError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| ChaChaError::new(ErrorKind::Iv, "iv not set"))?;
+
+        alloc_ctr_block(vm, key, iv)
+    }
+
+    fn alloc_keystream(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        len: usize,
+    ) -> Result<Keystream<Self::Nonce, Self::Counter, Self::Block>, Self::Error> {
+        let key = self
+            .key
+            .ok_or_else(|| ChaChaError::new(ErrorKind::Key, "key not set"))?;
+        let iv = self
+            .iv
+            .ok_or_else(|| ChaChaError::new(ErrorKind::Iv, "iv not set"))?;
+
+        let block_count = len.div_ceil(64);
+        let blocks = (0..block_count)
+            .map(|_| alloc_ctr_block(vm, key, iv))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Keystream::new(&blocks))
+    }
+}
+
+fn alloc_ctr_block(
+    vm: &mut dyn Vm<Binary>,
+    key: Array<U8, 32>,
+    iv: Array<U8, 12>,
+) -> Result<CtrBlock<Array<U8, 8>, Array<U8, 4>, Array<U8, 64>>, ChaChaError> {
+    let explicit_nonce: Array<U8, 8> = vm
+        .alloc()
+        .map_err(|err| ChaChaError::new(ErrorKind::Vm, err))?;
+    vm.mark_public(explicit_nonce)
+        .map_err(|err| ChaChaError::new(ErrorKind::Vm, err))?;
+
+    let counter: Array<U8, 4> = vm
+        .alloc()
+        .map_err(|err| ChaChaError::new(ErrorKind::Vm, err))?;
+    vm.mark_public(counter)
+        .map_err(|err| ChaChaError::new(ErrorKind::Vm, err))?;
+
+    let output = vm
+        .call(
+            Call::builder(CHACHA20.clone())
+                .arg(key)
+                .arg(iv)
+                .arg(explicit_nonce)
+                .arg(counter)
+                .build()
+                .expect("call should be valid"),
+        )
+        .map_err(|err| ChaChaError::new(ErrorKind::Vm, err))?;
+
+    Ok(CtrBlock {
+        explicit_nonce,
+        counter,
+        output,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mpz_common::context::test_st_context;
+    use mpz_garble::protocol::semihonest::{Evaluator, Garbler};
+    use mpz_memory_core::{correlated::Delta, Vector, ViewExt};
+    use mpz_ot::ideal::cot::ideal_cot;
+    use mpz_vm_core::Execute;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[tokio::test]
+    async fn test_chacha20_ctr() {
+        let key = [42_u8; 32];
+        let iv = [3_u8; 12];
+        let nonce = [5_u8; 8];
+        let start_counter = 1u32;
+
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut gen, mut ev) = mock_vm();
+
+        let chacha_gen = setup(key, iv, &mut gen);
+        let chacha_ev = setup(key, iv, &mut ev);
+
+        let msg = vec![42u8; 128];
+
+        let keystream_gen = chacha_gen.alloc_keystream(&mut gen, msg.len()).unwrap();
+        let keystream_ev = chacha_ev.alloc_keystream(&mut ev, msg.len()).unwrap();
+
+        let msg_ref_gen: Vector<U8> = gen.alloc_vec(msg.len()).unwrap();
+        gen.mark_public(msg_ref_gen).unwrap();
+        gen.assign(msg_ref_gen, msg.clone()).unwrap();
+        gen.commit(msg_ref_gen).unwrap();
+
+        let msg_ref_ev: Vector<U8> = ev.alloc_vec(msg.len()).unwrap();
+        ev.mark_public(msg_ref_ev).unwrap();
+        ev.assign(msg_ref_ev, msg.clone()).unwrap();
+        ev.commit(msg_ref_ev).unwrap();
+
+        let mut ctr = start_counter..;
+        keystream_gen
+            .assign(&mut gen, nonce, move || ctr.next().unwrap().to_le_bytes())
+            .unwrap();
+        let mut ctr = start_counter..;
+        keystream_ev
+            .assign(&mut ev, nonce, move || ctr.next().unwrap().to_le_bytes())
+            .unwrap();
+
+        let cipher_out_gen = keystream_gen.apply(&mut gen, msg_ref_gen).unwrap();
+        let cipher_out_ev = keystream_ev.apply(&mut ev, msg_ref_ev).unwrap();
+
+        let (ct_gen, ct_ev) = tokio::try_join!(
+            async {
+                let out = gen.decode(cipher_out_gen).unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                gen.execute(&mut ctx_a).await.unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+                out.await
+            },
+            async {
+                let out = ev.decode(cipher_out_ev).unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                ev.execute(&mut ctx_b).await.unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+                out.await
+            }
+        )
+        .unwrap();
+
+        assert_eq!(ct_gen, ct_ev);
+
+        let expected = chacha20_apply_keystream(key, iv, nonce, start_counter, msg);
+        assert_eq!(ct_gen, expected);
+    }
+
+    fn mock_vm() -> (impl Vm<Binary>, impl Vm<Binary>) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let delta = Delta::random(&mut rng);
+
+        let (cot_send, cot_recv) = ideal_cot(delta.into_inner());
+
+        let gen = Garbler::new(cot_send, [0u8; 16], delta);
+        let ev = Evaluator::new(cot_recv);
+
+        (gen, ev)
+    }
+
+    fn setup(key: [u8; 32], iv: [u8; 12], vm: &mut dyn Vm<Binary>) -> ChaCha20 {
+        let key_ref: Array<U8, 32> = vm.alloc().unwrap();
+        vm.mark_public(key_ref).unwrap();
+        vm.assign(key_ref, key).unwrap();
+        vm.commit(key_ref).unwrap();
+
+        let iv_ref: Array<U8, 12> = vm.alloc().unwrap();
+        vm.mark_public(iv_ref).unwrap();
+        vm.assign(iv_ref, iv).unwrap();
+        vm.commit(iv_ref).unwrap();
+
+        let mut chacha = ChaCha20::default();
+        chacha.set_key(key_ref);
+        chacha.set_iv(iv_ref);
+
+        chacha
+    }
+
+    fn chacha20_apply_keystream(
+        key: [u8; 32],
+        iv: [u8; 12],
+        explicit_nonce: [u8; 8],
+        start_ctr: u32,
+        msg: Vec<u8>,
+    ) -> Vec<u8> {
+        use ::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+        use chacha20::ChaCha20 as TestChaCha20;
+
+        let mut nonce = iv;
+        nonce[4..]
+            .iter_mut()
+            .zip(explicit_nonce)
+            .for_each(|(a, b)| *a ^= b);
+
+        let mut cipher = TestChaCha20::new(&key.into(), &nonce.into());
+        let mut out = msg.clone();
+
+        cipher
+            .try_seek(start_ctr as usize * 64)
+            .expect("start counter is less than keystream length");
+        cipher.apply_keystream(&mut out);
+
+        out
+    }
+}
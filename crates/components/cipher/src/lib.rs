@@ -10,6 +10,7 @@
 #![forbid(unsafe_code)]
 
 pub mod aes;
+pub mod chacha;
 
 use async_trait::async_trait;
 use mpz_circuits::circuits::xor;
@@ -142,6 +143,15 @@ where
         Ok(Self { blocks })
     }
 
+    /// Extends the keystream with additional material.
+    ///
+    /// The existing material is consumed before the additional material.
+    pub fn extend(&mut self, other: Self) {
+        for block in other.blocks.into_iter().rev() {
+            self.blocks.push_front(block);
+        }
+    }
+
     /// Applies the keystream to the provided input.
     ///
     /// # Arguments
@@ -101,8 +101,8 @@ async fn prf(mode: Mode) {
 
     let cf_hs_hash = [1u8; 32];
 
-    leader.set_cf_hash(cf_hs_hash).unwrap();
-    follower.set_cf_hash(cf_hs_hash).unwrap();
+    leader.set_cf_hash(&cf_hs_hash).unwrap();
+    follower.set_cf_hash(&cf_hs_hash).unwrap();
 
     while leader.wants_flush() || follower.wants_flush() {
         tokio::try_join!(
@@ -123,8 +123,8 @@ async fn prf(mode: Mode) {
 
     let sf_hs_hash = [2u8; 32];
 
-    leader.set_sf_hash(sf_hs_hash).unwrap();
-    follower.set_sf_hash(sf_hs_hash).unwrap();
+    leader.set_sf_hash(&sf_hs_hash).unwrap();
+    follower.set_sf_hash(&sf_hs_hash).unwrap();
 
     while leader.wants_flush() || follower.wants_flush() {
         tokio::try_join!(
@@ -1,6 +1,6 @@
-//! PRF modes.
+//! PRF and key schedule modes.
 
-/// Modes for the PRF.
+/// Modes for the PRF and the key schedule.
 #[derive(Debug, Clone, Copy)]
 pub enum Mode {
     /// Computes some hashes locally.
@@ -8,3 +8,25 @@ pub enum Mode {
     /// Computes the whole PRF in MPC.
     Normal,
 }
+
+/// Hash function of the TLS 1.2 PRF, which is determined by the cipher suite.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrfHash {
+    /// SHA-256, used by the `*_SHA256` cipher suites.
+    #[default]
+    Sha256,
+    /// SHA-384, used by the `*_SHA384` cipher suites.
+    ///
+    /// This PRF is always computed in MPC, regardless of the [`Mode`].
+    Sha384,
+}
+
+impl PrfHash {
+    /// Returns the output length of the hash function in bytes.
+    pub fn output_len(&self) -> usize {
+        match self {
+            PrfHash::Sha256 => 32,
+            PrfHash::Sha384 => 48,
+        }
+    }
+}
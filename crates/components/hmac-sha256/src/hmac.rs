@@ -1,14 +1,14 @@
-//! Computation of HMAC-SHA256.
+//! Computation of HMAC-SHA256 and HMAC-SHA384.
 //!
-//! HMAC-SHA256 is defined as
+//! HMAC is defined as
 //!
 //! HMAC(m) = H((key' xor opad) || H((key' xor ipad) || m))
 //!
-//! * H     - SHA256 hash function
-//! * key'  - key padded with zero bytes to 64 bytes (we do not support longer
-//!   keys)
-//! * opad  - 64 bytes of 0x5c
-//! * ipad  - 64 bytes of 0x36
+//! * H     - SHA256 or SHA384 hash function
+//! * key'  - key padded with zero bytes to the block size of H, i.e. 64 bytes
+//!   for SHA256 and 128 bytes for SHA384 (we do not support longer keys)
+//! * opad  - block size bytes of 0x5c
+//! * ipad  - block size bytes of 0x36
 //! * m     - message
 //!
 //! This implementation computes HMAC-SHA256 using intermediate results
@@ -22,15 +22,27 @@ use mpz_hash::sha256::Sha256;
 use mpz_vm_core::{
     memory::{
         binary::{Binary, U8},
-        Array,
+        Array, MemoryExt, ViewExt,
     },
     Vm,
 };
 
-use crate::PrfError;
+use crate::{sha256, sha384::Sha384, state_to_bytes, PrfError};
 
 pub(crate) const IPAD: [u8; 64] = [0x36; 64];
 pub(crate) const OPAD: [u8; 64] = [0x5c; 64];
+pub(crate) const IPAD_SHA384: [u8; 128] = [0x36; 128];
+pub(crate) const OPAD_SHA384: [u8; 128] = [0x5c; 128];
+
+/// Partial hashes of an HMAC key.
+///
+/// * `outer` - H(key' xor opad)
+/// * `inner` - H(key' xor ipad)
+#[derive(Debug, Clone)]
+pub(crate) enum HmacPartials {
+    Sha256 { outer: Sha256, inner: Sha256 },
+    Sha384 { outer: Sha384, inner: Sha384 },
+}
 
 /// Computes HMAC-SHA256
 ///
@@ -49,6 +61,48 @@ pub(crate) fn hmac_sha256(
     outer_partial.finalize(vm).map_err(PrfError::from)
 }
 
+/// Computes HMAC-SHA384
+///
+/// # Arguments
+///
+/// * `vm` - The virtual machine.
+/// * `outer_partial` - (key' xor opad)
+/// * `inner_local` - H((key' xor ipad) || m)
+pub(crate) fn hmac_sha384(
+    vm: &mut dyn Vm<Binary>,
+    mut outer_partial: Sha384,
+    inner_local: Array<U8, 48>,
+) -> Result<Array<U8, 48>, PrfError> {
+    outer_partial.update(&inner_local.into());
+    outer_partial.compress(vm)?;
+    outer_partial.finalize(vm)
+}
+
+/// Assigns the inner hash of an HMAC computed locally from the decoded inner
+/// partial hash state.
+///
+/// # Arguments
+///
+/// * `vm` - The virtual machine.
+/// * `inner_local` - H((key' xor ipad) || m)
+/// * `inner_partial` - The decoded hash state of (key' xor ipad)
+/// * `msg` - m
+pub(crate) fn assign_inner_local(
+    vm: &mut dyn Vm<Binary>,
+    inner_local: Array<U8, 32>,
+    inner_partial: [u32; 8],
+    msg: &[u8],
+) -> Result<(), PrfError> {
+    let inner_local_value = sha256(inner_partial, 64, msg);
+
+    vm.mark_public(inner_local).map_err(PrfError::vm)?;
+    vm.assign(inner_local, state_to_bytes(inner_local_value))
+        .map_err(PrfError::vm)?;
+    vm.commit(inner_local).map_err(PrfError::vm)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
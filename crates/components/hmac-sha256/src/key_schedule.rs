@@ -0,0 +1,645 @@
+//! Computation of the TLS 1.3 key schedule.
+//!
+//! The key schedule is defined in RFC 8446, Section 7.1 as
+//!
+//! HS   = HKDF-Extract(Derive-Secret(ES, "derived", ""), PMS)
+//! c_hs = Derive-Secret(HS, "c hs traffic", CH..SH)
+//! s_hs = Derive-Secret(HS, "s hs traffic", CH..SH)
+//! MS   = HKDF-Extract(Derive-Secret(HS, "derived", ""), 0)
+//! c_ap = Derive-Secret(MS, "c ap traffic", CH..SF)
+//! s_ap = Derive-Secret(MS, "s ap traffic", CH..SF)
+//!
+//! * ES  - the early secret, which is constant because we do not support PSK
+//! * PMS - the (EC)DHE shared secret
+//!
+//! HKDF-Extract(salt, ikm) is HMAC(salt, ikm). Because all of our outputs are
+//! at most 32 bytes long HKDF-Expand is a single HMAC invocation, so
+//! HKDF-Expand-Label(secret, label, context, len) is HMAC(secret, info || 0x01)
+//! truncated to `len` bytes.
+//!
+//! Apart from the extraction of the handshake secret all HMAC messages are
+//! public. In [`Mode::Reduced`] the inner hashes of these HMACs are computed
+//! locally, see [`crate::Mode`].
+
+use crate::{
+    hmac::{assign_inner_local, hmac_sha256, IPAD, OPAD},
+    prf::compute_partial,
+    KeyScheduleOutput, Mode, PrfError, TrafficKeys, TrafficSecrets,
+};
+use mpz_core::bitvec::BitVec;
+use mpz_hash::sha256::Sha256;
+use mpz_vm_core::{
+    memory::{
+        binary::{Binary, U8},
+        Array, DecodeFutureTyped, MemoryExt, Vector, ViewExt,
+    },
+    Vm,
+};
+use tracing::instrument;
+
+/// SHA-256 of the empty string.
+const EMPTY_HASH: [u8; 32] = [
+    0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f, 0xb9, 0x24,
+    0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+];
+
+/// Derive-Secret(ES, "derived", "") for the early secret without PSK.
+const DERIVED_EARLY_SECRET: [u8; 32] = [
+    0x6f, 0x26, 0x15, 0xa1, 0x08, 0xc7, 0x02, 0xc5, 0x67, 0x8f, 0x54, 0xfc, 0x9d, 0xba, 0xb6, 0x97,
+    0x16, 0xc0, 0x76, 0x18, 0x9c, 0x48, 0x25, 0x0c, 0xeb, 0xea, 0xc3, 0x57, 0x6c, 0x36, 0x11, 0xba,
+];
+
+/// MPC computation of the TLS 1.3 HKDF-SHA256 key schedule.
+#[derive(Debug)]
+pub struct MpcKeySchedule {
+    mode: Mode,
+    state: State,
+    pending: Vec<PublicHmac>,
+}
+
+impl MpcKeySchedule {
+    /// Creates a new instance of the key schedule.
+    ///
+    /// # Arguments
+    ///
+    /// `mode` - The key schedule mode.
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            state: State::Initialized,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Allocates resources for the key schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `pms` - The (EC)DHE shared secret.
+    #[instrument(level = "debug", skip_all, err)]
+    pub fn alloc(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        pms: Vector<U8>,
+    ) -> Result<KeyScheduleOutput, PrfError> {
+        let State::Initialized = self.state.take() else {
+            return Err(PrfError::state("key schedule not in initialized state"));
+        };
+
+        let mode = self.mode;
+        let pending = &mut self.pending;
+
+        // HS = HKDF-Extract(derived_es, PMS)
+        let derived_es = alloc_public(vm, DERIVED_EARLY_SECRET.to_vec())?;
+        let handshake_secret = HmacKey::alloc(vm, derived_es)?.sign(vm, &[pms])?;
+        let handshake_secret = HmacKey::alloc(vm, handshake_secret.into())?;
+
+        let client_hs = handshake_secret.sign_public(
+            mode,
+            vm,
+            Stage::Handshake,
+            PublicMsg::Traffic(b"c hs traffic"),
+            pending,
+        )?;
+        let server_hs = handshake_secret.sign_public(
+            mode,
+            vm,
+            Stage::Handshake,
+            PublicMsg::Traffic(b"s hs traffic"),
+            pending,
+        )?;
+        let handshake_keys =
+            TrafficKeys::alloc(mode, vm, Stage::Handshake, client_hs, server_hs, pending)?;
+
+        // MS = HKDF-Extract(Derive-Secret(HS, "derived", ""), 0)
+        let derived_hs = handshake_secret.sign_public(
+            mode,
+            vm,
+            Stage::Handshake,
+            PublicMsg::Fixed(hkdf_label(b"derived", &EMPTY_HASH, 32)),
+            pending,
+        )?;
+        let master_secret = HmacKey::alloc(vm, derived_hs.into())?.sign_public(
+            mode,
+            vm,
+            Stage::Handshake,
+            PublicMsg::Fixed(vec![0; 32]),
+            pending,
+        )?;
+        let master_secret = HmacKey::alloc(vm, master_secret.into())?;
+
+        let client_ap = master_secret.sign_public(
+            mode,
+            vm,
+            Stage::Application,
+            PublicMsg::Traffic(b"c ap traffic"),
+            pending,
+        )?;
+        let server_ap = master_secret.sign_public(
+            mode,
+            vm,
+            Stage::Application,
+            PublicMsg::Traffic(b"s ap traffic"),
+            pending,
+        )?;
+        let keys = TrafficKeys::alloc(mode, vm, Stage::Application, client_ap, server_ap, pending)?;
+
+        self.state = State::Handshake { hash: None };
+
+        Ok(KeyScheduleOutput {
+            handshake: TrafficSecrets {
+                client: client_hs,
+                server: server_hs,
+            },
+            handshake_keys,
+            keys,
+        })
+    }
+
+    /// Sets the handshake hash at ServerHello, ie. H(ClientHello..ServerHello).
+    ///
+    /// # Arguments
+    ///
+    /// * `handshake_hash` - The handshake transcript hash.
+    #[instrument(level = "debug", skip_all, err)]
+    pub fn set_hs_hash(&mut self, handshake_hash: [u8; 32]) -> Result<(), PrfError> {
+        let State::Handshake { hash } = &mut self.state else {
+            return Err(PrfError::state("key schedule not in handshake state"));
+        };
+
+        *hash = Some(handshake_hash);
+
+        Ok(())
+    }
+
+    /// Sets the handshake hash at server Finished, ie.
+    /// H(ClientHello..server Finished).
+    ///
+    /// # Arguments
+    ///
+    /// * `handshake_hash` - The handshake transcript hash.
+    #[instrument(level = "debug", skip_all, err)]
+    pub fn set_server_finished_hash(&mut self, handshake_hash: [u8; 32]) -> Result<(), PrfError> {
+        let State::Application { hash } = &mut self.state else {
+            return Err(PrfError::state("key schedule not in application state"));
+        };
+
+        *hash = Some(handshake_hash);
+
+        Ok(())
+    }
+
+    /// Returns if the key schedule needs to be flushed.
+    pub fn wants_flush(&self) -> bool {
+        matches!(
+            self.state,
+            State::Handshake { hash: Some(_) } | State::Application { hash: Some(_) }
+        )
+    }
+
+    /// Flushes the key schedule.
+    pub fn flush(&mut self, vm: &mut dyn Vm<Binary>) -> Result<(), PrfError> {
+        let (stage, hash) = match self.state {
+            State::Handshake { hash: Some(hash) } => (Stage::Handshake, hash),
+            State::Application { hash: Some(hash) } => (Stage::Application, hash),
+            _ => return Ok(()),
+        };
+
+        for mut hmac in std::mem::take(&mut self.pending) {
+            if hmac.stage != stage || !hmac.try_assign(vm, &hash)? {
+                self.pending.push(hmac);
+            }
+        }
+
+        if !self.pending.iter().any(|hmac| hmac.stage == stage) {
+            self.state = match stage {
+                Stage::Handshake => State::Application { hash: None },
+                Stage::Application => State::Complete,
+            };
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Initialized,
+    Handshake { hash: Option<[u8; 32]> },
+    Application { hash: Option<[u8; 32]> },
+    Complete,
+    Error,
+}
+
+impl State {
+    fn take(&mut self) -> State {
+        std::mem::replace(self, State::Error)
+    }
+}
+
+/// The stage of the key schedule in which a message becomes known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// After the ServerHello.
+    Handshake,
+    /// After the server Finished.
+    Application,
+}
+
+/// A public HMAC message.
+#[derive(Debug)]
+enum PublicMsg {
+    /// A message which does not depend on the handshake transcript.
+    Fixed(Vec<u8>),
+    /// The HKDF label of a traffic secret, which has the handshake hash of
+    /// the stage as context.
+    Traffic(&'static [u8]),
+}
+
+impl PublicMsg {
+    fn len(&self) -> usize {
+        match self {
+            PublicMsg::Fixed(msg) => msg.len(),
+            PublicMsg::Traffic(label) => hkdf_label(label, &[0; 32], 32).len(),
+        }
+    }
+
+    fn to_bytes(&self, hash: &[u8; 32]) -> Vec<u8> {
+        match self {
+            PublicMsg::Fixed(msg) => msg.clone(),
+            PublicMsg::Traffic(label) => hkdf_label(label, hash, 32),
+        }
+    }
+}
+
+/// An HMAC with a public message which is assigned when flushing its stage.
+#[derive(Debug)]
+struct PublicHmac {
+    stage: Stage,
+    msg: PublicMsg,
+    input: HmacInput,
+}
+
+#[derive(Debug)]
+enum HmacInput {
+    /// The message is hashed in the VM.
+    Normal { msg: Vector<U8> },
+    /// The inner hash is computed locally from the decoded inner partial hash.
+    Reduced {
+        inner_partial: DecodeFutureTyped<BitVec, [u32; 8]>,
+        inner_local: Array<U8, 32>,
+    },
+}
+
+impl PublicHmac {
+    /// Assigns the HMAC input, returning `false` if it is not ready yet.
+    fn try_assign(&mut self, vm: &mut dyn Vm<Binary>, hash: &[u8; 32]) -> Result<bool, PrfError> {
+        let msg = self.msg.to_bytes(hash);
+
+        match &mut self.input {
+            HmacInput::Normal { msg: msg_ref } => {
+                vm.assign(*msg_ref, msg).map_err(PrfError::vm)?;
+                vm.commit(*msg_ref).map_err(PrfError::vm)?;
+            }
+            HmacInput::Reduced {
+                inner_partial,
+                inner_local,
+            } => {
+                let Some(inner_partial) = inner_partial.try_recv().map_err(PrfError::vm)? else {
+                    return Ok(false);
+                };
+
+                assign_inner_local(vm, *inner_local, inner_partial, &msg)?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// An HMAC key with its precomputed partial hashes.
+#[derive(Debug, Clone)]
+struct HmacKey {
+    outer_partial: Sha256,
+    inner_partial: Sha256,
+}
+
+impl HmacKey {
+    fn alloc(vm: &mut dyn Vm<Binary>, key: Vector<U8>) -> Result<Self, PrfError> {
+        Ok(Self {
+            outer_partial: compute_partial(vm, key, OPAD)?,
+            inner_partial: compute_partial(vm, key, IPAD)?,
+        })
+    }
+
+    /// Computes the HMAC of a message in the VM.
+    fn sign(&self, vm: &mut dyn Vm<Binary>, msg: &[Vector<U8>]) -> Result<Array<U8, 32>, PrfError> {
+        let mut inner_local = self.inner_partial.clone();
+
+        msg.iter().for_each(|m| inner_local.update(m));
+        inner_local.compress(vm)?;
+        let inner_local = inner_local.finalize(vm)?;
+
+        hmac_sha256(vm, self.outer_partial.clone(), inner_local)
+    }
+
+    /// Computes the HMAC of a public message, which is assigned when flushing
+    /// `stage`.
+    fn sign_public(
+        &self,
+        mode: Mode,
+        vm: &mut dyn Vm<Binary>,
+        stage: Stage,
+        msg: PublicMsg,
+        pending: &mut Vec<PublicHmac>,
+    ) -> Result<Array<U8, 32>, PrfError> {
+        let (output, input) = match mode {
+            Mode::Normal => {
+                let msg_ref: Vector<U8> = vm.alloc_vec(msg.len()).map_err(PrfError::vm)?;
+                vm.mark_public(msg_ref).map_err(PrfError::vm)?;
+
+                (
+                    self.sign(vm, &[msg_ref])?,
+                    HmacInput::Normal { msg: msg_ref },
+                )
+            }
+            Mode::Reduced => {
+                let (inner_partial, _) = self
+                    .inner_partial
+                    .state()
+                    .expect("state should be set for inner_partial");
+                let inner_partial = vm.decode(inner_partial).map_err(PrfError::vm)?;
+
+                let inner_local: Array<U8, 32> = vm.alloc().map_err(PrfError::vm)?;
+                let output = hmac_sha256(vm, self.outer_partial.clone(), inner_local)?;
+
+                (
+                    output,
+                    HmacInput::Reduced {
+                        inner_partial,
+                        inner_local,
+                    },
+                )
+            }
+        };
+
+        pending.push(PublicHmac { stage, msg, input });
+
+        Ok(output)
+    }
+}
+
+impl TrafficKeys {
+    /// Derives the AES-128-GCM write keys and IVs from the traffic secrets.
+    fn alloc(
+        mode: Mode,
+        vm: &mut dyn Vm<Binary>,
+        stage: Stage,
+        client_secret: Array<U8, 32>,
+        server_secret: Array<U8, 32>,
+        pending: &mut Vec<PublicHmac>,
+    ) -> Result<Self, PrfError> {
+        let (client_write_key, client_iv) =
+            traffic_key_iv(mode, vm, stage, client_secret, pending)?;
+        let (server_write_key, server_iv) =
+            traffic_key_iv(mode, vm, stage, server_secret, pending)?;
+
+        Ok(Self {
+            client_write_key,
+            server_write_key,
+            client_iv,
+            server_iv,
+        })
+    }
+}
+
+/// Derives the AES-128-GCM write key and IV from a traffic secret.
+fn traffic_key_iv(
+    mode: Mode,
+    vm: &mut dyn Vm<Binary>,
+    stage: Stage,
+    secret: Array<U8, 32>,
+    pending: &mut Vec<PublicHmac>,
+) -> Result<(Array<U8, 16>, Array<U8, 12>), PrfError> {
+    let secret = HmacKey::alloc(vm, secret.into())?;
+
+    let key_msg = PublicMsg::Fixed(hkdf_label(b"key", &[], 16));
+    let mut key: Vector<U8> = secret
+        .sign_public(mode, vm, stage, key_msg, pending)?
+        .into();
+    key.truncate(16);
+
+    let iv_msg = PublicMsg::Fixed(hkdf_label(b"iv", &[], 12));
+    let mut iv: Vector<U8> = secret.sign_public(mode, vm, stage, iv_msg, pending)?.into();
+    iv.truncate(12);
+
+    Ok((
+        Array::<U8, 16>::try_from(key).unwrap(),
+        Array::<U8, 12>::try_from(iv).unwrap(),
+    ))
+}
+
+/// Returns the HKDF-Expand input for the first output block of
+/// HKDF-Expand-Label.
+pub(crate) fn hkdf_label(label: &[u8], context: &[u8], len: u16) -> Vec<u8> {
+    let mut info = Vec::with_capacity(10 + label.len() + context.len());
+    info.extend_from_slice(&len.to_be_bytes());
+    info.push((6 + label.len()) as u8);
+    info.extend_from_slice(b"tls13 ");
+    info.extend_from_slice(label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+    info.push(0x01);
+    info
+}
+
+fn alloc_public(vm: &mut dyn Vm<Binary>, value: Vec<u8>) -> Result<Vector<U8>, PrfError> {
+    let msg: Vector<U8> = vm.alloc_vec(value.len()).map_err(PrfError::vm)?;
+    vm.mark_public(msg).map_err(PrfError::vm)?;
+    vm.assign(msg, value).map_err(PrfError::vm)?;
+    vm.commit(msg).map_err(PrfError::vm)?;
+
+    Ok(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{hkdf_expand_label, hkdf_extract, mock_vm};
+    use mpz_common::context::test_st_context;
+    use mpz_vm_core::Execute;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_constants() {
+        let early_secret = hkdf_extract(&[0; 32], &[0; 32]);
+        assert_eq!(
+            hkdf_expand_label(&early_secret, b"derived", &EMPTY_HASH, 32),
+            DERIVED_EARLY_SECRET
+        );
+    }
+
+    #[tokio::test]
+    async fn test_key_schedule_reduced() {
+        test_key_schedule(Mode::Reduced).await;
+    }
+
+    #[tokio::test]
+    async fn test_key_schedule_normal() {
+        test_key_schedule(Mode::Normal).await;
+    }
+
+    async fn test_key_schedule(mode: Mode) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let pms: [u8; 32] = rng.random();
+        let hs_hash: [u8; 32] = rng.random();
+        let sf_hash: [u8; 32] = rng.random();
+
+        // Expected output
+        let hs = hkdf_extract(&DERIVED_EARLY_SECRET, &pms);
+        let c_hs = hkdf_expand_label(&hs, b"c hs traffic", &hs_hash, 32);
+        let s_hs = hkdf_expand_label(&hs, b"s hs traffic", &hs_hash, 32);
+        let ms = hkdf_extract(
+            &hkdf_expand_label(&hs, b"derived", &EMPTY_HASH, 32),
+            &[0; 32],
+        );
+        let c_ap = hkdf_expand_label(&ms, b"c ap traffic", &sf_hash, 32);
+        let s_ap = hkdf_expand_label(&ms, b"s ap traffic", &sf_hash, 32);
+        let hs_cwk_expected = hkdf_expand_label(&c_hs, b"key", &[], 16);
+        let hs_civ_expected = hkdf_expand_label(&c_hs, b"iv", &[], 12);
+        let hs_swk_expected = hkdf_expand_label(&s_hs, b"key", &[], 16);
+        let hs_siv_expected = hkdf_expand_label(&s_hs, b"iv", &[], 12);
+        let cwk_expected = hkdf_expand_label(&c_ap, b"key", &[], 16);
+        let civ_expected = hkdf_expand_label(&c_ap, b"iv", &[], 12);
+        let swk_expected = hkdf_expand_label(&s_ap, b"key", &[], 16);
+        let siv_expected = hkdf_expand_label(&s_ap, b"iv", &[], 12);
+
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut leader, mut follower) = mock_vm();
+
+        let leader_pms: Array<U8, 32> = leader.alloc().unwrap();
+        leader.mark_public(leader_pms).unwrap();
+        leader.assign(leader_pms, pms).unwrap();
+        leader.commit(leader_pms).unwrap();
+
+        let follower_pms: Array<U8, 32> = follower.alloc().unwrap();
+        follower.mark_public(follower_pms).unwrap();
+        follower.assign(follower_pms, pms).unwrap();
+        follower.commit(follower_pms).unwrap();
+
+        let mut ks_leader = MpcKeySchedule::new(mode);
+        let mut ks_follower = MpcKeySchedule::new(mode);
+
+        let leader_out = ks_leader.alloc(&mut leader, leader_pms.into()).unwrap();
+        let follower_out = ks_follower
+            .alloc(&mut follower, follower_pms.into())
+            .unwrap();
+
+        // handshake secrets
+        ks_leader.set_hs_hash(hs_hash).unwrap();
+        ks_follower.set_hs_hash(hs_hash).unwrap();
+
+        let mut c_hs_leader = leader.decode(leader_out.handshake.client).unwrap();
+        let mut s_hs_leader = leader.decode(leader_out.handshake.server).unwrap();
+        let mut c_hs_follower = follower.decode(follower_out.handshake.client).unwrap();
+        let mut s_hs_follower = follower.decode(follower_out.handshake.server).unwrap();
+
+        let hs_keys_leader = decode_keys(&mut leader, leader_out.handshake_keys);
+        let hs_keys_follower = decode_keys(&mut follower, follower_out.handshake_keys);
+
+        while ks_leader.wants_flush() || ks_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    ks_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    ks_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        let c_hs_leader = c_hs_leader.try_recv().unwrap().unwrap();
+        let s_hs_leader = s_hs_leader.try_recv().unwrap().unwrap();
+        let c_hs_follower = c_hs_follower.try_recv().unwrap().unwrap();
+        let s_hs_follower = s_hs_follower.try_recv().unwrap().unwrap();
+
+        assert_eq!(c_hs_leader, c_hs_follower);
+        assert_eq!(s_hs_leader, s_hs_follower);
+        assert_eq!(c_hs_leader.to_vec(), c_hs);
+        assert_eq!(s_hs_leader.to_vec(), s_hs);
+
+        let hs_keys_leader = recv_keys(hs_keys_leader);
+        let hs_keys_follower = recv_keys(hs_keys_follower);
+
+        assert_eq!(hs_keys_leader, hs_keys_follower);
+        assert_eq!(
+            hs_keys_leader,
+            (
+                hs_cwk_expected,
+                hs_swk_expected,
+                hs_civ_expected,
+                hs_siv_expected
+            )
+        );
+
+        // application keys
+        ks_leader.set_server_finished_hash(sf_hash).unwrap();
+        ks_follower.set_server_finished_hash(sf_hash).unwrap();
+
+        let keys_leader = decode_keys(&mut leader, leader_out.keys);
+        let keys_follower = decode_keys(&mut follower, follower_out.keys);
+
+        while ks_leader.wants_flush() || ks_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    ks_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    ks_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        let keys_leader = recv_keys(keys_leader);
+        let keys_follower = recv_keys(keys_follower);
+
+        assert_eq!(keys_leader, keys_follower);
+        assert_eq!(
+            keys_leader,
+            (cwk_expected, swk_expected, civ_expected, siv_expected)
+        );
+    }
+
+    type DecodedKeys = (
+        DecodeFutureTyped<BitVec, [u8; 16]>,
+        DecodeFutureTyped<BitVec, [u8; 16]>,
+        DecodeFutureTyped<BitVec, [u8; 12]>,
+        DecodeFutureTyped<BitVec, [u8; 12]>,
+    );
+
+    fn decode_keys(vm: &mut dyn Vm<Binary>, keys: TrafficKeys) -> DecodedKeys {
+        (
+            vm.decode(keys.client_write_key).unwrap(),
+            vm.decode(keys.server_write_key).unwrap(),
+            vm.decode(keys.client_iv).unwrap(),
+            vm.decode(keys.server_iv).unwrap(),
+        )
+    }
+
+    fn recv_keys(keys: DecodedKeys) -> (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>) {
+        let (mut cwk, mut swk, mut civ, mut siv) = keys;
+        (
+            cwk.try_recv().unwrap().unwrap().to_vec(),
+            swk.try_recv().unwrap().unwrap().to_vec(),
+            civ.try_recv().unwrap().unwrap().to_vec(),
+            siv.try_recv().unwrap().unwrap().to_vec(),
+        )
+    }
+}
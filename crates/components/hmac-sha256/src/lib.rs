@@ -1,15 +1,17 @@
-//! This crate contains the protocol for computing TLS 1.2 SHA-256 HMAC PRF.
+//! This crate contains the protocols for computing the TLS 1.2 HMAC PRF, with
+//! either SHA-256 or SHA-384, and the TLS 1.3 HKDF-SHA256 key schedule.
 
 #![deny(missing_docs, unreachable_pub, unused_must_use)]
 #![deny(clippy::all)]
 #![forbid(unsafe_code)]
 
 mod hmac;
+mod sha384;
 #[cfg(test)]
 mod test_utils;
 
 mod config;
-pub use config::Mode;
+pub use config::{Mode, PrfHash};
 
 mod error;
 pub use error::PrfError;
@@ -17,7 +19,10 @@ pub use error::PrfError;
 mod prf;
 pub use prf::MpcPrf;
 
-use mpz_vm_core::memory::{binary::U8, Array};
+mod key_schedule;
+pub use key_schedule::MpcKeySchedule;
+
+use mpz_vm_core::memory::{binary::U8, Array, Vector};
 
 /// PRF output.
 #[derive(Debug, Clone, Copy)]
@@ -31,16 +36,51 @@ pub struct PrfOutput {
 }
 
 /// Session keys computed by the PRF.
+///
+/// The length of the write keys and IVs depends on the cipher suite.
 #[derive(Debug, Clone, Copy)]
 pub struct SessionKeys {
+    /// Client write key.
+    pub client_write_key: Vector<U8>,
+    /// Server write key.
+    pub server_write_key: Vector<U8>,
+    /// Client IV.
+    pub client_iv: Vector<U8>,
+    /// Server IV.
+    pub server_iv: Vector<U8>,
+}
+
+/// TLS 1.3 key schedule output.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyScheduleOutput {
+    /// Handshake traffic secrets.
+    pub handshake: TrafficSecrets,
+    /// Handshake traffic keys.
+    pub handshake_keys: TrafficKeys,
+    /// Application traffic keys.
+    pub keys: TrafficKeys,
+}
+
+/// Client and server traffic secrets.
+#[derive(Debug, Clone, Copy)]
+pub struct TrafficSecrets {
+    /// Client traffic secret.
+    pub client: Array<U8, 32>,
+    /// Server traffic secret.
+    pub server: Array<U8, 32>,
+}
+
+/// Traffic keys computed by the key schedule.
+#[derive(Debug, Clone, Copy)]
+pub struct TrafficKeys {
     /// Client write key.
     pub client_write_key: Array<U8, 16>,
     /// Server write key.
     pub server_write_key: Array<U8, 16>,
     /// Client IV.
-    pub client_iv: Array<U8, 4>,
+    pub client_iv: Array<U8, 12>,
     /// Server IV.
-    pub server_iv: Array<U8, 4>,
+    pub server_iv: Array<U8, 12>,
 }
 
 fn sha256(mut state: [u32; 8], pos: usize, msg: &[u8]) -> [u32; 8] {
@@ -72,12 +112,12 @@ fn state_to_bytes(input: [u32; 8]) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
     use crate::{
-        test_utils::{mock_vm, prf_cf_vd, prf_keys, prf_ms, prf_sf_vd},
-        Mode, MpcPrf, SessionKeys,
+        test_utils::{mock_vm, prf_cf_vd, prf_ems, prf_keys, prf_ms, prf_sf_vd, prf_sha384},
+        Mode, MpcPrf, PrfHash, SessionKeys,
     };
     use mpz_common::context::test_st_context;
     use mpz_vm_core::{
-        memory::{binary::U8, Array, MemoryExt, ViewExt},
+        memory::{binary::U8, Array, MemoryExt, Vector, ViewExt},
         Execute,
     };
     use rand::{rngs::StdRng, Rng, SeedableRng};
@@ -85,27 +125,50 @@ mod tests {
     #[tokio::test]
     async fn test_prf_reduced() {
         let mode = Mode::Reduced;
-        test_prf(mode).await;
+        test_prf(mode, false, 32).await;
     }
 
     #[tokio::test]
     async fn test_prf_normal() {
         let mode = Mode::Normal;
-        test_prf(mode).await;
+        test_prf(mode, false, 32).await;
     }
 
-    async fn test_prf(mode: Mode) {
+    #[tokio::test]
+    async fn test_prf_ems_reduced() {
+        let mode = Mode::Reduced;
+        test_prf(mode, true, 32).await;
+    }
+
+    #[tokio::test]
+    async fn test_prf_ems_normal() {
+        let mode = Mode::Normal;
+        test_prf(mode, true, 32).await;
+    }
+
+    #[tokio::test]
+    async fn test_prf_pms_48_bytes() {
+        let mode = Mode::Normal;
+        test_prf(mode, false, 48).await;
+    }
+
+    async fn test_prf(mode: Mode, ems: bool, pms_len: usize) {
         let mut rng = StdRng::seed_from_u64(1);
         // Test input
-        let pms: [u8; 32] = rng.random();
+        let pms: Vec<u8> = (0..pms_len).map(|_| rng.random()).collect();
         let client_random: [u8; 32] = rng.random();
         let server_random: [u8; 32] = rng.random();
+        let session_hash: [u8; 32] = rng.random();
 
         let cf_hs_hash: [u8; 32] = rng.random();
         let sf_hs_hash: [u8; 32] = rng.random();
 
         // Expected output
-        let ms_expected = prf_ms(pms, client_random, server_random);
+        let ms_expected = if ems {
+            prf_ems(&pms, session_hash)
+        } else {
+            prf_ms(&pms, client_random, server_random)
+        };
 
         let [cwk_expected, swk_expected, civ_expected, siv_expected] =
             prf_keys(ms_expected, client_random, server_random);
@@ -125,18 +188,21 @@ mod tests {
         let (mut ctx_a, mut ctx_b) = test_st_context(128);
         let (mut leader, mut follower) = mock_vm();
 
-        let leader_pms: Array<U8, 32> = leader.alloc().unwrap();
+        let leader_pms: Vector<U8> = leader.alloc_vec(pms_len).unwrap();
         leader.mark_public(leader_pms).unwrap();
-        leader.assign(leader_pms, pms).unwrap();
+        leader.assign(leader_pms, pms.clone()).unwrap();
         leader.commit(leader_pms).unwrap();
 
-        let follower_pms: Array<U8, 32> = follower.alloc().unwrap();
+        let follower_pms: Vector<U8> = follower.alloc_vec(pms_len).unwrap();
         follower.mark_public(follower_pms).unwrap();
         follower.assign(follower_pms, pms).unwrap();
         follower.commit(follower_pms).unwrap();
 
-        let mut prf_leader = MpcPrf::new(mode);
-        let mut prf_follower = MpcPrf::new(mode);
+        let (mut prf_leader, mut prf_follower) = if ems {
+            (MpcPrf::new_ems(mode), MpcPrf::new_ems(mode))
+        } else {
+            (MpcPrf::new(mode), MpcPrf::new(mode))
+        };
 
         let leader_prf_out = prf_leader.alloc(&mut leader, leader_pms).unwrap();
         let follower_prf_out = prf_follower.alloc(&mut follower, follower_pms).unwrap();
@@ -148,6 +214,11 @@ mod tests {
         prf_leader.set_server_random(server_random).unwrap();
         prf_follower.set_server_random(server_random).unwrap();
 
+        if ems {
+            prf_leader.set_session_hash(&session_hash).unwrap();
+            prf_follower.set_session_hash(&session_hash).unwrap();
+        }
+
         let SessionKeys {
             client_write_key: cwk_leader,
             server_write_key: swk_leader,
@@ -207,8 +278,8 @@ mod tests {
         assert_eq!(siv_leader, siv_expected);
 
         // client finished
-        prf_leader.set_cf_hash(cf_hs_hash).unwrap();
-        prf_follower.set_cf_hash(cf_hs_hash).unwrap();
+        prf_leader.set_cf_hash(&cf_hs_hash).unwrap();
+        prf_follower.set_cf_hash(&cf_hs_hash).unwrap();
 
         let cf_vd_leader = leader_prf_out.cf_vd;
         let cf_vd_follower = follower_prf_out.cf_vd;
@@ -237,8 +308,8 @@ mod tests {
         assert_eq!(cf_vd_leader, cf_vd_expected);
 
         // server finished
-        prf_leader.set_sf_hash(sf_hs_hash).unwrap();
-        prf_follower.set_sf_hash(sf_hs_hash).unwrap();
+        prf_leader.set_sf_hash(&sf_hs_hash).unwrap();
+        prf_follower.set_sf_hash(&sf_hs_hash).unwrap();
 
         let sf_vd_leader = leader_prf_out.sf_vd;
         let sf_vd_follower = follower_prf_out.sf_vd;
@@ -266,4 +337,181 @@ mod tests {
         assert_eq!(sf_vd_leader, sf_vd_follower);
         assert_eq!(sf_vd_leader, sf_vd_expected);
     }
+
+    #[tokio::test]
+    async fn test_prf_sha384_reduced() {
+        let mode = Mode::Reduced;
+        test_prf_sha384(mode, false).await;
+    }
+
+    #[tokio::test]
+    async fn test_prf_sha384_ems_normal() {
+        let mode = Mode::Normal;
+        test_prf_sha384(mode, true).await;
+    }
+
+    async fn test_prf_sha384(mode: Mode, ems: bool) {
+        let mut rng = StdRng::seed_from_u64(2);
+        // Test input
+        let pms: [u8; 32] = rng.random();
+        let client_random: [u8; 32] = rng.random();
+        let server_random: [u8; 32] = rng.random();
+        let session_hash: [u8; 48] = rng.random();
+
+        let cf_hs_hash: [u8; 48] = rng.random();
+        let sf_hs_hash: [u8; 48] = rng.random();
+
+        // Expected output
+        let ms_expected = if ems {
+            prf_sha384(&pms, b"extended master secret", &session_hash, 48)
+        } else {
+            let seed = [client_random, server_random].concat();
+            prf_sha384(&pms, b"master secret", &seed, 48)
+        };
+
+        let seed = [server_random, client_random].concat();
+        let mut keys_expected = prf_sha384(&ms_expected, b"key expansion", &seed, 72);
+        let siv_expected = keys_expected.split_off(68);
+        let civ_expected = keys_expected.split_off(64);
+        let swk_expected = keys_expected.split_off(32);
+        let cwk_expected = keys_expected;
+
+        let cf_vd_expected = prf_sha384(&ms_expected, b"client finished", &cf_hs_hash, 12);
+        let sf_vd_expected = prf_sha384(&ms_expected, b"server finished", &sf_hs_hash, 12);
+
+        // Set up vm and prf
+        let (mut ctx_a, mut ctx_b) = test_st_context(128);
+        let (mut leader, mut follower) = mock_vm();
+
+        let leader_pms: Array<U8, 32> = leader.alloc().unwrap();
+        leader.mark_public(leader_pms).unwrap();
+        leader.assign(leader_pms, pms).unwrap();
+        leader.commit(leader_pms).unwrap();
+
+        let follower_pms: Array<U8, 32> = follower.alloc().unwrap();
+        follower.mark_public(follower_pms).unwrap();
+        follower.assign(follower_pms, pms).unwrap();
+        follower.commit(follower_pms).unwrap();
+
+        let new_prf = || {
+            let prf = if ems {
+                MpcPrf::new_ems(mode)
+            } else {
+                MpcPrf::new(mode)
+            };
+            prf.with_hash(PrfHash::Sha384).with_key_len(32)
+        };
+        let (mut prf_leader, mut prf_follower) = (new_prf(), new_prf());
+
+        let leader_prf_out = prf_leader.alloc(&mut leader, leader_pms.into()).unwrap();
+        let follower_prf_out = prf_follower
+            .alloc(&mut follower, follower_pms.into())
+            .unwrap();
+
+        prf_leader.set_client_random(client_random).unwrap();
+        prf_follower.set_client_random(client_random).unwrap();
+
+        prf_leader.set_server_random(server_random).unwrap();
+        prf_follower.set_server_random(server_random).unwrap();
+
+        if ems {
+            // A SHA-256 session hash is rejected.
+            assert!(prf_leader.set_session_hash(&[0; 32]).is_err());
+
+            prf_leader.set_session_hash(&session_hash).unwrap();
+            prf_follower.set_session_hash(&session_hash).unwrap();
+        }
+
+        let mut keys_leader = [
+            leader_prf_out.keys.client_write_key,
+            leader_prf_out.keys.server_write_key,
+            leader_prf_out.keys.client_iv,
+            leader_prf_out.keys.server_iv,
+        ]
+        .map(|key| leader.decode(key).unwrap());
+        let mut keys_follower = [
+            follower_prf_out.keys.client_write_key,
+            follower_prf_out.keys.server_write_key,
+            follower_prf_out.keys.client_iv,
+            follower_prf_out.keys.server_iv,
+        ]
+        .map(|key| follower.decode(key).unwrap());
+
+        while prf_leader.wants_flush() || prf_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    prf_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    prf_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        prf_leader.set_cf_hash(&cf_hs_hash).unwrap();
+        prf_follower.set_cf_hash(&cf_hs_hash).unwrap();
+
+        let mut cf_vd_leader = leader.decode(leader_prf_out.cf_vd).unwrap();
+        let mut cf_vd_follower = follower.decode(follower_prf_out.cf_vd).unwrap();
+
+        while prf_leader.wants_flush() || prf_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    prf_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    prf_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        prf_leader.set_sf_hash(&sf_hs_hash).unwrap();
+        prf_follower.set_sf_hash(&sf_hs_hash).unwrap();
+
+        let mut sf_vd_leader = leader.decode(leader_prf_out.sf_vd).unwrap();
+        let mut sf_vd_follower = follower.decode(follower_prf_out.sf_vd).unwrap();
+
+        while prf_leader.wants_flush() || prf_follower.wants_flush() {
+            tokio::try_join!(
+                async {
+                    prf_leader.flush(&mut leader).unwrap();
+                    leader.execute_all(&mut ctx_a).await
+                },
+                async {
+                    prf_follower.flush(&mut follower).unwrap();
+                    follower.execute_all(&mut ctx_b).await
+                }
+            )
+            .unwrap();
+        }
+
+        let keys_leader = keys_leader
+            .each_mut()
+            .map(|key| key.try_recv().unwrap().unwrap());
+        let keys_follower = keys_follower
+            .each_mut()
+            .map(|key| key.try_recv().unwrap().unwrap());
+
+        assert_eq!(keys_leader, keys_follower);
+        assert_eq!(
+            keys_leader,
+            [cwk_expected, swk_expected, civ_expected, siv_expected]
+        );
+
+        let cf_vd_leader = cf_vd_leader.try_recv().unwrap().unwrap();
+        let cf_vd_follower = cf_vd_follower.try_recv().unwrap().unwrap();
+        let sf_vd_leader = sf_vd_leader.try_recv().unwrap().unwrap();
+        let sf_vd_follower = sf_vd_follower.try_recv().unwrap().unwrap();
+
+        assert_eq!(cf_vd_leader, cf_vd_follower);
+        assert_eq!(cf_vd_leader.to_vec(), cf_vd_expected);
+        assert_eq!(sf_vd_leader, sf_vd_follower);
+        assert_eq!(sf_vd_leader.to_vec(), sf_vd_expected);
+    }
 }
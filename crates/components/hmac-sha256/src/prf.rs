@@ -1,13 +1,14 @@
 use crate::{
-    hmac::{IPAD, OPAD},
-    Mode, PrfError, PrfOutput,
+    hmac::{HmacPartials, IPAD, IPAD_SHA384, OPAD, OPAD_SHA384},
+    sha384::Sha384,
+    Mode, PrfError, PrfHash, PrfOutput,
 };
 use mpz_circuits::{circuits::xor, Circuit, CircuitBuilder};
 use mpz_hash::sha256::Sha256;
 use mpz_vm_core::{
     memory::{
         binary::{Binary, U8},
-        Array, MemoryExt, StaticSize, Vector, ViewExt,
+        MemoryExt, Vector, ViewExt,
     },
     Call, CallableExt, Vm,
 };
@@ -21,9 +22,16 @@ mod function;
 use function::Prf;
 
 /// MPC PRF for computing TLS 1.2 HMAC-SHA256 PRF.
+///
+/// The HMAC-SHA384 PRF of the `*_SHA384` cipher suites can be selected with
+/// [`MpcPrf::with_hash`].
 #[derive(Debug)]
 pub struct MpcPrf {
     mode: Mode,
+    ems: bool,
+    hash: PrfHash,
+    key_len: usize,
+    iv_len: usize,
     state: State,
 }
 
@@ -36,10 +44,59 @@ impl MpcPrf {
     pub fn new(mode: Mode) -> MpcPrf {
         Self {
             mode,
+            ems: false,
+            hash: PrfHash::default(),
+            key_len: 16,
+            iv_len: 4,
             state: State::Initialized,
         }
     }
 
+    /// Creates a new instance of the PRF which derives the extended master
+    /// secret from the session hash, see RFC 7627.
+    ///
+    /// # Arguments
+    ///
+    /// `mode` - The PRF mode.
+    pub fn new_ems(mode: Mode) -> MpcPrf {
+        Self {
+            ems: true,
+            ..Self::new(mode)
+        }
+    }
+
+    /// Sets the hash function of the PRF, defaults to SHA-256.
+    ///
+    /// # Arguments
+    ///
+    /// `hash` - The hash function.
+    pub fn with_hash(mut self, hash: PrfHash) -> MpcPrf {
+        self.hash = hash;
+        self
+    }
+
+    /// Sets the length of the client and server write keys in bytes, defaults
+    /// to 16.
+    ///
+    /// # Arguments
+    ///
+    /// `key_len` - The key length.
+    pub fn with_key_len(mut self, key_len: usize) -> MpcPrf {
+        self.key_len = key_len;
+        self
+    }
+
+    /// Sets the length of the client and server write IVs in bytes, defaults
+    /// to 4.
+    ///
+    /// # Arguments
+    ///
+    /// `iv_len` - The IV length.
+    pub fn with_iv_len(mut self, iv_len: usize) -> MpcPrf {
+        self.iv_len = iv_len;
+        self
+    }
+
     /// Allocates resources for the PRF.
     ///
     /// # Arguments
@@ -50,40 +107,35 @@ impl MpcPrf {
     pub fn alloc(
         &mut self,
         vm: &mut dyn Vm<Binary>,
-        pms: Array<U8, 32>,
+        pms: Vector<U8>,
     ) -> Result<PrfOutput, PrfError> {
         let State::Initialized = self.state.take() else {
             return Err(PrfError::state("PRF not in initialized state"));
         };
 
         let mode = self.mode;
-        let pms: Vector<U8> = pms.into();
 
-        let outer_partial_pms = compute_partial(vm, pms, OPAD)?;
-        let inner_partial_pms = compute_partial(vm, pms, IPAD)?;
+        let partials_pms = compute_partials(vm, pms, self.hash)?;
 
-        let master_secret =
-            Prf::alloc_master_secret(mode, vm, outer_partial_pms, inner_partial_pms)?;
+        let master_secret = if self.ems {
+            Prf::alloc_extended_master_secret(mode, vm, partials_pms)?
+        } else {
+            Prf::alloc_master_secret(mode, vm, partials_pms)?
+        };
         let ms = master_secret.output();
         let ms = merge_outputs(vm, ms, 48)?;
 
-        let outer_partial_ms = compute_partial(vm, ms, OPAD)?;
-        let inner_partial_ms = compute_partial(vm, ms, IPAD)?;
+        let partials_ms = compute_partials(vm, ms, self.hash)?;
 
-        let key_expansion =
-            Prf::alloc_key_expansion(mode, vm, outer_partial_ms.clone(), inner_partial_ms.clone())?;
-        let client_finished = Prf::alloc_client_finished(
-            mode,
-            vm,
-            outer_partial_ms.clone(),
-            inner_partial_ms.clone(),
-        )?;
-        let server_finished = Prf::alloc_server_finished(
+        // Client and server write keys and IVs.
+        let key_expansion = Prf::alloc_key_expansion(
             mode,
             vm,
-            outer_partial_ms.clone(),
-            inner_partial_ms.clone(),
+            partials_ms.clone(),
+            2 * self.key_len + 2 * self.iv_len,
         )?;
+        let client_finished = Prf::alloc_client_finished(mode, vm, partials_ms.clone())?;
+        let server_finished = Prf::alloc_server_finished(mode, vm, partials_ms)?;
 
         self.state = State::SessionKeys {
             client_random: None,
@@ -93,7 +145,7 @@ impl MpcPrf {
             server_finished,
         };
 
-        self.state.prf_output(vm)
+        self.state.prf_output(vm, self.key_len, self.iv_len)
     }
 
     /// Sets the client random.
@@ -131,9 +183,12 @@ impl MpcPrf {
         let client_random = client_random.expect("Client random should have been set by now");
         let server_random = random;
 
-        let mut seed_ms = client_random.to_vec();
-        seed_ms.extend_from_slice(&server_random);
-        master_secret.set_start_seed(seed_ms);
+        // The extended master secret is derived from the session hash instead.
+        if !self.ems {
+            let mut seed_ms = client_random.to_vec();
+            seed_ms.extend_from_slice(&server_random);
+            master_secret.set_start_seed(seed_ms);
+        }
 
         let mut seed_ke = server_random.to_vec();
         seed_ke.extend_from_slice(&client_random);
@@ -142,13 +197,41 @@ impl MpcPrf {
         Ok(())
     }
 
+    /// Sets the session hash, which is the handshake hash up to and including
+    /// the ClientKeyExchange message.
+    ///
+    /// Only used for the extended master secret.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_hash` - The session hash.
+    #[instrument(level = "debug", skip_all, err)]
+    pub fn set_session_hash(&mut self, session_hash: &[u8]) -> Result<(), PrfError> {
+        if !self.ems {
+            return Err(PrfError::state(
+                "PRF does not derive the extended master secret",
+            ));
+        }
+        self.check_hash_len(session_hash)?;
+
+        let State::SessionKeys { master_secret, .. } = &mut self.state else {
+            return Err(PrfError::state("PRF not set up"));
+        };
+
+        master_secret.set_start_seed(session_hash.to_vec());
+
+        Ok(())
+    }
+
     /// Sets the client finished handshake hash.
     ///
     /// # Arguments
     ///
     /// * `handshake_hash` - The handshake transcript hash.
     #[instrument(level = "debug", skip_all, err)]
-    pub fn set_cf_hash(&mut self, handshake_hash: [u8; 32]) -> Result<(), PrfError> {
+    pub fn set_cf_hash(&mut self, handshake_hash: &[u8]) -> Result<(), PrfError> {
+        self.check_hash_len(handshake_hash)?;
+
         let State::ClientFinished {
             client_finished, ..
         } = &mut self.state
@@ -168,7 +251,9 @@ impl MpcPrf {
     ///
     /// * `handshake_hash` - The handshake transcript hash.
     #[instrument(level = "debug", skip_all, err)]
-    pub fn set_sf_hash(&mut self, handshake_hash: [u8; 32]) -> Result<(), PrfError> {
+    pub fn set_sf_hash(&mut self, handshake_hash: &[u8]) -> Result<(), PrfError> {
+        self.check_hash_len(handshake_hash)?;
+
         let State::ServerFinished { server_finished } = &mut self.state else {
             return Err(PrfError::state("PRF not in server finished state"));
         };
@@ -179,6 +264,18 @@ impl MpcPrf {
         Ok(())
     }
 
+    fn check_hash_len(&self, hash: &[u8]) -> Result<(), PrfError> {
+        if hash.len() != self.hash.output_len() {
+            return Err(PrfError::state(format!(
+                "expected a {} byte hash, got {} bytes",
+                self.hash.output_len(),
+                hash.len()
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Returns if the PRF needs to be flushed.
     pub fn wants_flush(&self) -> bool {
         match &self.state {
@@ -258,6 +355,32 @@ impl MpcPrf {
     }
 }
 
+/// Computes the outer and inner partial hashes of an HMAC key.
+///
+/// # Arguments
+///
+/// * `vm` - Virtual machine.
+/// * `key` - HMAC key.
+/// * `hash` - Hash function of the HMAC.
+pub(crate) fn compute_partials(
+    vm: &mut dyn Vm<Binary>,
+    key: Vector<U8>,
+    hash: PrfHash,
+) -> Result<HmacPartials, PrfError> {
+    let partials = match hash {
+        PrfHash::Sha256 => HmacPartials::Sha256 {
+            outer: compute_partial(vm, key, OPAD)?,
+            inner: compute_partial(vm, key, IPAD)?,
+        },
+        PrfHash::Sha384 => HmacPartials::Sha384 {
+            outer: compute_partial_sha384(vm, key, OPAD_SHA384)?,
+            inner: compute_partial_sha384(vm, key, IPAD_SHA384)?,
+        },
+    };
+
+    Ok(partials)
+}
+
 /// Depending on the provided `mask` computes and returns `outer_partial` or
 /// `inner_partial` for HMAC-SHA256.
 ///
@@ -266,14 +389,45 @@ impl MpcPrf {
 /// * `vm` - Virtual machine.
 /// * `key` - Key to pad and xor.
 /// * `mask`- Mask used for padding.
-fn compute_partial(
+pub(crate) fn compute_partial(
     vm: &mut dyn Vm<Binary>,
     key: Vector<U8>,
     mask: [u8; 64],
 ) -> Result<Sha256, PrfError> {
-    let xor = Arc::new(xor(8 * 64));
+    let key_padded = pad_key(vm, key, &mask)?;
 
-    let additional_len = 64 - key.len();
+    let mut sha = Sha256::new_with_init(vm)?;
+    sha.update(&key_padded);
+    sha.compress(vm)?;
+    Ok(sha)
+}
+
+/// Depending on the provided `mask` computes and returns `outer_partial` or
+/// `inner_partial` for HMAC-SHA384.
+///
+/// # Arguments
+///
+/// * `vm` - Virtual machine.
+/// * `key` - Key to pad and xor.
+/// * `mask`- Mask used for padding.
+fn compute_partial_sha384(
+    vm: &mut dyn Vm<Binary>,
+    key: Vector<U8>,
+    mask: [u8; 128],
+) -> Result<Sha384, PrfError> {
+    let key_padded = pad_key(vm, key, &mask)?;
+
+    let mut sha = Sha384::new_with_init(vm)?;
+    sha.update(&key_padded);
+    sha.compress(vm)?;
+    Ok(sha)
+}
+
+/// Pads `key` with zero bytes to the length of `mask` and xors it with `mask`.
+fn pad_key(vm: &mut dyn Vm<Binary>, key: Vector<U8>, mask: &[u8]) -> Result<Vector<U8>, PrfError> {
+    let xor = Arc::new(xor(8 * mask.len()));
+
+    let additional_len = mask.len() - key.len();
     let padding = vec![0_u8; additional_len];
 
     let padding_ref: Vector<U8> = vm.alloc_vec(additional_len).map_err(PrfError::vm)?;
@@ -281,9 +435,9 @@ fn compute_partial(
     vm.assign(padding_ref, padding).map_err(PrfError::vm)?;
     vm.commit(padding_ref).map_err(PrfError::vm)?;
 
-    let mask_ref: Array<U8, 64> = vm.alloc().map_err(PrfError::vm)?;
+    let mask_ref: Vector<U8> = vm.alloc_vec(mask.len()).map_err(PrfError::vm)?;
     vm.mark_public(mask_ref).map_err(PrfError::vm)?;
-    vm.assign(mask_ref, mask).map_err(PrfError::vm)?;
+    vm.assign(mask_ref, mask.to_vec()).map_err(PrfError::vm)?;
     vm.commit(mask_ref).map_err(PrfError::vm)?;
 
     let xor = Call::builder(xor)
@@ -294,21 +448,18 @@ fn compute_partial(
         .map_err(PrfError::vm)?;
     let key_padded: Vector<U8> = vm.call(xor).map_err(PrfError::vm)?;
 
-    let mut sha = Sha256::new_with_init(vm)?;
-    sha.update(&key_padded);
-    sha.compress(vm)?;
-    Ok(sha)
+    Ok(key_padded)
 }
 
 fn merge_outputs(
     vm: &mut dyn Vm<Binary>,
-    inputs: Vec<Array<U8, 32>>,
+    inputs: Vec<Vector<U8>>,
     output_bytes: usize,
 ) -> Result<Vector<U8>, PrfError> {
-    assert!(output_bytes <= 32 * inputs.len());
+    let input_bytes: usize = inputs.iter().map(|input| input.len()).sum();
+    assert!(output_bytes <= input_bytes);
 
-    let bits = Array::<U8, 32>::SIZE * inputs.len();
-    let circ = gen_merge_circ(bits);
+    let circ = gen_merge_circ(8 * input_bytes);
 
     let mut builder = Call::builder(circ);
     for &input in inputs.iter() {
@@ -371,8 +522,12 @@ mod tests {
         leader.commit(input1_leader).unwrap();
         leader.commit(input2_leader).unwrap();
 
-        let merged_leader =
-            merge_outputs(&mut leader, vec![input1_leader, input2_leader], 48).unwrap();
+        let merged_leader = merge_outputs(
+            &mut leader,
+            vec![input1_leader.into(), input2_leader.into()],
+            48,
+        )
+        .unwrap();
         let mut merged_leader = leader.decode(merged_leader).unwrap();
 
         // follower
@@ -388,8 +543,12 @@ mod tests {
         follower.commit(input1_follower).unwrap();
         follower.commit(input2_follower).unwrap();
 
-        let merged_follower =
-            merge_outputs(&mut follower, vec![input1_follower, input2_follower], 48).unwrap();
+        let merged_follower = merge_outputs(
+            &mut follower,
+            vec![input1_follower.into(), input2_follower.into()],
+            48,
+        )
+        .unwrap();
         let mut merged_follower = follower.decode(merged_follower).unwrap();
 
         tokio::try_join!(
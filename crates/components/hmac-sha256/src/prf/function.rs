@@ -1,42 +1,61 @@
 //! Provides [`Prf`], for computing the TLS 1.2 PRF.
 
-use crate::{Mode, PrfError};
-use mpz_hash::sha256::Sha256;
+use crate::{hmac::HmacPartials, Mode, PrfError};
 use mpz_vm_core::{
     memory::{
         binary::{Binary, U8},
-        Array,
+        Vector,
     },
     Vm,
 };
 
 mod normal;
 mod reduced;
+mod sha384;
 
 #[derive(Debug)]
 pub(crate) enum Prf {
     Reduced(reduced::PrfFunction),
     Normal(normal::PrfFunction),
+    /// The SHA-384 PRF is always computed in MPC.
+    Sha384(sha384::PrfFunction),
 }
 
 impl Prf {
     pub(crate) fn alloc_master_secret(
         mode: Mode,
         vm: &mut dyn Vm<Binary>,
-        outer_partial: Sha256,
-        inner_partial: Sha256,
+        partials: HmacPartials,
     ) -> Result<Self, PrfError> {
-        let prf = match mode {
-            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_master_secret(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
-            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_master_secret(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
+        let prf = match (mode, partials) {
+            (Mode::Reduced, HmacPartials::Sha256 { outer, inner }) => {
+                Self::Reduced(reduced::PrfFunction::alloc_master_secret(vm, outer, inner)?)
+            }
+            (Mode::Normal, HmacPartials::Sha256 { outer, inner }) => {
+                Self::Normal(normal::PrfFunction::alloc_master_secret(vm, outer, inner)?)
+            }
+            (_, HmacPartials::Sha384 { outer, inner }) => {
+                Self::Sha384(sha384::PrfFunction::alloc_master_secret(vm, outer, inner)?)
+            }
+        };
+        Ok(prf)
+    }
+
+    pub(crate) fn alloc_extended_master_secret(
+        mode: Mode,
+        vm: &mut dyn Vm<Binary>,
+        partials: HmacPartials,
+    ) -> Result<Self, PrfError> {
+        let prf = match (mode, partials) {
+            (Mode::Reduced, HmacPartials::Sha256 { outer, inner }) => Self::Reduced(
+                reduced::PrfFunction::alloc_extended_master_secret(vm, outer, inner)?,
+            ),
+            (Mode::Normal, HmacPartials::Sha256 { outer, inner }) => Self::Normal(
+                normal::PrfFunction::alloc_extended_master_secret(vm, outer, inner)?,
+            ),
+            (_, HmacPartials::Sha384 { outer, inner }) => Self::Sha384(
+                sha384::PrfFunction::alloc_extended_master_secret(vm, outer, inner)?,
+            ),
         };
         Ok(prf)
     }
@@ -44,62 +63,57 @@ impl Prf {
     pub(crate) fn alloc_key_expansion(
         mode: Mode,
         vm: &mut dyn Vm<Binary>,
-        outer_partial: Sha256,
-        inner_partial: Sha256,
+        partials: HmacPartials,
+        output_len: usize,
     ) -> Result<Self, PrfError> {
-        let prf = match mode {
-            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_key_expansion(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
-            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_key_expansion(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
+        let prf = match (mode, partials) {
+            (Mode::Reduced, HmacPartials::Sha256 { outer, inner }) => Self::Reduced(
+                reduced::PrfFunction::alloc_key_expansion(vm, outer, inner, output_len)?,
+            ),
+            (Mode::Normal, HmacPartials::Sha256 { outer, inner }) => Self::Normal(
+                normal::PrfFunction::alloc_key_expansion(vm, outer, inner, output_len)?,
+            ),
+            (_, HmacPartials::Sha384 { outer, inner }) => Self::Sha384(
+                sha384::PrfFunction::alloc_key_expansion(vm, outer, inner, output_len)?,
+            ),
         };
         Ok(prf)
     }
 
     pub(crate) fn alloc_client_finished(
-        config: Mode,
+        mode: Mode,
         vm: &mut dyn Vm<Binary>,
-        outer_partial: Sha256,
-        inner_partial: Sha256,
+        partials: HmacPartials,
     ) -> Result<Self, PrfError> {
-        let prf = match config {
-            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_client_finished(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
-            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_client_finished(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
+        let prf = match (mode, partials) {
+            (Mode::Reduced, HmacPartials::Sha256 { outer, inner }) => Self::Reduced(
+                reduced::PrfFunction::alloc_client_finished(vm, outer, inner)?,
+            ),
+            (Mode::Normal, HmacPartials::Sha256 { outer, inner }) => Self::Normal(
+                normal::PrfFunction::alloc_client_finished(vm, outer, inner)?,
+            ),
+            (_, HmacPartials::Sha384 { outer, inner }) => Self::Sha384(
+                sha384::PrfFunction::alloc_client_finished(vm, outer, inner)?,
+            ),
         };
         Ok(prf)
     }
 
     pub(crate) fn alloc_server_finished(
-        config: Mode,
+        mode: Mode,
         vm: &mut dyn Vm<Binary>,
-        outer_partial: Sha256,
-        inner_partial: Sha256,
+        partials: HmacPartials,
     ) -> Result<Self, PrfError> {
-        let prf = match config {
-            Mode::Reduced => Self::Reduced(reduced::PrfFunction::alloc_server_finished(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
-            Mode::Normal => Self::Normal(normal::PrfFunction::alloc_server_finished(
-                vm,
-                outer_partial,
-                inner_partial,
-            )?),
+        let prf = match (mode, partials) {
+            (Mode::Reduced, HmacPartials::Sha256 { outer, inner }) => Self::Reduced(
+                reduced::PrfFunction::alloc_server_finished(vm, outer, inner)?,
+            ),
+            (Mode::Normal, HmacPartials::Sha256 { outer, inner }) => Self::Normal(
+                normal::PrfFunction::alloc_server_finished(vm, outer, inner)?,
+            ),
+            (_, HmacPartials::Sha384 { outer, inner }) => Self::Sha384(
+                sha384::PrfFunction::alloc_server_finished(vm, outer, inner)?,
+            ),
         };
         Ok(prf)
     }
@@ -108,6 +122,7 @@ impl Prf {
         match self {
             Prf::Reduced(prf) => prf.wants_flush(),
             Prf::Normal(prf) => prf.wants_flush(),
+            Prf::Sha384(prf) => prf.wants_flush(),
         }
     }
 
@@ -115,6 +130,7 @@ impl Prf {
         match self {
             Prf::Reduced(prf) => prf.flush(vm),
             Prf::Normal(prf) => prf.flush(vm),
+            Prf::Sha384(prf) => prf.flush(vm),
         }
     }
 
@@ -122,13 +138,15 @@ impl Prf {
         match self {
             Prf::Reduced(prf) => prf.set_start_seed(seed),
             Prf::Normal(prf) => prf.set_start_seed(seed),
+            Prf::Sha384(prf) => prf.set_start_seed(seed),
         }
     }
 
-    pub(crate) fn output(&self) -> Vec<Array<U8, 32>> {
+    pub(crate) fn output(&self) -> Vec<Vector<U8>> {
         match self {
-            Prf::Reduced(prf) => prf.output(),
-            Prf::Normal(prf) => prf.output(),
+            Prf::Reduced(prf) => prf.output().into_iter().map(Vector::from).collect(),
+            Prf::Normal(prf) => prf.output().into_iter().map(Vector::from).collect(),
+            Prf::Sha384(prf) => prf.output().into_iter().map(Vector::from).collect(),
         }
     }
 }
@@ -136,6 +154,7 @@ impl Prf {
 #[cfg(test)]
 mod tests {
     use crate::{
+        hmac::HmacPartials,
         prf::{compute_partial, function::Prf},
         test_utils::{mock_vm, phash},
         Mode,
@@ -186,8 +205,10 @@ mod tests {
         let mut prf_leader = Prf::alloc_master_secret(
             mode,
             &mut leader,
-            outer_partial_leader,
-            inner_partial_leader,
+            HmacPartials::Sha256 {
+                outer: outer_partial_leader,
+                inner: inner_partial_leader,
+            },
         )
         .unwrap();
         prf_leader.set_start_seed(start_seed.clone());
@@ -211,8 +232,10 @@ mod tests {
         let mut prf_follower = Prf::alloc_master_secret(
             mode,
             &mut follower,
-            outer_partial_follower,
-            inner_partial_follower,
+            HmacPartials::Sha256 {
+                outer: outer_partial_follower,
+                inner: inner_partial_follower,
+            },
         )
         .unwrap();
         prf_follower.set_start_seed(start_seed.clone());
@@ -2,7 +2,10 @@
 
 use std::collections::VecDeque;
 
-use crate::{hmac::hmac_sha256, sha256, state_to_bytes, PrfError};
+use crate::{
+    hmac::{assign_inner_local, hmac_sha256},
+    PrfError,
+};
 use mpz_core::bitvec::BitVec;
 use mpz_hash::sha256::Sha256;
 use mpz_vm_core::{
@@ -45,6 +48,7 @@ enum PrfState {
 
 impl PrfFunction {
     const MS_LABEL: &[u8] = b"master secret";
+    const EMS_LABEL: &[u8] = b"extended master secret";
     const KEY_LABEL: &[u8] = b"key expansion";
     const CF_LABEL: &[u8] = b"client finished";
     const SF_LABEL: &[u8] = b"server finished";
@@ -57,12 +61,27 @@ impl PrfFunction {
         Self::alloc(vm, Self::MS_LABEL, outer_partial, inner_partial, 48)
     }
 
+    pub(crate) fn alloc_extended_master_secret(
+        vm: &mut dyn Vm<Binary>,
+        outer_partial: Sha256,
+        inner_partial: Sha256,
+    ) -> Result<Self, PrfError> {
+        Self::alloc(vm, Self::EMS_LABEL, outer_partial, inner_partial, 48)
+    }
+
     pub(crate) fn alloc_key_expansion(
         vm: &mut dyn Vm<Binary>,
         outer_partial: Sha256,
         inner_partial: Sha256,
+        output_len: usize,
     ) -> Result<Self, PrfError> {
-        Self::alloc(vm, Self::KEY_LABEL, outer_partial, inner_partial, 40)
+        Self::alloc(
+            vm,
+            Self::KEY_LABEL,
+            outer_partial,
+            inner_partial,
+            output_len,
+        )
     }
 
     pub(crate) fn alloc_client_finished(
@@ -217,22 +236,6 @@ impl PrfFunction {
     }
 }
 
-fn assign_inner_local(
-    vm: &mut dyn Vm<Binary>,
-    inner_local: Array<U8, 32>,
-    inner_partial: [u32; 8],
-    msg: &[u8],
-) -> Result<(), PrfError> {
-    let inner_local_value = sha256(inner_partial, 64, msg);
-
-    vm.mark_public(inner_local).map_err(PrfError::vm)?;
-    vm.assign(inner_local, state_to_bytes(inner_local_value))
-        .map_err(PrfError::vm)?;
-    vm.commit(inner_local).map_err(PrfError::vm)?;
-
-    Ok(())
-}
-
 /// Like PHash but stores the output as the decoding future because in the
 /// reduced Prf we need to decode this output.
 #[derive(Debug)]
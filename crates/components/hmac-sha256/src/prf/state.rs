@@ -5,7 +5,7 @@ use crate::{
 use mpz_vm_core::{
     memory::{
         binary::{Binary, U8},
-        Array, FromRaw, ToRaw,
+        Array, FromRaw, ToRaw, Vector,
     },
     Vm,
 };
@@ -37,7 +37,12 @@ impl State {
         std::mem::replace(self, State::Error)
     }
 
-    pub(crate) fn prf_output(&self, vm: &mut dyn Vm<Binary>) -> Result<PrfOutput, PrfError> {
+    pub(crate) fn prf_output(
+        &self,
+        vm: &mut dyn Vm<Binary>,
+        key_len: usize,
+        iv_len: usize,
+    ) -> Result<PrfOutput, PrfError> {
         let State::SessionKeys {
             key_expansion,
             client_finished,
@@ -50,7 +55,7 @@ impl State {
             ));
         };
 
-        let keys = get_session_keys(key_expansion.output(), vm)?;
+        let keys = get_session_keys(key_expansion.output(), key_len, iv_len, vm)?;
         let cf_vd = get_client_finished_vd(client_finished.output(), vm)?;
         let sf_vd = get_server_finished_vd(server_finished.output(), vm)?;
 
@@ -61,16 +66,19 @@ impl State {
 }
 
 fn get_session_keys(
-    output: Vec<Array<U8, 32>>,
+    output: Vec<Vector<U8>>,
+    key_len: usize,
+    iv_len: usize,
     vm: &mut dyn Vm<Binary>,
 ) -> Result<SessionKeys, PrfError> {
-    let mut keys = merge_outputs(vm, output, 40)?;
-    debug_assert!(keys.len() == 40, "session keys len should be 40");
+    let len = 2 * key_len + 2 * iv_len;
+    let mut keys = merge_outputs(vm, output, len)?;
+    debug_assert!(keys.len() == len, "session keys len should be {len}");
 
-    let server_iv = Array::<U8, 4>::try_from(keys.split_off(36)).unwrap();
-    let client_iv = Array::<U8, 4>::try_from(keys.split_off(32)).unwrap();
-    let server_write_key = Array::<U8, 16>::try_from(keys.split_off(16)).unwrap();
-    let client_write_key = Array::<U8, 16>::try_from(keys).unwrap();
+    let server_iv = keys.split_off(2 * key_len + iv_len);
+    let client_iv = keys.split_off(2 * key_len);
+    let server_write_key = keys.split_off(key_len);
+    let client_write_key = keys;
 
     let session_keys = SessionKeys {
         client_write_key,
@@ -83,7 +91,7 @@ fn get_session_keys(
 }
 
 fn get_client_finished_vd(
-    output: Vec<Array<U8, 32>>,
+    output: Vec<Vector<U8>>,
     vm: &mut dyn Vm<Binary>,
 ) -> Result<Array<U8, 12>, PrfError> {
     let cf_vd = merge_outputs(vm, output, 12)?;
@@ -93,7 +101,7 @@ fn get_client_finished_vd(
 }
 
 fn get_server_finished_vd(
-    output: Vec<Array<U8, 32>>,
+    output: Vec<Vector<U8>>,
     vm: &mut dyn Vm<Binary>,
 ) -> Result<Array<U8, 12>, PrfError> {
     let sf_vd = merge_outputs(vm, output, 12)?;
@@ -0,0 +1,432 @@
+//! 2PC SHA-384, built from the SHA-512 compression function.
+//!
+//! The hash state is kept as 64 bytes, i.e. eight big-endian 64-bit words.
+
+use std::sync::{Arc, LazyLock};
+
+use mpz_circuits::{Circuit, CircuitBuilder, Feed, Node};
+use mpz_vm_core::{
+    memory::{
+        binary::{Binary, U8},
+        Array, MemoryExt, Vector, ViewExt,
+    },
+    Call, CallableExt, Vm,
+};
+
+use crate::PrfError;
+
+/// Block size of SHA-384 in bytes.
+pub(crate) const BLOCK_LEN: usize = 128;
+
+/// Initial hash state of SHA-384.
+const IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+/// SHA-512 compression function circuit.
+///
+/// # Inputs
+///
+/// 0. STATE: 64 bytes
+/// 1. BLOCK: 128 bytes
+///
+/// # Outputs
+///
+/// 0. STATE: 64 bytes
+pub(crate) static SHA512_COMPRESS: LazyLock<Arc<Circuit>> =
+    LazyLock::new(|| Arc::new(build_sha512_compress()));
+
+const K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// A 64-bit word of wires, least significant bit first.
+type Word = [Node<Feed>; 64];
+
+/// 2PC SHA-384 hasher.
+#[derive(Debug, Clone)]
+pub(crate) struct Sha384 {
+    state: Vector<U8>,
+    chunks: Vec<Vector<U8>>,
+    processed: usize,
+}
+
+impl Sha384 {
+    /// Creates a new hasher with the SHA-384 initial state.
+    pub(crate) fn new_with_init(vm: &mut dyn Vm<Binary>) -> Result<Self, PrfError> {
+        let state: Vector<U8> = vm.alloc_vec(64).map_err(PrfError::vm)?;
+        vm.mark_public(state).map_err(PrfError::vm)?;
+        vm.assign(state, state_to_bytes(IV)).map_err(PrfError::vm)?;
+        vm.commit(state).map_err(PrfError::vm)?;
+
+        Ok(Self {
+            state,
+            chunks: Vec::new(),
+            processed: 0,
+        })
+    }
+
+    /// Appends data to the message.
+    pub(crate) fn update(&mut self, data: &Vector<U8>) {
+        if !data.is_empty() {
+            self.chunks.push(*data);
+        }
+    }
+
+    /// Compresses all complete blocks of the message.
+    pub(crate) fn compress(&mut self, vm: &mut dyn Vm<Binary>) -> Result<(), PrfError> {
+        let mut pending = Vec::new();
+        let mut pending_len = 0;
+
+        for chunk in std::mem::take(&mut self.chunks) {
+            let mut pos = 0;
+            while pos < chunk.len() {
+                let len = (BLOCK_LEN - pending_len).min(chunk.len() - pos);
+                pending.push(chunk.get(pos..pos + len).expect("range is in bounds"));
+                pending_len += len;
+                pos += len;
+
+                if pending_len == BLOCK_LEN {
+                    self.compress_block(vm, std::mem::take(&mut pending))?;
+                    pending_len = 0;
+                }
+            }
+        }
+
+        self.chunks = pending;
+
+        Ok(())
+    }
+
+    /// Pads the message and returns the hash.
+    pub(crate) fn finalize(mut self, vm: &mut dyn Vm<Binary>) -> Result<Array<U8, 48>, PrfError> {
+        let msg_len = self.processed + self.chunks.iter().map(|c| c.len()).sum::<usize>();
+
+        let padding = padding(msg_len);
+        let padding_ref: Vector<U8> = vm.alloc_vec(padding.len()).map_err(PrfError::vm)?;
+        vm.mark_public(padding_ref).map_err(PrfError::vm)?;
+        vm.assign(padding_ref, padding).map_err(PrfError::vm)?;
+        vm.commit(padding_ref).map_err(PrfError::vm)?;
+
+        self.update(&padding_ref);
+        self.compress(vm)?;
+
+        let mut hash = self.state;
+        hash.truncate(48);
+
+        Ok(Array::try_from(hash).expect("hash is 48 bytes"))
+    }
+
+    fn compress_block(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        block: Vec<Vector<U8>>,
+    ) -> Result<(), PrfError> {
+        let mut call = Call::builder(SHA512_COMPRESS.clone()).arg(self.state);
+        for chunk in block {
+            call = call.arg(chunk);
+        }
+        let call = call.build().map_err(PrfError::vm)?;
+
+        self.state = vm.call(call).map_err(PrfError::vm)?;
+        self.processed += BLOCK_LEN;
+
+        Ok(())
+    }
+}
+
+/// Returns the SHA-384 padding for a message of `msg_len` bytes.
+fn padding(msg_len: usize) -> Vec<u8> {
+    let zeros = (BLOCK_LEN - (msg_len + 17) % BLOCK_LEN) % BLOCK_LEN;
+
+    let mut padding = vec![0x80];
+    padding.resize(1 + zeros, 0);
+    padding.extend_from_slice(&((msg_len as u128) * 8).to_be_bytes());
+    padding
+}
+
+/// Converts a SHA-512 hash state into bytes.
+fn state_to_bytes(state: [u64; 8]) -> Vec<u8> {
+    state.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+fn build_sha512_compress() -> Circuit {
+    let mut builder = CircuitBuilder::new();
+
+    let state: [Word; 8] = std::array::from_fn(|_| input_word(&mut builder));
+    let block: [Word; 16] = std::array::from_fn(|_| input_word(&mut builder));
+
+    let mut w = block.to_vec();
+    for t in 16..80 {
+        let s0 = sigma(&mut builder, &w[t - 15], &[1, 8], 7);
+        let s1 = sigma(&mut builder, &w[t - 2], &[19, 61], 6);
+        let sum = add(&mut builder, &s1, &w[t - 7]);
+        let sum = add(&mut builder, &sum, &s0);
+        w.push(add(&mut builder, &sum, &w[t - 16]));
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    for t in 0..80 {
+        let s1 = sigma(&mut builder, &e, &[14, 18, 41], 64);
+        let choice = ch(&mut builder, &e, &f, &g);
+        let t1 = add(&mut builder, &h, &s1);
+        let t1 = add(&mut builder, &t1, &choice);
+        let t1 = add_const(&mut builder, &t1, K[t]);
+        let t1 = add(&mut builder, &t1, &w[t]);
+
+        let s0 = sigma(&mut builder, &a, &[28, 34, 39], 64);
+        let majority = maj(&mut builder, &a, &b, &c);
+        let t2 = add(&mut builder, &s0, &majority);
+
+        h = g;
+        g = f;
+        f = e;
+        e = add(&mut builder, &d, &t1);
+        d = c;
+        c = b;
+        b = a;
+        a = add(&mut builder, &t1, &t2);
+    }
+
+    for (word, value) in state.iter().zip([a, b, c, d, e, f, g, h]) {
+        let word = add(&mut builder, word, &value);
+        output_word(&mut builder, word);
+    }
+
+    builder
+        .build()
+        .expect("SHA-512 compression circuit is valid")
+}
+
+/// Adds a big-endian word of input bytes.
+fn input_word(builder: &mut CircuitBuilder) -> Word {
+    let bits: [Node<Feed>; 64] = std::array::from_fn(|_| builder.add_input());
+    std::array::from_fn(|i| bits[8 * (7 - i / 8) + i % 8])
+}
+
+/// Adds a word as big-endian output bytes.
+fn output_word(builder: &mut CircuitBuilder, word: Word) {
+    for i in 0..64 {
+        builder.add_output(word[8 * (7 - i / 8) + i % 8]);
+    }
+}
+
+/// Xors the right rotations of `x` by `rotations`, and the right shift of `x`
+/// by `shift` if it is less than 64.
+fn sigma(builder: &mut CircuitBuilder, x: &Word, rotations: &[usize], shift: usize) -> Word {
+    std::array::from_fn(|i| {
+        let mut bit = x[(i + rotations[0]) % 64];
+        for r in &rotations[1..] {
+            bit = builder.add_xor_gate(bit, x[(i + r) % 64]);
+        }
+        if i + shift < 64 {
+            bit = builder.add_xor_gate(bit, x[i + shift]);
+        }
+        bit
+    })
+}
+
+/// Computes `(e & f) ^ (!e & g)` as `g ^ (e & (f ^ g))`.
+fn ch(builder: &mut CircuitBuilder, e: &Word, f: &Word, g: &Word) -> Word {
+    std::array::from_fn(|i| {
+        let f_g = builder.add_xor_gate(f[i], g[i]);
+        let and = builder.add_and_gate(e[i], f_g);
+        builder.add_xor_gate(g[i], and)
+    })
+}
+
+/// Computes `(a & b) ^ (a & c) ^ (b & c)` as `a ^ ((a ^ b) & (a ^ c))`.
+fn maj(builder: &mut CircuitBuilder, a: &Word, b: &Word, c: &Word) -> Word {
+    std::array::from_fn(|i| {
+        let a_b = builder.add_xor_gate(a[i], b[i]);
+        let a_c = builder.add_xor_gate(a[i], c[i]);
+        let and = builder.add_and_gate(a_b, a_c);
+        builder.add_xor_gate(a[i], and)
+    })
+}
+
+/// Ripple-carry addition modulo 2^64.
+fn add(builder: &mut CircuitBuilder, x: &Word, y: &Word) -> Word {
+    let mut out = *x;
+    let mut carry = builder.add_and_gate(x[0], y[0]);
+    out[0] = builder.add_xor_gate(x[0], y[0]);
+    for i in 1..64 {
+        let x_c = builder.add_xor_gate(x[i], carry);
+        out[i] = builder.add_xor_gate(x_c, y[i]);
+        if i < 63 {
+            let y_c = builder.add_xor_gate(y[i], carry);
+            let and = builder.add_and_gate(x_c, y_c);
+            carry = builder.add_xor_gate(carry, and);
+        }
+    }
+    out
+}
+
+/// Ripple-carry addition of a constant modulo 2^64.
+fn add_const(builder: &mut CircuitBuilder, x: &Word, value: u64) -> Word {
+    let bit = |i: usize| (value >> i) & 1 == 1;
+
+    // Skip the low zero bits, which do not change the word.
+    let Some(start) = (0..64).find(|&i| bit(i)) else {
+        return *x;
+    };
+
+    let mut out = *x;
+    out[start] = builder.add_inv_gate(x[start]);
+    let mut carry = x[start];
+    for i in start + 1..64 {
+        let x_c = builder.add_xor_gate(x[i], carry);
+        out[i] = if bit(i) {
+            builder.add_inv_gate(x_c)
+        } else {
+            x_c
+        };
+        if i < 63 {
+            let and = builder.add_and_gate(x[i], carry);
+            // With a constant one the carry is `x | carry`.
+            carry = if bit(i) {
+                builder.add_xor_gate(x_c, and)
+            } else {
+                and
+            };
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mock_vm, sha384};
+    use mpz_common::context::test_st_context;
+    use mpz_vm_core::Execute;
+
+    #[tokio::test]
+    async fn test_sha384() {
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut leader, mut follower) = mock_vm();
+
+        // Spans two blocks and exercises the split across the block boundary.
+        let msg: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let expected = sha384(&msg);
+
+        let hash_leader = hash(&mut leader, &msg);
+        let mut hash_leader = leader.decode(hash_leader).unwrap();
+
+        let hash_follower = hash(&mut follower, &msg);
+        let mut hash_follower = follower.decode(hash_follower).unwrap();
+
+        tokio::try_join!(
+            leader.execute_all(&mut ctx_a),
+            follower.execute_all(&mut ctx_b)
+        )
+        .unwrap();
+
+        let hash_leader = hash_leader.try_recv().unwrap().unwrap();
+        let hash_follower = hash_follower.try_recv().unwrap().unwrap();
+
+        assert_eq!(hash_leader, hash_follower);
+        assert_eq!(hash_leader.to_vec(), expected);
+    }
+
+    fn hash(vm: &mut dyn Vm<Binary>, msg: &[u8]) -> Array<U8, 48> {
+        let (first, second) = msg.split_at(100);
+
+        let mut sha = Sha384::new_with_init(vm).unwrap();
+        for part in [first, second] {
+            let part_ref: Vector<U8> = vm.alloc_vec(part.len()).unwrap();
+            vm.mark_public(part_ref).unwrap();
+            vm.assign(part_ref, part.to_vec()).unwrap();
+            vm.commit(part_ref).unwrap();
+            sha.update(&part_ref);
+        }
+        sha.compress(vm).unwrap();
+        sha.finalize(vm).unwrap()
+    }
+}
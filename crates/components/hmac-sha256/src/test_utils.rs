@@ -20,7 +20,7 @@ pub(crate) fn mock_vm() -> (Garbler<IdealCOTSender>, Evaluator<IdealCOTReceiver>
     (gen, ev)
 }
 
-pub(crate) fn prf_ms(pms: [u8; 32], client_random: [u8; 32], server_random: [u8; 32]) -> [u8; 48] {
+pub(crate) fn prf_ms(pms: &[u8], client_random: [u8; 32], server_random: [u8; 32]) -> [u8; 48] {
     let mut label_start_seed = b"master secret".to_vec();
     label_start_seed.extend_from_slice(&client_random);
     label_start_seed.extend_from_slice(&server_random);
@@ -30,6 +30,15 @@ pub(crate) fn prf_ms(pms: [u8; 32], client_random: [u8; 32], server_random: [u8;
     ms.try_into().unwrap()
 }
 
+pub(crate) fn prf_ems(pms: &[u8], session_hash: [u8; 32]) -> [u8; 48] {
+    let mut label_start_seed = b"extended master secret".to_vec();
+    label_start_seed.extend_from_slice(&session_hash);
+
+    let ems = phash(pms.to_vec(), &label_start_seed, 2)[..48].to_vec();
+
+    ems.try_into().unwrap()
+}
+
 pub(crate) fn prf_keys(
     ms: [u8; 48],
     client_random: [u8; 32],
@@ -97,6 +106,43 @@ pub(crate) fn hmac_sha256(key: Vec<u8>, msg: &[u8]) -> [u8; 32] {
     state_to_bytes(hmac)
 }
 
+/// HKDF-Extract as defined in RFC 5869.
+pub(crate) fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    hmac_sha256(salt.to_vec(), ikm)
+}
+
+/// HKDF-Expand-Label as defined in RFC 8446, Section 7.1.
+pub(crate) fn hkdf_expand_label(
+    secret: &[u8],
+    label: &[u8],
+    context: &[u8],
+    len: usize,
+) -> Vec<u8> {
+    let label = [b"tls13 ", label].concat();
+
+    let mut hkdf_label = (len as u16).to_be_bytes().to_vec();
+    hkdf_label.push(label.len() as u8);
+    hkdf_label.extend_from_slice(&label);
+    hkdf_label.push(context.len() as u8);
+    hkdf_label.extend_from_slice(context);
+
+    // HKDF-Expand
+    let mut output = Vec::new();
+    let mut t = Vec::new();
+    let mut i = 1u8;
+    while output.len() < len {
+        let mut msg = t.clone();
+        msg.extend_from_slice(&hkdf_label);
+        msg.push(i);
+        t = hmac_sha256(secret.to_vec(), &msg).to_vec();
+        output.extend_from_slice(&t);
+        i += 1;
+    }
+    output.truncate(len);
+
+    output
+}
+
 pub(crate) fn compute_outer_partial(mut key: Vec<u8>) -> [u32; 8] {
     assert!(key.len() <= 64);
 
@@ -140,10 +186,26 @@ pub(crate) fn compress_256(mut state: [u32; 8], msg: &[u8]) -> [u32; 8] {
     state
 }
 
+pub(crate) fn sha384(msg: &[u8]) -> Vec<u8> {
+    use sha2::{Digest, Sha384};
+
+    Sha384::digest(msg).to_vec()
+}
+
+/// Computes `len` bytes of the TLS 1.2 HMAC-SHA384 PRF.
+pub(crate) fn prf_sha384(secret: &[u8], label: &[u8], seed: &[u8], len: usize) -> Vec<u8> {
+    let mut output = vec![0; len];
+    ring_prf::prf_sha384(&mut output, secret, label, seed);
+    output
+}
+
 // Borrowed from Rustls for testing
 // https://github.com/rustls/rustls/blob/main/rustls/src/tls12/prf.rs
 mod ring_prf {
-    use ring::{hmac, hmac::HMAC_SHA256};
+    use ring::{
+        hmac,
+        hmac::{HMAC_SHA256, HMAC_SHA384},
+    };
 
     fn concat_sign(key: &hmac::Key, a: &[u8], b: &[u8]) -> hmac::Tag {
         let mut ctx = hmac::Context::with_key(key);
@@ -152,12 +214,12 @@ mod ring_prf {
         ctx.sign()
     }
 
-    fn p(out: &mut [u8], secret: &[u8], seed: &[u8]) {
-        let hmac_key = hmac::Key::new(HMAC_SHA256, secret);
+    fn p(alg: hmac::Algorithm, out: &mut [u8], secret: &[u8], seed: &[u8]) {
+        let hmac_key = hmac::Key::new(alg, secret);
 
         // A(1)
         let mut current_a = hmac::sign(&hmac_key, seed);
-        let chunk_size = HMAC_SHA256.digest_algorithm().output_len();
+        let chunk_size = alg.digest_algorithm().output_len();
         for chunk in out.chunks_mut(chunk_size) {
             // P_hash[i] = HMAC_hash(secret, A(i) + seed)
             let p_term = concat_sign(&hmac_key, current_a.as_ref(), seed);
@@ -177,7 +239,12 @@ mod ring_prf {
 
     pub(crate) fn prf(out: &mut [u8], secret: &[u8], label: &[u8], seed: &[u8]) {
         let joined_seed = concat(label, seed);
-        p(out, secret, &joined_seed);
+        p(HMAC_SHA256, out, secret, &joined_seed);
+    }
+
+    pub(crate) fn prf_sha384(out: &mut [u8], secret: &[u8], label: &[u8], seed: &[u8]) {
+        let joined_seed = concat(label, seed);
+        p(HMAC_SHA384, out, secret, &joined_seed);
     }
 }
 
@@ -194,7 +261,7 @@ fn test_prf_reference_ms() {
     let mut seed = Vec::from(client_random);
     seed.extend_from_slice(&server_random);
 
-    let ms = prf_ms(pms, client_random, server_random);
+    let ms = prf_ms(&pms, client_random, server_random);
 
     let mut expected_ms: [u8; 48] = [0; 48];
     prf_ref(&mut expected_ms, &pms, label, &seed);
@@ -202,6 +269,24 @@ fn test_prf_reference_ms() {
     assert_eq!(ms, expected_ms);
 }
 
+#[test]
+fn test_prf_reference_ems() {
+    use ring_prf::prf as prf_ref;
+
+    let mut rng = StdRng::from_seed([5; 32]);
+
+    let pms: [u8; 32] = rng.random();
+    let label: &[u8] = b"extended master secret";
+    let session_hash: [u8; 32] = rng.random();
+
+    let ems = prf_ems(&pms, session_hash);
+
+    let mut expected_ems: [u8; 48] = [0; 48];
+    prf_ref(&mut expected_ems, &pms, label, &session_hash);
+
+    assert_eq!(ems, expected_ems);
+}
+
 #[test]
 fn test_prf_reference_ke() {
     use ring_prf::prf as prf_ref;
@@ -7,25 +7,38 @@ use std::sync::Arc;
 ///
 /// # Inputs
 ///
-/// 0. PMS_SHARE_A0: 32 bytes PMS Additive Share
-/// 1. PMS_SHARE_B0: 32 bytes PMS Additive Share
-/// 2. PMS_SHARE_A1: 32 bytes PMS Additive Share
-/// 3. PMS_SHARE_B1: 32 bytes PMS Additive Share
-/// 4. MODULUS: 32 bytes field modulus
+/// 0. PMS_SHARE_A0: `len` bytes PMS Additive Share
+/// 1. PMS_SHARE_B0: `len` bytes PMS Additive Share
+/// 2. PMS_SHARE_A1: `len` bytes PMS Additive Share
+/// 3. PMS_SHARE_B1: `len` bytes PMS Additive Share
+/// 4. MODULUS: `len` bytes field modulus
 ///
 /// # Outputs
 /// 0. PMS_0: Pre-master Secret = PMS_SHARE_A0 + PMS_SHARE_B0
 /// 1. PMS_1: Pre-master Secret = PMS_SHARE_A1 + PMS_SHARE_B1
 /// 2. EQ: Equality check of PMS_0 and PMS_1
-pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
+///
+/// The shares and the modulus are big-endian. The PMS is big-endian, or
+/// little-endian if `little_endian` is set.
+pub(crate) fn build_pms_circuit(len: usize, little_endian: bool) -> Arc<Circuit> {
     let mut builder = CircuitBuilder::new();
 
-    let share_a0 = (0..32 * 8).map(|_| builder.add_input()).collect::<Vec<_>>();
-    let share_b0 = (0..32 * 8).map(|_| builder.add_input()).collect::<Vec<_>>();
-    let share_a1 = (0..32 * 8).map(|_| builder.add_input()).collect::<Vec<_>>();
-    let share_b1 = (0..32 * 8).map(|_| builder.add_input()).collect::<Vec<_>>();
+    let share_a0 = (0..len * 8)
+        .map(|_| builder.add_input())
+        .collect::<Vec<_>>();
+    let share_b0 = (0..len * 8)
+        .map(|_| builder.add_input())
+        .collect::<Vec<_>>();
+    let share_a1 = (0..len * 8)
+        .map(|_| builder.add_input())
+        .collect::<Vec<_>>();
+    let share_b1 = (0..len * 8)
+        .map(|_| builder.add_input())
+        .collect::<Vec<_>>();
 
-    let modulus = (0..32 * 8).map(|_| builder.add_input()).collect::<Vec<_>>();
+    let modulus = (0..len * 8)
+        .map(|_| builder.add_input())
+        .collect::<Vec<_>>();
 
     /// assumes input is provided as big endian
     fn to_little_endian(input: &[Node<Feed>]) -> Vec<Node<Feed>> {
@@ -45,12 +58,7 @@ pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
         &to_little_endian(&modulus),
     );
 
-    // return output as big endian
-    for node in pms_0.chunks_exact(8).rev() {
-        for &bit in node.iter() {
-            builder.add_output(bit);
-        }
-    }
+    add_pms_output(&mut builder, &pms_0, little_endian);
 
     let pms_1 = add_mod(
         &mut builder,
@@ -59,12 +67,7 @@ pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
         &to_little_endian(&modulus),
     );
 
-    // return output as big endian
-    for node in pms_1.chunks_exact(8).rev() {
-        for &bit in node.iter() {
-            builder.add_output(bit);
-        }
-    }
+    add_pms_output(&mut builder, &pms_1, little_endian);
 
     for (a, b) in pms_0.into_iter().zip(pms_1) {
         let out = builder.add_xor_gate(a, b);
@@ -73,3 +76,18 @@ pub(crate) fn build_pms_circuit() -> Arc<Circuit> {
 
     Arc::new(builder.build().expect("pms circuit is valid"))
 }
+
+/// Adds the PMS as an output, the input is little endian.
+fn add_pms_output(builder: &mut CircuitBuilder, pms: &[Node<Feed>], little_endian: bool) {
+    let bytes: Box<dyn Iterator<Item = &[Node<Feed>]>> = if little_endian {
+        Box::new(pms.chunks_exact(8))
+    } else {
+        Box::new(pms.chunks_exact(8).rev())
+    };
+
+    for node in bytes {
+        for &bit in node.iter() {
+            builder.add_output(bit);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Elliptic curves supported by the key exchange.
+
+mod field;
+mod secp256r1;
+mod secp384r1;
+mod x25519;
+
+pub use field::{Fe25519, P384};
+pub use secp256r1::Secp256r1;
+pub use secp384r1::Secp384r1;
+pub use x25519::{X25519Point, X25519};
+
+use std::fmt::Debug;
+
+use mpz_fields::Field;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{KeyExchangeError, PublicKey};
+
+/// An elliptic curve supported by the key exchange.
+///
+/// The shared point is the sum of the leader's and the follower's ECDH
+/// point. Its x-coordinate is secret-shared using share conversion over the
+/// base field of the curve.
+pub trait Curve: Debug + Send + Sync + 'static {
+    /// Base field of the curve.
+    type Field: Field;
+    /// Secret key.
+    type SecretKey: Debug + Clone + Send + Sync + 'static;
+    /// Curve point with both affine coordinates.
+    type Point: Debug + Copy + Send + Sync + Serialize + DeserializeOwned + 'static;
+
+    /// Modulus of the base field, big-endian.
+    ///
+    /// Its length is the length of the PMS.
+    const MODULUS: &'static [u8];
+    /// Whether the PMS is the little-endian encoding of the x-coordinate.
+    const PMS_LITTLE_ENDIAN: bool;
+
+    /// Generates a random secret key.
+    fn random_secret_key() -> Self::SecretKey;
+
+    /// Returns the public key of the secret key.
+    fn public_key(secret_key: &Self::SecretKey) -> Self::Point;
+
+    /// Adds two points.
+    fn add(a: &Self::Point, b: &Self::Point) -> Result<Self::Point, KeyExchangeError>;
+
+    /// Computes the ECDH shared point.
+    fn diffie_hellman(
+        secret_key: &Self::SecretKey,
+        public_key: &Self::Point,
+    ) -> Result<Self::Point, KeyExchangeError>;
+
+    /// Returns the affine coordinates `[x, y]` of the point.
+    fn coordinates(point: &Self::Point) -> Result<[Self::Field; 2], KeyExchangeError>;
+
+    /// Returns the constant `c` in the x-coordinate of a point sum,
+    /// `x_3 = λ² - x_1 - x_2 - c`.
+    fn sum_offset() -> Self::Field;
+
+    /// Decodes a point from a public key.
+    fn from_public_key(key: PublicKey) -> Result<Self::Point, KeyExchangeError>;
+
+    /// Encodes the point as a public key.
+    fn to_public_key(point: &Self::Point) -> Result<PublicKey, KeyExchangeError>;
+}
@@ -0,0 +1,228 @@
+//! Base fields of curves which are not provided by `mpz-fields`.
+
+/// Implements a field element wrapping an arkworks prime field.
+macro_rules! prime_field {
+    ($name:ident, $inner:ty, $bytes:expr, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #[serde(into = "Vec<u8>", try_from = "Vec<u8>")]
+        pub struct $name(pub(crate) $inner);
+
+        opaque_debug::implement!($name);
+
+        impl TryFrom<Vec<u8>> for $name {
+            type Error = crate::KeyExchangeError;
+
+            /// Converts little-endian bytes into a field element.
+            fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+                use ark_serialize::CanonicalDeserialize;
+
+                if value.len() != $bytes {
+                    return Err(crate::KeyExchangeError::key(
+                        "field element has the wrong length",
+                    ));
+                }
+
+                <$inner>::deserialize_uncompressed(&value[..])
+                    .map(Self)
+                    .map_err(|_| crate::KeyExchangeError::key("field element is not reduced"))
+            }
+        }
+
+        impl From<$name> for Vec<u8> {
+            fn from(value: $name) -> Self {
+                use ark_ff::{BigInteger, PrimeField};
+
+                value.0.into_bigint().to_bytes_le()
+            }
+        }
+
+        impl std::ops::Add for $name {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+
+            fn mul(self, rhs: Self) -> Self::Output {
+                Self(self.0 * rhs.0)
+            }
+        }
+
+        impl std::ops::Neg for $name {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+
+        impl mpz_fields::Field for $name {
+            const BIT_SIZE: usize = $bytes * 8;
+
+            fn zero() -> Self {
+                Self(<$inner as ark_ff::Zero>::zero())
+            }
+
+            fn one() -> Self {
+                Self(<$inner as ark_ff::One>::one())
+            }
+
+            fn two_pow(rhs: u32) -> Self {
+                use ark_ff::Field;
+
+                let mut out = <$inner as ark_ff::One>::one();
+                for _ in 0..rhs {
+                    out.double_in_place();
+                }
+                Self(out)
+            }
+
+            fn inverse(self) -> Option<Self> {
+                ark_ff::Field::inverse(&self.0).map(Self)
+            }
+
+            fn to_le_bytes(&self) -> Vec<u8> {
+                use ark_ff::{BigInteger, PrimeField};
+
+                self.0.into_bigint().to_bytes_le()
+            }
+
+            fn to_be_bytes(&self) -> Vec<u8> {
+                use ark_ff::{BigInteger, PrimeField};
+
+                self.0.into_bigint().to_bytes_be()
+            }
+        }
+
+        impl mpz_fields::UniformRand for $name {
+            fn rand<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+                use ark_ff::PrimeField;
+
+                // Reducing twice the number of bits makes the bias negligible.
+                let bytes: Vec<u8> = (0..2 * $bytes).map(|_| rng.random()).collect();
+                Self(<$inner>::from_le_bytes_mod_order(&bytes))
+            }
+        }
+
+        impl itybity::BitLength for $name {
+            const BITS: usize = $bytes * 8;
+        }
+
+        impl itybity::GetBit<itybity::Lsb0> for $name {
+            fn get_bit(&self, index: usize) -> bool {
+                use ark_ff::{BigInteger, PrimeField};
+
+                self.0.into_bigint().get_bit(index)
+            }
+        }
+
+        impl itybity::GetBit<itybity::Msb0> for $name {
+            fn get_bit(&self, index: usize) -> bool {
+                use ark_ff::{BigInteger, PrimeField};
+
+                self.0
+                    .into_bigint()
+                    .get_bit(<Self as itybity::BitLength>::BITS - 1 - index)
+            }
+        }
+
+        impl itybity::FromBitIterator for $name {
+            fn from_lsb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+                use ark_ff::{BigInteger, PrimeField};
+
+                let bits = iter.into_iter().collect::<Vec<_>>();
+                Self(
+                    <$inner>::from_bigint(BigInteger::from_bits_le(&bits))
+                        .expect("bits are reduced"),
+                )
+            }
+
+            fn from_msb0_iter(iter: impl IntoIterator<Item = bool>) -> Self {
+                use ark_ff::{BigInteger, PrimeField};
+
+                let bits = iter.into_iter().collect::<Vec<_>>();
+                Self(
+                    <$inner>::from_bigint(BigInteger::from_bits_be(&bits))
+                        .expect("bits are reduced"),
+                )
+            }
+        }
+    };
+}
+
+prime_field!(
+    Fe25519,
+    ark_curve25519::Fq,
+    32,
+    "An element of the base field of Curve25519, `GF(2^255 - 19)`."
+);
+
+prime_field!(
+    P384,
+    ark_secp384r1::Fq,
+    48,
+    "An element of the base field of NIST P-384."
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use itybity::{GetBit, Lsb0, Msb0};
+    use mpz_fields::{Field, UniformRand};
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::*;
+
+    #[test]
+    fn test_fe25519_bytes_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let a = Fe25519::rand(&mut rng);
+
+        let bytes: Vec<u8> = a.into();
+
+        assert_eq!(bytes, a.to_le_bytes());
+        assert_eq!(Fe25519::try_from(bytes).unwrap(), a);
+    }
+
+    #[test]
+    fn test_p384_bytes_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let a = P384::rand(&mut rng);
+
+        let bytes: Vec<u8> = a.into();
+
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(P384::try_from(bytes).unwrap(), a);
+        assert!(P384::try_from(vec![0u8; 32]).is_err());
+    }
+
+    #[rstest]
+    #[case::fe25519(Fe25519::rand(&mut StdRng::seed_from_u64(0)))]
+    #[case::p384(P384::rand(&mut StdRng::seed_from_u64(0)))]
+    fn test_inverse<F: Field>(#[case] a: F) {
+        assert_eq!(a * a.inverse().unwrap(), F::one());
+        assert!(F::zero().inverse().is_none());
+    }
+
+    #[test]
+    fn test_fe25519_bits() {
+        let a = Fe25519::two_pow(254);
+
+        assert!(GetBit::<Lsb0>::get_bit(&a, 254));
+        assert!(GetBit::<Msb0>::get_bit(&a, 1));
+        assert!(!GetBit::<Lsb0>::get_bit(&a, 0));
+    }
+}
@@ -0,0 +1,128 @@
+//! NIST P-256.
+
+use mpz_fields::{p256::P256, Field};
+use p256::{EncodedPoint, PublicKey as P256PublicKey, SecretKey};
+use rand06_compat::Rand0_6CompatExt;
+
+use crate::{curve::Curve, KeyExchangeError, PublicKey};
+
+/// NIST P-256 prime big-endian.
+static P: [u8; 32] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+];
+
+/// The secp256r1 curve, also known as NIST P-256.
+#[derive(Debug, Clone, Copy)]
+pub struct Secp256r1;
+
+impl Curve for Secp256r1 {
+    type Field = P256;
+    type SecretKey = SecretKey;
+    type Point = P256PublicKey;
+
+    const MODULUS: &'static [u8] = &P;
+    const PMS_LITTLE_ENDIAN: bool = false;
+
+    fn random_secret_key() -> SecretKey {
+        SecretKey::random(&mut rand::rng().compat())
+    }
+
+    fn public_key(secret_key: &SecretKey) -> P256PublicKey {
+        secret_key.public_key()
+    }
+
+    fn add(a: &P256PublicKey, b: &P256PublicKey) -> Result<P256PublicKey, KeyExchangeError> {
+        Ok(P256PublicKey::from_affine(
+            (a.to_projective() + b.to_projective()).to_affine(),
+        )?)
+    }
+
+    fn diffie_hellman(
+        secret_key: &SecretKey,
+        public_key: &P256PublicKey,
+    ) -> Result<P256PublicKey, KeyExchangeError> {
+        // We need to mimic the [diffie-hellman](p256::ecdh::diffie_hellman)
+        // function without the [SharedSecret](p256::ecdh::SharedSecret)
+        // wrapper, because this makes it harder to get the result as an EC
+        // curve point.
+        let shared_secret = {
+            let public_projective = public_key.to_projective();
+            (public_projective * secret_key.to_nonzero_scalar().as_ref()).to_affine()
+        };
+
+        Ok(P256PublicKey::from_affine(shared_secret)?)
+    }
+
+    fn coordinates(point: &P256PublicKey) -> Result<[P256; 2], KeyExchangeError> {
+        decompose_point(EncodedPoint::from(point))
+    }
+
+    fn sum_offset() -> P256 {
+        P256::zero()
+    }
+
+    fn from_public_key(key: PublicKey) -> Result<P256PublicKey, KeyExchangeError> {
+        match key {
+            PublicKey::Secp256r1(key) => Ok(key),
+            _ => Err(KeyExchangeError::key("expected a secp256r1 key")),
+        }
+    }
+
+    fn to_public_key(point: &P256PublicKey) -> Result<PublicKey, KeyExchangeError> {
+        Ok(PublicKey::Secp256r1(*point))
+    }
+}
+
+/// Decomposes the x and y coordinates of a SEC1 encoded point.
+fn decompose_point(point: EncodedPoint) -> Result<[P256; 2], KeyExchangeError> {
+    // Coordinates are stored as big-endian bytes.
+    let mut x: [u8; 32] = (*point
+        .x()
+        .ok_or(KeyExchangeError::key("key share is an identity point"))?)
+    .into();
+    let mut y: [u8; 32] = (*point.y().ok_or(KeyExchangeError::key(
+        "key share is an identity point or compressed",
+    ))?)
+    .into();
+
+    // Reverse to little endian.
+    x.reverse();
+    y.reverse();
+
+    let x = P256::try_from(x).unwrap();
+    let y = P256::try_from(y).unwrap();
+
+    Ok([x, y])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use p256::{elliptic_curve::sec1::ToEncodedPoint, NonZeroScalar};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_decompose_point() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let p_expected: [u8; 32] = rng.random();
+        let p_expected = curve_point_from_be_bytes(p_expected);
+
+        let p256: [P256; 2] = decompose_point(p_expected).unwrap();
+
+        let x: [u8; 32] = p256[0].to_be_bytes().try_into().unwrap();
+        let y: [u8; 32] = p256[1].to_be_bytes().try_into().unwrap();
+
+        let p = EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+
+        assert_eq!(p_expected, p);
+    }
+
+    fn curve_point_from_be_bytes(bytes: [u8; 32]) -> EncodedPoint {
+        let scalar = NonZeroScalar::from_repr(bytes.into()).unwrap();
+        let pk = P256PublicKey::from_secret_scalar(&scalar);
+        pk.to_encoded_point(false)
+    }
+}
@@ -0,0 +1,120 @@
+//! NIST P-384.
+
+use p384::{EncodedPoint, PublicKey as P384PublicKey, SecretKey};
+use rand06_compat::Rand0_6CompatExt;
+
+use mpz_fields::Field;
+
+use crate::{
+    curve::{Curve, P384},
+    KeyExchangeError, PublicKey,
+};
+
+/// NIST P-384 prime big-endian.
+static P: [u8; 48] = [
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE,
+    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF,
+];
+
+/// The secp384r1 curve, also known as NIST P-384.
+#[derive(Debug, Clone, Copy)]
+pub struct Secp384r1;
+
+impl Curve for Secp384r1 {
+    type Field = P384;
+    type SecretKey = SecretKey;
+    type Point = P384PublicKey;
+
+    const MODULUS: &'static [u8] = &P;
+    const PMS_LITTLE_ENDIAN: bool = false;
+
+    fn random_secret_key() -> SecretKey {
+        SecretKey::random(&mut rand::rng().compat())
+    }
+
+    fn public_key(secret_key: &SecretKey) -> P384PublicKey {
+        secret_key.public_key()
+    }
+
+    fn add(a: &P384PublicKey, b: &P384PublicKey) -> Result<P384PublicKey, KeyExchangeError> {
+        Ok(P384PublicKey::from_affine(
+            (a.to_projective() + b.to_projective()).to_affine(),
+        )?)
+    }
+
+    fn diffie_hellman(
+        secret_key: &SecretKey,
+        public_key: &P384PublicKey,
+    ) -> Result<P384PublicKey, KeyExchangeError> {
+        let shared_secret =
+            (public_key.to_projective() * secret_key.to_nonzero_scalar().as_ref()).to_affine();
+
+        Ok(P384PublicKey::from_affine(shared_secret)?)
+    }
+
+    fn coordinates(point: &P384PublicKey) -> Result<[P384; 2], KeyExchangeError> {
+        decompose_point(EncodedPoint::from(point))
+    }
+
+    fn sum_offset() -> P384 {
+        P384::zero()
+    }
+
+    fn from_public_key(key: PublicKey) -> Result<P384PublicKey, KeyExchangeError> {
+        match key {
+            PublicKey::Secp384r1(key) => Ok(key),
+            _ => Err(KeyExchangeError::key("expected a secp384r1 key")),
+        }
+    }
+
+    fn to_public_key(point: &P384PublicKey) -> Result<PublicKey, KeyExchangeError> {
+        Ok(PublicKey::Secp384r1(*point))
+    }
+}
+
+/// Decomposes the x and y coordinates of a SEC1 encoded point.
+fn decompose_point(point: EncodedPoint) -> Result<[P384; 2], KeyExchangeError> {
+    // Coordinates are stored as big-endian bytes.
+    let mut x = point
+        .x()
+        .ok_or(KeyExchangeError::key("key share is an identity point"))?
+        .to_vec();
+    let mut y = point
+        .y()
+        .ok_or(KeyExchangeError::key(
+            "key share is an identity point or compressed",
+        ))?
+        .to_vec();
+
+    // Reverse to little endian.
+    x.reverse();
+    y.reverse();
+
+    Ok([P384::try_from(x)?, P384::try_from(y)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use p384::{elliptic_curve::sec1::ToEncodedPoint, NonZeroScalar};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_decompose_point() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let scalar = NonZeroScalar::from_repr(rng.random::<[u8; 48]>().into()).unwrap();
+        let p_expected = P384PublicKey::from_secret_scalar(&scalar).to_encoded_point(false);
+
+        let [x, y] = decompose_point(p_expected).unwrap();
+
+        let x: [u8; 48] = x.to_be_bytes().try_into().unwrap();
+        let y: [u8; 48] = y.to_be_bytes().try_into().unwrap();
+
+        let p = EncodedPoint::from_affine_coordinates(&x.into(), &y.into(), false);
+
+        assert_eq!(p_expected, p);
+    }
+}
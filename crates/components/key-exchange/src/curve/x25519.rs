@@ -0,0 +1,228 @@
+//! Curve25519 as used by X25519, see RFC 7748.
+//!
+//! Public keys are only the u-coordinate of a point on the Montgomery curve
+//! `v² = u³ + 486662u² + u`. Internally, points are kept in the birationally
+//! equivalent twisted Edwards form so that they can be added, and mapped to
+//! Montgomery coordinates for the point addition protocol.
+
+use ark_curve25519::{EdwardsAffine, Fq, Fr};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand06_compat::Rand0_6CompatExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    curve::{Curve, Fe25519},
+    KeyExchangeError, PublicKey,
+};
+
+/// Curve25519 prime `2^255 - 19` big-endian.
+static P: [u8; 32] = [
+    0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xED,
+];
+
+/// Coefficient `A` of the Montgomery curve.
+const MONTGOMERY_A: u64 = 486662;
+
+/// The X25519 key exchange over Curve25519.
+#[derive(Debug, Clone, Copy)]
+pub struct X25519;
+
+/// A point on Curve25519 in the prime-order subgroup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "[u8; 32]", try_from = "[u8; 32]")]
+pub struct X25519Point(EdwardsAffine);
+
+impl From<X25519Point> for [u8; 32] {
+    fn from(value: X25519Point) -> Self {
+        let mut bytes = [0u8; 32];
+        value
+            .0
+            .serialize_compressed(&mut bytes[..])
+            .expect("point fits into 32 bytes");
+        bytes
+    }
+}
+
+impl TryFrom<[u8; 32]> for X25519Point {
+    type Error = KeyExchangeError;
+
+    fn try_from(value: [u8; 32]) -> Result<Self, Self::Error> {
+        let point = EdwardsAffine::deserialize_compressed(&value[..])
+            .map_err(|_| KeyExchangeError::key("invalid curve25519 point"))?;
+
+        Self::new(point)
+    }
+}
+
+impl X25519Point {
+    fn new(point: EdwardsAffine) -> Result<Self, KeyExchangeError> {
+        if point.is_zero() {
+            return Err(KeyExchangeError::key("point is the identity"));
+        } else if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(KeyExchangeError::key(
+                "point is not in the prime-order subgroup",
+            ));
+        }
+
+        Ok(Self(point))
+    }
+
+    /// Returns the Montgomery u-coordinate.
+    fn u(&self) -> Fq {
+        let EdwardsAffine { y, .. } = self.0;
+
+        // The identity is the only point with y = 1.
+        (Fq::one() + y)
+            * (Fq::one() - y)
+                .inverse()
+                .expect("point is not the identity")
+    }
+}
+
+impl Curve for X25519 {
+    type Field = Fe25519;
+    type SecretKey = Fr;
+    type Point = X25519Point;
+
+    const MODULUS: &'static [u8] = &P;
+    const PMS_LITTLE_ENDIAN: bool = true;
+
+    fn random_secret_key() -> Fr {
+        Fr::rand(&mut rand::rng().compat())
+    }
+
+    fn public_key(secret_key: &Fr) -> X25519Point {
+        X25519Point((EdwardsAffine::generator() * secret_key).into_affine())
+    }
+
+    fn add(a: &X25519Point, b: &X25519Point) -> Result<X25519Point, KeyExchangeError> {
+        X25519Point::new((a.0 + b.0).into_affine())
+    }
+
+    fn diffie_hellman(
+        secret_key: &Fr,
+        public_key: &X25519Point,
+    ) -> Result<X25519Point, KeyExchangeError> {
+        X25519Point::new((public_key.0 * secret_key).into_affine())
+    }
+
+    fn coordinates(point: &X25519Point) -> Result<[Fe25519; 2], KeyExchangeError> {
+        let EdwardsAffine { x, .. } = point.0;
+
+        let u = point.u();
+        let v = sqrt_neg_486664()
+            * u
+            * x.inverse()
+                .ok_or(KeyExchangeError::key("point has order 2 or less"))?;
+
+        Ok([Fe25519(u), Fe25519(v)])
+    }
+
+    fn sum_offset() -> Fe25519 {
+        Fe25519(Fq::from(MONTGOMERY_A))
+    }
+
+    fn from_public_key(key: PublicKey) -> Result<X25519Point, KeyExchangeError> {
+        let PublicKey::X25519(mut bytes) = key else {
+            return Err(KeyExchangeError::key("expected an x25519 key"));
+        };
+
+        // The most significant bit is masked, see RFC 7748 section 5.
+        bytes[31] &= 0x7F;
+        let u = Fq::from_le_bytes_mod_order(&bytes);
+
+        // Lift the u-coordinate to the twisted Edwards curve. Either of the
+        // two points with this u-coordinate yields the same shared secret.
+        let y = (u - Fq::one())
+            * (u + Fq::one())
+                .inverse()
+                .ok_or(KeyExchangeError::key("invalid x25519 key"))?;
+        let point = EdwardsAffine::get_point_from_y_unchecked(y, false)
+            .ok_or(KeyExchangeError::key("x25519 key is not on the curve"))?;
+
+        X25519Point::new(point)
+    }
+
+    fn to_public_key(point: &X25519Point) -> Result<PublicKey, KeyExchangeError> {
+        let bytes: [u8; 32] = point
+            .u()
+            .into_bigint()
+            .to_bytes_le()
+            .try_into()
+            .expect("field element is 32 bytes");
+
+        if bytes == [0u8; 32] {
+            return Err(KeyExchangeError::key("x25519 key is zero"));
+        }
+
+        Ok(PublicKey::X25519(bytes))
+    }
+}
+
+/// Returns the square root of `-(A + 2)` which scales the v-coordinate of the
+/// birational map from the twisted Edwards curve.
+fn sqrt_neg_486664() -> Fq {
+    (-Fq::from(MONTGOMERY_A + 2))
+        .sqrt()
+        .expect("-486664 is a square")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT, Scalar};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_secret_key(rng: &mut StdRng) -> Fr {
+        Fr::from_le_bytes_mod_order(&rng.random::<[u8; 32]>())
+    }
+
+    #[test]
+    fn test_public_key_encoding() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let secret_key = random_secret_key(&mut rng);
+
+        let PublicKey::X25519(key) =
+            X25519::to_public_key(&X25519::public_key(&secret_key)).unwrap()
+        else {
+            panic!("expected an x25519 key");
+        };
+
+        let scalar: [u8; 32] = secret_key.into_bigint().to_bytes_le().try_into().unwrap();
+        let expected =
+            (ED25519_BASEPOINT_POINT * Scalar::from_bytes_mod_order(scalar)).to_montgomery();
+
+        assert_eq!(key, expected.to_bytes());
+    }
+
+    #[test]
+    fn test_public_key_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let point = X25519::public_key(&random_secret_key(&mut rng));
+
+        let key = X25519::to_public_key(&point).unwrap();
+        let decoded = X25519::from_public_key(key).unwrap();
+
+        assert_eq!(X25519::to_public_key(&decoded).unwrap(), key);
+    }
+
+    #[test]
+    fn test_coordinates_on_montgomery_curve() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let point = X25519::public_key(&random_secret_key(&mut rng));
+
+        let [Fe25519(u), Fe25519(v)] = X25519::coordinates(&point).unwrap();
+        let a = Fq::from(MONTGOMERY_A);
+
+        assert_eq!(v * v, u * u * u + a * u * u + u);
+    }
+
+    #[test]
+    fn test_invalid_public_key() {
+        assert!(X25519::from_public_key(PublicKey::X25519([0u8; 32])).is_err());
+    }
+}
@@ -3,68 +3,62 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
-use p256::{EncodedPoint, PublicKey, SecretKey};
-use rand06_compat::Rand0_6CompatExt;
 use serio::{sink::SinkExt, stream::IoStreamExt};
 use tokio::sync::Mutex;
 use tracing::instrument;
 
 use mpz_common::{Context, Flush};
 use mpz_core::bitvec::BitVec;
-use mpz_fields::{p256::P256, Field};
+use mpz_fields::Field;
 use mpz_memory_core::{
     binary::{Binary, U8},
-    Array, DecodeFutureTyped, MemoryExt, ViewExt,
+    DecodeFutureTyped, MemoryExt, Vector, ViewExt,
 };
 use mpz_share_conversion::{AdditiveToMultiplicative, MultiplicativeToAdditive, ShareConvert};
 use mpz_vm_core::{CallBuilder, CallableExt, Vm};
 
 use crate::{
-    circuit::build_pms_circuit, point_addition::derive_x_coord_share, KeyExchange,
-    KeyExchangeError, Pms, Role,
+    circuit::build_pms_circuit,
+    curve::{Curve, Secp256r1},
+    point_addition::derive_x_coord_share,
+    KeyExchange, KeyExchangeError, Pms, PublicKey, Role,
 };
 
-/// NIST P-256 prime big-endian.
-static P: [u8; 32] = [
-    0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
-];
-
 #[derive(Debug)]
-enum State {
+enum State<G: Curve> {
     Initialized,
     Setup {
-        share_a0: Array<U8, 32>,
-        share_b0: Array<U8, 32>,
-        share_a1: Array<U8, 32>,
-        share_b1: Array<U8, 32>,
-        eq: Array<U8, 32>,
+        share_a0: Vector<U8>,
+        share_b0: Vector<U8>,
+        share_a1: Vector<U8>,
+        share_b1: Vector<U8>,
+        eq: Vector<U8>,
     },
     FollowerKey {
-        follower_key: PublicKey,
-        share_a0: Array<U8, 32>,
-        share_b0: Array<U8, 32>,
-        share_a1: Array<U8, 32>,
-        share_b1: Array<U8, 32>,
-        eq: Array<U8, 32>,
+        follower_key: G::Point,
+        share_a0: Vector<U8>,
+        share_b0: Vector<U8>,
+        share_a1: Vector<U8>,
+        share_b1: Vector<U8>,
+        eq: Vector<U8>,
     },
     ComputedECShares {
-        share_a0: Array<U8, 32>,
-        share_b0: Array<U8, 32>,
-        share_a1: Array<U8, 32>,
-        share_b1: Array<U8, 32>,
-        eq: Array<U8, 32>,
-        pms_0: P256,
-        pms_1: P256,
+        share_a0: Vector<U8>,
+        share_b0: Vector<U8>,
+        share_a1: Vector<U8>,
+        share_b1: Vector<U8>,
+        eq: Vector<U8>,
+        pms_0: G::Field,
+        pms_1: G::Field,
     },
     EqualityCheck {
-        eq: DecodeFutureTyped<BitVec, [u8; 32]>,
+        eq: DecodeFutureTyped<BitVec, Vec<u8>>,
     },
     Complete,
     Error,
 }
 
-impl State {
+impl<G: Curve> State<G> {
     fn take(&mut self) -> Self {
         std::mem::replace(self, Self::Error)
     }
@@ -73,23 +67,23 @@ impl State {
 /// An MPC key exchange protocol.
 ///
 /// Can be either a leader or a follower depending on the `role` field in
-/// [`KeyExchangeConfig`].
+/// [`KeyExchangeConfig`]. The key exchange is performed over the curve `G`.
 #[derive(Debug)]
-pub struct MpcKeyExchange<C0, C1> {
+pub struct MpcKeyExchange<C0, C1, G: Curve = Secp256r1> {
     /// Share conversion protocol 0.
     converter_0: Arc<Mutex<C0>>,
     /// Share conversion protocol 1.
     converter_1: Arc<Mutex<C1>>,
     role: Role,
     /// The state of the protocol.
-    state: State,
+    state: State<G>,
     /// This party's private key.
-    private_key: SecretKey,
+    private_key: G::SecretKey,
     /// Server's public key.
     server_key: Option<PublicKey>,
 }
 
-impl<C0, C1> MpcKeyExchange<C0, C1> {
+impl<C0, C1, G: Curve> MpcKeyExchange<C0, C1, G> {
     /// Creates a new [`MpcKeyExchange`].
     ///
     /// # Arguments
@@ -98,7 +92,7 @@ impl<C0, C1> MpcKeyExchange<C0, C1> {
     /// * `converter_0` - Share conversion protocol instance 0.
     /// * `converter_1` - Share conversion protocol instance 1.
     pub fn new(role: Role, converter_0: C0, converter_1: C1) -> Self {
-        let private_key = SecretKey::random(&mut rand::rng().compat());
+        let private_key = G::random_secret_key();
 
         Self {
             converter_0: Arc::new(Mutex::new(converter_0)),
@@ -112,10 +106,11 @@ impl<C0, C1> MpcKeyExchange<C0, C1> {
 }
 
 #[async_trait]
-impl<C0, C1> KeyExchange for MpcKeyExchange<C0, C1>
+impl<C0, C1, G> KeyExchange for MpcKeyExchange<C0, C1, G>
 where
-    C0: ShareConvert<P256> + Flush + Send + 'static,
-    C1: ShareConvert<P256> + Flush + Send + 'static,
+    G: Curve,
+    C0: ShareConvert<G::Field> + Flush + Send + 'static,
+    C1: ShareConvert<G::Field> + Flush + Send + 'static,
 {
     #[instrument(level = "debug", skip_all, err)]
     fn alloc(&mut self, vm: &mut dyn Vm<Binary>) -> Result<Pms, KeyExchangeError> {
@@ -137,45 +132,47 @@ where
         AdditiveToMultiplicative::alloc(&mut *converter_1, 2)
             .map_err(KeyExchangeError::share_conversion)?;
 
+        let len = G::MODULUS.len();
         let (share_a0, share_b0, share_a1, share_b1) = match self.role {
             Role::Leader => {
-                let share_a0: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_a0: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_private(share_a0).map_err(KeyExchangeError::vm)?;
 
-                let share_b0: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_b0: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_blind(share_b0).map_err(KeyExchangeError::vm)?;
 
-                let share_a1: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_a1: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_private(share_a1).map_err(KeyExchangeError::vm)?;
 
-                let share_b1: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_b1: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_blind(share_b1).map_err(KeyExchangeError::vm)?;
 
                 (share_a0, share_b0, share_a1, share_b1)
             }
             Role::Follower => {
-                let share_a0: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_a0: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_blind(share_a0).map_err(KeyExchangeError::vm)?;
 
-                let share_b0: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_b0: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_private(share_b0).map_err(KeyExchangeError::vm)?;
 
-                let share_a1: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_a1: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_blind(share_a1).map_err(KeyExchangeError::vm)?;
 
-                let share_b1: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+                let share_b1: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
                 vm.mark_private(share_b1).map_err(KeyExchangeError::vm)?;
 
                 (share_a0, share_b0, share_a1, share_b1)
             }
         };
 
-        let p_constant: Array<U8, 32> = vm.alloc().map_err(KeyExchangeError::vm)?;
+        let p_constant: Vector<U8> = vm.alloc_vec(len).map_err(KeyExchangeError::vm)?;
         vm.mark_public(p_constant).map_err(KeyExchangeError::vm)?;
-        vm.assign(p_constant, P).map_err(KeyExchangeError::vm)?;
+        vm.assign(p_constant, G::MODULUS.to_vec())
+            .map_err(KeyExchangeError::vm)?;
         vm.commit(p_constant).map_err(KeyExchangeError::vm)?;
 
-        let pms_circuit = build_pms_circuit();
+        let pms_circuit = build_pms_circuit(len, G::PMS_LITTLE_ENDIAN);
         let pms_call = CallBuilder::new(pms_circuit)
             .arg(share_a0)
             .arg(share_b0)
@@ -185,8 +182,10 @@ where
             .build()
             .map_err(KeyExchangeError::vm)?;
 
-        let (pms, _, eq): (Array<U8, 32>, Array<U8, 32>, Array<U8, 32>) =
-            vm.call(pms_call).map_err(KeyExchangeError::vm)?;
+        // The outputs are PMS_0, PMS_1 and EQ.
+        let mut pms: Vector<U8> = vm.call(pms_call).map_err(KeyExchangeError::vm)?;
+        let eq = pms.split_off(2 * len);
+        let _pms_1 = pms.split_off(len);
 
         self.state = State::Setup {
             share_a0,
@@ -201,6 +200,9 @@ where
 
     #[instrument(level = "debug", skip_all, err)]
     fn set_server_key(&mut self, server_key: PublicKey) -> Result<(), KeyExchangeError> {
+        // Validates the key.
+        G::from_public_key(server_key)?;
+
         self.server_key = Some(server_key);
 
         Ok(())
@@ -222,14 +224,12 @@ where
             ));
         };
 
-        let public_key = self.private_key.public_key();
+        let public_key = G::public_key(&self.private_key);
 
         // Combine public keys.
-        let client_public_key = PublicKey::from_affine(
-            (public_key.to_projective() + follower_key.to_projective()).to_affine(),
-        )?;
+        let client_public_key = G::add(&public_key, follower_key)?;
 
-        Ok(client_public_key)
+        G::to_public_key(&client_public_key)
     }
 
     #[instrument(level = "debug", skip_all, err)]
@@ -245,7 +245,7 @@ where
             return Err(KeyExchangeError::state("should be in setup state"));
         };
 
-        let public_key = self.private_key.public_key();
+        let public_key = G::public_key(&self.private_key);
         let role = self.role;
         let mut converter_0 = self.converter_0.clone().try_lock_owned().unwrap();
         let mut converter_1 = self.converter_1.clone().try_lock_owned().unwrap();
@@ -307,8 +307,9 @@ where
         let server_key = self
             .server_key
             .ok_or_else(|| KeyExchangeError::role("server key is not set"))?;
+        let server_key = G::from_public_key(server_key)?;
 
-        let (pms_0, pms_1) = compute_ec_shares(
+        let (pms_0, pms_1) = compute_ec_shares::<G, _, _>(
             ctx,
             self.role,
             self.converter_0.clone(),
@@ -349,14 +350,14 @@ where
             ));
         };
 
-        let share_0_bytes: [u8; 32] = pms_0
-            .to_be_bytes()
-            .try_into()
-            .expect("pms share is 32 bytes");
-        let share_1_bytes: [u8; 32] = pms_1
-            .to_be_bytes()
-            .try_into()
-            .expect("pms share is 32 bytes");
+        let share_0_bytes = pms_0.to_be_bytes();
+        let share_1_bytes = pms_1.to_be_bytes();
+
+        if share_0_bytes.len() != G::MODULUS.len() || share_1_bytes.len() != G::MODULUS.len() {
+            return Err(KeyExchangeError::share_conversion(
+                "pms share has the wrong length",
+            ));
+        }
 
         match self.role {
             Role::Leader => {
@@ -402,7 +403,7 @@ where
 
         let eq = eq.await.map_err(KeyExchangeError::vm)?;
 
-        if eq != [0u8; 32] {
+        if eq.iter().any(|byte| *byte != 0) {
             return Err(KeyExchangeError::share_conversion("PMS values not equal"));
         }
 
@@ -412,39 +413,31 @@ where
     }
 }
 
-async fn compute_ec_shares<C0, C1>(
+async fn compute_ec_shares<G, C0, C1>(
     ctx: &mut Context,
     role: Role,
     converter_0: Arc<Mutex<C0>>,
     converter_1: Arc<Mutex<C1>>,
-    private_key: SecretKey,
-    server_key: PublicKey,
-) -> Result<(P256, P256), KeyExchangeError>
+    private_key: G::SecretKey,
+    server_key: G::Point,
+) -> Result<(G::Field, G::Field), KeyExchangeError>
 where
-    C0: ShareConvert<P256> + Flush + Send + 'static,
-    C1: ShareConvert<P256> + Flush + Send + 'static,
+    G: Curve,
+    C0: ShareConvert<G::Field> + Flush + Send + 'static,
+    C1: ShareConvert<G::Field> + Flush + Send + 'static,
 {
     // Compute the leader's/follower's share of the pre-master secret.
-    //
-    // We need to mimic the [diffie-hellman](p256::ecdh::diffie_hellman) function
-    // without the [SharedSecret](p256::ecdh::SharedSecret) wrapper, because
-    // this makes it harder to get the result as an EC curve point.
-    let shared_secret = {
-        let public_projective = server_key.to_projective();
-        (public_projective * private_key.to_nonzero_scalar().as_ref()).to_affine()
-    };
-
-    let encoded_point = EncodedPoint::from(PublicKey::from_affine(shared_secret)?);
+    let shared_point = G::diffie_hellman(&private_key, &server_key)?;
 
     let mut converter_0 = converter_0.try_lock_owned().unwrap();
     let mut converter_1 = converter_1.try_lock_owned().unwrap();
     let (pms_share_0, pms_share_1) = ctx
         .try_join(
             async move |ctx| {
-                derive_x_coord_share(ctx, role, &mut *converter_0, encoded_point).await
+                derive_x_coord_share::<G, _>(ctx, role, &mut *converter_0, shared_point).await
             },
             async move |ctx| {
-                derive_x_coord_share(ctx, role, &mut *converter_1, encoded_point).await
+                derive_x_coord_share::<G, _>(ctx, role, &mut *converter_1, shared_point).await
             },
         )
         .await??;
@@ -456,9 +449,11 @@ where
 mod tests {
     use super::*;
     use crate::error::ErrorRepr;
+    use ark_ff::{BigInteger, PrimeField};
+    use curve25519_dalek::{MontgomeryPoint, Scalar};
     use mpz_common::context::test_st_context;
     use mpz_core::Block;
-    use mpz_fields::UniformRand;
+    use mpz_fields::{p256::P256, UniformRand};
     use mpz_garble::protocol::semihonest::{Evaluator, Garbler};
     use mpz_memory_core::correlated::Delta;
     use mpz_ot::ideal::cot::{ideal_cot, IdealCOTReceiver, IdealCOTSender};
@@ -468,11 +463,14 @@ mod tests {
     use mpz_vm_core::Execute;
     use p256::{NonZeroScalar, PublicKey, SecretKey};
     use rand::rngs::StdRng;
+    use rand06_compat::Rand0_6CompatExt;
     use rand_core::SeedableRng;
     use rstest::*;
 
-    impl<C0, C1> MpcKeyExchange<C0, C1> {
-        fn set_pms_0(&mut self, pms: P256) {
+    use crate::curve::{Secp384r1, X25519};
+
+    impl<C0, C1, G: Curve> MpcKeyExchange<C0, C1, G> {
+        fn set_pms_0(&mut self, pms: G::Field) {
             let State::ComputedECShares { pms_0, .. } = &mut self.state else {
                 panic!("Can only set private key in initialized state")
             };
@@ -499,7 +497,7 @@ mod tests {
         )
         .unwrap();
 
-        let (mut leader, mut follower) = create_pair();
+        let (mut leader, mut follower) = create_pair::<Secp256r1>();
         leader.private_key = leader_private_key.clone();
         follower.private_key = follower_private_key.clone();
 
@@ -509,13 +507,13 @@ mod tests {
         tokio::try_join!(leader.setup(&mut ctx_a), follower.setup(&mut ctx_b)).unwrap();
 
         let client_public_key = leader.client_key().unwrap();
-        assert_eq!(client_public_key, expected_client_public_key);
+        assert_eq!(client_public_key, expected_client_public_key.into());
 
         let mut leader_pms = gen.decode(leader_pms).unwrap();
         let mut follower_pms = ev.decode(follower_pms).unwrap();
 
-        leader.set_server_key(server_public_key).unwrap();
-        follower.set_server_key(server_public_key).unwrap();
+        leader.set_server_key(server_public_key.into()).unwrap();
+        follower.set_server_key(server_public_key.into()).unwrap();
 
         let (leader_pms, follower_pms) = tokio::join!(
             async {
@@ -545,13 +543,139 @@ mod tests {
         );
 
         let expected_ecdh_x =
-            p256::ecdh::diffie_hellman(server_secret_key, client_public_key.as_affine());
+            p256::ecdh::diffie_hellman(server_secret_key, expected_client_public_key.as_affine());
         let expected_ecdh_x = expected_ecdh_x.raw_secret_bytes().to_vec();
 
         assert_eq!(leader_pms, follower_pms);
         assert_eq!(leader_pms.to_vec(), expected_ecdh_x);
     }
 
+    #[tokio::test]
+    async fn test_key_exchange_x25519() {
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut gen, mut ev) = mock_vm();
+
+        let server_secret_key = X25519::random_secret_key();
+        let server_public_key =
+            X25519::to_public_key(&X25519::public_key(&server_secret_key)).unwrap();
+
+        let (mut leader, mut follower) = create_pair::<X25519>();
+
+        let leader_pms = leader.alloc(&mut gen).unwrap();
+        let follower_pms = follower.alloc(&mut ev).unwrap();
+
+        tokio::try_join!(leader.setup(&mut ctx_a), follower.setup(&mut ctx_b)).unwrap();
+
+        let crate::PublicKey::X25519(client_public_key) = leader.client_key().unwrap() else {
+            panic!("expected an x25519 key");
+        };
+
+        let mut leader_pms = gen.decode(leader_pms).unwrap();
+        let mut follower_pms = ev.decode(follower_pms).unwrap();
+
+        leader.set_server_key(server_public_key).unwrap();
+        follower.set_server_key(server_public_key).unwrap();
+
+        let (leader_pms, follower_pms) = tokio::join!(
+            async {
+                leader.compute_shares(&mut ctx_a).await.unwrap();
+                leader.assign(&mut gen).unwrap();
+
+                gen.flush(&mut ctx_a).await.unwrap();
+                gen.execute(&mut ctx_a).await.unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+
+                leader.finalize().await.unwrap();
+
+                leader_pms.try_recv().unwrap().unwrap()
+            },
+            async {
+                follower.compute_shares(&mut ctx_b).await.unwrap();
+                follower.assign(&mut ev).unwrap();
+
+                ev.flush(&mut ctx_b).await.unwrap();
+                ev.execute(&mut ctx_b).await.unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+
+                follower.finalize().await.unwrap();
+
+                follower_pms.try_recv().unwrap().unwrap()
+            }
+        );
+
+        let server_scalar: [u8; 32] = server_secret_key
+            .into_bigint()
+            .to_bytes_le()
+            .try_into()
+            .unwrap();
+        let expected_pms =
+            MontgomeryPoint(client_public_key) * Scalar::from_bytes_mod_order(server_scalar);
+
+        assert_eq!(leader_pms, follower_pms);
+        assert_eq!(leader_pms, expected_pms.to_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_key_exchange_secp384r1() {
+        let mut rng = StdRng::seed_from_u64(0).compat();
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+        let (mut gen, mut ev) = mock_vm();
+
+        let server_secret_key = p384::NonZeroScalar::random(&mut rng);
+        let server_public_key = p384::PublicKey::from_secret_scalar(&server_secret_key);
+
+        let (mut leader, mut follower) = create_pair::<Secp384r1>();
+
+        let leader_pms = leader.alloc(&mut gen).unwrap();
+        let follower_pms = follower.alloc(&mut ev).unwrap();
+
+        tokio::try_join!(leader.setup(&mut ctx_a), follower.setup(&mut ctx_b)).unwrap();
+
+        let crate::PublicKey::Secp384r1(client_public_key) = leader.client_key().unwrap() else {
+            panic!("expected a secp384r1 key");
+        };
+
+        let mut leader_pms = gen.decode(leader_pms).unwrap();
+        let mut follower_pms = ev.decode(follower_pms).unwrap();
+
+        leader.set_server_key(server_public_key.into()).unwrap();
+        follower.set_server_key(server_public_key.into()).unwrap();
+
+        let (leader_pms, follower_pms) = tokio::join!(
+            async {
+                leader.compute_shares(&mut ctx_a).await.unwrap();
+                leader.assign(&mut gen).unwrap();
+
+                gen.flush(&mut ctx_a).await.unwrap();
+                gen.execute(&mut ctx_a).await.unwrap();
+                gen.flush(&mut ctx_a).await.unwrap();
+
+                leader.finalize().await.unwrap();
+
+                leader_pms.try_recv().unwrap().unwrap()
+            },
+            async {
+                follower.compute_shares(&mut ctx_b).await.unwrap();
+                follower.assign(&mut ev).unwrap();
+
+                ev.flush(&mut ctx_b).await.unwrap();
+                ev.execute(&mut ctx_b).await.unwrap();
+                ev.flush(&mut ctx_b).await.unwrap();
+
+                follower.finalize().await.unwrap();
+
+                follower_pms.try_recv().unwrap().unwrap()
+            }
+        );
+
+        let expected_ecdh_x =
+            p384::ecdh::diffie_hellman(server_secret_key, client_public_key.as_affine());
+
+        assert_eq!(leader_pms.len(), 48);
+        assert_eq!(leader_pms, follower_pms);
+        assert_eq!(leader_pms, expected_ecdh_x.raw_secret_bytes().to_vec());
+    }
+
     #[tokio::test]
     async fn test_compute_ec_shares() {
         let mut rng = StdRng::seed_from_u64(0).compat();
@@ -578,7 +702,7 @@ mod tests {
 
         let ((leader_share_0, leader_share_1), (follower_share_0, follower_share_1)) =
             tokio::try_join!(
-                compute_ec_shares(
+                compute_ec_shares::<Secp256r1, _, _>(
                     &mut ctx_leader,
                     Role::Leader,
                     leader_converter_0,
@@ -586,7 +710,7 @@ mod tests {
                     leader_private_key,
                     server_public_key
                 ),
-                compute_ec_shares(
+                compute_ec_shares::<Secp256r1, _, _>(
                     &mut ctx_follower,
                     Role::Follower,
                     follower_converter_0,
@@ -638,7 +762,7 @@ mod tests {
         )
         .unwrap();
 
-        let (mut leader, mut follower) = create_pair();
+        let (mut leader, mut follower) = create_pair::<Secp256r1>();
         leader.private_key = leader_private_key.clone();
         follower.private_key = follower_private_key.clone();
 
@@ -648,13 +772,13 @@ mod tests {
         tokio::try_join!(leader.setup(&mut ctx_a), follower.setup(&mut ctx_b)).unwrap();
 
         let client_public_key = leader.client_key().unwrap();
-        assert_eq!(client_public_key, expected_client_public_key);
+        assert_eq!(client_public_key, expected_client_public_key.into());
 
         let bad_pms_share = P256::rand(&mut rng);
 
         let (leader_err, follower_err) = tokio::join!(
             async {
-                leader.set_server_key(server_public_key).unwrap();
+                leader.set_server_key(server_public_key.into()).unwrap();
                 leader.compute_shares(&mut ctx_a).await.unwrap();
 
                 // Replace the leader's share with a different value.
@@ -671,7 +795,7 @@ mod tests {
                 leader.finalize().await
             },
             async {
-                follower.set_server_key(server_public_key).unwrap();
+                follower.set_server_key(server_public_key.into()).unwrap();
                 follower.compute_shares(&mut ctx_b).await.unwrap();
 
                 // Replace the follower's share with a different value.
@@ -718,7 +842,11 @@ mod tests {
 
                 let p_constant: Array<U8, 32> = vm.alloc().unwrap();
                 vm.mark_public(p_constant).unwrap();
-                vm.assign(p_constant, P).unwrap();
+                vm.assign(
+                    p_constant,
+                    <[u8; 32]>::try_from(Secp256r1::MODULUS).unwrap(),
+                )
+                .unwrap();
                 vm.commit(p_constant).unwrap();
 
                 let share_a0: Array<U8, 32> = vm.alloc().unwrap();
@@ -733,7 +861,7 @@ mod tests {
                 let share_b1: Array<U8, 32> = vm.alloc().unwrap();
                 vm.mark_blind(share_b1).unwrap();
 
-                let pms_circuit = build_pms_circuit();
+                let pms_circuit = build_pms_circuit(32, false);
                 let pms_call = CallBuilder::new(pms_circuit)
                     .arg(share_a0)
                     .arg(share_b0)
@@ -766,7 +894,11 @@ mod tests {
                 let mut vm = ev;
                 let p_constant: Array<U8, 32> = vm.alloc().unwrap();
                 vm.mark_public(p_constant).unwrap();
-                vm.assign(p_constant, P).unwrap();
+                vm.assign(
+                    p_constant,
+                    <[u8; 32]>::try_from(Secp256r1::MODULUS).unwrap(),
+                )
+                .unwrap();
                 vm.commit(p_constant).unwrap();
 
                 let share_a0: Array<U8, 32> = vm.alloc().unwrap();
@@ -781,7 +913,7 @@ mod tests {
                 let share_b1: Array<U8, 32> = vm.alloc().unwrap();
                 vm.mark_private(share_b1).unwrap();
 
-                let pms_circuit = build_pms_circuit();
+                let pms_circuit = build_pms_circuit(32, false);
                 let pms_call = CallBuilder::new(pms_circuit)
                     .arg(share_a0)
                     .arg(share_b0)
@@ -820,9 +952,9 @@ mod tests {
     }
 
     #[allow(clippy::type_complexity)]
-    fn create_pair() -> (
-        MpcKeyExchange<IdealShareConvertSender<P256>, IdealShareConvertReceiver<P256>>,
-        MpcKeyExchange<IdealShareConvertReceiver<P256>, IdealShareConvertSender<P256>>,
+    fn create_pair<G: Curve>() -> (
+        MpcKeyExchange<IdealShareConvertSender<G::Field>, IdealShareConvertReceiver<G::Field>, G>,
+        MpcKeyExchange<IdealShareConvertReceiver<G::Field>, IdealShareConvertSender<G::Field>, G>,
     ) {
         let (leader_converter_0, follower_converter_0) = ideal_share_convert(Block::ZERO);
         let (follower_converter_1, leader_converter_1) = ideal_share_convert(Block::ZERO);
@@ -15,12 +15,14 @@
 #![forbid(unsafe_code)]
 
 mod circuit;
+pub mod curve;
 pub(crate) mod error;
 mod exchange;
 #[cfg(feature = "mock")]
 pub mod mock;
 pub(crate) mod point_addition;
 
+pub use curve::{Curve, Secp256r1, Secp384r1, X25519};
 pub use error::KeyExchangeError;
 pub use exchange::MpcKeyExchange;
 
@@ -28,13 +30,38 @@ use async_trait::async_trait;
 use mpz_common::Context;
 use mpz_memory_core::{
     binary::{Binary, U8},
-    Array,
+    Vector,
 };
 use mpz_vm_core::Vm;
-use p256::PublicKey;
 
 /// Pre-master secret.
-pub type Pms = Array<U8, 32>;
+///
+/// The length is that of the field elements of the curve, i.e. 32 bytes for
+/// secp256r1 and X25519 and 48 bytes for secp384r1.
+pub type Pms = Vector<U8>;
+
+/// A public key of the key exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicKey {
+    /// secp256r1 public key.
+    Secp256r1(p256::PublicKey),
+    /// X25519 public key, the little-endian u-coordinate.
+    X25519([u8; 32]),
+    /// secp384r1 public key.
+    Secp384r1(p384::PublicKey),
+}
+
+impl From<p256::PublicKey> for PublicKey {
+    fn from(key: p256::PublicKey) -> Self {
+        Self::Secp256r1(key)
+    }
+}
+
+impl From<p384::PublicKey> for PublicKey {
+    fn from(key: p384::PublicKey) -> Self {
+        Self::Secp384r1(key)
+    }
+}
 
 /// Role in the key exchange protocol.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -1,19 +1,34 @@
 //! This module provides mock types for key exchange leader and follower and a
 //! function to create such a pair.
 
-use crate::{MpcKeyExchange, Role};
+use async_trait::async_trait;
+use mpz_common::Context;
 use mpz_core::Block;
-use mpz_fields::p256::P256;
+use mpz_fields::Field;
+use mpz_memory_core::{
+    binary::{Binary, U8},
+    MemoryExt, Vector, ViewExt,
+};
 use mpz_share_conversion::ideal::{
     ideal_share_convert, IdealShareConvertReceiver, IdealShareConvertSender,
 };
+use mpz_vm_core::Vm;
+use serio::{sink::SinkExt, stream::IoStreamExt};
+
+use crate::{
+    curve::{Curve, Secp256r1},
+    KeyExchange, KeyExchangeError, MpcKeyExchange, Pms, PublicKey, Role,
+};
 
 /// A mock key exchange instance.
-pub type MockKeyExchange =
-    MpcKeyExchange<IdealShareConvertSender<P256>, IdealShareConvertReceiver<P256>>;
+pub type MockKeyExchange<G = Secp256r1> = MpcKeyExchange<
+    IdealShareConvertSender<<G as Curve>::Field>,
+    IdealShareConvertReceiver<<G as Curve>::Field>,
+    G,
+>;
 
-/// Creates a mock pair of key exchange leader and follower.
-pub fn create_mock_key_exchange_pair() -> (MockKeyExchange, MockKeyExchange) {
+/// Creates a mock pair of key exchange leader and follower over the curve `G`.
+pub fn create_mock_key_exchange_pair<G: Curve>() -> (MockKeyExchange<G>, MockKeyExchange<G>) {
     let (leader_converter_0, follower_converter_0) = ideal_share_convert(Block::ZERO);
     let (follower_converter_1, leader_converter_1) = ideal_share_convert(Block::ZERO);
 
@@ -24,17 +39,162 @@ pub fn create_mock_key_exchange_pair() -> (MockKeyExchange, MockKeyExchange) {
     (leader, follower)
 }
 
+/// An ideal key exchange which computes the PMS in plaintext.
+///
+/// The follower sends its ECDH point to the leader, who computes the PMS and
+/// assigns it as a private input. This reveals the PMS to the leader and must
+/// only be used for testing.
+#[derive(Debug)]
+pub struct IdealKeyExchange<G: Curve = Secp256r1> {
+    role: Role,
+    private_key: G::SecretKey,
+    server_key: Option<PublicKey>,
+    follower_key: Option<G::Point>,
+    pms_ref: Option<Pms>,
+    pms: Option<Vec<u8>>,
+}
+
+impl<G: Curve> IdealKeyExchange<G> {
+    /// Creates a new [`IdealKeyExchange`].
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            private_key: G::random_secret_key(),
+            server_key: None,
+            follower_key: None,
+            pms_ref: None,
+            pms: None,
+        }
+    }
+}
+
+#[async_trait]
+impl<G: Curve> KeyExchange for IdealKeyExchange<G> {
+    fn alloc(&mut self, vm: &mut dyn Vm<Binary>) -> Result<Pms, KeyExchangeError> {
+        if self.pms_ref.is_some() {
+            return Err(KeyExchangeError::state("PMS is already allocated"));
+        }
+
+        let pms: Vector<U8> = vm
+            .alloc_vec(G::MODULUS.len())
+            .map_err(KeyExchangeError::vm)?;
+        match self.role {
+            Role::Leader => vm.mark_private(pms).map_err(KeyExchangeError::vm)?,
+            Role::Follower => vm.mark_blind(pms).map_err(KeyExchangeError::vm)?,
+        }
+
+        self.pms_ref = Some(pms);
+
+        Ok(pms)
+    }
+
+    fn set_server_key(&mut self, server_key: PublicKey) -> Result<(), KeyExchangeError> {
+        // Validates the key.
+        G::from_public_key(server_key)?;
+
+        self.server_key = Some(server_key);
+
+        Ok(())
+    }
+
+    fn server_key(&self) -> Option<PublicKey> {
+        self.server_key
+    }
+
+    fn client_key(&self) -> Result<PublicKey, KeyExchangeError> {
+        let Role::Leader = self.role else {
+            return Err(KeyExchangeError::role("follower does not learn client key"));
+        };
+
+        let follower_key = self
+            .follower_key
+            .as_ref()
+            .ok_or_else(|| KeyExchangeError::state("setup has not been performed"))?;
+
+        G::to_public_key(&G::add(&G::public_key(&self.private_key), follower_key)?)
+    }
+
+    async fn setup(&mut self, ctx: &mut Context) -> Result<(), KeyExchangeError> {
+        let public_key = G::public_key(&self.private_key);
+        let follower_key = match self.role {
+            Role::Leader => ctx.io_mut().expect_next().await?,
+            Role::Follower => {
+                ctx.io_mut().send(public_key).await?;
+                public_key
+            }
+        };
+
+        self.follower_key = Some(follower_key);
+
+        Ok(())
+    }
+
+    async fn compute_shares(&mut self, ctx: &mut Context) -> Result<(), KeyExchangeError> {
+        let server_key = self
+            .server_key
+            .ok_or_else(|| KeyExchangeError::role("server key is not set"))?;
+        let server_key = G::from_public_key(server_key)?;
+
+        let shared_point = G::diffie_hellman(&self.private_key, &server_key)?;
+
+        match self.role {
+            Role::Leader => {
+                let follower_point: G::Point = ctx.io_mut().expect_next().await?;
+                let [x, _] = G::coordinates(&G::add(&shared_point, &follower_point)?)?;
+
+                let mut pms = x.to_be_bytes();
+                if G::PMS_LITTLE_ENDIAN {
+                    pms.reverse();
+                }
+
+                self.pms = Some(pms);
+            }
+            Role::Follower => ctx.io_mut().send(shared_point).await?,
+        }
+
+        Ok(())
+    }
+
+    fn assign(&mut self, vm: &mut dyn Vm<Binary>) -> Result<(), KeyExchangeError> {
+        let pms_ref = self
+            .pms_ref
+            .ok_or_else(|| KeyExchangeError::state("PMS is not allocated"))?;
+
+        if let Role::Leader = self.role {
+            let pms = self
+                .pms
+                .take()
+                .ok_or_else(|| KeyExchangeError::state("PMS has not been computed"))?;
+            vm.assign(pms_ref, pms).map_err(KeyExchangeError::vm)?;
+        }
+        vm.commit(pms_ref).map_err(KeyExchangeError::vm)?;
+
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<(), KeyExchangeError> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use mpz_garble::protocol::semihonest::{Evaluator, Garbler};
     use mpz_ot::ideal::cot::{IdealCOTReceiver, IdealCOTSender};
 
     use super::*;
-    use crate::KeyExchange;
+    use crate::curve::X25519;
+    use mpz_common::context::test_st_context;
+    use mpz_fields::p256::P256;
+    use mpz_memory_core::correlated::Delta;
+    use mpz_ot::ideal::cot::ideal_cot;
+    use mpz_vm_core::Execute;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand06_compat::Rand0_6CompatExt;
 
     #[test]
     fn test_mock_is_ke() {
-        let (leader, follower) = create_mock_key_exchange_pair();
+        let (leader, follower) = create_mock_key_exchange_pair::<Secp256r1>();
 
         fn is_key_exchange<T: KeyExchange, V>(_: T) {}
 
@@ -48,4 +208,78 @@ mod tests {
             Evaluator<IdealCOTReceiver>,
         >(follower);
     }
+
+    #[test]
+    fn test_mock_x25519_is_ke() {
+        let (leader, follower) = create_mock_key_exchange_pair::<X25519>();
+
+        fn is_key_exchange<T: KeyExchange, V>(_: T) {}
+
+        is_key_exchange::<MockKeyExchange<X25519>, Garbler<IdealCOTSender>>(leader);
+        is_key_exchange::<MockKeyExchange<X25519>, Evaluator<IdealCOTReceiver>>(follower);
+    }
+
+    #[tokio::test]
+    async fn test_ideal_key_exchange() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut ctx_a, mut ctx_b) = test_st_context(8);
+
+        let delta = Delta::random(&mut rng);
+        let (cot_send, cot_recv) = ideal_cot(delta.into_inner());
+        let mut gen = Garbler::new(cot_send, [0u8; 16], delta);
+        let mut ev = Evaluator::new(cot_recv);
+
+        let server_secret_key = p256::NonZeroScalar::random(&mut rng.compat());
+        let server_public_key = p256::PublicKey::from_secret_scalar(&server_secret_key);
+
+        let mut leader = IdealKeyExchange::<Secp256r1>::new(Role::Leader);
+        let mut follower = IdealKeyExchange::<Secp256r1>::new(Role::Follower);
+
+        let leader_pms = leader.alloc(&mut gen).unwrap();
+        let follower_pms = follower.alloc(&mut ev).unwrap();
+
+        tokio::try_join!(leader.setup(&mut ctx_a), follower.setup(&mut ctx_b)).unwrap();
+
+        let PublicKey::Secp256r1(client_public_key) = leader.client_key().unwrap() else {
+            panic!("expected a secp256r1 key");
+        };
+
+        let mut leader_pms = gen.decode(leader_pms).unwrap();
+        let mut follower_pms = ev.decode(follower_pms).unwrap();
+
+        leader.set_server_key(server_public_key.into()).unwrap();
+        follower.set_server_key(server_public_key.into()).unwrap();
+
+        tokio::try_join!(
+            leader.compute_shares(&mut ctx_a),
+            follower.compute_shares(&mut ctx_b)
+        )
+        .unwrap();
+
+        leader.assign(&mut gen).unwrap();
+        follower.assign(&mut ev).unwrap();
+
+        tokio::try_join!(
+            async {
+                gen.flush(&mut ctx_a).await?;
+                gen.execute(&mut ctx_a).await?;
+                gen.flush(&mut ctx_a).await
+            },
+            async {
+                ev.flush(&mut ctx_b).await?;
+                ev.execute(&mut ctx_b).await?;
+                ev.flush(&mut ctx_b).await
+            }
+        )
+        .unwrap();
+
+        let leader_pms = leader_pms.try_recv().unwrap().unwrap();
+        let follower_pms = follower_pms.try_recv().unwrap().unwrap();
+
+        let expected_ecdh_x =
+            p256::ecdh::diffie_hellman(server_secret_key, client_public_key.as_affine());
+
+        assert_eq!(leader_pms, follower_pms);
+        assert_eq!(leader_pms, expected_ecdh_x.raw_secret_bytes().to_vec());
+    }
 }
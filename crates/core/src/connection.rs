@@ -4,7 +4,12 @@ use std::fmt;
 
 use rustls_pki_types as webpki_types;
 use serde::{Deserialize, Serialize};
-use tls_core::msgs::{codec::Codec, enums::NamedGroup, handshake::ServerECDHParams};
+use sha2::{Digest, Sha256};
+use tls_core::msgs::{
+    codec::{Codec, Reader},
+    enums::{NamedGroup, ProtocolVersion},
+    handshake::{HandshakeMessagePayload, HandshakePayload, ServerECDHParams},
+};
 
 use crate::webpki::{CertificateDer, ServerCertVerifier, ServerCertVerifierError};
 
@@ -30,6 +35,50 @@ impl TryFrom<tls_core::msgs::enums::ProtocolVersion> for TlsVersion {
     }
 }
 
+/// AEAD algorithm of the TLS cipher suite.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AeadAlgorithm {
+    /// AES-128-GCM.
+    #[default]
+    Aes128Gcm,
+    /// AES-256-GCM.
+    Aes256Gcm,
+    /// ChaCha20-Poly1305, see RFC 7905.
+    ChaCha20Poly1305,
+}
+
+impl AeadAlgorithm {
+    /// Returns the length of the write keys in bytes.
+    pub fn key_len(&self) -> usize {
+        match self {
+            AeadAlgorithm::Aes128Gcm => 16,
+            AeadAlgorithm::Aes256Gcm => 32,
+            AeadAlgorithm::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// Returns the length of the write IVs in TLS 1.2 in bytes.
+    pub fn iv_len(&self) -> usize {
+        match self {
+            AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => 4,
+            AeadAlgorithm::ChaCha20Poly1305 => 12,
+        }
+    }
+}
+
+impl TryFrom<&tls_core::suites::AEADAlgorithm> for AeadAlgorithm {
+    type Error = &'static str;
+
+    fn try_from(value: &tls_core::suites::AEADAlgorithm) -> Result<Self, Self::Error> {
+        Ok(match value {
+            tls_core::suites::AEADAlgorithm::AES_128_GCM => AeadAlgorithm::Aes128Gcm,
+            tls_core::suites::AEADAlgorithm::AES_256_GCM => AeadAlgorithm::Aes256Gcm,
+            tls_core::suites::AEADAlgorithm::CHACHA20_POLY1305 => AeadAlgorithm::ChaCha20Poly1305,
+        })
+    }
+}
+
 /// Server's name.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ServerName {
@@ -107,13 +156,41 @@ impl TryFrom<String> for DnsName {
 }
 
 /// Type of a public key.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 #[allow(non_camel_case_types)]
 pub enum KeyType {
     /// secp256r1.
+    #[default]
     SECP256R1 = 0x0017,
+    /// X25519, see RFC 7748.
+    X25519 = 0x001d,
+    /// secp384r1.
+    SECP384R1 = 0x0018,
+}
+
+impl From<KeyType> for NamedGroup {
+    fn from(value: KeyType) -> Self {
+        match value {
+            KeyType::SECP256R1 => NamedGroup::secp256r1,
+            KeyType::X25519 => NamedGroup::X25519,
+            KeyType::SECP384R1 => NamedGroup::secp384r1,
+        }
+    }
+}
+
+impl TryFrom<NamedGroup> for KeyType {
+    type Error = &'static str;
+
+    fn try_from(value: NamedGroup) -> Result<Self, Self::Error> {
+        match value {
+            NamedGroup::secp256r1 => Ok(KeyType::SECP256R1),
+            NamedGroup::X25519 => Ok(KeyType::X25519),
+            NamedGroup::secp384r1 => Ok(KeyType::SECP384R1),
+            _ => Err("unsupported key type"),
+        }
+    }
 }
 
 /// Signature algorithm used on the key exchange parameters.
@@ -211,9 +288,7 @@ pub struct ServerEphemKey {
 impl ServerEphemKey {
     /// Encodes the key exchange parameters as in TLS.
     pub(crate) fn kx_params(&self) -> Vec<u8> {
-        let group = match self.typ {
-            KeyType::SECP256R1 => NamedGroup::secp256r1,
-        };
+        let group = NamedGroup::from(self.typ);
 
         let mut kx_params = Vec::new();
         ServerECDHParams::new(group, &self.key).encode(&mut kx_params);
@@ -226,12 +301,8 @@ impl TryFrom<tls_core::key::PublicKey> for ServerEphemKey {
     type Error = &'static str;
 
     fn try_from(value: tls_core::key::PublicKey) -> Result<Self, Self::Error> {
-        let tls_core::msgs::enums::NamedGroup::secp256r1 = value.group else {
-            return Err("unsupported key type");
-        };
-
         Ok(ServerEphemKey {
-            typ: KeyType::SECP256R1,
+            typ: KeyType::try_from(value.group)?,
             key: value.key,
         })
     }
@@ -268,6 +339,36 @@ pub struct CertBindingV1_2 {
     pub server_ephemeral_key: ServerEphemKey,
 }
 
+/// TLS 1.3 certificate binding.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertBindingV1_3 {
+    /// Server's ephemeral public key.
+    pub server_ephemeral_key: ServerEphemKey,
+    /// Encoded handshake messages from the ClientHello up to and including the
+    /// server's Certificate message.
+    ///
+    /// The server signs the hash of these messages in its CertificateVerify
+    /// message.
+    pub handshake_messages: Vec<u8>,
+}
+
+impl CertBindingV1_3 {
+    /// Returns the message signed by the server in its CertificateVerify
+    /// message.
+    pub fn signed_message(&self) -> Vec<u8> {
+        tls13_signed_message(&self.handshake_messages)
+    }
+}
+
+/// Returns the message signed by the server in TLS 1.3, see section 4.4.3 of
+/// RFC 8446.
+fn tls13_signed_message(handshake_messages: &[u8]) -> Vec<u8> {
+    let mut message = vec![0x20; 64];
+    message.extend_from_slice(b"TLS 1.3, server CertificateVerify\x00");
+    message.extend_from_slice(&Sha256::digest(handshake_messages));
+    message
+}
+
 /// TLS certificate binding.
 ///
 /// This is the data that the server signs using its public key in the
@@ -280,6 +381,8 @@ pub struct CertBindingV1_2 {
 pub enum CertBinding {
     /// TLS 1.2 certificate binding.
     V1_2(CertBindingV1_2),
+    /// TLS 1.3 certificate binding.
+    V1_3(CertBindingV1_3),
 }
 
 /// Verify data from the TLS handshake finished messages.
@@ -318,14 +421,15 @@ impl HandshakeData {
         server_ephemeral_key: &ServerEphemKey,
         server_name: &ServerName,
     ) -> Result<(), HandshakeVerificationError> {
-        #[allow(irrefutable_let_patterns)]
-        let CertBinding::V1_2(CertBindingV1_2 {
-            client_random,
-            server_random,
-            server_ephemeral_key: expected_server_ephemeral_key,
-        }) = &self.binding
-        else {
-            unreachable!("only TLS 1.2 is implemented")
+        let expected_server_ephemeral_key = match &self.binding {
+            CertBinding::V1_2(CertBindingV1_2 {
+                server_ephemeral_key,
+                ..
+            }) => server_ephemeral_key,
+            CertBinding::V1_3(CertBindingV1_3 {
+                server_ephemeral_key,
+                ..
+            }) => server_ephemeral_key,
         };
 
         if server_ephemeral_key != expected_server_ephemeral_key {
@@ -343,11 +447,27 @@ impl HandshakeData {
             .verify_server_cert(end_entity, intermediates, server_name, time)
             .map_err(HandshakeVerificationError::ServerCert)?;
 
-        // Verify the signature matches the certificate and key exchange parameters.
-        let mut message = Vec::new();
-        message.extend_from_slice(client_random);
-        message.extend_from_slice(server_random);
-        message.extend_from_slice(&server_ephemeral_key.kx_params());
+        // Construct the message signed by the server.
+        let message = match &self.binding {
+            CertBinding::V1_2(CertBindingV1_2 {
+                client_random,
+                server_random,
+                ..
+            }) => {
+                let mut message = Vec::new();
+                message.extend_from_slice(client_random);
+                message.extend_from_slice(server_random);
+                message.extend_from_slice(&server_ephemeral_key.kx_params());
+                message
+            }
+            CertBinding::V1_3(CertBindingV1_3 {
+                handshake_messages, ..
+            }) => {
+                verify_handshake_messages(handshake_messages, &self.certs, server_ephemeral_key)?;
+
+                tls13_signed_message(handshake_messages)
+            }
+        };
 
         use webpki::ring as alg;
         let sig_alg = match self.sig.alg {
@@ -382,6 +502,71 @@ impl HandshakeData {
     }
 }
 
+/// Verifies that the TLS 1.3 handshake messages are consistent with the
+/// certificate chain and the server's ephemeral key.
+fn verify_handshake_messages(
+    handshake_messages: &[u8],
+    certs: &[CertificateDer],
+    server_ephemeral_key: &ServerEphemKey,
+) -> Result<(), HandshakeVerificationError> {
+    let mut reader = Reader::init(handshake_messages);
+    let mut server_hello = None;
+    let mut last = None;
+    while reader.any_left() {
+        let msg = HandshakeMessagePayload::read_version(&mut reader, ProtocolVersion::TLSv1_3)
+            .ok_or(HandshakeVerificationError::InvalidHandshakeMessages(
+                "malformed handshake message",
+            ))?;
+
+        if let HandshakePayload::ServerHello(payload) = &msg.payload {
+            if server_hello
+                .replace(payload.get_key_share().cloned())
+                .is_some()
+            {
+                return Err(HandshakeVerificationError::InvalidHandshakeMessages(
+                    "multiple server hello messages",
+                ));
+            }
+        }
+
+        last = Some(msg.payload);
+    }
+
+    let key_share = server_hello
+        .ok_or(HandshakeVerificationError::InvalidHandshakeMessages(
+            "missing server hello",
+        ))?
+        .ok_or(HandshakeVerificationError::InvalidHandshakeMessages(
+            "missing server key share",
+        ))?;
+
+    let group = NamedGroup::from(server_ephemeral_key.typ);
+
+    if key_share.group != group || key_share.payload.0 != server_ephemeral_key.key {
+        return Err(HandshakeVerificationError::InvalidServerEphemeralKey);
+    }
+
+    let Some(HandshakePayload::CertificateTLS13(payload)) = last else {
+        return Err(HandshakeVerificationError::InvalidHandshakeMessages(
+            "last message is not the server certificate",
+        ));
+    };
+
+    if payload.entries.len() != certs.len()
+        || payload
+            .entries
+            .iter()
+            .zip(certs)
+            .any(|(entry, cert)| entry.cert.0 != cert.0)
+    {
+        return Err(HandshakeVerificationError::InvalidHandshakeMessages(
+            "certificate message does not match the certificate chain",
+        ));
+    }
+
+    Ok(())
+}
+
 /// Errors that can occur when verifying a certificate chain or signature.
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -394,6 +579,8 @@ pub enum HandshakeVerificationError {
     InvalidServerSignature,
     #[error("invalid server ephemeral key")]
     InvalidServerEphemeralKey,
+    #[error("invalid handshake messages: {0}")]
+    InvalidHandshakeMessages(&'static str),
     #[error("server certificate verification failed: {0}")]
     ServerCert(ServerCertVerifierError),
 }
@@ -590,7 +777,10 @@ mod tests {
         #[case] mut data: ConnectionFixture,
     ) {
         let CertBinding::V1_2(CertBindingV1_2 { client_random, .. }) =
-            &mut data.server_cert_data.binding;
+            &mut data.server_cert_data.binding
+        else {
+            unreachable!("fixtures are TLS 1.2")
+        };
         client_random[31] = client_random[31].wrapping_add(1);
 
         let err = data.server_cert_data.verify(
@@ -675,6 +865,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_key_type_named_group() {
+        for typ in [KeyType::SECP256R1, KeyType::X25519, KeyType::SECP384R1] {
+            assert_eq!(KeyType::try_from(NamedGroup::from(typ)).unwrap(), typ);
+        }
+
+        assert!(KeyType::try_from(NamedGroup::X448).is_err());
+    }
+
+    #[test]
+    fn test_kx_params_secp384r1() {
+        let key = ServerEphemKey {
+            typ: KeyType::SECP384R1,
+            key: vec![4u8; 97],
+        };
+
+        let kx_params = key.kx_params();
+
+        // Named curve, the group and the length of the key.
+        assert_eq!(kx_params[..4], [3, 0x00, 0x18, 97]);
+        assert_eq!(kx_params[4..], key.key);
+    }
+
     /// Expect to fail when no cert provided.
     #[rstest]
     #[case::tlsnotary(tlsnotary())]
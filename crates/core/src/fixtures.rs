@@ -9,8 +9,8 @@ use hex::FromHex;
 
 use crate::{
     connection::{
-        CertBinding, CertBindingV1_2, ConnectionInfo, DnsName, HandshakeData, KeyType,
-        ServerEphemKey, ServerName, ServerSignature, SignatureAlgorithm, TlsVersion,
+        CertBinding, CertBindingV1_2, CertBindingV1_3, ConnectionInfo, DnsName, HandshakeData,
+        KeyType, ServerEphemKey, ServerName, ServerSignature, SignatureAlgorithm, TlsVersion,
         TranscriptLength,
     },
     transcript::{
@@ -122,11 +122,16 @@ impl ConnectionFixture {
 
     /// Returns the server_ephemeral_key fixture.
     pub fn server_ephemeral_key(&self) -> &ServerEphemKey {
-        let CertBinding::V1_2(CertBindingV1_2 {
-            server_ephemeral_key,
-            ..
-        }) = &self.server_cert_data.binding;
-        server_ephemeral_key
+        match &self.server_cert_data.binding {
+            CertBinding::V1_2(CertBindingV1_2 {
+                server_ephemeral_key,
+                ..
+            }) => server_ephemeral_key,
+            CertBinding::V1_3(CertBindingV1_3 {
+                server_ephemeral_key,
+                ..
+            }) => server_ephemeral_key,
+        }
     }
 }
 
@@ -9,6 +9,7 @@ pub mod connection;
 pub mod fixtures;
 pub mod hash;
 pub mod merkle;
+pub mod metrics;
 pub mod transcript;
 pub mod webpki;
 pub use rangeset;
@@ -18,7 +19,8 @@ use rangeset::{RangeSet, ToRangeSet, UnionMut};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    connection::{HandshakeData, ServerName},
+    connection::{ConnectionInfo, HandshakeData, ServerName},
+    metrics::SessionMetrics,
     transcript::{
         encoding::EncoderSecret, Direction, PartialTranscript, Transcript, TranscriptCommitConfig,
         TranscriptCommitRequest, TranscriptCommitment, TranscriptSecret,
@@ -26,11 +28,11 @@ use crate::{
 };
 
 /// Configuration to prove information to the verifier.
+///
+/// Carries a disclosure configuration for each connection which is proven.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProveConfig {
-    server_identity: bool,
-    reveal: Option<(RangeSet<usize>, RangeSet<usize>)>,
-    transcript_commit: Option<TranscriptCommitConfig>,
+    connections: Vec<ConnectionProveConfig>,
 }
 
 impl ProveConfig {
@@ -39,6 +41,38 @@ impl ProveConfig {
         ProveConfigBuilder::new(transcript)
     }
 
+    /// Returns the configuration of each connection to prove, in the order
+    /// the connections were made.
+    pub fn connections(&self) -> &[ConnectionProveConfig] {
+        &self.connections
+    }
+}
+
+/// Configuration to prove information about a single connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionProveConfig {
+    connection: usize,
+    server_identity: bool,
+    reveal: Option<(RangeSet<usize>, RangeSet<usize>)>,
+    transcript_commit: Option<TranscriptCommitConfig>,
+}
+
+impl ConnectionProveConfig {
+    fn new(connection: usize) -> Self {
+        Self {
+            connection,
+            server_identity: false,
+            reveal: None,
+            transcript_commit: None,
+        }
+    }
+
+    /// Returns the index of the connection, in the order the connections were
+    /// made.
+    pub fn connection(&self) -> usize {
+        self.connection
+    }
+
     /// Returns `true` if the server identity is to be proven.
     pub fn server_identity(&self) -> bool {
         self.server_identity
@@ -56,34 +90,66 @@ impl ProveConfig {
 }
 
 /// Builder for [`ProveConfig`].
+///
+/// The builder configures the first connection, other connections are
+/// selected with [`connection`](Self::connection).
 #[derive(Debug)]
 pub struct ProveConfigBuilder<'a> {
     transcript: &'a Transcript,
-    server_identity: bool,
-    reveal: Option<(RangeSet<usize>, RangeSet<usize>)>,
-    transcript_commit: Option<TranscriptCommitConfig>,
+    current: usize,
+    connections: Vec<ConnectionProveConfig>,
 }
 
 impl<'a> ProveConfigBuilder<'a> {
-    /// Creates a new builder.
+    /// Creates a new builder for the first connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `transcript` - The transcript of the first connection.
     pub fn new(transcript: &'a Transcript) -> Self {
         Self {
             transcript,
-            server_identity: false,
-            reveal: None,
-            transcript_commit: None,
+            current: 0,
+            connections: vec![ConnectionProveConfig::new(0)],
         }
     }
 
+    /// Selects the connection which is configured by subsequent calls, in the
+    /// order the connections were made.
+    ///
+    /// Every selected connection is proven, configurations of previously
+    /// selected connections are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection` - The index of the connection.
+    /// * `transcript` - The transcript of the connection.
+    pub fn connection(&mut self, connection: usize, transcript: &'a Transcript) -> &mut Self {
+        self.transcript = transcript;
+        self.current = match self
+            .connections
+            .iter()
+            .position(|config| config.connection == connection)
+        {
+            Some(current) => current,
+            None => {
+                self.connections
+                    .push(ConnectionProveConfig::new(connection));
+                self.connections.len() - 1
+            }
+        };
+        self
+    }
+
     /// Proves the server identity.
     pub fn server_identity(&mut self) -> &mut Self {
-        self.server_identity = true;
+        self.current_mut().server_identity = true;
         self
     }
 
     /// Configures transcript commitments.
     pub fn transcript_commit(&mut self, transcript_commit: TranscriptCommitConfig) -> &mut Self {
-        self.transcript_commit = Some(transcript_commit);
+        self.current_mut().transcript_commit = Some(transcript_commit);
         self
     }
 
@@ -105,7 +171,7 @@ impl<'a> ProveConfigBuilder<'a> {
             ));
         }
 
-        let (sent, recv) = self.reveal.get_or_insert_default();
+        let (sent, recv) = self.current_mut().reveal.get_or_insert_default();
         match direction {
             Direction::Sent => sent.union_mut(&idx),
             Direction::Received => recv.union_mut(&idx),
@@ -125,7 +191,7 @@ impl<'a> ProveConfigBuilder<'a> {
     /// Reveals all of the sent data transcript.
     pub fn reveal_sent_all(&mut self) -> Result<&mut Self, ProveConfigBuilderError> {
         let len = self.transcript.len_of_direction(Direction::Sent);
-        let (sent, _) = self.reveal.get_or_insert_default();
+        let (sent, _) = self.current_mut().reveal.get_or_insert_default();
         sent.union_mut(&(0..len));
         Ok(self)
     }
@@ -141,18 +207,21 @@ impl<'a> ProveConfigBuilder<'a> {
     /// Reveals all of the received data transcript.
     pub fn reveal_recv_all(&mut self) -> Result<&mut Self, ProveConfigBuilderError> {
         let len = self.transcript.len_of_direction(Direction::Received);
-        let (_, recv) = self.reveal.get_or_insert_default();
+        let (_, recv) = self.current_mut().reveal.get_or_insert_default();
         recv.union_mut(&(0..len));
         Ok(self)
     }
 
     /// Builds the configuration.
     pub fn build(self) -> Result<ProveConfig, ProveConfigBuilderError> {
-        Ok(ProveConfig {
-            server_identity: self.server_identity,
-            reveal: self.reveal,
-            transcript_commit: self.transcript_commit,
-        })
+        let mut connections = self.connections;
+        connections.sort_by_key(|config| config.connection);
+
+        Ok(ProveConfig { connections })
+    }
+
+    fn current_mut(&mut self) -> &mut ConnectionProveConfig {
+        &mut self.connections[self.current]
     }
 }
 
@@ -206,10 +275,14 @@ pub struct VerifyConfigBuilderError(#[from] VerifyConfigBuilderErrorRepr);
 #[derive(Debug, thiserror::Error)]
 enum VerifyConfigBuilderErrorRepr {}
 
-/// Request to prove statements about the connection.
+/// Request to prove statements about a connection.
+///
+/// The prover sends one request for each connection it proves.
 #[doc(hidden)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProveRequest {
+    /// Index of the connection.
+    pub connection: usize,
     /// Handshake data.
     pub handshake: Option<(ServerName, HandshakeData)>,
     /// Transcript data.
@@ -221,10 +294,14 @@ pub struct ProveRequest {
 /// Prover output.
 #[derive(Serialize, Deserialize)]
 pub struct ProverOutput {
+    /// Index of the connection, in the order the connections were made.
+    pub connection: usize,
     /// Transcript commitments.
     pub transcript_commitments: Vec<TranscriptCommitment>,
     /// Transcript commitment secrets.
     pub transcript_secrets: Vec<TranscriptSecret>,
+    /// Bandwidth and timing of the session up to and including this proof.
+    pub metrics: SessionMetrics,
 }
 
 opaque_debug::implement!(ProverOutput);
@@ -232,6 +309,10 @@ opaque_debug::implement!(ProverOutput);
 /// Verifier output.
 #[derive(Serialize, Deserialize)]
 pub struct VerifierOutput {
+    /// Index of the connection, in the order the connections were made.
+    pub connection: usize,
+    /// Connection information.
+    pub connection_info: ConnectionInfo,
     /// Server identity.
     pub server_name: Option<ServerName>,
     /// Transcript data.
@@ -240,6 +321,39 @@ pub struct VerifierOutput {
     pub encoder_secret: Option<EncoderSecret>,
     /// Transcript commitments.
     pub transcript_commitments: Vec<TranscriptCommitment>,
+    /// Bandwidth and timing of the session up to and including this
+    /// verification.
+    pub metrics: SessionMetrics,
 }
 
 opaque_debug::implement!(VerifierOutput);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prove_config_connections() {
+        let first = Transcript::new(b"GET / HTTP/1.1", b"HTTP/1.1 200 OK");
+        let second = Transcript::new(b"GET /api HTTP/1.1", b"HTTP/1.1 404 Not Found");
+
+        let mut builder = ProveConfig::builder(&first);
+        builder.connection(1, &second);
+        builder.reveal_recv_all().unwrap();
+        builder.connection(0, &first);
+        builder.server_identity();
+        assert!(builder.reveal_recv(&(0..20)).is_err());
+
+        let config = builder.build().unwrap();
+        let [first, second] = config.connections() else {
+            panic!("expected two connections");
+        };
+
+        assert_eq!(first.connection(), 0);
+        assert!(first.server_identity());
+        assert!(first.reveal().is_none());
+        assert_eq!(second.connection(), 1);
+        assert!(!second.server_identity());
+        assert_eq!(second.reveal().unwrap().1, RangeSet::from(0..22));
+    }
+}
@@ -0,0 +1,48 @@
+//! Session metrics.
+
+use std::{ops::AddAssign, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Bandwidth and wall time of a protocol phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseMetrics {
+    /// Number of bytes sent to the peer.
+    pub sent: u64,
+    /// Number of bytes received from the peer.
+    pub recv: u64,
+    /// Wall time spent in the phase.
+    pub duration: Duration,
+}
+
+impl AddAssign for PhaseMetrics {
+    fn add_assign(&mut self, rhs: Self) {
+        self.sent += rhs.sent;
+        self.recv += rhs.recv;
+        self.duration += rhs.duration;
+    }
+}
+
+/// Bandwidth and wall time of a session between the prover and the verifier,
+/// attributed to protocol phases.
+///
+/// Only the traffic between the prover and the verifier is accounted for, the
+/// traffic with the server is not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMetrics {
+    /// Negotiation of the protocol configuration and measurement of the link.
+    pub setup: PhaseMetrics,
+    /// Preprocessing, i.e. the oblivious transfer setup and garbling.
+    pub preprocess: PhaseMetrics,
+    /// The TLS handshake with the server.
+    pub handshake: PhaseMetrics,
+    /// Online encryption and decryption of application data records.
+    pub online: PhaseMetrics,
+    /// Decryption of the deferred records and finalization of the MPC.
+    pub decryption: PhaseMetrics,
+    /// Proving, including the commitments to the transcript.
+    pub prove: PhaseMetrics,
+    /// Totals of the session so far, including the traffic which is not
+    /// attributed to any phase.
+    pub total: PhaseMetrics,
+}
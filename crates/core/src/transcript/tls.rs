@@ -2,7 +2,8 @@
 
 use crate::{
     connection::{
-        CertBinding, CertBindingV1_2, ServerEphemKey, ServerSignature, TlsVersion, VerifyData,
+        CertBinding, CertBindingV1_2, CertBindingV1_3, ServerEphemKey, ServerSignature, TlsVersion,
+        VerifyData,
     },
     transcript::{Direction, Transcript},
     webpki::CertificateDer,
@@ -42,70 +43,74 @@ impl TlsTranscript {
         let mut sent_iter = sent.iter();
         let mut recv_iter = recv.iter();
 
-        // Make sure the client finished verify data message was sent first.
-        if let Some(record) = sent_iter.next() {
-            let payload = record
-                .plaintext
-                .as_ref()
-                .ok_or(TlsTranscriptError::validation(
-                    "client finished message was hidden from the follower",
-                ))?;
-
-            let mut reader = Reader::init(payload);
-            let payload =
-                HandshakeMessagePayload::read_version(&mut reader, ProtocolVersion::TLSv1_2)
+        // In TLS 1.3 the finished messages are encrypted with the handshake
+        // traffic keys, so they are not part of the record transcript.
+        if let TlsVersion::V1_2 = version {
+            // Make sure the client finished verify data message was sent first.
+            if let Some(record) = sent_iter.next() {
+                let payload = record
+                    .plaintext
+                    .as_ref()
                     .ok_or(TlsTranscriptError::validation(
-                        "first record sent was not a handshake message",
+                        "client finished message was hidden from the follower",
                     ))?;
 
-            let HandshakePayload::Finished(vd) = payload.payload else {
-                return Err(TlsTranscriptError::validation(
-                    "first record sent was not a client finished message",
-                ));
-            };
-
-            if vd.0 != verify_data.client_finished {
+                let mut reader = Reader::init(payload);
+                let payload =
+                    HandshakeMessagePayload::read_version(&mut reader, ProtocolVersion::TLSv1_2)
+                        .ok_or(TlsTranscriptError::validation(
+                            "first record sent was not a handshake message",
+                        ))?;
+
+                let HandshakePayload::Finished(vd) = payload.payload else {
+                    return Err(TlsTranscriptError::validation(
+                        "first record sent was not a client finished message",
+                    ));
+                };
+
+                if vd.0 != verify_data.client_finished {
+                    return Err(TlsTranscriptError::validation(
+                        "inconsistent client finished verify data",
+                    ));
+                }
+            } else {
                 return Err(TlsTranscriptError::validation(
-                    "inconsistent client finished verify data",
+                    "client finished was not sent",
                 ));
             }
-        } else {
-            return Err(TlsTranscriptError::validation(
-                "client finished was not sent",
-            ));
-        }
 
-        // Make sure the server finished verify data message was received first.
-        if let Some(record) = recv_iter.next() {
-            let payload = record
-                .plaintext
-                .as_ref()
-                .ok_or(TlsTranscriptError::validation(
-                    "server finished message was hidden from the follower",
-                ))?;
-
-            let mut reader = Reader::init(payload);
-            let payload =
-                HandshakeMessagePayload::read_version(&mut reader, ProtocolVersion::TLSv1_2)
+            // Make sure the server finished verify data message was received first.
+            if let Some(record) = recv_iter.next() {
+                let payload = record
+                    .plaintext
+                    .as_ref()
                     .ok_or(TlsTranscriptError::validation(
-                        "first record received was not a handshake message",
+                        "server finished message was hidden from the follower",
                     ))?;
 
-            let HandshakePayload::Finished(vd) = payload.payload else {
-                return Err(TlsTranscriptError::validation(
-                    "first record received was not a server finished message",
-                ));
-            };
-
-            if vd.0 != verify_data.server_finished {
+                let mut reader = Reader::init(payload);
+                let payload =
+                    HandshakeMessagePayload::read_version(&mut reader, ProtocolVersion::TLSv1_2)
+                        .ok_or(TlsTranscriptError::validation(
+                            "first record received was not a handshake message",
+                        ))?;
+
+                let HandshakePayload::Finished(vd) = payload.payload else {
+                    return Err(TlsTranscriptError::validation(
+                        "first record received was not a server finished message",
+                    ));
+                };
+
+                if vd.0 != verify_data.server_finished {
+                    return Err(TlsTranscriptError::validation(
+                        "inconsistent server finished verify data",
+                    ));
+                }
+            } else {
                 return Err(TlsTranscriptError::validation(
-                    "inconsistent server finished verify data",
+                    "server finished was not received",
                 ));
             }
-        } else {
-            return Err(TlsTranscriptError::validation(
-                "server finished was not received",
-            ));
         }
 
         // Verify last record sent was either application data or close notify.
@@ -231,6 +236,10 @@ impl TlsTranscript {
                 server_ephemeral_key,
                 ..
             }) => server_ephemeral_key,
+            CertBinding::V1_3(CertBindingV1_3 {
+                server_ephemeral_key,
+                ..
+            }) => server_ephemeral_key,
         }
     }
 
@@ -250,6 +259,9 @@ impl TlsTranscript {
     }
 
     /// Returns the application data transcript.
+    ///
+    /// In TLS 1.3 the content type is encrypted as the last byte of each
+    /// record, which is removed from the transcript.
     pub fn to_transcript(&self) -> Result<Transcript, TlsTranscriptError> {
         let mut sent = Vec::new();
         let mut recv = Vec::new();
@@ -259,15 +271,11 @@ impl TlsTranscript {
             .iter()
             .filter(|record| record.typ == ContentType::ApplicationData)
         {
-            let plaintext = record
-                .plaintext
-                .as_ref()
-                .ok_or(ErrorRepr::Incomplete {
-                    direction: Direction::Sent,
-                    seq: record.seq,
-                })?
-                .clone();
-            sent.extend_from_slice(&plaintext);
+            let plaintext = record.plaintext.as_ref().ok_or(ErrorRepr::Incomplete {
+                direction: Direction::Sent,
+                seq: record.seq,
+            })?;
+            sent.extend_from_slice(self.content(plaintext));
         }
 
         for record in self
@@ -275,19 +283,23 @@ impl TlsTranscript {
             .iter()
             .filter(|record| record.typ == ContentType::ApplicationData)
         {
-            let plaintext = record
-                .plaintext
-                .as_ref()
-                .ok_or(ErrorRepr::Incomplete {
-                    direction: Direction::Received,
-                    seq: record.seq,
-                })?
-                .clone();
-            recv.extend_from_slice(&plaintext);
+            let plaintext = record.plaintext.as_ref().ok_or(ErrorRepr::Incomplete {
+                direction: Direction::Received,
+                seq: record.seq,
+            })?;
+            recv.extend_from_slice(self.content(plaintext));
         }
 
         Ok(Transcript::new(sent, recv))
     }
+
+    /// Returns the record content of the plaintext.
+    fn content<'a>(&self, plaintext: &'a [u8]) -> &'a [u8] {
+        match self.version {
+            TlsVersion::V1_2 => plaintext,
+            TlsVersion::V1_3 => &plaintext[..plaintext.len().saturating_sub(1)],
+        }
+    }
 }
 
 /// A TLS record.
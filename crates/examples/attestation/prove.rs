@@ -255,7 +255,7 @@ async fn notarize(
         transcript_commitments,
         transcript_secrets,
         ..
-    } = prover.prove(&disclosure_config).await?;
+    } = prover.prove(&disclosure_config).await?.remove(0);
 
     let transcript = prover.transcript().clone();
     let tls_transcript = prover.tls_transcript().clone();
@@ -331,7 +331,7 @@ async fn notary<S: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
         transcript_commitments,
         encoder_secret,
         ..
-    } = verifier.verify(&VerifyConfig::default()).await?;
+    } = verifier.verify(&VerifyConfig::default()).await?.remove(0);
 
     let tls_transcript = verifier.tls_transcript().clone();
 
@@ -209,7 +209,8 @@ async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
     } = verifier
         .verify(socket.compat(), &VerifyConfig::default())
         .await
-        .unwrap();
+        .unwrap()
+        .remove(0);
 
     let server_name = server_name.expect("prover should have revealed server name");
     let transcript = transcript.expect("prover should have revealed transcript data");
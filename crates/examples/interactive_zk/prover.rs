@@ -156,7 +156,7 @@ pub async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     let prove_config = prove_config_builder.build()?;
 
     // MPC-TLS prove
-    let prover_output = prover.prove(&prove_config).await?;
+    let prover_output = prover.prove(&prove_config).await?.remove(0);
     prover.close().await?;
 
     // Prove birthdate is more than 18 years ago.
@@ -49,7 +49,8 @@ pub async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>
         ..
     } = verifier
         .verify(socket.compat(), &VerifyConfig::default())
-        .await?;
+        .await?
+        .remove(0);
 
     let server_name = server_name.ok_or("Prover should have revealed server name")?;
     let transcript = transcript.ok_or("Prover should have revealed transcript data")?;
@@ -198,6 +198,18 @@ pub struct ProverMetrics {
     pub downloaded_total: u64,
     /// Peak heap memory usage in bytes.
     pub heap_max_bytes: Option<usize>,
+    /// Estimated amount of data uploaded to the verifier in bytes during
+    /// preprocessing.
+    pub estimated_uploaded_preprocess: u64,
+    /// Estimated amount of data downloaded from the verifier in bytes during
+    /// preprocessing.
+    pub estimated_downloaded_preprocess: u64,
+    /// Estimated amount of data uploaded to the verifier in bytes after
+    /// preprocessing.
+    pub estimated_uploaded_online: u64,
+    /// Estimated amount of data downloaded from the verifier in bytes after
+    /// preprocessing.
+    pub estimated_downloaded_online: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -233,6 +245,18 @@ pub struct Measurement {
     pub downloaded_total: u64,
     /// Peak heap memory usage in bytes.
     pub heap_max_bytes: Option<usize>,
+    /// Estimated amount of data uploaded to the verifier in bytes during
+    /// preprocessing.
+    pub estimated_uploaded_preprocess: u64,
+    /// Estimated amount of data downloaded from the verifier in bytes during
+    /// preprocessing.
+    pub estimated_downloaded_preprocess: u64,
+    /// Estimated amount of data uploaded to the verifier in bytes after
+    /// preprocessing.
+    pub estimated_uploaded_online: u64,
+    /// Estimated amount of data downloaded from the verifier in bytes after
+    /// preprocessing.
+    pub estimated_downloaded_online: u64,
 }
 
 impl Measurement {
@@ -255,6 +279,10 @@ impl Measurement {
             uploaded_total: metrics.uploaded_total,
             downloaded_total: metrics.downloaded_total,
             heap_max_bytes: metrics.heap_max_bytes,
+            estimated_uploaded_preprocess: metrics.estimated_uploaded_preprocess,
+            estimated_downloaded_preprocess: metrics.estimated_downloaded_preprocess,
+            estimated_uploaded_online: metrics.estimated_uploaded_online,
+            estimated_downloaded_online: metrics.estimated_downloaded_online,
         }
     }
 }
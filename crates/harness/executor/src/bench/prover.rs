@@ -5,7 +5,7 @@ use futures::{AsyncReadExt, AsyncWriteExt, TryFutureExt};
 
 use harness_core::bench::{Bench, ProverMetrics};
 use tlsn::{
-    config::{CertificateDer, ProtocolConfig, RootCertStore},
+    config::{CertificateDer, CostEstimate, ProtocolConfig, RootCertStore},
     connection::ServerName,
     prover::{ProveConfig, Prover, ProverConfig, TlsConfig},
 };
@@ -33,6 +33,9 @@ pub async fn bench_prover(provider: &IoProvider, config: &Bench) -> Result<Prove
 
     let protocol_config = builder.build()?;
 
+    // Estimate the cost up front to compare it with the measured traffic.
+    let estimate = CostEstimate::new(&protocol_config, protocol_config.network(), None);
+
     let mut tls_config_builder = TlsConfig::builder();
     tls_config_builder.root_store(RootCertStore {
         roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
@@ -109,5 +112,9 @@ pub async fn bench_prover(provider: &IoProvider, config: &Bench) -> Result<Prove
         uploaded_total: sent.load(Ordering::Relaxed),
         downloaded_total: recv.load(Ordering::Relaxed),
         heap_max_bytes: None,
+        estimated_uploaded_preprocess: estimate.preprocess_upload,
+        estimated_downloaded_preprocess: estimate.preprocess_download,
+        estimated_uploaded_online: estimate.online_upload,
+        estimated_downloaded_online: estimate.online_download,
     })
 }
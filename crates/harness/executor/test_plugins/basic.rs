@@ -138,7 +138,8 @@ async fn verifier(provider: &IoProvider) {
             &VerifyConfig::default(),
         )
         .await
-        .unwrap();
+        .unwrap()
+        .remove(0);
 
     let ServerName::Dns(server_name) = server_name.unwrap();
 
@@ -1,5 +1,9 @@
 use derive_builder::Builder;
 use hmac_sha256::Mode as PrfMode;
+use tls_core::suites::{SupportedCipherSuite, ALL_CIPHER_SUITES};
+use tlsn_core::connection::{AeadAlgorithm, KeyType, TlsVersion};
+
+use crate::record_layer::Ceilings;
 
 /// Number of TLS protocol bytes that will be sent.
 const PROTOCOL_DATA_SENT: usize = 32;
@@ -13,6 +17,14 @@ const PROTOCOL_RECORD_COUNT_SENT: usize = 2;
 /// to the application data records.
 const PROTOCOL_RECORD_COUNT_RECV: usize = 2;
 
+/// Number of additional TLS 1.3 protocol bytes that will be received.
+///
+/// In TLS 1.3 post-handshake messages, e.g. session tickets, are encrypted with
+/// the application traffic keys.
+const PROTOCOL_DATA_RECV_TLS13: usize = 1024;
+/// Number of additional TLS 1.3 protocol records that will be received.
+const PROTOCOL_RECORD_COUNT_RECV_TLS13: usize = 2;
+
 /// Computes the record count configuration given the data volume.
 ///
 /// Accurately estimating a good default is challenging as we do not
@@ -58,11 +70,40 @@ pub struct Config {
     /// decrypted for free.
     pub(crate) max_recv_online: usize,
     /// Maximum number of received bytes.
-    #[allow(unused)]
     pub(crate) max_recv: usize,
+    /// Hard ceiling on the number of sent bytes.
+    ///
+    /// Capacity beyond `max_sent` is allocated on demand during the connection
+    /// up to this ceiling. Defaults to `max_sent`.
+    pub(crate) sent_ceiling: usize,
+    /// Hard ceiling on the number of sent TLS records. Defaults to an estimate
+    /// based on `sent_ceiling`.
+    pub(crate) sent_records_ceiling: usize,
+    /// Hard ceiling on the number of received bytes which will be decrypted
+    /// while the TLS connection is active.
+    ///
+    /// Capacity beyond `max_recv_online` is allocated on demand during the
+    /// connection up to this ceiling. Defaults to `max_recv_online`.
+    pub(crate) recv_online_ceiling: usize,
+    /// Hard ceiling on the number of received TLS records to be decrypted
+    /// online. Defaults to an estimate based on `recv_online_ceiling`.
+    pub(crate) recv_records_online_ceiling: usize,
+    /// Hard ceiling on the number of received bytes. Defaults to `max_recv`.
+    pub(crate) recv_ceiling: usize,
     /// Configuration options for the PRF.
     #[builder(setter(custom))]
     pub(crate) prf: PrfMode,
+    /// TLS protocol version.
+    pub(crate) version: TlsVersion,
+    /// Whether to derive the extended master secret (RFC 7627).
+    ///
+    /// If enabled, the server is required to negotiate the extension. Only
+    /// applies to TLS 1.2.
+    pub(crate) extended_master_secret: bool,
+    /// AEAD algorithm of the cipher suite.
+    pub(crate) aead: AeadAlgorithm,
+    /// Key exchange group.
+    pub(crate) key_type: KeyType,
 }
 
 impl Config {
@@ -70,6 +111,125 @@ impl Config {
     pub fn builder() -> ConfigBuilder {
         ConfigBuilder::default()
     }
+
+    /// Returns whether decryption of received data is deferred until after the
+    /// MPC-TLS connection is closed.
+    pub fn defer_decryption(&self) -> bool {
+        self.defer_decryption
+    }
+
+    /// Returns the maximum number of sent TLS records, including protocol
+    /// records.
+    pub fn max_sent_records(&self) -> usize {
+        self.max_sent_records
+    }
+
+    /// Returns the maximum number of sent bytes, including protocol data.
+    pub fn max_sent(&self) -> usize {
+        self.max_sent
+    }
+
+    /// Returns the maximum number of received TLS records to be decrypted
+    /// online, including protocol records.
+    pub fn max_recv_records_online(&self) -> usize {
+        self.max_recv_records_online
+    }
+
+    /// Returns the maximum number of received bytes which will be decrypted
+    /// online, including protocol data.
+    pub fn max_recv_online(&self) -> usize {
+        self.max_recv_online
+    }
+
+    /// Returns the maximum number of received bytes, including protocol data.
+    pub fn max_recv(&self) -> usize {
+        self.max_recv
+    }
+
+    /// Returns the hard ceiling on the number of sent bytes, including protocol
+    /// data.
+    pub fn sent_ceiling(&self) -> usize {
+        self.sent_ceiling
+    }
+
+    /// Returns the hard ceiling on the number of sent TLS records, including
+    /// protocol records.
+    pub fn sent_records_ceiling(&self) -> usize {
+        self.sent_records_ceiling
+    }
+
+    /// Returns the hard ceiling on the number of received bytes which will be
+    /// decrypted online, including protocol data.
+    pub fn recv_online_ceiling(&self) -> usize {
+        self.recv_online_ceiling
+    }
+
+    /// Returns the hard ceiling on the number of received TLS records to be
+    /// decrypted online, including protocol records.
+    pub fn recv_records_online_ceiling(&self) -> usize {
+        self.recv_records_online_ceiling
+    }
+
+    /// Returns the hard ceiling on the number of received bytes, including
+    /// protocol data.
+    pub fn recv_ceiling(&self) -> usize {
+        self.recv_ceiling
+    }
+
+    /// Returns whether capacity is allocated on demand beyond the configured
+    /// maxima.
+    pub fn is_resizable(&self) -> bool {
+        self.sent_ceiling > self.max_sent
+            || self.sent_records_ceiling > self.max_sent_records
+            || self.recv_online_ceiling > self.max_recv_online
+            || self.recv_records_online_ceiling > self.max_recv_records_online
+            || self.recv_ceiling > self.max_recv
+    }
+
+    pub(crate) fn ceilings(&self) -> Ceilings {
+        Ceilings {
+            sent_records: self.sent_records_ceiling,
+            recv_records_online: self.recv_records_online_ceiling,
+            sent: self.sent_ceiling,
+            recv_online: self.recv_online_ceiling,
+            recv: self.recv_ceiling,
+        }
+    }
+
+    /// Returns whether the extended master secret is derived.
+    pub fn extended_master_secret(&self) -> bool {
+        self.extended_master_secret
+    }
+
+    /// Returns the AEAD algorithm of the cipher suite.
+    pub fn aead(&self) -> AeadAlgorithm {
+        self.aead
+    }
+
+    /// Returns the key exchange group.
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
+
+    /// Returns whether the cipher suite can be used with this configuration.
+    pub fn supports_suite(&self, suite: &SupportedCipherSuite) -> bool {
+        let version = match self.version {
+            TlsVersion::V1_2 => tls_core::msgs::enums::ProtocolVersion::TLSv1_2,
+            TlsVersion::V1_3 => tls_core::msgs::enums::ProtocolVersion::TLSv1_3,
+        };
+
+        suite.version().version == version
+            && AeadAlgorithm::try_from(suite.aead_algorithm()).ok() == Some(self.aead)
+    }
+
+    /// Returns the cipher suites which can be used with this configuration.
+    pub fn cipher_suites(&self) -> Vec<SupportedCipherSuite> {
+        ALL_CIPHER_SUITES
+            .iter()
+            .filter(|suite| self.supports_suite(suite))
+            .copied()
+            .collect()
+    }
 }
 
 impl ConfigBuilder {
@@ -82,14 +242,13 @@ impl ConfigBuilder {
     /// Builds the configuration.
     pub fn build(&self) -> Result<Config, ConfigBuilderError> {
         let defer_decryption = self.defer_decryption.unwrap_or(true);
-        let max_sent = PROTOCOL_DATA_SENT
-            + self
-                .max_sent
-                .ok_or(ConfigBuilderError::UninitializedField("max_sent"))?;
-        let mut max_recv_online = self
+        let max_sent = self
+            .max_sent
+            .ok_or(ConfigBuilderError::UninitializedField("max_sent"))?;
+        let max_recv_online = self
             .max_recv_online
             .ok_or(ConfigBuilderError::UninitializedField("max_recv_online"))?;
-        let mut max_recv = self
+        let max_recv = self
             .max_recv
             .ok_or(ConfigBuilderError::UninitializedField("max_recv"))?;
 
@@ -99,17 +258,118 @@ impl ConfigBuilder {
             ));
         }
 
-        max_recv_online += PROTOCOL_DATA_RECV;
-        max_recv += PROTOCOL_DATA_RECV;
+        let sent_ceiling = self.sent_ceiling.unwrap_or(max_sent);
+        let recv_online_ceiling = self.recv_online_ceiling.unwrap_or(max_recv_online);
+        let recv_ceiling = self.recv_ceiling.unwrap_or(max_recv);
+
+        if sent_ceiling < max_sent {
+            return Err(ConfigBuilderError::ValidationError(
+                "sent_ceiling must be greater than or equal to max_sent".to_string(),
+            ));
+        } else if recv_online_ceiling < max_recv_online {
+            return Err(ConfigBuilderError::ValidationError(
+                "recv_online_ceiling must be greater than or equal to max_recv_online".to_string(),
+            ));
+        } else if recv_ceiling < max_recv {
+            return Err(ConfigBuilderError::ValidationError(
+                "recv_ceiling must be greater than or equal to max_recv".to_string(),
+            ));
+        } else if recv_online_ceiling > recv_ceiling {
+            return Err(ConfigBuilderError::ValidationError(
+                "recv_online_ceiling must be less than or equal to recv_ceiling".to_string(),
+            ));
+        }
+
+        let version = self.version.unwrap_or(TlsVersion::V1_2);
 
-        let max_sent_records = self
+        let mut max_sent = PROTOCOL_DATA_SENT + max_sent;
+        let mut sent_ceiling = PROTOCOL_DATA_SENT + sent_ceiling;
+        let mut max_recv_online = PROTOCOL_DATA_RECV + max_recv_online;
+        let mut recv_online_ceiling = PROTOCOL_DATA_RECV + recv_online_ceiling;
+        let mut max_recv = PROTOCOL_DATA_RECV + max_recv;
+        let mut recv_ceiling = PROTOCOL_DATA_RECV + recv_ceiling;
+        if let TlsVersion::V1_3 = version {
+            max_recv_online += PROTOCOL_DATA_RECV_TLS13;
+            recv_online_ceiling += PROTOCOL_DATA_RECV_TLS13;
+            max_recv += PROTOCOL_DATA_RECV_TLS13;
+            recv_ceiling += PROTOCOL_DATA_RECV_TLS13;
+        }
+
+        let mut max_sent_records = self
             .max_sent_records
             .unwrap_or_else(|| PROTOCOL_RECORD_COUNT_SENT + default_record_count(max_sent));
-        let max_recv_records_online = self
+        let mut max_recv_records_online = self
             .max_recv_records_online
             .unwrap_or_else(|| PROTOCOL_RECORD_COUNT_RECV + default_record_count(max_recv_online));
 
+        // Unless set explicitly, the record ceilings are only raised if the
+        // corresponding data ceiling is.
+        let mut sent_records_ceiling = match self.sent_records_ceiling {
+            Some(ceiling) => ceiling,
+            None if sent_ceiling > max_sent => max_sent_records
+                .max(PROTOCOL_RECORD_COUNT_SENT + default_record_count(sent_ceiling)),
+            None => max_sent_records,
+        };
+        let mut recv_records_online_ceiling = match self.recv_records_online_ceiling {
+            Some(ceiling) => ceiling,
+            None if recv_online_ceiling > max_recv_online => max_recv_records_online
+                .max(PROTOCOL_RECORD_COUNT_RECV + default_record_count(recv_online_ceiling)),
+            None => max_recv_records_online,
+        };
+
+        if sent_records_ceiling < max_sent_records {
+            return Err(ConfigBuilderError::ValidationError(
+                "sent_records_ceiling must be greater than or equal to max_sent_records"
+                    .to_string(),
+            ));
+        } else if recv_records_online_ceiling < max_recv_records_online {
+            return Err(ConfigBuilderError::ValidationError(
+                "recv_records_online_ceiling must be greater than or equal to max_recv_records_online"
+                    .to_string(),
+            ));
+        }
+
+        if let TlsVersion::V1_3 = version {
+            max_recv_records_online += PROTOCOL_RECORD_COUNT_RECV_TLS13;
+            recv_records_online_ceiling += PROTOCOL_RECORD_COUNT_RECV_TLS13;
+
+            // Each TLS 1.3 record carries its content type as an additional
+            // encrypted byte.
+            max_sent += max_sent_records;
+            sent_ceiling += sent_records_ceiling;
+            max_recv_online += max_recv_records_online;
+            recv_online_ceiling += recv_records_online_ceiling;
+            max_recv += max_recv_records_online + default_record_count(max_recv);
+            recv_ceiling += recv_records_online_ceiling + default_record_count(recv_ceiling);
+        }
+
         let prf = self.prf.unwrap_or(PrfMode::Normal);
+        let extended_master_secret = self.extended_master_secret.unwrap_or(false);
+        let aead = self.aead.unwrap_or_default();
+
+        match (version, aead) {
+            (TlsVersion::V1_3, AeadAlgorithm::Aes256Gcm) => {
+                return Err(ConfigBuilderError::ValidationError(
+                    "AES-256-GCM is not supported with TLS 1.3".to_string(),
+                ));
+            }
+            (TlsVersion::V1_3, AeadAlgorithm::ChaCha20Poly1305) => {
+                return Err(ConfigBuilderError::ValidationError(
+                    "ChaCha20-Poly1305 is not supported with TLS 1.3".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let key_type = self.key_type.unwrap_or_default();
+        if !matches!(
+            key_type,
+            KeyType::SECP256R1 | KeyType::X25519 | KeyType::SECP384R1
+        ) {
+            return Err(ConfigBuilderError::ValidationError(format!(
+                "unsupported key type: {key_type:?}"
+            )));
+        }
 
         Ok(Config {
             defer_decryption,
@@ -118,7 +378,16 @@ impl ConfigBuilder {
             max_recv_records_online,
             max_recv_online,
             max_recv,
+            sent_ceiling,
+            sent_records_ceiling,
+            recv_online_ceiling,
+            recv_records_online_ceiling,
+            recv_ceiling,
             prf,
+            version,
+            extended_master_secret,
+            aead,
+            key_type,
         })
     }
 }
@@ -131,4 +400,117 @@ mod tests {
     fn test_default_record_count() {
         assert_eq!(default_record_count(1 << 15), 15);
     }
+
+    #[test]
+    fn test_ceilings() {
+        let config = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 14)
+            .build()
+            .unwrap();
+
+        assert!(!config.is_resizable());
+        assert_eq!(config.sent_ceiling(), config.max_sent());
+        assert_eq!(config.recv_online_ceiling(), config.max_recv_online());
+        assert_eq!(
+            config.recv_records_online_ceiling(),
+            config.max_recv_records_online()
+        );
+
+        let config = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 14)
+            .recv_online_ceiling(1 << 16)
+            .recv_ceiling(1 << 16)
+            .build()
+            .unwrap();
+
+        assert!(config.is_resizable());
+        assert_eq!(config.sent_ceiling(), config.max_sent());
+        assert_eq!(config.recv_online_ceiling(), PROTOCOL_DATA_RECV + (1 << 16));
+        assert_eq!(
+            config.recv_records_online_ceiling(),
+            PROTOCOL_RECORD_COUNT_RECV + default_record_count(PROTOCOL_DATA_RECV + (1 << 16))
+        );
+
+        let result = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 14)
+            .recv_ceiling(1 << 12)
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cipher_suites() {
+        let config = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 12)
+            .aead(AeadAlgorithm::Aes256Gcm)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config
+                .cipher_suites()
+                .iter()
+                .map(|suite| suite.suite())
+                .collect::<Vec<_>>(),
+            [
+                tls_core::msgs::enums::CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+                tls_core::msgs::enums::CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tls13_aes_256_unsupported() {
+        let result = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 12)
+            .version(TlsVersion::V1_3)
+            .aead(AeadAlgorithm::Aes256Gcm)
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chacha20_poly1305_cipher_suites() {
+        let config = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 12)
+            .aead(AeadAlgorithm::ChaCha20Poly1305)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config
+                .cipher_suites()
+                .iter()
+                .map(|suite| suite.suite())
+                .collect::<Vec<_>>(),
+            [
+                tls_core::msgs::enums::CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                tls_core::msgs::enums::CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+            ]
+        );
+
+        let result = Config::builder()
+            .max_sent(1 << 12)
+            .max_recv_online(1 << 12)
+            .max_recv(1 << 12)
+            .version(TlsVersion::V1_3)
+            .aead(AeadAlgorithm::ChaCha20Poly1305)
+            .build();
+
+        assert!(result.is_err());
+    }
 }
@@ -70,3 +70,101 @@ impl Future for OneTimePadShared<[u8; 16]> {
         }
     }
 }
+
+/// Supports decoding a value to the leader only.
+pub(crate) enum LeaderOnly<T> {
+    Leader {
+        masked: DecodeFutureTyped<BitVec, T>,
+        otp: T,
+    },
+    Follower,
+}
+
+impl<const N: usize> LeaderOnly<[u8; N]> {
+    pub(crate) fn new(
+        role: Role,
+        value: Array<U8, N>,
+        vm: &mut dyn Vm<Binary>,
+    ) -> Result<Self, VmError> {
+        match role {
+            Role::Leader => {
+                let mut rng = rand::rng();
+                let otp: [u8; N] = from_fn(|_| rng.random());
+                let masked = vm.mask_private(value, otp)?;
+                let masked = vm.decode(masked)?;
+
+                Ok(Self::Leader { masked, otp })
+            }
+            Role::Follower => {
+                let masked = vm.mask_blind(value)?;
+                _ = vm.decode(masked)?;
+
+                Ok(Self::Follower)
+            }
+        }
+    }
+
+    /// Returns the decoded value, which is only known to the leader.
+    pub(crate) fn try_recv(&mut self) -> Result<Option<[u8; N]>, DecodeError> {
+        match self {
+            Self::Leader { masked, otp } => Ok(masked.try_recv()?.map(|mut value| {
+                value.iter_mut().zip(otp).for_each(|(a, b)| *a ^= *b);
+                value
+            })),
+            Self::Follower => Ok(None),
+        }
+    }
+}
+
+pin_project! {
+    /// Supports decoding a value to the follower only.
+    #[project = FollowerOnlyProj]
+    pub(crate) enum FollowerOnly<T> {
+        Leader,
+        Follower {
+            #[pin] masked: DecodeFutureTyped<BitVec, T>,
+            otp: T,
+        }
+    }
+}
+
+impl<const N: usize> FollowerOnly<[u8; N]> {
+    pub(crate) fn new(
+        role: Role,
+        value: Array<U8, N>,
+        vm: &mut dyn Vm<Binary>,
+    ) -> Result<Self, VmError> {
+        match role {
+            Role::Leader => {
+                let masked = vm.mask_blind(value)?;
+                _ = vm.decode(masked)?;
+
+                Ok(Self::Leader)
+            }
+            Role::Follower => {
+                let mut rng = rand::rng();
+                let otp: [u8; N] = from_fn(|_| rng.random());
+                let masked = vm.mask_private(value, otp)?;
+                let masked = vm.decode(masked)?;
+
+                Ok(Self::Follower { masked, otp })
+            }
+        }
+    }
+}
+
+impl<const N: usize> Future for FollowerOnly<[u8; N]> {
+    /// The decoded value, which is only known to the follower.
+    type Output = Result<Option<[u8; N]>, DecodeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            FollowerOnlyProj::Leader => Poll::Ready(Ok(None)),
+            FollowerOnlyProj::Follower { masked, otp } => {
+                let mut value = ready!(masked.poll(cx))?;
+                value.iter_mut().zip(otp).for_each(|(a, b)| *a ^= *b);
+                Poll::Ready(Ok(Some(value)))
+            }
+        }
+    }
+}
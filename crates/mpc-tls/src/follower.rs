@@ -1,14 +1,14 @@
 use crate::{
-    msg::{Message, StartHandshake},
-    record_layer::{aead::MpcAesGcm, RecordLayer},
-    Config, MpcTlsError, Role, SessionKeys, Vm,
+    kdf::Kdf,
+    msg::{Message, SessionHash, StartHandshake},
+    record_layer::{aead::MpcAead, RecordLayer},
+    utils::{decode_key_share, ideal_key_exchange},
+    Config, MpcTlsError, ProgressFn, Role, SessionKeys, Vm,
 };
-use hmac_sha256::{MpcPrf, PrfOutput};
 use ke::KeyExchange;
 use key_exchange::{self as ke, MpcKeyExchange};
 use mpz_common::{Context, Flush};
-use mpz_core::{bitvec::BitVec, Block};
-use mpz_memory_core::{DecodeFutureTyped, MemoryExt};
+use mpz_core::Block;
 use mpz_ole::{Receiver as OLEReceiver, Sender as OLESender};
 use mpz_ot::{
     rcot::{RCOTReceiver, RCOTSender},
@@ -22,7 +22,7 @@ use serio::stream::IoStreamExt;
 use std::mem;
 use tls_core::msgs::enums::NamedGroup;
 use tlsn_core::{
-    connection::{CertBinding, CertBindingV1_2, TlsVersion, VerifyData},
+    connection::{CertBinding, CertBindingV1_2, CertBindingV1_3, KeyType, TlsVersion, VerifyData},
     transcript::TlsTranscript,
 };
 use tracing::{debug, instrument};
@@ -53,33 +53,64 @@ impl MpcTlsFollower {
     {
         let mut rng = rand::rng();
 
-        let ke = Box::new(MpcKeyExchange::new(
-            key_exchange::Role::Follower,
-            ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
-                RandomizeRCOTReceiver::new(cot_recv.0),
-            ))),
-            ShareConversionSender::new(OLESender::new(
-                Block::random(&mut rng),
-                AnySender::new(RandomizeRCOTSender::new(cot_send)),
-            )),
-        )) as Box<dyn KeyExchange + Send + Sync>;
+        let ke = if cfg!(tlsn_insecure_ideal) {
+            // The ideal key exchange does not use oblivious transfer.
+            drop(cot_send);
+            ideal_key_exchange(key_exchange::Role::Follower, config.key_type)
+        } else {
+            match config.key_type {
+                KeyType::SECP256R1 => Box::new(MpcKeyExchange::<_, _, ke::Secp256r1>::new(
+                    key_exchange::Role::Follower,
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv.0),
+                    ))),
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send)),
+                    )),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                KeyType::X25519 => Box::new(MpcKeyExchange::<_, _, ke::X25519>::new(
+                    key_exchange::Role::Follower,
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv.0),
+                    ))),
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send)),
+                    )),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                KeyType::SECP384R1 => Box::new(MpcKeyExchange::<_, _, ke::Secp384r1>::new(
+                    key_exchange::Role::Follower,
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv.0),
+                    ))),
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send)),
+                    )),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                _ => unreachable!("key type is validated by the config builder"),
+            }
+        };
 
-        let prf = MpcPrf::new(config.prf);
+        let kdf = Kdf::new(&config);
 
-        let encrypter = MpcAesGcm::new(
+        let encrypter = MpcAead::new(
+            config.aead,
             ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
                 RandomizeRCOTReceiver::new(cot_recv.1),
             ))),
             Role::Follower,
         );
-        let decrypter = MpcAesGcm::new(
+        let decrypter = MpcAead::new(
+            config.aead,
             ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
                 RandomizeRCOTReceiver::new(cot_recv.2),
             ))),
             Role::Follower,
         );
 
-        let record_layer = RecordLayer::new(Role::Follower, encrypter, decrypter);
+        let record_layer = RecordLayer::new(Role::Follower, config.aead, encrypter, decrypter);
 
         Self {
             config,
@@ -87,31 +118,46 @@ impl MpcTlsFollower {
             state: State::Init {
                 vm,
                 ke,
-                prf,
+                kdf,
                 record_layer,
             },
         }
     }
 
+    /// Sets a callback which is notified of the progress of the connection.
+    ///
+    /// Must be called before [`alloc`](Self::alloc).
+    pub fn set_progress(&mut self, progress: ProgressFn) -> Result<(), MpcTlsError> {
+        let State::Init { record_layer, .. } = &mut self.state else {
+            return Err(MpcTlsError::state(
+                "must be in init state to set progress callback",
+            ));
+        };
+
+        record_layer.set_progress(progress);
+
+        Ok(())
+    }
+
     /// Allocates resources for the connection.
     pub fn alloc(&mut self) -> Result<SessionKeys, MpcTlsError> {
         let State::Init {
             vm,
             mut ke,
-            mut prf,
+            mut kdf,
             mut record_layer,
         } = self.state.take()
         else {
             return Err(MpcTlsError::state("must be in init state to allocate"));
         };
 
-        let (keys, cf_vd, sf_vd, sw_mac_key) = {
+        let (keys, sw_mac_key) = {
             let vm = &mut (*vm
                 .try_lock()
                 .map_err(|_| MpcTlsError::other("VM lock is held"))?);
 
             let pms = ke.alloc(vm)?;
-            let PrfOutput { keys, cf_vd, sf_vd } = prf.alloc(vm, pms)?;
+            let keys = kdf.alloc(Role::Follower, vm, pms)?;
             record_layer.set_keys(
                 keys.client_write_key,
                 keys.client_iv,
@@ -119,9 +165,6 @@ impl MpcTlsFollower {
                 keys.server_iv,
             )?;
 
-            let cf_vd = vm.decode(cf_vd).map_err(MpcTlsError::alloc)?;
-            let sf_vd = vm.decode(sf_vd).map_err(MpcTlsError::alloc)?;
-
             let server_write_mac_key = record_layer.alloc(
                 vm,
                 self.config.max_sent_records,
@@ -129,12 +172,14 @@ impl MpcTlsFollower {
                 self.config.max_sent,
                 self.config.max_recv_online,
                 self.config.max_recv,
+                self.config.ceilings(),
             )?;
 
-            (keys, cf_vd, sf_vd, server_write_mac_key)
+            (keys, server_write_mac_key)
         };
 
         let keys: SessionKeys = SessionKeys {
+            aead: self.config.aead,
             client_write_key: keys.client_write_key,
             client_write_iv: keys.client_iv,
             server_write_key: keys.server_write_key,
@@ -145,10 +190,8 @@ impl MpcTlsFollower {
         self.state = State::Setup {
             vm,
             ke,
-            prf,
+            kdf,
             record_layer,
-            cf_vd,
-            sf_vd,
         };
 
         Ok(keys)
@@ -160,10 +203,8 @@ impl MpcTlsFollower {
         let State::Setup {
             vm,
             mut ke,
-            prf,
+            kdf,
             mut record_layer,
-            cf_vd,
-            sf_vd,
             ..
         } = self.state.take()
         else {
@@ -204,10 +245,8 @@ impl MpcTlsFollower {
         self.state = State::Ready {
             vm,
             ke,
-            prf,
+            kdf,
             record_layer,
-            cf_vd,
-            sf_vd,
         };
 
         Ok(())
@@ -219,10 +258,8 @@ impl MpcTlsFollower {
         let State::Ready {
             vm,
             mut ke,
-            mut prf,
+            mut kdf,
             mut record_layer,
-            cf_vd: mut cf_vd_fut,
-            sf_vd: mut sf_vd_fut,
             ..
         } = self.state.take()
         else {
@@ -243,7 +280,7 @@ impl MpcTlsFollower {
                         return Err(MpcTlsError::hs("client random already set"));
                     }
 
-                    prf.set_client_random(random.random)?;
+                    kdf.set_client_random(random.random)?;
                     client_random = Some(random.random);
                 }
                 Message::StartHandshake(StartHandshake { time: prover_time }) => {
@@ -267,26 +304,40 @@ impl MpcTlsFollower {
                         return Err(MpcTlsError::hs("server random already set"));
                     }
 
-                    prf.set_server_random(random.random)?;
+                    kdf.set_server_random(random.random)?;
                     server_random = Some(random.random);
                 }
+                Message::SessionHash(SessionHash { hash }) => {
+                    if server_key.is_some() {
+                        return Err(MpcTlsError::hs(
+                            "session hash must be set before server key",
+                        ));
+                    }
+
+                    kdf.set_session_hash(hash)?;
+                }
                 Message::SetServerKey(key) => {
                     if server_key.is_some() {
                         return Err(MpcTlsError::hs("server key already set"));
                     }
 
                     let key = key.key;
-                    let NamedGroup::secp256r1 = key.group else {
+                    if key.group != NamedGroup::from(self.config.key_type) {
                         return Err(MpcTlsError::hs("unsupported server key group"));
-                    };
+                    }
 
-                    ke.set_server_key(
-                        p256::PublicKey::from_sec1_bytes(&key.key)
-                            .map_err(|_| MpcTlsError::hs("failed to parse server key"))?,
-                    )?;
+                    ke.set_server_key(decode_key_share(&key)?)?;
 
                     server_key = Some(key);
 
+                    // In TLS 1.3 the key exchange is completed once the
+                    // ServerHello hash is known.
+                    if let TlsVersion::V1_3 = self.config.version {
+                        continue;
+                    }
+
+                    kdf.check_master_secret()?;
+
                     let mut vm = vm
                         .try_lock()
                         .map_err(|_| MpcTlsError::other("VM lock is held"))?;
@@ -294,8 +345,8 @@ impl MpcTlsFollower {
                     ke.compute_shares(&mut self.ctx).await?;
                     ke.assign(&mut (*vm))?;
 
-                    while prf.wants_flush() {
-                        prf.flush(&mut *vm)?;
+                    while kdf.wants_flush() {
+                        kdf.flush(&mut *vm)?;
                         vm.execute_all(&mut self.ctx)
                             .await
                             .map_err(MpcTlsError::hs)?;
@@ -304,6 +355,49 @@ impl MpcTlsFollower {
                     ke.finalize().await?;
                     record_layer.setup(&mut self.ctx).await?;
                 }
+                Message::ServerHelloHash(hash) => {
+                    if server_key.is_none() {
+                        return Err(MpcTlsError::hs("server key not set"));
+                    }
+
+                    let mut vm = vm
+                        .try_lock()
+                        .map_err(|_| MpcTlsError::other("VM lock is held"))?;
+
+                    ke.compute_shares(&mut self.ctx).await?;
+                    ke.assign(&mut (*vm))?;
+                    kdf.tls13()?.set_hs_hash(hash.handshake_hash)?;
+
+                    while kdf.wants_flush() {
+                        kdf.flush(&mut *vm)?;
+                        vm.execute_all(&mut self.ctx)
+                            .await
+                            .map_err(MpcTlsError::hs)?;
+                    }
+
+                    ke.finalize().await?;
+
+                    // The handshake traffic secrets are only revealed to the leader.
+                    kdf.tls13()?.handshake_secrets()?;
+                }
+                Message::ServerFinishedHash(hash) => {
+                    let mut vm = vm
+                        .try_lock()
+                        .map_err(|_| MpcTlsError::other("VM lock is held"))?;
+
+                    kdf.tls13()?.set_server_finished_hash(hash.handshake_hash)?;
+
+                    while kdf.wants_flush() {
+                        kdf.flush(&mut *vm)?;
+                        vm.execute_all(&mut self.ctx)
+                            .await
+                            .map_err(MpcTlsError::hs)?;
+                    }
+
+                    let (client_iv, server_iv) = kdf.tls13()?.application_ivs()?;
+                    record_layer.set_tls13_ivs(client_iv, server_iv);
+                    record_layer.setup(&mut self.ctx).await?;
+                }
                 Message::ClientFinishedVd(vd) => {
                     if cf_vd.is_some() {
                         return Err(MpcTlsError::hs("client finished VD already computed"));
@@ -313,17 +407,20 @@ impl MpcTlsFollower {
                         .try_lock()
                         .map_err(|_| MpcTlsError::other("VM lock is held"))?;
 
-                    prf.set_cf_hash(vd.handshake_hash)?;
+                    kdf.tls12()?.prf.set_cf_hash(&vd.handshake_hash)?;
 
-                    while prf.wants_flush() {
-                        prf.flush(&mut *vm)?;
+                    while kdf.wants_flush() {
+                        kdf.flush(&mut *vm)?;
                         vm.execute_all(&mut self.ctx)
                             .await
                             .map_err(MpcTlsError::hs)?;
                     }
 
                     cf_vd = Some(
-                        cf_vd_fut
+                        kdf.tls12()?
+                            .cf_vd
+                            .as_mut()
+                            .ok_or(MpcTlsError::state("client finished VD not allocated"))?
                             .try_recv()
                             .map_err(MpcTlsError::hs)?
                             .ok_or(MpcTlsError::hs("client finished VD not computed"))?,
@@ -338,17 +435,20 @@ impl MpcTlsFollower {
                         .try_lock()
                         .map_err(|_| MpcTlsError::other("VM lock is held"))?;
 
-                    prf.set_sf_hash(vd.handshake_hash)?;
+                    kdf.tls12()?.prf.set_sf_hash(&vd.handshake_hash)?;
 
-                    while prf.wants_flush() {
-                        prf.flush(&mut *vm)?;
+                    while kdf.wants_flush() {
+                        kdf.flush(&mut *vm)?;
                         vm.execute_all(&mut self.ctx)
                             .await
                             .map_err(MpcTlsError::hs)?;
                     }
 
                     sf_vd = Some(
-                        sf_vd_fut
+                        kdf.tls12()?
+                            .sf_vd
+                            .as_mut()
+                            .ok_or(MpcTlsError::state("server finished VD not allocated"))?
                             .try_recv()
                             .map_err(MpcTlsError::hs)?
                             .ok_or(MpcTlsError::hs("server finished VD not computed"))?,
@@ -380,9 +480,9 @@ impl MpcTlsFollower {
                 Message::StartTraffic => {
                     record_layer.start_traffic();
                 }
-                Message::Flush { is_decrypting } => {
+                Message::Flush { decrypt } => {
                     record_layer
-                        .flush(&mut self.ctx, vm.clone(), is_decrypting)
+                        .flush(&mut self.ctx, vm.clone(), &decrypt)
                         .await?;
                     debug!("flushed record layer");
                 }
@@ -400,29 +500,53 @@ impl MpcTlsFollower {
 
         let time = time.ok_or(MpcTlsError::hs("time was not set"))?;
         let server_key = server_key.ok_or(MpcTlsError::hs("server key not set"))?;
-        let client_random = client_random.ok_or(MpcTlsError::hs("client random not set"))?;
-        let server_random = server_random.ok_or(MpcTlsError::hs("client random not set"))?;
-        let cf_vd = cf_vd.ok_or(MpcTlsError::hs("client finished VD not computed"))?;
-        let sf_vd = sf_vd.ok_or(MpcTlsError::hs("server finished VD not computed"))?;
-
-        let handshake_data = CertBinding::V1_2(CertBindingV1_2 {
-            client_random,
-            server_random,
-            server_ephemeral_key: server_key
-                .try_into()
-                .expect("only supported key scheme should have been accepted"),
-        });
+        let server_ephemeral_key = server_key
+            .try_into()
+            .expect("only supported key scheme should have been accepted");
+
+        let (handshake_data, verify_data) = match self.config.version {
+            TlsVersion::V1_2 => {
+                let client_random =
+                    client_random.ok_or(MpcTlsError::hs("client random not set"))?;
+                let server_random =
+                    server_random.ok_or(MpcTlsError::hs("server random not set"))?;
+                let cf_vd = cf_vd.ok_or(MpcTlsError::hs("client finished VD not computed"))?;
+                let sf_vd = sf_vd.ok_or(MpcTlsError::hs("server finished VD not computed"))?;
+
+                (
+                    CertBinding::V1_2(CertBindingV1_2 {
+                        client_random,
+                        server_random,
+                        server_ephemeral_key,
+                    }),
+                    VerifyData {
+                        client_finished: cf_vd.to_vec(),
+                        server_finished: sf_vd.to_vec(),
+                    },
+                )
+            }
+            // The handshake messages and finished verify data are encrypted
+            // with the handshake traffic keys, which are not known to the
+            // follower.
+            TlsVersion::V1_3 => (
+                CertBinding::V1_3(CertBindingV1_3 {
+                    server_ephemeral_key,
+                    handshake_messages: Vec::new(),
+                }),
+                VerifyData {
+                    client_finished: Vec::new(),
+                    server_finished: Vec::new(),
+                },
+            ),
+        };
 
         let transcript = TlsTranscript::new(
             time,
-            TlsVersion::V1_2,
+            self.config.version,
             None,
             None,
             handshake_data,
-            VerifyData {
-                client_finished: cf_vd.to_vec(),
-                server_finished: sf_vd.to_vec(),
-            },
+            verify_data,
             sent_records,
             recv_records,
         )
@@ -436,24 +560,20 @@ enum State {
     Init {
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
     },
     Setup {
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
-        cf_vd: DecodeFutureTyped<BitVec, [u8; 12]>,
-        sf_vd: DecodeFutureTyped<BitVec, [u8; 12]>,
     },
     Ready {
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
-        cf_vd: DecodeFutureTyped<BitVec, [u8; 12]>,
-        sf_vd: DecodeFutureTyped<BitVec, [u8; 12]>,
     },
     Error,
 }
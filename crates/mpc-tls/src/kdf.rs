@@ -0,0 +1,260 @@
+//! Key derivation for the supported TLS versions.
+
+use hmac_sha256::{KeyScheduleOutput, MpcKeySchedule, MpcPrf, PrfHash, PrfOutput, SessionKeys};
+use mpz_core::bitvec::BitVec;
+use mpz_memory_core::{
+    binary::{Binary, U8},
+    Array, DecodeFutureTyped, Vector,
+};
+use mpz_vm_core::{prelude::*, Vm};
+use tlsn_core::connection::{AeadAlgorithm, TlsVersion};
+
+use crate::{decode::LeaderOnly, Config, MpcTlsError, Role};
+
+/// Key derivation function of the TLS connection.
+pub(crate) enum Kdf {
+    /// TLS 1.2 PRF.
+    Tls12(Tls12Kdf),
+    /// TLS 1.3 key schedule.
+    Tls13(Tls13Kdf),
+}
+
+/// TLS 1.2 key derivation.
+pub(crate) struct Tls12Kdf {
+    pub(crate) prf: MpcPrf,
+    /// Whether the extended master secret is derived.
+    ems: bool,
+    session_hash: Option<Vec<u8>>,
+    pub(crate) cf_vd: Option<DecodeFutureTyped<BitVec, [u8; 12]>>,
+    pub(crate) sf_vd: Option<DecodeFutureTyped<BitVec, [u8; 12]>>,
+}
+
+/// TLS 1.3 key derivation.
+pub(crate) struct Tls13Kdf {
+    ks: MpcKeySchedule,
+    client_hs_secret: Option<LeaderOnly<[u8; 32]>>,
+    server_hs_secret: Option<LeaderOnly<[u8; 32]>>,
+    client_iv: Option<DecodeFutureTyped<BitVec, [u8; 12]>>,
+    server_iv: Option<DecodeFutureTyped<BitVec, [u8; 12]>>,
+}
+
+impl Kdf {
+    /// Creates a new key derivation function for the configured TLS version.
+    pub(crate) fn new(config: &Config) -> Self {
+        match config.version {
+            TlsVersion::V1_2 => Self::Tls12(Tls12Kdf {
+                prf: if config.extended_master_secret {
+                    MpcPrf::new_ems(config.prf)
+                } else {
+                    MpcPrf::new(config.prf)
+                }
+                .with_hash(prf_hash(config.aead))
+                .with_key_len(config.aead.key_len())
+                .with_iv_len(config.aead.iv_len()),
+                ems: config.extended_master_secret,
+                session_hash: None,
+                cf_vd: None,
+                sf_vd: None,
+            }),
+            TlsVersion::V1_3 => Self::Tls13(Tls13Kdf {
+                ks: MpcKeySchedule::new(config.prf),
+                client_hs_secret: None,
+                server_hs_secret: None,
+                client_iv: None,
+                server_iv: None,
+            }),
+        }
+    }
+
+    /// Allocates the key derivation, returning the traffic keys.
+    ///
+    /// For TLS 1.3 the returned IVs are the first 4 bytes of the write IVs,
+    /// the remainder is decoded to both parties, see
+    /// [`Tls13Kdf::application_ivs`].
+    pub(crate) fn alloc(
+        &mut self,
+        role: Role,
+        vm: &mut dyn Vm<Binary>,
+        pms: Vector<U8>,
+    ) -> Result<SessionKeys, MpcTlsError> {
+        match self {
+            Self::Tls12(kdf) => {
+                let PrfOutput { keys, cf_vd, sf_vd } = kdf.prf.alloc(vm, pms)?;
+
+                kdf.cf_vd = Some(vm.decode(cf_vd).map_err(MpcTlsError::alloc)?);
+                kdf.sf_vd = Some(vm.decode(sf_vd).map_err(MpcTlsError::alloc)?);
+
+                Ok(keys)
+            }
+            Self::Tls13(kdf) => {
+                let KeyScheduleOutput {
+                    handshake, keys, ..
+                } = kdf.ks.alloc(vm, pms)?;
+
+                // The handshake traffic secrets are only revealed to the leader,
+                // which protects the handshake records locally.
+                kdf.client_hs_secret =
+                    Some(LeaderOnly::new(role, handshake.client, vm).map_err(MpcTlsError::alloc)?);
+                kdf.server_hs_secret =
+                    Some(LeaderOnly::new(role, handshake.server, vm).map_err(MpcTlsError::alloc)?);
+
+                kdf.client_iv = Some(vm.decode(keys.client_iv).map_err(MpcTlsError::alloc)?);
+                kdf.server_iv = Some(vm.decode(keys.server_iv).map_err(MpcTlsError::alloc)?);
+
+                Ok(SessionKeys {
+                    client_write_key: keys.client_write_key.into(),
+                    server_write_key: keys.server_write_key.into(),
+                    client_iv: implicit_iv(keys.client_iv),
+                    server_iv: implicit_iv(keys.server_iv),
+                })
+            }
+        }
+    }
+
+    /// Sets the client random, which is only used in TLS 1.2.
+    pub(crate) fn set_client_random(&mut self, random: [u8; 32]) -> Result<(), MpcTlsError> {
+        if let Self::Tls12(kdf) = self {
+            kdf.prf.set_client_random(random)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the server random, which is only used in TLS 1.2.
+    pub(crate) fn set_server_random(&mut self, random: [u8; 32]) -> Result<(), MpcTlsError> {
+        if let Self::Tls12(kdf) = self {
+            kdf.prf.set_server_random(random)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the session hash for the extended master secret, which is only
+    /// used in TLS 1.2.
+    pub(crate) fn set_session_hash(&mut self, hash: Vec<u8>) -> Result<(), MpcTlsError> {
+        let kdf = self.tls12()?;
+
+        if !kdf.ems {
+            return Err(MpcTlsError::hs("extended master secret is not enabled"));
+        } else if kdf.session_hash.is_some() {
+            return Err(MpcTlsError::hs("session hash already set"));
+        }
+
+        kdf.prf.set_session_hash(&hash)?;
+        kdf.session_hash = Some(hash);
+
+        Ok(())
+    }
+
+    /// Checks that all inputs of the master secret are set.
+    pub(crate) fn check_master_secret(&self) -> Result<(), MpcTlsError> {
+        match self {
+            Self::Tls12(kdf) if kdf.ems && kdf.session_hash.is_none() => Err(MpcTlsError::hs(
+                "session hash is required for the extended master secret",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns whether the key derivation wants to be flushed.
+    pub(crate) fn wants_flush(&self) -> bool {
+        match self {
+            Self::Tls12(kdf) => kdf.prf.wants_flush(),
+            Self::Tls13(kdf) => kdf.ks.wants_flush(),
+        }
+    }
+
+    /// Flushes the key derivation.
+    pub(crate) fn flush(&mut self, vm: &mut dyn Vm<Binary>) -> Result<(), MpcTlsError> {
+        match self {
+            Self::Tls12(kdf) => kdf.prf.flush(vm)?,
+            Self::Tls13(kdf) => kdf.ks.flush(vm)?,
+        }
+
+        Ok(())
+    }
+
+    /// Returns the TLS 1.2 key derivation.
+    pub(crate) fn tls12(&mut self) -> Result<&mut Tls12Kdf, MpcTlsError> {
+        match self {
+            Self::Tls12(kdf) => Ok(kdf),
+            Self::Tls13(_) => Err(MpcTlsError::state("connection is not TLS 1.2")),
+        }
+    }
+
+    /// Returns the TLS 1.3 key derivation.
+    pub(crate) fn tls13(&mut self) -> Result<&mut Tls13Kdf, MpcTlsError> {
+        match self {
+            Self::Tls13(kdf) => Ok(kdf),
+            Self::Tls12(_) => Err(MpcTlsError::state("connection is not TLS 1.3")),
+        }
+    }
+}
+
+impl Tls13Kdf {
+    /// Sets the handshake hash up to and including the ServerHello.
+    pub(crate) fn set_hs_hash(&mut self, hash: [u8; 32]) -> Result<(), MpcTlsError> {
+        self.ks.set_hs_hash(hash).map_err(MpcTlsError::hs)
+    }
+
+    /// Sets the handshake hash up to and including the server Finished.
+    pub(crate) fn set_server_finished_hash(&mut self, hash: [u8; 32]) -> Result<(), MpcTlsError> {
+        self.ks
+            .set_server_finished_hash(hash)
+            .map_err(MpcTlsError::hs)
+    }
+
+    /// Returns the client and server handshake traffic secrets.
+    ///
+    /// Returns `None` for the follower.
+    pub(crate) fn handshake_secrets(
+        &mut self,
+    ) -> Result<Option<([u8; 32], [u8; 32])>, MpcTlsError> {
+        let (Some(client), Some(server)) = (
+            self.client_hs_secret.as_mut(),
+            self.server_hs_secret.as_mut(),
+        ) else {
+            return Err(MpcTlsError::state("key schedule is not allocated"));
+        };
+
+        let client = client.try_recv().map_err(MpcTlsError::hs)?;
+        let server = server.try_recv().map_err(MpcTlsError::hs)?;
+
+        Ok(client.zip(server))
+    }
+
+    /// Returns the client and server application write IVs.
+    pub(crate) fn application_ivs(&mut self) -> Result<([u8; 12], [u8; 12]), MpcTlsError> {
+        let (Some(client), Some(server)) = (self.client_iv.as_mut(), self.server_iv.as_mut())
+        else {
+            return Err(MpcTlsError::state("key schedule is not allocated"));
+        };
+
+        let client = client
+            .try_recv()
+            .map_err(MpcTlsError::hs)?
+            .ok_or_else(|| MpcTlsError::hs("client iv is not decoded"))?;
+        let server = server
+            .try_recv()
+            .map_err(MpcTlsError::hs)?
+            .ok_or_else(|| MpcTlsError::hs("server iv is not decoded"))?;
+
+        Ok((client, server))
+    }
+}
+
+/// Returns the hash function of the TLS 1.2 PRF for the AEAD algorithm of the
+/// supported cipher suites.
+fn prf_hash(aead: AeadAlgorithm) -> PrfHash {
+    match aead {
+        AeadAlgorithm::Aes128Gcm | AeadAlgorithm::ChaCha20Poly1305 => PrfHash::Sha256,
+        AeadAlgorithm::Aes256Gcm => PrfHash::Sha384,
+    }
+}
+
+/// Returns the first 4 bytes of a TLS 1.3 write IV.
+fn implicit_iv(iv: Array<U8, 12>) -> Vector<U8> {
+    let mut iv: Vector<U8> = iv.into();
+    iv.truncate(4);
+    iv
+}
@@ -1,23 +1,28 @@
 mod actor;
+mod handshake;
 
 use crate::{
     error::MpcTlsError,
+    kdf::Kdf,
     msg::{
-        ClientFinishedVd, Decrypt, Encrypt, Message, ServerFinishedVd, SetClientRandom,
-        SetServerKey, SetServerRandom, StartHandshake,
+        ClientFinishedVd, Decrypt, Encrypt, Message, ServerFinishedHash, ServerFinishedVd,
+        ServerHelloHash, SessionHash, SetClientRandom, SetServerKey, SetServerRandom,
+        StartHandshake,
     },
-    record_layer::{aead::MpcAesGcm, DecryptMode, EncryptMode, RecordLayer},
-    utils::opaque_into_parts,
-    Config, Role, SessionKeys, Vm,
+    record_layer::{aead::MpcAead, DecryptMode, EncryptMode, OnlineDecryption, RecordLayer},
+    utils::{
+        decode_key_share, encode_key_share, ideal_key_exchange, opaque_into_parts,
+        opaque_into_parts_tls13, unpad_tls13,
+    },
+    Config, ProgressFn, Role, SessionKeys, Vm,
 };
 use async_trait::async_trait;
-use hmac_sha256::{MpcPrf, PrfOutput};
+use handshake::HandshakeCipher;
 use ke::KeyExchange;
 use key_exchange::{self as ke, MpcKeyExchange};
 use ludi::Context as LudiContext;
 use mpz_common::{Context, Flush};
-use mpz_core::{bitvec::BitVec, Block};
-use mpz_memory_core::DecodeFutureTyped;
+use mpz_core::Block;
 use mpz_ole::{Receiver as OLEReceiver, Sender as OLESender};
 use mpz_ot::{
     rcot::{RCOTReceiver, RCOTSender},
@@ -37,14 +42,17 @@ use tls_core::{
     msgs::{
         base::Payload,
         enums::{CipherSuite, ContentType, NamedGroup, ProtocolVersion},
-        handshake::Random,
+        handshake::{DigitallySignedStruct, Random},
         message::{OpaqueMessage, PlainMessage},
     },
     suites::SupportedCipherSuite,
     verify::verify_sig_determine_alg,
 };
 use tlsn_core::{
-    connection::{CertBinding, CertBindingV1_2, ServerSignature, TlsVersion, VerifyData},
+    connection::{
+        AeadAlgorithm, CertBinding, CertBindingV1_2, CertBindingV1_3, KeyType, ServerSignature,
+        TlsVersion, VerifyData,
+    },
     transcript::TlsTranscript,
     webpki::CertificateDer,
 };
@@ -63,8 +71,10 @@ pub struct MpcTlsLeader {
     /// When set, notifies the backend that there are TLS messages which need to
     /// be decrypted.
     notifier: BackendNotifier,
-    /// Whether the record layer is decrypting application data.
-    is_decrypting: bool,
+    /// Application data which the record layer decrypts online.
+    decrypt: OnlineDecryption,
+    /// Local protection of TLS 1.3 handshake records.
+    handshake: Option<HandshakeCipher>,
 }
 
 impl MpcTlsLeader {
@@ -82,27 +92,58 @@ impl MpcTlsLeader {
     {
         let mut rng = rand::rng();
 
-        let ke = Box::new(MpcKeyExchange::new(
-            key_exchange::Role::Leader,
-            ShareConversionSender::new(OLESender::new(
-                Block::random(&mut rng),
-                AnySender::new(RandomizeRCOTSender::new(cot_send.0)),
-            )),
-            ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
-                RandomizeRCOTReceiver::new(cot_recv),
-            ))),
-        )) as Box<dyn KeyExchange + Send + Sync>;
+        let ke = if cfg!(tlsn_insecure_ideal) {
+            // The ideal key exchange does not use oblivious transfer.
+            drop(cot_recv);
+            ideal_key_exchange(key_exchange::Role::Leader, config.key_type)
+        } else {
+            match config.key_type {
+                KeyType::SECP256R1 => Box::new(MpcKeyExchange::<_, _, ke::Secp256r1>::new(
+                    key_exchange::Role::Leader,
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send.0)),
+                    )),
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv),
+                    ))),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                KeyType::X25519 => Box::new(MpcKeyExchange::<_, _, ke::X25519>::new(
+                    key_exchange::Role::Leader,
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send.0)),
+                    )),
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv),
+                    ))),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                KeyType::SECP384R1 => Box::new(MpcKeyExchange::<_, _, ke::Secp384r1>::new(
+                    key_exchange::Role::Leader,
+                    ShareConversionSender::new(OLESender::new(
+                        Block::random(&mut rng),
+                        AnySender::new(RandomizeRCOTSender::new(cot_send.0)),
+                    )),
+                    ShareConversionReceiver::new(OLEReceiver::new(AnyReceiver::new(
+                        RandomizeRCOTReceiver::new(cot_recv),
+                    ))),
+                )) as Box<dyn KeyExchange + Send + Sync>,
+                _ => unreachable!("key type is validated by the config builder"),
+            }
+        };
 
-        let prf = MpcPrf::new(config.prf);
+        let kdf = Kdf::new(&config);
 
-        let encrypter = MpcAesGcm::new(
+        let encrypter = MpcAead::new(
+            config.aead,
             ShareConversionSender::new(OLESender::new(
                 Block::random(&mut rng),
                 AnySender::new(RandomizeRCOTSender::new(cot_send.1)),
             )),
             Role::Leader,
         );
-        let decrypter = MpcAesGcm::new(
+        let decrypter = MpcAead::new(
+            config.aead,
             ShareConversionSender::new(OLESender::new(
                 Block::random(&mut rng),
                 AnySender::new(RandomizeRCOTSender::new(cot_send.2)),
@@ -110,9 +151,13 @@ impl MpcTlsLeader {
             Role::Leader,
         );
 
-        let record_layer = RecordLayer::new(Role::Leader, encrypter, decrypter);
+        let record_layer = RecordLayer::new(Role::Leader, config.aead, encrypter, decrypter);
 
-        let is_decrypting = !config.defer_decryption;
+        let decrypt = if config.defer_decryption {
+            OnlineDecryption::None
+        } else {
+            OnlineDecryption::All
+        };
         Self {
             self_handle: None,
             config,
@@ -120,21 +165,37 @@ impl MpcTlsLeader {
                 ctx,
                 vm,
                 ke,
-                prf,
+                kdf,
                 record_layer,
             },
             notifier: BackendNotifier::new(),
-            is_decrypting,
+            decrypt,
+            handshake: None,
         }
     }
 
+    /// Sets a callback which is notified of the progress of the connection.
+    ///
+    /// Must be called before [`alloc`](Self::alloc).
+    pub fn set_progress(&mut self, progress: ProgressFn) -> Result<(), MpcTlsError> {
+        let State::Init { record_layer, .. } = &mut self.state else {
+            return Err(MpcTlsError::state(
+                "must be in init state to set progress callback",
+            ));
+        };
+
+        record_layer.set_progress(progress);
+
+        Ok(())
+    }
+
     /// Allocates resources for the connection.
     pub fn alloc(&mut self) -> Result<SessionKeys, MpcTlsError> {
         let State::Init {
             ctx,
             vm,
             mut ke,
-            mut prf,
+            mut kdf,
             mut record_layer,
         } = self.state.take()
         else {
@@ -150,7 +211,7 @@ impl MpcTlsLeader {
 
         // Allocate.
         let pms = ke.alloc(&mut (*vm_lock))?;
-        let PrfOutput { keys, cf_vd, sf_vd } = prf.alloc(&mut (*vm_lock), pms)?;
+        let keys = kdf.alloc(Role::Leader, &mut (*vm_lock), pms)?;
         record_layer.set_keys(
             keys.client_write_key,
             keys.client_iv,
@@ -158,9 +219,6 @@ impl MpcTlsLeader {
             keys.server_iv,
         )?;
 
-        let cf_vd = vm_lock.decode(cf_vd).map_err(MpcTlsError::alloc)?;
-        let sf_vd = vm_lock.decode(sf_vd).map_err(MpcTlsError::alloc)?;
-
         let server_write_mac_key = record_layer.alloc(
             &mut (*vm_lock),
             self.config.max_sent_records,
@@ -168,9 +226,11 @@ impl MpcTlsLeader {
             self.config.max_sent,
             self.config.max_recv_online,
             self.config.max_recv,
+            self.config.ceilings(),
         )?;
 
         let keys: SessionKeys = SessionKeys {
+            aead: self.config.aead,
             client_write_key: keys.client_write_key,
             client_write_iv: keys.client_iv,
             server_write_key: keys.server_write_key,
@@ -182,10 +242,8 @@ impl MpcTlsLeader {
             ctx,
             vm,
             ke,
-            prf,
+            kdf,
             record_layer,
-            cf_vd_fut: cf_vd,
-            sf_vd_fut: sf_vd,
             client_random,
         };
 
@@ -199,10 +257,8 @@ impl MpcTlsLeader {
             mut ctx,
             vm,
             mut ke,
-            mut prf,
+            mut kdf,
             mut record_layer,
-            cf_vd_fut,
-            sf_vd_fut,
             client_random,
             ..
         } = self.state.take()
@@ -250,16 +306,14 @@ impl MpcTlsLeader {
             .await
             .map_err(MpcTlsError::from)?;
 
-        prf.set_client_random(client_random.0)?;
+        kdf.set_client_random(client_random.0)?;
 
         self.state = State::Handshake {
             ctx,
             vm,
             ke,
-            prf,
+            kdf,
             record_layer,
-            cf_vd_fut,
-            sf_vd_fut,
             time: None,
             protocol_version: None,
             cipher_suite: None,
@@ -267,7 +321,7 @@ impl MpcTlsLeader {
             server_random: None,
             server_cert_details: None,
             server_key: None,
-            server_kx_details: None,
+            server_auth: None,
         };
 
         Ok(())
@@ -288,7 +342,7 @@ impl MpcTlsLeader {
             server_random,
             server_cert_details,
             server_key,
-            server_kx_details,
+            server_auth,
             ..
         } = self.state.take()
         else {
@@ -317,8 +371,9 @@ impl MpcTlsLeader {
 
         let version = match protocol_version {
             ProtocolVersion::TLSv1_2 => TlsVersion::V1_2,
+            ProtocolVersion::TLSv1_3 => TlsVersion::V1_3,
             version => {
-                panic!("only TLS 1.2 should have been accepted: {version:?}")
+                panic!("only TLS 1.2 or 1.3 should have been accepted: {version:?}")
             }
         };
 
@@ -328,31 +383,62 @@ impl MpcTlsLeader {
             .map(|cert| CertificateDer(cert.0.clone()))
             .collect();
 
-        let mut sig_msg = Vec::new();
-        sig_msg.extend_from_slice(&client_random.0);
-        sig_msg.extend_from_slice(&server_random.0);
-        sig_msg.extend_from_slice(server_kx_details.kx_params());
-
-        let server_signature_alg = verify_sig_determine_alg(
-            &server_cert_details.cert_chain()[0],
-            &sig_msg,
-            server_kx_details.kx_sig(),
-        )
-        .expect("only supported signature should have been accepted");
-
-        let server_signature = ServerSignature {
-            alg: server_signature_alg.into(),
-            sig: server_kx_details.kx_sig().sig.0.clone(),
+        let server_ephemeral_key = server_key
+            .try_into()
+            .expect("only supported key scheme should have been accepted");
+
+        let (server_signature, handshake_data) = match server_auth {
+            ServerAuth::Tls12(server_kx_details) => {
+                let mut sig_msg = Vec::new();
+                sig_msg.extend_from_slice(&client_random.0);
+                sig_msg.extend_from_slice(&server_random.0);
+                sig_msg.extend_from_slice(server_kx_details.kx_params());
+
+                let server_signature_alg = verify_sig_determine_alg(
+                    &server_cert_details.cert_chain()[0],
+                    &sig_msg,
+                    server_kx_details.kx_sig(),
+                )
+                .expect("only supported signature should have been accepted");
+
+                let server_signature = ServerSignature {
+                    alg: server_signature_alg.into(),
+                    sig: server_kx_details.kx_sig().sig.0.clone(),
+                };
+
+                let handshake_data = CertBinding::V1_2(CertBindingV1_2 {
+                    client_random: client_random.0,
+                    server_random: server_random.0,
+                    server_ephemeral_key,
+                });
+
+                (server_signature, handshake_data)
+            }
+            ServerAuth::Tls13 {
+                handshake_messages,
+                sig,
+            } => {
+                let binding = CertBindingV1_3 {
+                    server_ephemeral_key,
+                    handshake_messages,
+                };
+
+                let server_signature_alg = verify_sig_determine_alg(
+                    &server_cert_details.cert_chain()[0],
+                    &binding.signed_message(),
+                    &sig,
+                )
+                .expect("only supported signature should have been accepted");
+
+                let server_signature = ServerSignature {
+                    alg: server_signature_alg.into(),
+                    sig: sig.sig.0,
+                };
+
+                (server_signature, CertBinding::V1_3(binding))
+            }
         };
 
-        let handshake_data = CertBinding::V1_2(CertBindingV1_2 {
-            client_random: client_random.0,
-            server_random: server_random.0,
-            server_ephemeral_key: server_key
-                .try_into()
-                .expect("only supported key scheme should have been accepted"),
-        });
-
         let transcript = TlsTranscript::new(
             time,
             version,
@@ -360,8 +446,8 @@ impl MpcTlsLeader {
             Some(server_signature),
             handshake_data,
             VerifyData {
-                client_finished: cf_vd.to_vec(),
-                server_finished: sf_vd.to_vec(),
+                client_finished: cf_vd,
+                server_finished: sf_vd,
             },
             sent_records,
             recv_records,
@@ -381,8 +467,33 @@ impl MpcTlsLeader {
     /// Defers decryption of any incoming messages.
     #[instrument(level = "debug", skip_all, err)]
     pub async fn defer_decryption(&mut self) -> Result<(), MpcTlsError> {
-        self.is_decrypting = false;
-        self.notifier.clear();
+        self.set_online_decryption(OnlineDecryption::None).await
+    }
+
+    /// Sets which application data is decrypted online.
+    ///
+    /// Received records which are not selected are decrypted when the
+    /// connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if records are selected by position in TLS 1.3, see
+    /// [`OnlineDecryption`].
+    #[instrument(level = "debug", skip_all, err)]
+    pub async fn set_online_decryption(
+        &mut self,
+        decrypt: OnlineDecryption,
+    ) -> Result<(), MpcTlsError> {
+        if self.config.version == TlsVersion::V1_3 && decrypt.is_positional() {
+            return Err(MpcTlsError::other(
+                "selecting records or bytes for online decryption is not supported in TLS 1.3",
+            ));
+        }
+
+        if decrypt.is_none() {
+            self.notifier.clear();
+        }
+        self.decrypt = decrypt;
 
         Ok(())
     }
@@ -421,6 +532,14 @@ impl Backend for MpcTlsLeader {
 
         trace!("setting cipher suite: {:?}", suite);
 
+        if !self.config.supports_suite(&suite) {
+            return Err(MpcTlsError::hs(format!(
+                "cipher suite {:?} is not supported by the configuration",
+                suite.suite()
+            ))
+            .into());
+        }
+
         *cipher_suite = Some(suite.suite());
 
         Ok(())
@@ -430,12 +549,35 @@ impl Backend for MpcTlsLeader {
         unimplemented!()
     }
 
-    async fn set_encrypt(&mut self, _mode: tls_backend::EncryptMode) -> Result<(), BackendError> {
-        unimplemented!()
+    async fn set_encrypt(&mut self, mode: tls_backend::EncryptMode) -> Result<(), BackendError> {
+        let handshake = self
+            .handshake
+            .as_mut()
+            .ok_or_else(|| MpcTlsError::state("handshake keys are not set"))?;
+
+        handshake.encrypting = match mode {
+            tls_backend::EncryptMode::Handshake => true,
+            tls_backend::EncryptMode::Application => false,
+            mode => {
+                return Err(MpcTlsError::hs(format!("unsupported encrypt mode: {mode:?}")).into())
+            }
+        };
+
+        Ok(())
     }
 
-    async fn set_decrypt(&mut self, _mode: tls_backend::DecryptMode) -> Result<(), BackendError> {
-        unimplemented!()
+    async fn set_decrypt(&mut self, mode: tls_backend::DecryptMode) -> Result<(), BackendError> {
+        let handshake = self
+            .handshake
+            .as_mut()
+            .ok_or_else(|| MpcTlsError::state("handshake keys are not set"))?;
+
+        handshake.decrypting = match mode {
+            tls_backend::DecryptMode::Handshake => true,
+            tls_backend::DecryptMode::Application => false,
+        };
+
+        Ok(())
     }
 
     async fn get_client_random(&mut self) -> Result<Random, BackendError> {
@@ -460,16 +602,13 @@ impl Backend for MpcTlsLeader {
             .client_key()
             .map_err(|err| BackendError::InvalidState(err.to_string()))?;
 
-        Ok(PublicKey::new(
-            NamedGroup::secp256r1,
-            &p256::EncodedPoint::from(pk).to_bytes(),
-        ))
+        Ok(encode_key_share(pk))
     }
 
     async fn set_server_random(&mut self, random: Random) -> Result<(), BackendError> {
         let State::Handshake {
             ctx,
-            prf,
+            kdf,
             server_random,
             time,
             ..
@@ -499,7 +638,7 @@ impl Backend for MpcTlsLeader {
             .await
             .map_err(MpcTlsError::from)?;
 
-        prf.set_server_random(random.0).map_err(MpcTlsError::hs)?;
+        kdf.set_server_random(random.0)?;
         *server_random = Some(random);
 
         Ok(())
@@ -516,7 +655,7 @@ impl Backend for MpcTlsLeader {
             );
         };
 
-        if key.group != NamedGroup::secp256r1 {
+        if key.group != NamedGroup::from(self.config.key_type) {
             return Err(BackendError::InvalidServerKey);
         }
 
@@ -554,38 +693,222 @@ impl Backend for MpcTlsLeader {
         &mut self,
         kx_details: ServerKxDetails,
     ) -> Result<(), BackendError> {
-        let State::Handshake {
-            server_kx_details, ..
-        } = &mut self.state
-        else {
+        let State::Handshake { server_auth, .. } = &mut self.state else {
             return Err(
                 MpcTlsError::state("must be in handshake state to set server kx details").into(),
             );
         };
 
-        *server_kx_details = Some(kx_details);
+        *server_auth = Some(ServerAuth::Tls12(kx_details));
 
         Ok(())
     }
 
-    async fn set_hs_hash_client_key_exchange(
+    async fn set_server_signature_tls13(
         &mut self,
-        _hash: Vec<u8>,
+        handshake_messages: Vec<u8>,
+        sig: DigitallySignedStruct,
     ) -> Result<(), BackendError> {
+        let State::Handshake { server_auth, .. } = &mut self.state else {
+            return Err(
+                MpcTlsError::state("must be in handshake state to set server signature").into(),
+            );
+        };
+
+        *server_auth = Some(ServerAuth::Tls13 {
+            handshake_messages,
+            sig,
+        });
+
         Ok(())
     }
 
-    async fn set_hs_hash_server_hello(&mut self, _hash: Vec<u8>) -> Result<(), BackendError> {
+    async fn set_hs_hash_client_key_exchange(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        let State::Handshake { ctx, kdf, .. } = &mut self.state else {
+            return Err(
+                MpcTlsError::state("must be in handshake state to set session hash").into(),
+            );
+        };
+
+        kdf.set_session_hash(hash.clone())?;
+
+        ctx.io_mut()
+            .send(Message::SessionHash(SessionHash { hash }))
+            .await
+            .map_err(MpcTlsError::from)?;
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn set_hs_hash_server_hello(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        if self.config.version != TlsVersion::V1_3 {
+            return Ok(());
+        }
+
+        let State::Handshake {
+            ctx,
+            vm,
+            ke,
+            kdf,
+            server_key,
+            ..
+        } = &mut self.state
+        else {
+            return Err(
+                MpcTlsError::state("must be in handshake state to set server hello hash").into(),
+            );
+        };
+
+        debug!("computing handshake secrets");
+
+        let hash: [u8; 32] = hash
+            .try_into()
+            .map_err(|_| MpcTlsError::hs("server hello handshake hash is not 32 bytes"))?;
+        let server_key = server_key
+            .as_ref()
+            .ok_or_else(|| MpcTlsError::hs("server key is not set"))?;
+
+        ctx.io_mut()
+            .send(Message::ServerHelloHash(ServerHelloHash {
+                handshake_hash: hash,
+            }))
+            .await
+            .map_err(MpcTlsError::from)?;
+
+        ke.set_server_key(decode_key_share(server_key)?)
+            .map_err(|err| BackendError::InvalidState(err.to_string()))?;
+
+        ke.compute_shares(ctx).await.map_err(MpcTlsError::hs)?;
+
+        let mut vm = vm
+            .try_lock()
+            .map_err(|_| MpcTlsError::other("VM lock is held"))?;
+
+        ke.assign(&mut (*vm)).map_err(MpcTlsError::hs)?;
+        kdf.tls13()?.set_hs_hash(hash)?;
+
+        while kdf.wants_flush() {
+            kdf.flush(&mut *vm)?;
+            vm.execute_all(ctx).await.map_err(MpcTlsError::hs)?;
+        }
+
+        ke.finalize().await.map_err(MpcTlsError::hs)?;
+
+        let (client_secret, server_secret) = kdf
+            .tls13()?
+            .handshake_secrets()?
+            .ok_or_else(|| MpcTlsError::hs("handshake secrets are not decoded"))?;
+
+        self.handshake = Some(HandshakeCipher::new(client_secret, server_secret));
+
+        Ok(())
+    }
+
+    #[instrument(level = "debug", skip_all, err)]
+    async fn set_hs_hash_server_finished(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        let State::Handshake {
+            mut ctx,
+            vm,
+            ke,
+            mut kdf,
+            mut record_layer,
+            time,
+            protocol_version,
+            client_random,
+            server_random,
+            server_cert_details,
+            server_key,
+            server_auth,
+            ..
+        } = self.state.take()
+        else {
+            return Err(MpcTlsError::state(
+                "must be in handshake state to set server finished hash",
+            )
+            .into());
+        };
+
+        debug!("computing application keys");
+
+        let hash: [u8; 32] = hash
+            .try_into()
+            .map_err(|_| MpcTlsError::hs("server finished handshake hash is not 32 bytes"))?;
+        let time = time.ok_or_else(|| MpcTlsError::hs("time is not set"))?;
+        let protocol_version =
+            protocol_version.ok_or_else(|| MpcTlsError::hs("protocol version is not set"))?;
+        let server_random =
+            server_random.ok_or_else(|| MpcTlsError::hs("server random is not set"))?;
+        let server_cert_details =
+            server_cert_details.ok_or_else(|| MpcTlsError::hs("server cert details is not set"))?;
+        let server_key = server_key.ok_or_else(|| MpcTlsError::hs("server key is not set"))?;
+        let server_auth =
+            server_auth.ok_or_else(|| MpcTlsError::hs("server signature is not set"))?;
+
+        ctx.io_mut()
+            .send(Message::ServerFinishedHash(ServerFinishedHash {
+                handshake_hash: hash,
+            }))
+            .await
+            .map_err(MpcTlsError::from)?;
+
+        {
+            let mut vm_lock = vm
+                .try_lock()
+                .map_err(|_| MpcTlsError::other("VM lock is held"))?;
+
+            kdf.tls13()?.set_server_finished_hash(hash)?;
+
+            while kdf.wants_flush() {
+                kdf.flush(&mut *vm_lock)?;
+                vm_lock
+                    .execute_all(&mut ctx)
+                    .await
+                    .map_err(MpcTlsError::hs)?;
+            }
+
+            let (client_iv, server_iv) = kdf.tls13()?.application_ivs()?;
+            record_layer.set_tls13_ivs(client_iv, server_iv);
+            record_layer.setup(&mut ctx).await?;
+        }
+
+        debug!("application keys computed");
+
+        let sf_vd = self
+            .handshake
+            .as_ref()
+            .and_then(|handshake| handshake.sf_vd.clone());
+
+        self.state = State::Active {
+            ctx,
+            vm,
+            _ke: ke,
+            kdf,
+            record_layer,
+            cf_vd: None,
+            sf_vd,
+            time,
+            protocol_version,
+            client_random,
+            server_random,
+            server_cert_details,
+            server_key,
+            server_auth,
+        };
+
         Ok(())
     }
 
     #[instrument(level = "debug", skip_all, err)]
     async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
+        if let Some(handshake) = &mut self.handshake {
+            return Ok(handshake.server_finished_vd(&hash));
+        }
+
         let State::Active {
             ctx,
             vm,
-            prf,
-            sf_vd_fut,
+            kdf,
             sf_vd,
             ..
         } = &mut self.state
@@ -597,9 +920,10 @@ impl Backend for MpcTlsLeader {
 
         debug!("computing server finished verify data");
 
-        let hash: [u8; 32] = hash
-            .try_into()
-            .map_err(|_| MpcTlsError::hs("server finished handshake hash is not 32 bytes"))?;
+        kdf.tls12()?
+            .prf
+            .set_sf_hash(&hash)
+            .map_err(MpcTlsError::hs)?;
 
         ctx.io_mut()
             .send(Message::ServerFinishedVd(ServerFinishedVd {
@@ -611,21 +935,25 @@ impl Backend for MpcTlsLeader {
         let mut vm = vm
             .try_lock()
             .map_err(|_| MpcTlsError::other("VM lock is held"))?;
-        prf.set_sf_hash(hash).map_err(MpcTlsError::hs)?;
 
-        while prf.wants_flush() {
-            prf.flush(&mut *vm).map_err(MpcTlsError::hs)?;
+        while kdf.wants_flush() {
+            kdf.flush(&mut *vm)?;
             vm.execute_all(ctx).await.map_err(MpcTlsError::hs)?;
         }
 
-        let vd = sf_vd_fut
+        let vd = kdf
+            .tls12()?
+            .sf_vd
+            .as_mut()
+            .ok_or_else(|| MpcTlsError::state("sf_vd is not allocated"))?
             .try_recv()
             .map_err(MpcTlsError::hs)?
-            .ok_or_else(|| MpcTlsError::hs("sf_vd is not decoded"))?;
+            .ok_or_else(|| MpcTlsError::hs("sf_vd is not decoded"))?
+            .to_vec();
 
-        *sf_vd = Some(vd);
+        *sf_vd = Some(vd.clone());
 
-        Ok(vd.to_vec())
+        Ok(vd)
     }
 
     #[instrument(level = "debug", skip_all, err)]
@@ -633,8 +961,7 @@ impl Backend for MpcTlsLeader {
         let State::Active {
             ctx,
             vm,
-            prf,
-            cf_vd_fut,
+            kdf,
             cf_vd,
             ..
         } = &mut self.state
@@ -646,9 +973,17 @@ impl Backend for MpcTlsLeader {
 
         debug!("computing client finished verify data");
 
-        let hash: [u8; 32] = hash
-            .try_into()
-            .map_err(|_| MpcTlsError::hs("client finished handshake hash is not 32 bytes"))?;
+        if let Some(handshake) = &self.handshake {
+            let vd = handshake.client_finished_vd(&hash);
+            *cf_vd = Some(vd.clone());
+
+            return Ok(vd);
+        }
+
+        kdf.tls12()?
+            .prf
+            .set_cf_hash(&hash)
+            .map_err(MpcTlsError::hs)?;
 
         ctx.io_mut()
             .send(Message::ClientFinishedVd(ClientFinishedVd {
@@ -660,21 +995,25 @@ impl Backend for MpcTlsLeader {
         let mut vm = vm
             .try_lock()
             .map_err(|_| MpcTlsError::hs("VM lock is held"))?;
-        prf.set_cf_hash(hash).map_err(MpcTlsError::hs)?;
 
-        while prf.wants_flush() {
-            prf.flush(&mut *vm).map_err(MpcTlsError::hs)?;
+        while kdf.wants_flush() {
+            kdf.flush(&mut *vm)?;
             vm.execute_all(ctx).await.map_err(MpcTlsError::hs)?;
         }
 
-        let vd = cf_vd_fut
+        let vd = kdf
+            .tls12()?
+            .cf_vd
+            .as_mut()
+            .ok_or_else(|| MpcTlsError::state("cf_vd is not allocated"))?
             .try_recv()
             .map_err(MpcTlsError::hs)?
-            .ok_or_else(|| MpcTlsError::hs("cf_vd is not decoded"))?;
+            .ok_or_else(|| MpcTlsError::hs("cf_vd is not decoded"))?
+            .to_vec();
 
-        *cf_vd = Some(vd);
+        *cf_vd = Some(vd.clone());
 
-        Ok(vd.to_vec())
+        Ok(vd)
     }
 
     #[instrument(level = "debug", skip_all, err)]
@@ -683,17 +1022,15 @@ impl Backend for MpcTlsLeader {
             mut ctx,
             vm,
             mut ke,
-            mut prf,
+            mut kdf,
             mut record_layer,
-            cf_vd_fut,
-            sf_vd_fut,
             time,
             protocol_version,
             client_random,
             server_random,
             server_cert_details,
             server_key,
-            server_kx_details,
+            server_auth,
             ..
         } = self.state.take()
         else {
@@ -712,14 +1049,13 @@ impl Backend for MpcTlsLeader {
         let server_cert_details =
             server_cert_details.ok_or_else(|| MpcTlsError::hs("server cert details is not set"))?;
         let server_key = server_key.ok_or_else(|| MpcTlsError::hs("server key is not set"))?;
-        let server_kx_details =
-            server_kx_details.ok_or_else(|| MpcTlsError::hs("server kx details is not set"))?;
+        let server_auth =
+            server_auth.ok_or_else(|| MpcTlsError::hs("server kx details is not set"))?;
 
-        ke.set_server_key(
-            p256::PublicKey::from_sec1_bytes(&server_key.key).map_err(MpcTlsError::hs)?,
-        )
-        .map_err(|err| BackendError::InvalidState(err.to_string()))?;
+        ke.set_server_key(decode_key_share(&server_key)?)
+            .map_err(|err| BackendError::InvalidState(err.to_string()))?;
 
+        kdf.check_master_secret()?;
         ke.compute_shares(&mut ctx).await.map_err(MpcTlsError::hs)?;
 
         {
@@ -729,8 +1065,8 @@ impl Backend for MpcTlsLeader {
 
             ke.assign(&mut (*vm_lock)).map_err(MpcTlsError::hs)?;
 
-            while prf.wants_flush() {
-                prf.flush(&mut *vm_lock).map_err(MpcTlsError::hs)?;
+            while kdf.wants_flush() {
+                kdf.flush(&mut *vm_lock)?;
                 vm_lock
                     .execute_all(&mut ctx)
                     .await
@@ -747,10 +1083,8 @@ impl Backend for MpcTlsLeader {
             ctx,
             vm,
             _ke: ke,
-            prf,
+            kdf,
             record_layer,
-            cf_vd_fut,
-            sf_vd_fut,
             cf_vd: None,
             sf_vd: None,
             time,
@@ -759,7 +1093,7 @@ impl Backend for MpcTlsLeader {
             server_random,
             server_cert_details,
             server_key,
-            server_kx_details,
+            server_auth,
         };
 
         Ok(())
@@ -767,6 +1101,19 @@ impl Backend for MpcTlsLeader {
 
     #[instrument(level = "debug", skip_all, err)]
     async fn push_incoming(&mut self, msg: OpaqueMessage) -> Result<(), BackendError> {
+        if let Some(handshake) = self
+            .handshake
+            .as_mut()
+            .filter(|handshake| handshake.decrypting)
+        {
+            debug!(
+                "decrypting incoming handshake message, len: {}",
+                msg.payload.0.len()
+            );
+
+            return handshake.decrypt(msg).map_err(BackendError::from);
+        }
+
         let (ctx, record_layer) = match &mut self.state {
             State::Handshake {
                 ctx, record_layer, ..
@@ -788,7 +1135,14 @@ impl Backend for MpcTlsLeader {
             version,
             payload,
         } = msg;
-        let (explicit_nonce, ciphertext, tag) = opaque_into_parts(payload.0)?;
+        let (explicit_nonce, ciphertext, tag) = match (self.config.version, self.config.aead) {
+            (TlsVersion::V1_3, _) | (TlsVersion::V1_2, AeadAlgorithm::ChaCha20Poly1305) => {
+                // The nonce is computed by the record layer.
+                let (ciphertext, tag) = opaque_into_parts_tls13(payload.0)?;
+                (Vec::new(), ciphertext, tag)
+            }
+            (TlsVersion::V1_2, _) => opaque_into_parts(payload.0)?,
+        };
 
         debug!(
             "received incoming message, type: {:?}, len: {}",
@@ -827,6 +1181,20 @@ impl Backend for MpcTlsLeader {
 
     #[instrument(level = "debug", skip_all, err)]
     async fn next_incoming(&mut self) -> Result<Option<PlainMessage>, BackendError> {
+        if let Some(msg) = self
+            .handshake
+            .as_mut()
+            .and_then(|handshake| handshake.next_decrypted())
+        {
+            debug!(
+                "processing incoming handshake message, type: {:?}, len: {}",
+                msg.typ,
+                msg.payload.0.len()
+            );
+
+            return Ok(Some(msg));
+        }
+
         let record_layer = match &mut self.state {
             State::Handshake { record_layer, .. } => record_layer,
             State::Active { record_layer, .. } => record_layer,
@@ -840,15 +1208,25 @@ impl Backend for MpcTlsLeader {
             }
         };
 
-        let record = record_layer.next_decrypted().map(|record| PlainMessage {
-            typ: record.typ,
-            version: record.version,
-            payload: Payload::new(
-                record
+        let record = record_layer
+            .next_decrypted()
+            .map(|record| {
+                let mut plaintext = record
                     .plaintext
-                    .expect("leader should always know plaintext"),
-            ),
-        });
+                    .expect("leader should always know plaintext");
+
+                let typ = match self.config.version {
+                    TlsVersion::V1_2 => record.typ,
+                    TlsVersion::V1_3 => unpad_tls13(&mut plaintext)?,
+                };
+
+                Ok::<_, MpcTlsError>(PlainMessage {
+                    typ,
+                    version: record.version,
+                    payload: Payload::new(plaintext),
+                })
+            })
+            .transpose()?;
 
         if let Some(record) = &record {
             debug!(
@@ -863,6 +1241,20 @@ impl Backend for MpcTlsLeader {
 
     #[instrument(level = "debug", skip_all, err)]
     async fn push_outgoing(&mut self, msg: PlainMessage) -> Result<(), BackendError> {
+        if let Some(handshake) = self
+            .handshake
+            .as_mut()
+            .filter(|handshake| handshake.encrypting)
+        {
+            debug!(
+                "encrypting outgoing handshake message, type: {:?}, len: {}",
+                msg.typ,
+                msg.payload.0.len()
+            );
+
+            return handshake.encrypt(msg).map_err(BackendError::from);
+        }
+
         let (ctx, record_layer) = match &mut self.state {
             State::Handshake {
                 ctx, record_layer, ..
@@ -890,7 +1282,12 @@ impl Backend for MpcTlsLeader {
             version,
             payload,
         } = msg;
-        let plaintext = payload.0;
+        let mut plaintext = payload.0;
+
+        if let TlsVersion::V1_3 = self.config.version {
+            // The content type is encrypted along with the content.
+            plaintext.push(typ.get_u8());
+        }
 
         let mode = match typ {
             ContentType::ApplicationData => EncryptMode::Private,
@@ -918,6 +1315,19 @@ impl Backend for MpcTlsLeader {
 
     #[instrument(level = "debug", skip_all, err)]
     async fn next_outgoing(&mut self) -> Result<Option<OpaqueMessage>, BackendError> {
+        if let Some(msg) = self
+            .handshake
+            .as_mut()
+            .and_then(|handshake| handshake.next_encrypted())
+        {
+            debug!(
+                "sending outgoing handshake message, len: {}",
+                msg.payload.0.len()
+            );
+
+            return Ok(Some(msg));
+        }
+
         let record_layer = match &mut self.state {
             State::Handshake { record_layer, .. } => record_layer,
             State::Active { record_layer, .. } => record_layer,
@@ -931,14 +1341,40 @@ impl Backend for MpcTlsLeader {
             }
         };
 
+        let version = self.config.version;
+        let aead = self.config.aead;
         let record = record_layer.next_encrypted().map(|record| {
-            let mut payload = record.explicit_nonce;
-            payload.extend_from_slice(&record.ciphertext);
-            payload.extend_from_slice(&record.tag.expect("leader should always know tag"));
-            OpaqueMessage {
-                typ: record.typ,
-                version: record.version,
-                payload: Payload::new(payload),
+            let tag = record.tag.expect("leader should always know tag");
+            match version {
+                // ChaCha20-Poly1305 records do not carry an explicit nonce.
+                TlsVersion::V1_2 if aead == AeadAlgorithm::ChaCha20Poly1305 => {
+                    let mut payload = record.ciphertext;
+                    payload.extend_from_slice(&tag);
+                    OpaqueMessage {
+                        typ: record.typ,
+                        version: record.version,
+                        payload: Payload::new(payload),
+                    }
+                }
+                TlsVersion::V1_2 => {
+                    let mut payload = record.explicit_nonce;
+                    payload.extend_from_slice(&record.ciphertext);
+                    payload.extend_from_slice(&tag);
+                    OpaqueMessage {
+                        typ: record.typ,
+                        version: record.version,
+                        payload: Payload::new(payload),
+                    }
+                }
+                TlsVersion::V1_3 => {
+                    let mut payload = record.ciphertext;
+                    payload.extend_from_slice(&tag);
+                    OpaqueMessage {
+                        typ: ContentType::ApplicationData,
+                        version: ProtocolVersion::TLSv1_2,
+                        payload: Payload::new(payload),
+                    }
+                }
             }
         });
 
@@ -1013,13 +1449,13 @@ impl Backend for MpcTlsLeader {
 
         ctx.io_mut()
             .send(Message::Flush {
-                is_decrypting: self.is_decrypting,
+                decrypt: self.decrypt.clone(),
             })
             .await
             .map_err(MpcTlsError::from)?;
 
         record_layer
-            .flush(ctx, vm.clone(), self.is_decrypting)
+            .flush(ctx, vm.clone(), &self.decrypt)
             .await
             .map_err(BackendError::from)
     }
@@ -1043,32 +1479,40 @@ impl Backend for MpcTlsLeader {
     }
 }
 
+/// Data signed by the server to authenticate the handshake.
+enum ServerAuth {
+    /// TLS 1.2 key exchange parameters.
+    Tls12(ServerKxDetails),
+    /// TLS 1.3 handshake messages up to and including the server certificate,
+    /// and the signature from the CertificateVerify message.
+    Tls13 {
+        handshake_messages: Vec<u8>,
+        sig: DigitallySignedStruct,
+    },
+}
+
 enum State {
     Init {
         ctx: Context,
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
     },
     Setup {
         ctx: Context,
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
-        cf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
-        sf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
         client_random: Random,
     },
     Handshake {
         ctx: Context,
         vm: Vm,
         ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
-        cf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
-        sf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
         time: Option<u64>,
         protocol_version: Option<ProtocolVersion>,
         cipher_suite: Option<CipherSuite>,
@@ -1076,25 +1520,23 @@ enum State {
         server_random: Option<Random>,
         server_cert_details: Option<ServerCertDetails>,
         server_key: Option<PublicKey>,
-        server_kx_details: Option<ServerKxDetails>,
+        server_auth: Option<ServerAuth>,
     },
     Active {
         ctx: Context,
         vm: Vm,
         _ke: Box<dyn KeyExchange + Send + Sync + 'static>,
-        prf: MpcPrf,
+        kdf: Kdf,
         record_layer: RecordLayer,
-        cf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
-        sf_vd_fut: DecodeFutureTyped<BitVec, [u8; 12]>,
-        cf_vd: Option<[u8; 12]>,
-        sf_vd: Option<[u8; 12]>,
+        cf_vd: Option<Vec<u8>>,
+        sf_vd: Option<Vec<u8>>,
         time: u64,
         protocol_version: ProtocolVersion,
         client_random: Random,
         server_random: Random,
         server_cert_details: ServerCertDetails,
         server_key: PublicKey,
-        server_kx_details: ServerKxDetails,
+        server_auth: ServerAuth,
     },
     Closed {
         ctx: Context,
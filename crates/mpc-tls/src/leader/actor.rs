@@ -1,6 +1,6 @@
 use crate::{
     leader::{MpcTlsLeader, State},
-    MpcTlsError,
+    MpcTlsError, OnlineDecryption,
 };
 use async_trait::async_trait;
 use ludi::{mailbox, Actor, Address, Context as LudiCtx, Dispatch, Error, Handler, Message, Wrap};
@@ -13,7 +13,7 @@ use tls_core::{
     key::PublicKey,
     msgs::{
         enums::ProtocolVersion,
-        handshake::Random,
+        handshake::{DigitallySignedStruct, Random},
         message::{OpaqueMessage, PlainMessage},
     },
     suites::SupportedCipherSuite,
@@ -40,6 +40,17 @@ impl MpcTlsLeaderCtrl {
             .map_err(MpcTlsError::actor)?
     }
 
+    /// Sets which received application data is decrypted online.
+    pub async fn set_online_decryption(
+        &self,
+        decrypt: OnlineDecryption,
+    ) -> Result<(), MpcTlsError> {
+        self.address
+            .send(SetOnlineDecryption { decrypt })
+            .await
+            .map_err(MpcTlsError::actor)?
+    }
+
     /// Stops the leader actor.
     pub async fn stop(&self) -> Result<(), MpcTlsError> {
         self.address
@@ -186,6 +197,18 @@ impl Dispatch<MpcTlsLeader> for MpcTlsLeaderMsg {
                 })
                 .await;
             }
+            MpcTlsLeaderMsg::BackendMsgSetServerSignatureTls13(msg) => {
+                msg.dispatch(actor, ctx, |value| {
+                    ret(Self::Return::BackendMsgSetServerSignatureTls13(value))
+                })
+                .await;
+            }
+            MpcTlsLeaderMsg::BackendMsgSetHsHashServerFinished(msg) => {
+                msg.dispatch(actor, ctx, |value| {
+                    ret(Self::Return::BackendMsgSetHsHashServerFinished(value))
+                })
+                .await;
+            }
             MpcTlsLeaderMsg::BackendMsgGetServerFinishedVd(msg) => {
                 msg.dispatch(actor, ctx, |value| {
                     ret(Self::Return::BackendMsgGetServerFinishedVd(value))
@@ -264,6 +287,12 @@ impl Dispatch<MpcTlsLeader> for MpcTlsLeaderMsg {
                 })
                 .await;
             }
+            MpcTlsLeaderMsg::SetOnlineDecryption(msg) => {
+                msg.dispatch(actor, ctx, |value| {
+                    ret(Self::Return::SetOnlineDecryption(value))
+                })
+                .await;
+            }
             MpcTlsLeaderMsg::Stop(msg) => {
                 msg.dispatch(actor, ctx, |value| ret(Self::Return::Stop(value)))
                     .await;
@@ -371,6 +400,27 @@ impl Backend for MpcTlsLeaderCtrl {
             .map_err(|err| BackendError::InternalError(err.to_string()))?
     }
 
+    async fn set_server_signature_tls13(
+        &mut self,
+        handshake_messages: Vec<u8>,
+        sig: DigitallySignedStruct,
+    ) -> Result<(), BackendError> {
+        self.address
+            .send(BackendMsgSetServerSignatureTls13 {
+                handshake_messages,
+                sig,
+            })
+            .await
+            .map_err(|err| BackendError::InternalError(err.to_string()))?
+    }
+
+    async fn set_hs_hash_server_finished(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        self.address
+            .send(BackendMsgSetHsHashServerFinished { hash })
+            .await
+            .map_err(|err| BackendError::InternalError(err.to_string()))?
+    }
+
     async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
         self.address
             .send(BackendMsgGetServerFinishedVd { hash })
@@ -729,6 +779,49 @@ impl Handler<BackendMsgSetHsHashServerHello> for MpcTlsLeader {
     }
 }
 
+impl Dispatch<MpcTlsLeader> for BackendMsgSetServerSignatureTls13 {
+    fn dispatch<R: FnOnce(Self::Return) + Send>(
+        self,
+        actor: &mut MpcTlsLeader,
+        ctx: &mut LudiCtx<MpcTlsLeader>,
+        ret: R,
+    ) -> impl Future<Output = ()> + Send {
+        actor.process(self, ctx, ret)
+    }
+}
+
+impl Handler<BackendMsgSetServerSignatureTls13> for MpcTlsLeader {
+    async fn handle(
+        &mut self,
+        msg: BackendMsgSetServerSignatureTls13,
+        _ctx: &mut LudiCtx<Self>,
+    ) -> <BackendMsgSetServerSignatureTls13 as Message>::Return {
+        self.set_server_signature_tls13(msg.handshake_messages, msg.sig)
+            .await
+    }
+}
+
+impl Dispatch<MpcTlsLeader> for BackendMsgSetHsHashServerFinished {
+    fn dispatch<R: FnOnce(Self::Return) + Send>(
+        self,
+        actor: &mut MpcTlsLeader,
+        ctx: &mut LudiCtx<MpcTlsLeader>,
+        ret: R,
+    ) -> impl Future<Output = ()> + Send {
+        actor.process(self, ctx, ret)
+    }
+}
+
+impl Handler<BackendMsgSetHsHashServerFinished> for MpcTlsLeader {
+    async fn handle(
+        &mut self,
+        msg: BackendMsgSetHsHashServerFinished,
+        _ctx: &mut LudiCtx<Self>,
+    ) -> <BackendMsgSetHsHashServerFinished as Message>::Return {
+        self.set_hs_hash_server_finished(msg.hash).await
+    }
+}
+
 impl Dispatch<MpcTlsLeader> for BackendMsgGetServerFinishedVd {
     fn dispatch<R: FnOnce(Self::Return) + Send>(
         self,
@@ -1002,6 +1095,27 @@ impl Handler<DeferDecryption> for MpcTlsLeader {
     }
 }
 
+impl Dispatch<MpcTlsLeader> for SetOnlineDecryption {
+    fn dispatch<R: FnOnce(Self::Return) + Send>(
+        self,
+        actor: &mut MpcTlsLeader,
+        ctx: &mut LudiCtx<MpcTlsLeader>,
+        ret: R,
+    ) -> impl Future<Output = ()> + Send {
+        actor.process(self, ctx, ret)
+    }
+}
+
+impl Handler<SetOnlineDecryption> for MpcTlsLeader {
+    async fn handle(
+        &mut self,
+        msg: SetOnlineDecryption,
+        _ctx: &mut LudiCtx<Self>,
+    ) -> <SetOnlineDecryption as Message>::Return {
+        self.set_online_decryption(msg.decrypt).await
+    }
+}
+
 impl Dispatch<MpcTlsLeader> for Stop {
     fn dispatch<R: FnOnce(Self::Return) + Send>(
         self,
@@ -1036,6 +1150,8 @@ pub enum MpcTlsLeaderMsg {
     BackendMsgSetServerKxDetails(BackendMsgSetServerKxDetails),
     BackendMsgSetHsHashClientKeyExchange(BackendMsgSetHsHashClientKeyExchange),
     BackendMsgSetHsHashServerHello(BackendMsgSetHsHashServerHello),
+    BackendMsgSetServerSignatureTls13(BackendMsgSetServerSignatureTls13),
+    BackendMsgSetHsHashServerFinished(BackendMsgSetHsHashServerFinished),
     BackendMsgGetServerFinishedVd(BackendMsgGetServerFinishedVd),
     BackendMsgGetClientFinishedVd(BackendMsgGetClientFinishedVd),
     BackendMsgPrepareEncryption(BackendMsgPrepareEncryption),
@@ -1049,6 +1165,7 @@ pub enum MpcTlsLeaderMsg {
     BackendMsgIsEmpty(BackendMsgIsEmpty),
     BackendMsgServerClosed(BackendMsgServerClosed),
     DeferDecryption(DeferDecryption),
+    SetOnlineDecryption(SetOnlineDecryption),
     Stop(Stop),
 }
 
@@ -1071,6 +1188,8 @@ pub enum MpcTlsLeaderMsgReturn {
     BackendMsgSetServerKxDetails(<BackendMsgSetServerKxDetails as Message>::Return),
     BackendMsgSetHsHashClientKeyExchange(<BackendMsgSetHsHashClientKeyExchange as Message>::Return),
     BackendMsgSetHsHashServerHello(<BackendMsgSetHsHashServerHello as Message>::Return),
+    BackendMsgSetServerSignatureTls13(<BackendMsgSetServerSignatureTls13 as Message>::Return),
+    BackendMsgSetHsHashServerFinished(<BackendMsgSetHsHashServerFinished as Message>::Return),
     BackendMsgGetServerFinishedVd(<BackendMsgGetServerFinishedVd as Message>::Return),
     BackendMsgGetClientFinishedVd(<BackendMsgGetClientFinishedVd as Message>::Return),
     BackendMsgPrepareEncryption(<BackendMsgPrepareEncryption as Message>::Return),
@@ -1084,6 +1203,7 @@ pub enum MpcTlsLeaderMsgReturn {
     BackendMsgIsEmpty(<BackendMsgIsEmpty as Message>::Return),
     BackendMsgServerClosed(<BackendMsgServerClosed as Message>::Return),
     DeferDecryption(<DeferDecryption as Message>::Return),
+    SetOnlineDecryption(<SetOnlineDecryption as Message>::Return),
     Stop(<Stop as Message>::Return),
 }
 
@@ -1430,6 +1550,61 @@ impl Wrap<BackendMsgSetHsHashServerHello> for MpcTlsLeaderMsg {
     }
 }
 
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct BackendMsgSetServerSignatureTls13 {
+    pub handshake_messages: Vec<u8>,
+    pub sig: DigitallySignedStruct,
+}
+
+impl Message for BackendMsgSetServerSignatureTls13 {
+    type Return = Result<(), BackendError>;
+}
+
+impl From<BackendMsgSetServerSignatureTls13> for MpcTlsLeaderMsg {
+    fn from(value: BackendMsgSetServerSignatureTls13) -> Self {
+        MpcTlsLeaderMsg::BackendMsgSetServerSignatureTls13(value)
+    }
+}
+
+impl Wrap<BackendMsgSetServerSignatureTls13> for MpcTlsLeaderMsg {
+    fn unwrap_return(
+        ret: Self::Return,
+    ) -> Result<<BackendMsgSetServerSignatureTls13 as Message>::Return, Error> {
+        match ret {
+            Self::Return::BackendMsgSetServerSignatureTls13(value) => Ok(value),
+            _ => Err(Error::Wrapper),
+        }
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct BackendMsgSetHsHashServerFinished {
+    pub hash: Vec<u8>,
+}
+
+impl Message for BackendMsgSetHsHashServerFinished {
+    type Return = Result<(), BackendError>;
+}
+
+impl From<BackendMsgSetHsHashServerFinished> for MpcTlsLeaderMsg {
+    fn from(value: BackendMsgSetHsHashServerFinished) -> Self {
+        MpcTlsLeaderMsg::BackendMsgSetHsHashServerFinished(value)
+    }
+}
+
+impl Wrap<BackendMsgSetHsHashServerFinished> for MpcTlsLeaderMsg {
+    fn unwrap_return(
+        ret: Self::Return,
+    ) -> Result<<BackendMsgSetHsHashServerFinished as Message>::Return, Error> {
+        match ret {
+            Self::Return::BackendMsgSetHsHashServerFinished(value) => Ok(value),
+            _ => Err(Error::Wrapper),
+        }
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub struct BackendMsgGetServerFinishedVd {
@@ -1756,6 +1931,32 @@ impl Wrap<DeferDecryption> for MpcTlsLeaderMsg {
     }
 }
 
+/// Message to set which received application data is decrypted online.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub struct SetOnlineDecryption {
+    pub decrypt: OnlineDecryption,
+}
+
+impl Message for SetOnlineDecryption {
+    type Return = Result<(), MpcTlsError>;
+}
+
+impl From<SetOnlineDecryption> for MpcTlsLeaderMsg {
+    fn from(value: SetOnlineDecryption) -> Self {
+        MpcTlsLeaderMsg::SetOnlineDecryption(value)
+    }
+}
+
+impl Wrap<SetOnlineDecryption> for MpcTlsLeaderMsg {
+    fn unwrap_return(ret: Self::Return) -> Result<<SetOnlineDecryption as Message>::Return, Error> {
+        match ret {
+            Self::Return::SetOnlineDecryption(value) => Ok(value),
+            _ => Err(Error::Wrapper),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Stop;
 
@@ -0,0 +1,246 @@
+//! Local protection of TLS 1.3 handshake records.
+//!
+//! In TLS 1.3 the handshake messages following the ServerHello are encrypted
+//! with keys derived from the handshake traffic secrets. These secrets are
+//! revealed to the leader, which protects the handshake records locally.
+
+use std::collections::VecDeque;
+
+use aes_gcm::{aead::AeadMutInPlace, Aes128Gcm, NewAead};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tls_core::{
+    cipher::make_tls13_aad,
+    msgs::{
+        base::Payload,
+        enums::{ContentType, ProtocolVersion},
+        message::{OpaqueMessage, PlainMessage},
+    },
+};
+
+use crate::{
+    utils::{opaque_into_parts_tls13, unpad_tls13},
+    MpcTlsError,
+};
+
+/// Protects TLS 1.3 handshake records using the handshake traffic secrets.
+pub(crate) struct HandshakeCipher {
+    client: TrafficKeys,
+    server: TrafficKeys,
+    /// Whether outgoing handshake records are encrypted locally.
+    pub(crate) encrypting: bool,
+    /// Whether incoming handshake records are decrypted locally.
+    pub(crate) decrypting: bool,
+    /// Server finished verify data.
+    pub(crate) sf_vd: Option<Vec<u8>>,
+    encrypted: VecDeque<OpaqueMessage>,
+    decrypted: VecDeque<PlainMessage>,
+}
+
+impl HandshakeCipher {
+    /// Creates a new handshake cipher.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_secret` - Client handshake traffic secret.
+    /// * `server_secret` - Server handshake traffic secret.
+    pub(crate) fn new(client_secret: [u8; 32], server_secret: [u8; 32]) -> Self {
+        Self {
+            client: TrafficKeys::new(client_secret),
+            server: TrafficKeys::new(server_secret),
+            encrypting: false,
+            decrypting: false,
+            sf_vd: None,
+            encrypted: VecDeque::new(),
+            decrypted: VecDeque::new(),
+        }
+    }
+
+    /// Computes the server finished verify data.
+    pub(crate) fn server_finished_vd(&mut self, hash: &[u8]) -> Vec<u8> {
+        let vd = self.server.finished_vd(hash);
+        self.sf_vd = Some(vd.clone());
+        vd
+    }
+
+    /// Computes the client finished verify data.
+    pub(crate) fn client_finished_vd(&self, hash: &[u8]) -> Vec<u8> {
+        self.client.finished_vd(hash)
+    }
+
+    /// Encrypts an outgoing message.
+    pub(crate) fn encrypt(&mut self, msg: PlainMessage) -> Result<(), MpcTlsError> {
+        let mut payload = msg.payload.0;
+        payload.push(msg.typ.get_u8());
+
+        let aad = make_tls13_aad(payload.len() + TAG_LEN);
+        let nonce = self.client.next_nonce();
+        let tag = self
+            .client
+            .cipher
+            .encrypt_in_place_detached(&nonce.into(), &aad, &mut payload)
+            .map_err(|_| MpcTlsError::hs("failed to encrypt handshake record"))?;
+        payload.extend_from_slice(&tag);
+
+        self.encrypted.push_back(OpaqueMessage {
+            typ: ContentType::ApplicationData,
+            version: ProtocolVersion::TLSv1_2,
+            payload: Payload::new(payload),
+        });
+
+        Ok(())
+    }
+
+    /// Decrypts an incoming message.
+    pub(crate) fn decrypt(&mut self, msg: OpaqueMessage) -> Result<(), MpcTlsError> {
+        if msg.typ != ContentType::ApplicationData {
+            return Err(MpcTlsError::hs(format!(
+                "unexpected handshake record content type: {:?}",
+                msg.typ
+            )));
+        }
+
+        let aad = make_tls13_aad(msg.payload.0.len());
+        let (mut plaintext, tag) = opaque_into_parts_tls13(msg.payload.0)?;
+        let nonce = self.server.next_nonce();
+        self.server
+            .cipher
+            .decrypt_in_place_detached(&nonce.into(), &aad, &mut plaintext, tag.as_slice().into())
+            .map_err(|_| MpcTlsError::hs("failed to decrypt handshake record"))?;
+
+        let typ = unpad_tls13(&mut plaintext)?;
+
+        self.decrypted.push_back(PlainMessage {
+            typ,
+            version: ProtocolVersion::TLSv1_3,
+            payload: Payload::new(plaintext),
+        });
+
+        Ok(())
+    }
+
+    /// Returns the next encrypted outgoing message.
+    pub(crate) fn next_encrypted(&mut self) -> Option<OpaqueMessage> {
+        self.encrypted.pop_front()
+    }
+
+    /// Returns the next decrypted incoming message.
+    pub(crate) fn next_decrypted(&mut self) -> Option<PlainMessage> {
+        self.decrypted.pop_front()
+    }
+}
+
+impl std::fmt::Debug for HandshakeCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandshakeCipher")
+            .field("encrypting", &self.encrypting)
+            .field("decrypting", &self.decrypting)
+            .finish_non_exhaustive()
+    }
+}
+
+const TAG_LEN: usize = 16;
+
+/// Traffic keys of one direction.
+struct TrafficKeys {
+    secret: [u8; 32],
+    cipher: Aes128Gcm,
+    iv: [u8; 12],
+    seq: u64,
+}
+
+impl TrafficKeys {
+    fn new(secret: [u8; 32]) -> Self {
+        let key: [u8; 16] = hkdf_expand_label(&secret, b"key", 16)
+            .try_into()
+            .expect("key is 16 bytes");
+        let iv: [u8; 12] = hkdf_expand_label(&secret, b"iv", 12)
+            .try_into()
+            .expect("iv is 12 bytes");
+
+        Self {
+            secret,
+            cipher: Aes128Gcm::new(&key.into()),
+            iv,
+            seq: 0,
+        }
+    }
+
+    fn next_nonce(&mut self) -> [u8; 12] {
+        let mut nonce = self.iv;
+        nonce[4..]
+            .iter_mut()
+            .zip(self.seq.to_be_bytes())
+            .for_each(|(a, b)| *a ^= b);
+        self.seq += 1;
+
+        nonce
+    }
+
+    fn finished_vd(&self, hash: &[u8]) -> Vec<u8> {
+        let finished_key = hkdf_expand_label(&self.secret, b"finished", 32);
+        hmac_sha256(&finished_key, hash).to_vec()
+    }
+}
+
+/// HKDF-Expand-Label with an empty context, see section 7.1 of RFC 8446.
+///
+/// Only supports outputs up to 32 bytes.
+fn hkdf_expand_label(secret: &[u8; 32], label: &[u8], len: u16) -> Vec<u8> {
+    const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+    let mut info = Vec::new();
+    info.extend_from_slice(&len.to_be_bytes());
+    info.push((LABEL_PREFIX.len() + label.len()) as u8);
+    info.extend_from_slice(LABEL_PREFIX);
+    info.extend_from_slice(label);
+    // Empty context.
+    info.push(0);
+    // HKDF-Expand block counter.
+    info.push(1);
+
+    let mut output = hmac_sha256(secret, &info).to_vec();
+    output.truncate(len as usize);
+    output
+}
+
+fn hmac_sha256(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(msg);
+    mac.finalize().into_bytes().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handshake_cipher_roundtrip() {
+        let mut client = HandshakeCipher::new([1u8; 32], [2u8; 32]);
+        // A server sees the secrets swapped.
+        let mut server = HandshakeCipher::new([2u8; 32], [1u8; 32]);
+
+        for i in 0..3u8 {
+            client
+                .encrypt(PlainMessage {
+                    typ: ContentType::Handshake,
+                    version: ProtocolVersion::TLSv1_3,
+                    payload: Payload::new(vec![i; 42]),
+                })
+                .unwrap();
+            let msg = client.next_encrypted().unwrap();
+            assert_eq!(msg.typ, ContentType::ApplicationData);
+
+            server.decrypt(msg).unwrap();
+            let msg = server.next_decrypted().unwrap();
+            assert_eq!(msg.typ, ContentType::Handshake);
+            assert_eq!(msg.payload.0, vec![i; 42]);
+        }
+
+        assert_eq!(
+            client.client_finished_vd(&[0u8; 32]),
+            server.server_finished_vd(&[0u8; 32])
+        );
+    }
+}
@@ -8,6 +8,7 @@ mod config;
 mod decode;
 mod error;
 pub(crate) mod follower;
+mod kdf;
 pub(crate) mod leader;
 mod msg;
 mod record_layer;
@@ -17,14 +18,16 @@ pub use config::{Config, ConfigBuilder, ConfigBuilderError};
 pub use error::MpcTlsError;
 pub use follower::MpcTlsFollower;
 pub use leader::{LeaderCtrl, MpcTlsLeader};
+pub use record_layer::OnlineDecryption;
 
 use std::{future::Future, pin::Pin, sync::Arc};
 
 use mpz_memory_core::{
     binary::{Binary, U8},
-    Array,
+    Array, Vector,
 };
 use mpz_vm_core::Vm as VmTrait;
+use tlsn_core::connection::AeadAlgorithm;
 
 use tokio::sync::Mutex;
 
@@ -38,17 +41,48 @@ pub(crate) enum Role {
     Follower,
 }
 
+/// Progress of an MPC-TLS connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Progress {
+    /// The handshake is complete and application data is processed.
+    HandshakeComplete,
+    /// Records were processed, with the total number of records processed so
+    /// far.
+    Records {
+        /// Number of sent records which were encrypted.
+        sent: usize,
+        /// Number of received records which were decrypted.
+        recv: usize,
+    },
+    /// The connection was closed and the deferred records are decrypted.
+    DeferredDecryption {
+        /// Number of received records which are decrypted.
+        records: usize,
+    },
+    /// The transcript was committed.
+    Committed,
+}
+
+/// Callback which is notified of the [`Progress`] of an MPC-TLS connection.
+pub type ProgressFn = Arc<dyn Fn(Progress) + Send + Sync + 'static>;
+
 /// TLS session keys.
 #[derive(Debug, Clone)]
 pub struct SessionKeys {
+    /// AEAD algorithm the keys are used with.
+    pub aead: AeadAlgorithm,
     /// Client write key.
-    pub client_write_key: Array<U8, 16>,
+    pub client_write_key: Vector<U8>,
     /// Client write IV.
-    pub client_write_iv: Array<U8, 4>,
+    pub client_write_iv: Vector<U8>,
     /// Server write key.
-    pub server_write_key: Array<U8, 16>,
+    pub server_write_key: Vector<U8>,
     /// Server write IV.
-    pub server_write_iv: Array<U8, 4>,
+    pub server_write_iv: Vector<U8>,
     /// Server write MAC key.
-    pub server_write_mac_key: Array<U8, 16>,
+    ///
+    /// `None` for ChaCha20-Poly1305, which derives a one-time key for each
+    /// record.
+    pub server_write_mac_key: Option<Array<U8, 16>>,
 }
@@ -4,7 +4,7 @@ use tls_core::{
     msgs::enums::{ContentType, ProtocolVersion},
 };
 
-use crate::record_layer::{DecryptMode, EncryptMode};
+use crate::record_layer::{DecryptMode, EncryptMode, OnlineDecryption};
 
 /// MPC-TLS protocol message.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,12 +13,15 @@ pub(crate) enum Message {
     StartHandshake(StartHandshake),
     SetServerRandom(SetServerRandom),
     SetServerKey(SetServerKey),
+    SessionHash(SessionHash),
     ClientFinishedVd(ClientFinishedVd),
     ServerFinishedVd(ServerFinishedVd),
+    ServerHelloHash(ServerHelloHash),
+    ServerFinishedHash(ServerFinishedHash),
     Encrypt(Encrypt),
     Decrypt(Decrypt),
     StartTraffic,
-    Flush { is_decrypting: bool },
+    Flush { decrypt: OnlineDecryption },
     CloseConnection,
 }
 
@@ -42,6 +45,11 @@ pub(crate) struct SetServerKey {
     pub(crate) key: PublicKey,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct SessionHash {
+    pub(crate) hash: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Decrypt {
     pub(crate) typ: ContentType,
@@ -63,11 +71,21 @@ pub(crate) struct Encrypt {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ClientFinishedVd {
-    pub handshake_hash: [u8; 32],
+    pub handshake_hash: Vec<u8>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ServerFinishedVd {
+    pub handshake_hash: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ServerHelloHash {
+    pub handshake_hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ServerFinishedHash {
     pub handshake_hash: [u8; 32],
 }
 
@@ -1,40 +1,50 @@
 //! TLS record layer.
 
 pub(crate) mod aead;
-mod aes_gcm;
 mod decrypt;
 mod encrypt;
+mod local;
 
-use std::{collections::VecDeque, mem::take, sync::Arc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    mem::take,
+    sync::Arc,
+};
 
-use aead::MpcAesGcm;
+use aead::MpcAead;
 use futures::TryFutureExt;
 use mpz_common::{Context, Task};
 use mpz_memory_core::{
     binary::{Binary, U8},
-    Array,
+    Array, Vector,
 };
 use mpz_vm_core::Vm as VmTrait;
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use tls_core::{
-    cipher::make_tls12_aad,
+    cipher::{make_tls12_aad, make_tls13_aad},
     msgs::enums::{ContentType, ProtocolVersion},
 };
-use tlsn_core::transcript::Record;
+use tlsn_core::{connection::AeadAlgorithm, transcript::Record};
 use tokio::sync::Mutex;
 use tracing::{debug, instrument};
 
 use crate::{
-    record_layer::{aes_gcm::AesGcm, decrypt::DecryptOp, encrypt::EncryptOp},
-    MpcTlsError, Role, Vm,
+    record_layer::{
+        decrypt::{DecryptOp, RecordPosition},
+        encrypt::EncryptOp,
+        local::LocalAead,
+    },
+    MpcTlsError, Progress, ProgressFn, Role, Vm,
 };
 pub(crate) use decrypt::DecryptMode;
+pub use decrypt::OnlineDecryption;
 pub(crate) use encrypt::EncryptMode;
 
 const MAX_RECORD_SIZE: usize = 1026 * 16;
 // This limits how much the leader can cause the follower to allocate.
 const MAX_BUFFER_SIZE: usize = (16 * (1 << 20)) / MAX_RECORD_SIZE;
+const TAG_LEN: usize = 16;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct PlainRecord {
@@ -69,15 +79,47 @@ impl State {
     }
 }
 
+/// The last 8 bytes of the TLS 1.3 client and server write IVs, which are
+/// XORed with the record sequence number to form the per-record nonce.
+#[derive(Debug, Clone, Copy)]
+struct NonceMasks {
+    client: [u8; 8],
+    server: [u8; 8],
+}
+
+fn tls13_nonce(mask: [u8; 8], seq: u64) -> Vec<u8> {
+    let mut nonce = seq.to_be_bytes();
+    nonce.iter_mut().zip(mask).for_each(|(a, b)| *a ^= b);
+    nonce.to_vec()
+}
+
+/// Hard ceilings up to which the record layer allocates capacity on demand.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Ceilings {
+    /// Number of sent records.
+    pub(crate) sent_records: usize,
+    /// Number of received records decrypted online.
+    pub(crate) recv_records_online: usize,
+    /// Number of sent bytes.
+    pub(crate) sent: usize,
+    /// Number of received bytes decrypted online.
+    pub(crate) recv_online: usize,
+    /// Number of received bytes.
+    pub(crate) recv: usize,
+}
+
 /// MPC-TLS record layer.
 pub(crate) struct RecordLayer {
     role: Role,
     write_seq: u64,
     read_seq: u64,
-    encrypter: Arc<Mutex<MpcAesGcm>>,
-    decrypt: Arc<Mutex<MpcAesGcm>>,
-    aes_gcm: AesGcm,
+    aead: AeadAlgorithm,
+    encrypter: Arc<Mutex<MpcAead>>,
+    decrypt: Arc<Mutex<MpcAead>>,
+    local: LocalAead,
     state: State,
+    /// Set when records are protected according to TLS 1.3.
+    nonce_masks: Option<NonceMasks>,
     /// Whether the record layer has started processing application data.
     started: bool,
     /// Number of bytes sent.
@@ -86,47 +128,90 @@ pub(crate) struct RecordLayer {
     recv_online: usize,
     /// Number of bytes received.
     recv: usize,
+    /// Number of application data records received.
+    recv_app_records: usize,
+    /// Number of bytes of application data received.
+    recv_app: usize,
     /// Maximum number of bytes sent.
     max_sent: usize,
     /// Maximum number of bytes received to be decrypted online.
     max_recv_online: usize,
     /// Maximum number of bytes received.
     max_recv: usize,
+    /// Number of sent records allocated.
+    max_sent_records: usize,
+    /// Number of received records allocated to be decrypted online.
+    max_recv_records_online: usize,
+    /// Ceilings up to which the maxima are extended on demand.
+    ceilings: Ceilings,
 
     encrypt_buffer: Vec<EncryptOp>,
     decrypt_buffer: Vec<DecryptOp>,
     encrypted_buffer: VecDeque<EncryptedRecord>,
     decrypted_buffer: VecDeque<PlainRecord>,
+    /// Decrypted records which are preceded by records that are not decrypted
+    /// yet, keyed by sequence number.
+    held_buffer: BTreeMap<u64, PlainRecord>,
+    progress: Option<ProgressFn>,
 }
 
 impl RecordLayer {
     /// Creates a new record layer.
-    pub(crate) fn new(role: Role, encrypt: MpcAesGcm, decrypt: MpcAesGcm) -> Self {
+    pub(crate) fn new(role: Role, aead: AeadAlgorithm, encrypt: MpcAead, decrypt: MpcAead) -> Self {
         Self {
             role,
             write_seq: 0,
             read_seq: 0,
+            aead,
             encrypter: Arc::new(Mutex::new(encrypt)),
             decrypt: Arc::new(Mutex::new(decrypt)),
-            aes_gcm: AesGcm::new(role),
+            local: LocalAead::new(role, aead),
             state: State::Init,
+            nonce_masks: None,
             started: false,
             sent: 0,
             recv_online: 0,
             recv: 0,
+            recv_app_records: 0,
+            recv_app: 0,
             max_sent: 0,
             max_recv_online: 0,
             max_recv: 0,
+            max_sent_records: 0,
+            max_recv_records_online: 0,
+            ceilings: Ceilings {
+                sent_records: 0,
+                recv_records_online: 0,
+                sent: 0,
+                recv_online: 0,
+                recv: 0,
+            },
             encrypt_buffer: Vec::new(),
             decrypt_buffer: Vec::new(),
             encrypted_buffer: VecDeque::new(),
             decrypted_buffer: VecDeque::new(),
+            held_buffer: BTreeMap::new(),
+            progress: None,
+        }
+    }
+
+    /// Sets a callback which is notified of the progress.
+    pub(crate) fn set_progress(&mut self, progress: ProgressFn) {
+        self.progress = Some(progress);
+    }
+
+    fn report(&self, progress: Progress) {
+        if let Some(f) = &self.progress {
+            f(progress);
         }
     }
 
     /// Allocates resources for the record layer, returning a reference
     /// to the server write MAC key.
     ///
+    /// ChaCha20-Poly1305 has no MAC key, instead a one-time key is derived for
+    /// each record.
+    ///
     /// # Arguments
     ///
     /// * `vm` - Virtual machine.
@@ -136,6 +221,9 @@ impl RecordLayer {
     /// * `recv_len_online` - Total length of received records to be decrypted
     ///   online.
     /// * `recv_len` - Total length of received records to allocate.
+    /// * `ceilings` - Ceilings up to which more capacity is allocated on
+    ///   demand.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn alloc(
         &mut self,
         vm: &mut dyn VmTrait<Binary>,
@@ -144,7 +232,8 @@ impl RecordLayer {
         sent_len: usize,
         recv_len_online: usize,
         recv_len: usize,
-    ) -> Result<Array<U8, 16>, MpcTlsError> {
+        ceilings: Ceilings,
+    ) -> Result<Option<Array<U8, 16>>, MpcTlsError> {
         let State::Init = self.state.take() else {
             return Err(MpcTlsError::other("record layer is already allocated"));
         };
@@ -177,11 +266,14 @@ impl RecordLayer {
             Role::Follower => None,
         };
 
-        self.aes_gcm.alloc(vm)?;
+        self.local.alloc(vm)?;
 
         self.max_sent += sent_len;
         self.max_recv_online += recv_len_online;
         self.max_recv += recv_len;
+        self.max_sent_records += sent_records;
+        self.max_recv_records_online += recv_records;
+        self.ceilings = ceilings;
 
         self.state = State::Online {
             recv_otp,
@@ -189,7 +281,7 @@ impl RecordLayer {
             recv_records: Vec::new(),
         };
 
-        decrypt.ghash_key().map_err(MpcTlsError::record_layer)
+        decrypt.mac_key().map_err(MpcTlsError::record_layer)
     }
 
     pub(crate) async fn preprocess(&mut self, ctx: &mut Context) -> Result<(), MpcTlsError> {
@@ -219,10 +311,10 @@ impl RecordLayer {
     /// Sets the keys for the record layer.
     pub(crate) fn set_keys(
         &mut self,
-        client_write_key: Array<U8, 16>,
-        client_iv: Array<U8, 4>,
-        server_write_key: Array<U8, 16>,
-        server_iv: Array<U8, 4>,
+        client_write_key: Vector<U8>,
+        client_iv: Vector<U8>,
+        server_write_key: Vector<U8>,
+        server_iv: Vector<U8>,
     ) -> Result<(), MpcTlsError> {
         let mut encrypt = self
             .encrypter
@@ -233,15 +325,35 @@ impl RecordLayer {
             .try_lock()
             .map_err(|_| MpcTlsError::other("decrypt lock is held"))?;
 
-        encrypt.set_key(client_write_key);
-        encrypt.set_iv(client_iv);
-        decrypt.set_key(server_write_key);
-        decrypt.set_iv(server_iv);
-        self.aes_gcm.set_key(server_write_key, server_iv);
+        encrypt
+            .set_key(client_write_key)
+            .map_err(MpcTlsError::record_layer)?;
+        encrypt
+            .set_iv(client_iv)
+            .map_err(MpcTlsError::record_layer)?;
+        decrypt
+            .set_key(server_write_key)
+            .map_err(MpcTlsError::record_layer)?;
+        decrypt
+            .set_iv(server_iv)
+            .map_err(MpcTlsError::record_layer)?;
+        self.local.set_key(server_write_key, server_iv);
 
         Ok(())
     }
 
+    /// Sets the TLS 1.3 client and server write IVs.
+    ///
+    /// Once set, the record layer computes the per-record nonces and
+    /// additional data according to TLS 1.3. The first 4 bytes of the IVs must
+    /// be set as the implicit IVs via [`RecordLayer::set_keys`].
+    pub(crate) fn set_tls13_ivs(&mut self, client_iv: [u8; 12], server_iv: [u8; 12]) {
+        self.nonce_masks = Some(NonceMasks {
+            client: client_iv[4..].try_into().expect("mask is 8 bytes"),
+            server: server_iv[4..].try_into().expect("mask is 8 bytes"),
+        });
+    }
+
     /// Sets up the record layer.
     pub(crate) async fn setup(&mut self, ctx: &mut Context) -> Result<(), MpcTlsError> {
         let mut encrypt = self
@@ -272,6 +384,7 @@ impl RecordLayer {
             && self.decrypt_buffer.is_empty()
             && self.encrypted_buffer.is_empty()
             && self.decrypted_buffer.is_empty()
+            && self.held_buffer.is_empty()
     }
 
     pub(crate) fn wants_flush(&self) -> bool {
@@ -281,6 +394,7 @@ impl RecordLayer {
     pub(crate) fn start_traffic(&mut self) {
         self.started = true;
         debug!("started processing application data");
+        self.report(Progress::HandshakeComplete);
     }
 
     pub(crate) fn push_encrypt(
@@ -293,10 +407,10 @@ impl RecordLayer {
     ) -> Result<(), MpcTlsError> {
         if self.encrypt_buffer.len() >= MAX_BUFFER_SIZE {
             return Err(MpcTlsError::peer("encrypt buffer is full"));
-        } else if self.sent + len > self.max_sent {
+        } else if self.sent + len > self.ceilings.sent {
             return Err(MpcTlsError::record_layer(format!(
-                "attempted to send more data than was configured, increase `max_sent` in the config: current={}, additional={}, max={}",
-                self.sent, len, self.max_sent
+                "attempted to send more data than was configured, increase `max_sent` or `sent_ceiling` in the config: current={}, additional={}, max={}",
+                self.sent, len, self.ceilings.sent
             )));
         }
 
@@ -327,16 +441,29 @@ impl RecordLayer {
     ) -> Result<(), MpcTlsError> {
         if self.decrypt_buffer.len() >= MAX_BUFFER_SIZE {
             return Err(MpcTlsError::peer("decrypt buffer is full"));
-        } else if self.recv + ciphertext.len() > self.max_recv {
+        } else if self.recv + ciphertext.len() > self.ceilings.recv {
             return Err(MpcTlsError::record_layer(format!(
-                "attempted to receive more data than was configured, increase `max_recv` in the config: current={}, additional={}, max={}",
-                self.recv, ciphertext.len(), self.max_recv
+                "attempted to receive more data than was configured, increase `max_recv` or `recv_ceiling` in the config: current={}, additional={}, max={}",
+                self.recv, ciphertext.len(), self.ceilings.recv
             )));
         }
 
-        let (seq, aad) = self.next_read(typ, version, ciphertext.len());
+        let (seq, nonce, aad) = self.next_read(typ, version, ciphertext.len());
+        // In TLS 1.3 and with ChaCha20-Poly1305 the nonce is not transmitted
+        // with the record.
+        let explicit_nonce = nonce.unwrap_or(explicit_nonce);
+        let position = (typ == ContentType::ApplicationData).then(|| {
+            let position = RecordPosition {
+                index: self.recv_app_records,
+                range: self.recv_app..self.recv_app + ciphertext.len(),
+            };
+            self.recv_app_records += 1;
+            self.recv_app += ciphertext.len();
+            position
+        });
+
         self.recv += ciphertext.len();
-        self.decrypt_buffer.push(DecryptOp::new(
+        let mut op = DecryptOp::new(
             seq,
             typ,
             version,
@@ -345,7 +472,9 @@ impl RecordLayer {
             aad,
             tag,
             mode,
-        ));
+        );
+        op.position = position;
+        self.decrypt_buffer.push(op);
 
         Ok(())
     }
@@ -377,7 +506,7 @@ impl RecordLayer {
         &mut self,
         ctx: &mut Context,
         vm: Vm,
-        is_decrypting: bool,
+        decrypt: &OnlineDecryption,
     ) -> Result<(), MpcTlsError> {
         let State::Online {
             recv_otp,
@@ -406,34 +535,103 @@ impl RecordLayer {
             .map_err(|_| MpcTlsError::record_layer("decrypt lock is held"))?;
 
         let encrypt_ops: Vec<_> = self.encrypt_buffer.drain(..).collect();
-        let decrypt_ops: Vec<_> = if is_decrypting {
-            self.decrypt_buffer.drain(..).collect()
-        } else {
-            // Process non-application data even if we're not decrypting.
-            let decrypt_pos = self
-                .decrypt_buffer
-                .iter()
-                .position(|op| op.typ == ContentType::ApplicationData)
-                .unwrap_or(self.decrypt_buffer.len());
-
-            self.decrypt_buffer.drain(..decrypt_pos).collect()
-        };
+        // Select the records which are decrypted online, the remaining records
+        // stay buffered until they are selected or the connection is closed.
+        let mut decrypt_ops = Vec::new();
+        let mut deferred = false;
+        for op in take(&mut self.decrypt_buffer) {
+            let online = match &op.position {
+                Some(position) => decrypt.contains(position),
+                // Process non-application data unless it follows deferred
+                // records.
+                None => !deferred,
+            };
+
+            if online {
+                decrypt_ops.push(op);
+            } else {
+                deferred = true;
+                self.decrypt_buffer.push(op);
+            }
+        }
 
         if encrypt_ops.is_empty() && decrypt_ops.is_empty() {
             debug!("no operations to process, skipping");
             return Ok(());
         }
 
-        if is_decrypting {
+        // Extend the capacity if the operations exceed what was allocated.
+        // Both parties know the lengths of the operations, so they extend
+        // deterministically without further communication.
+        let mut sent_extend = Vec::new();
+        if self.sent > self.max_sent {
+            sent_extend = encrypt_ops.iter().map(|op| op.len).collect();
+            self.max_sent = self.sent;
+        }
+
+        let sent_records_needed = sent_records.len() + encrypt_ops.len();
+        let sent_records_extend = sent_records_needed.saturating_sub(self.max_sent_records);
+        if sent_records_needed > self.ceilings.sent_records {
+            return Err(MpcTlsError::record_layer(format!(
+                "attempted to send more records than was configured, increase `max_sent_records` or `sent_records_ceiling` in the config: current={}, additional={}, max={}",
+                sent_records.len(), encrypt_ops.len(), self.ceilings.sent_records
+            )));
+        }
+
+        if sent_records_extend > 0 || !sent_extend.is_empty() {
+            debug!(
+                "extending encryption capacity by {} records and {} bytes",
+                sent_records_extend,
+                sent_extend.iter().sum::<usize>()
+            );
+            encrypter
+                .extend(&mut (*vm), sent_records_extend, &sent_extend)
+                .map_err(MpcTlsError::record_layer)?;
+            self.max_sent_records += sent_records_extend;
+        }
+
+        let mut recv_extend = Vec::new();
+        if !decrypt.is_none() {
             let decrypt_len: usize = decrypt_ops.iter().map(|op| op.ciphertext.len()).sum();
-            if self.recv_online + decrypt_len > self.max_recv_online {
+            if self.recv_online + decrypt_len > self.ceilings.recv_online {
                 return Err(MpcTlsError::record_layer(format!(
-                    "attempted to decrypt more data in the online phase than was configured, increase `max_recv_online` in the config: current={}, additional={}, max={}",
-                    self.recv_online, decrypt_len, self.max_recv_online
+                    "attempted to decrypt more data in the online phase than was configured, increase `max_recv_online` or `recv_online_ceiling` in the config: current={}, additional={}, max={}",
+                    self.recv_online, decrypt_len, self.ceilings.recv_online
                 )));
-            } else {
-                self.recv_online += decrypt_len;
             }
+
+            self.recv_online += decrypt_len;
+            if self.recv_online > self.max_recv_online {
+                recv_extend = decrypt_ops.iter().map(|op| op.ciphertext.len()).collect();
+                self.max_recv_online = self.recv_online;
+
+                if let Some(recv_otp) = recv_otp.as_mut() {
+                    let len = recv_otp.len();
+                    recv_otp.resize(len + decrypt_len, 0);
+                    rand::rng().fill_bytes(&mut recv_otp[len..]);
+                }
+            }
+        }
+
+        let recv_records_needed = recv_records.len() + decrypt_ops.len();
+        let recv_records_extend = recv_records_needed.saturating_sub(self.max_recv_records_online);
+        if recv_records_needed > self.ceilings.recv_records_online {
+            return Err(MpcTlsError::record_layer(format!(
+                "attempted to decrypt more records in the online phase than was configured, increase `max_recv_records_online` or `recv_records_online_ceiling` in the config: current={}, additional={}, max={}",
+                recv_records.len(), decrypt_ops.len(), self.ceilings.recv_records_online
+            )));
+        }
+
+        if recv_records_extend > 0 || !recv_extend.is_empty() {
+            debug!(
+                "extending decryption capacity by {} records and {} bytes",
+                recv_records_extend,
+                recv_extend.iter().sum::<usize>()
+            );
+            decrypter
+                .extend(&mut (*vm), recv_records_extend, &recv_extend)
+                .map_err(MpcTlsError::record_layer)?;
+            self.max_recv_records_online += recv_records_extend;
         }
 
         debug!(
@@ -497,11 +695,14 @@ impl RecordLayer {
 
         for (op, pending) in decrypt_ops.into_iter().zip(pending_decrypt) {
             let plaintext = pending.output.try_decrypt()?;
-            self.decrypted_buffer.push_back(PlainRecord {
-                typ: op.typ,
-                version: op.version,
-                plaintext: plaintext.clone(),
-            });
+            self.held_buffer.insert(
+                op.seq,
+                PlainRecord {
+                    typ: op.typ,
+                    version: op.version,
+                    plaintext: plaintext.clone(),
+                },
+            );
 
             recv_records.push(Record {
                 seq: op.seq,
@@ -513,9 +714,26 @@ impl RecordLayer {
             });
         }
 
+        let (sent, recv) = (sent_records.len(), recv_records.len());
+        self.report(Progress::Records { sent, recv });
+        self.release_decrypted();
+
         Ok(())
     }
 
+    /// Releases held decrypted records in order, up to the first record which
+    /// is not decrypted yet.
+    fn release_decrypted(&mut self) {
+        let pending = self.decrypt_buffer.iter().map(|op| op.seq).min();
+        while let Some(entry) = self.held_buffer.first_entry() {
+            if pending.is_some_and(|seq| *entry.key() > seq) {
+                break;
+            }
+
+            self.decrypted_buffer.push_back(entry.remove());
+        }
+    }
+
     /// Commits to the record layer, returning a transcript in which the
     /// received records are unauthenticated from the follower's perspective.
     pub(crate) async fn commit(
@@ -550,17 +768,20 @@ impl RecordLayer {
             .map_err(|_| MpcTlsError::record_layer("decrypt lock is held"))?;
 
         let buffered_ops = take(&mut self.decrypt_buffer);
+        self.report(Progress::DeferredDecryption {
+            records: buffered_ops.len(),
+        });
 
         // Reveal decryption key to the leader.
-        self.aes_gcm.decode_key(&mut (*vm))?;
+        self.local.decode_key(&mut (*vm))?;
         vm.flush(ctx).await.map_err(MpcTlsError::record_layer)?;
-        self.aes_gcm.finish_decode()?;
+        self.local.finish_decode()?;
 
         let pending_decrypts = decrypt::decrypt_local(
             self.role,
             &mut (*vm),
             &mut decrypter,
-            &mut self.aes_gcm,
+            &mut self.local,
             &buffered_ops,
         )?;
 
@@ -570,11 +791,14 @@ impl RecordLayer {
 
         for (op, pending) in buffered_ops.into_iter().zip(pending_decrypts) {
             let plaintext = pending.output.try_decrypt()?;
-            self.decrypted_buffer.push_back(PlainRecord {
-                typ: op.typ,
-                version: op.version,
-                plaintext: plaintext.clone(),
-            });
+            self.held_buffer.insert(
+                op.seq,
+                PlainRecord {
+                    typ: op.typ,
+                    version: op.version,
+                    plaintext: plaintext.clone(),
+                },
+            );
 
             recv_records.push(Record {
                 seq: op.seq,
@@ -586,7 +810,12 @@ impl RecordLayer {
             });
         }
 
+        // Records decrypted online may precede deferred records.
+        recv_records.sort_by_key(|record| record.seq);
+        self.release_decrypted();
+
         self.state = State::Complete {};
+        self.report(Progress::Committed);
 
         Ok((sent_records, recv_records))
     }
@@ -599,8 +828,16 @@ impl RecordLayer {
     ) -> (u64, Vec<u8>, Vec<u8>) {
         let seq = self.write_seq;
         self.write_seq += 1;
-        let explicit_nonce = seq.to_be_bytes().to_vec();
-        let aad = make_tls12_aad(seq, typ, version, len).to_vec();
+        let (explicit_nonce, aad) = match &self.nonce_masks {
+            Some(masks) => (
+                tls13_nonce(masks.client, seq),
+                make_tls13_aad(len + TAG_LEN).to_vec(),
+            ),
+            None => (
+                seq.to_be_bytes().to_vec(),
+                make_tls12_aad(seq, typ, version, len).to_vec(),
+            ),
+        };
 
         (seq, explicit_nonce, aad)
     }
@@ -610,12 +847,24 @@ impl RecordLayer {
         typ: ContentType,
         version: ProtocolVersion,
         len: usize,
-    ) -> (u64, Vec<u8>) {
+    ) -> (u64, Option<Vec<u8>>, Vec<u8>) {
         let seq = self.read_seq;
         self.read_seq += 1;
-        let aad = make_tls12_aad(seq, typ, version, len).to_vec();
+        let (nonce, aad) = match &self.nonce_masks {
+            Some(masks) => (
+                Some(tls13_nonce(masks.server, seq)),
+                make_tls13_aad(len + TAG_LEN).to_vec(),
+            ),
+            // ChaCha20-Poly1305 records do not carry an explicit nonce, it
+            // is the sequence number instead.
+            None if self.aead == AeadAlgorithm::ChaCha20Poly1305 => (
+                Some(seq.to_be_bytes().to_vec()),
+                make_tls12_aad(seq, typ, version, len).to_vec(),
+            ),
+            None => (None, make_tls12_aad(seq, typ, version, len).to_vec()),
+        };
 
-        (seq, aad)
+        (seq, nonce, aad)
     }
 }
 
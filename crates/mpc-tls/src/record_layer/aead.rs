@@ -1,17 +1,301 @@
 mod aes_gcm;
+mod chacha20_poly1305;
 mod ghash;
+mod poly1305;
 
-pub(crate) use aes_gcm::MpcAesGcm;
-use cipher::{aes::AesError, CipherError};
-pub(crate) use ghash::{ComputeTags, VerifyTags};
+use std::future::Future;
 
-use mpz_memory_core::{binary::U8, Array};
-use mpz_vm_core::VmError;
+use aes_gcm::MpcAesGcm;
+use async_trait::async_trait;
+use chacha20_poly1305::MpcChaCha20Poly1305;
+use cipher::{
+    aes::{AesError, AesKey},
+    chacha::ChaChaError,
+    CipherError,
+};
+use mpz_common::{Context, Flush, Task};
+use mpz_fields::gf2_128::Gf2_128;
+use mpz_memory_core::{
+    binary::{Binary, U8},
+    Array, Vector,
+};
+use mpz_share_conversion::ShareConvert;
+use mpz_vm_core::{Vm, VmError};
+use tlsn_core::connection::AeadAlgorithm;
+
+use crate::{record_layer::TagData, Role};
 
 type Nonce = Array<U8, 8>;
 type Ctr = Array<U8, 4>;
 type Block = Array<U8, 16>;
 
+/// MPC AEAD of the negotiated cipher suite.
+///
+/// Resources are allocated for the AEAD algorithm of the configuration, the
+/// leader rejects cipher suites with a different AEAD algorithm.
+pub(crate) enum MpcAead {
+    AesGcm(MpcAesGcm),
+    ChaCha20Poly1305(MpcChaCha20Poly1305),
+}
+
+impl MpcAead {
+    /// Creates a new AEAD instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `aead` - AEAD algorithm.
+    /// * `converter` - GF2_128 share converter, only used for AES-GCM.
+    /// * `role` - Role of the party.
+    pub(crate) fn new<C>(aead: AeadAlgorithm, converter: C, role: Role) -> Self
+    where
+        C: ShareConvert<Gf2_128> + Flush + Send + Sync + 'static,
+    {
+        match aead {
+            AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => {
+                Self::AesGcm(MpcAesGcm::new(converter, role))
+            }
+            AeadAlgorithm::ChaCha20Poly1305 => {
+                Self::ChaCha20Poly1305(MpcChaCha20Poly1305::new(role))
+            }
+        }
+    }
+
+    /// Allocates resources.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine to allocate in.
+    /// * `records` - Number of records to allocate.
+    /// * `len` - Length of the input text in bytes.
+    pub(crate) fn alloc(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        records: usize,
+        len: usize,
+    ) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.alloc(vm, records, len),
+            Self::ChaCha20Poly1305(aead) => aead.alloc(vm, records, len),
+        }
+    }
+
+    /// Allocates additional resources.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine to allocate in.
+    /// * `records` - Number of additional records to allocate.
+    /// * `lens` - Lengths of the records to allocate input text for.
+    pub(crate) fn extend(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        records: usize,
+        lens: &[usize],
+    ) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.extend(vm, records, lens),
+            Self::ChaCha20Poly1305(aead) => aead.extend(vm, records, lens),
+        }
+    }
+
+    pub(crate) async fn preprocess(&mut self, ctx: &mut Context) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.preprocess(ctx).await,
+            // The Poly1305 keys are computed per record.
+            Self::ChaCha20Poly1305(_) => Ok(()),
+        }
+    }
+
+    /// Sets the write key.
+    pub(crate) fn set_key(&mut self, key: Vector<U8>) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.set_key(AesKey::try_from(key)?),
+            Self::ChaCha20Poly1305(aead) => aead.set_key(to_array(key)?),
+        }
+
+        Ok(())
+    }
+
+    /// Sets the implicit write IV.
+    pub(crate) fn set_iv(&mut self, iv: Vector<U8>) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.set_iv(to_array(iv)?),
+            Self::ChaCha20Poly1305(aead) => aead.set_iv(to_array(iv)?),
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn setup(&mut self, ctx: &mut Context) -> Result<(), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.setup(ctx).await,
+            Self::ChaCha20Poly1305(_) => Ok(()),
+        }
+    }
+
+    /// Returns `len` bytes of input and output text.
+    ///
+    /// The outer context is responsible for assigning to the input text.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `explicit_nonce` - Explicit nonce.
+    /// * `len` - Number of bytes to take.
+    pub(crate) fn apply_keystream(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        explicit_nonce: Vec<u8>,
+        len: usize,
+    ) -> Result<(Vector<U8>, Vector<U8>), AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.apply_keystream(vm, explicit_nonce, len),
+            Self::ChaCha20Poly1305(aead) => aead.apply_keystream(vm, explicit_nonce, len),
+        }
+    }
+
+    /// Returns `len` bytes of keystream.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `explicit_nonce` - Explicit nonce.
+    /// * `len` - Number of bytes to take.
+    pub(crate) fn take_keystream(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        explicit_nonce: Vec<u8>,
+        len: usize,
+    ) -> Result<Vector<U8>, AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.take_keystream(vm, explicit_nonce, len),
+            Self::ChaCha20Poly1305(aead) => aead.take_keystream(vm, explicit_nonce, len),
+        }
+    }
+
+    /// Returns the VM reference to the GHASH key.
+    ///
+    /// Returns `None` for ChaCha20-Poly1305, which uses a one-time key per
+    /// record.
+    pub(crate) fn mac_key(&mut self) -> Result<Option<Array<U8, 16>>, AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead.ghash_key().map(Some),
+            Self::ChaCha20Poly1305(_) => Ok(None),
+        }
+    }
+
+    /// Computes tags for the provided ciphertexts.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `ciphertexts` - Ciphertexts to compute the tags for.
+    /// * `data` - Tag data associated with `ciphertexts`.
+    pub(crate) fn compute_tags<C>(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        ciphertexts: Vec<C>,
+        data: Vec<TagData>,
+    ) -> Result<ComputeTags, AeadError>
+    where
+        C: Future<Output = Result<Vec<u8>, AeadError>> + Send + Sync + 'static,
+    {
+        match self {
+            Self::AesGcm(aead) => aead
+                .compute_tags(vm, ciphertexts, data)
+                .map(ComputeTags::Ghash),
+            Self::ChaCha20Poly1305(aead) => aead
+                .compute_tags(vm, ciphertexts, data)
+                .map(ComputeTags::Poly1305),
+        }
+    }
+
+    /// Verifies the tags for the provided ciphertexts.
+    ///
+    /// Ciphertexts are only authenticated from the leader's perspective.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `data` - Tag data associated with `tags`.
+    /// * `ciphertexts` - Ciphertexts to verify the tags for.
+    /// * `tags` - Tags to verify.
+    pub(crate) fn verify_tags(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        data: Vec<TagData>,
+        ciphertexts: Vec<Vec<u8>>,
+        tags: Vec<Vec<u8>>,
+    ) -> Result<VerifyTags, AeadError> {
+        match self {
+            Self::AesGcm(aead) => aead
+                .verify_tags(vm, data, ciphertexts, tags)
+                .map(VerifyTags::Ghash),
+            Self::ChaCha20Poly1305(aead) => aead
+                .verify_tags(vm, data, ciphertexts, tags)
+                .map(VerifyTags::Poly1305),
+        }
+    }
+}
+
+fn to_array<const N: usize>(value: Vector<U8>) -> Result<Array<U8, N>, AeadError> {
+    if value.len() != N {
+        return Err(AeadError::cipher(format!(
+            "invalid length: expected {}, got {}",
+            N,
+            value.len()
+        )));
+    }
+
+    Ok(Array::try_from(value).expect("length was checked"))
+}
+
+/// Computes the tags of sent records.
+#[must_use = "compute tags operation must be awaited"]
+pub(crate) enum ComputeTags {
+    Ghash(ghash::ComputeTags),
+    Poly1305(poly1305::ComputeTags),
+}
+
+#[async_trait]
+impl Task for ComputeTags {
+    type Output = Result<Option<Vec<Vec<u8>>>, AeadError>;
+
+    async fn run(self, ctx: &mut Context) -> Self::Output {
+        match self {
+            Self::Ghash(tags) => tags.run(ctx).await,
+            Self::Poly1305(tags) => tags.run(ctx).await,
+        }
+    }
+
+    async fn run_boxed(self: Box<Self>, ctx: &mut Context) -> Self::Output {
+        self.run(ctx).await
+    }
+}
+
+/// Verifies the tags of received records.
+#[must_use = "verify tags operation must be awaited"]
+pub(crate) enum VerifyTags {
+    Ghash(ghash::VerifyTags),
+    Poly1305(poly1305::VerifyTags),
+}
+
+#[async_trait]
+impl Task for VerifyTags {
+    type Output = Result<(), AeadError>;
+
+    async fn run(self, ctx: &mut Context) -> Self::Output {
+        match self {
+            Self::Ghash(tags) => tags.run(ctx).await,
+            Self::Poly1305(tags) => tags.run(ctx).await,
+        }
+    }
+
+    async fn run_boxed(self: Box<Self>, ctx: &mut Context) -> Self::Output {
+        self.run(ctx).await
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub(crate) struct AeadError(ErrorRepr);
@@ -67,3 +351,9 @@ impl From<AesError> for AeadError {
         Self(ErrorRepr::Cipher(Box::new(err)))
     }
 }
+
+impl From<ChaChaError> for AeadError {
+    fn from(err: ChaChaError) -> Self {
+        Self(ErrorRepr::Cipher(Box::new(err)))
+    }
+}
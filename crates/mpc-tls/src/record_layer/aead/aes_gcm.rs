@@ -1,6 +1,9 @@
 use std::{future::Future, sync::Arc};
 
-use cipher::{aes::Aes128, Cipher, CtrBlock, Keystream};
+use cipher::{
+    aes::{Aes, AesKey},
+    Cipher, CtrBlock, Keystream,
+};
 use mpz_common::{Context, Flush};
 use mpz_fields::gf2_128::Gf2_128;
 use mpz_memory_core::{
@@ -56,7 +59,7 @@ impl State {
 
 pub(crate) struct MpcAesGcm {
     role: Role,
-    aes: Aes128,
+    aes: Aes,
     state: State,
 }
 
@@ -68,7 +71,7 @@ impl MpcAesGcm {
     {
         Self {
             role,
-            aes: Aes128::default(),
+            aes: Aes::default(),
             state: State::Init {
                 ghash: Box::new(MpcGhash::new(converter)),
             },
@@ -149,7 +152,7 @@ impl MpcAesGcm {
         Ok(())
     }
 
-    pub(crate) fn set_key(&mut self, key: Array<U8, 16>) {
+    pub(crate) fn set_key(&mut self, key: AesKey) {
         self.aes.set_key(key);
     }
 
@@ -185,6 +188,62 @@ impl MpcAesGcm {
         Ok(())
     }
 
+    /// Allocates additional resources.
+    ///
+    /// If `lens` is not empty, the remaining input text is replaced with
+    /// enough input text and keystream for records of the provided lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine to allocate in.
+    /// * `records` - Number of additional records to allocate.
+    /// * `lens` - Lengths of the records to allocate input text for.
+    pub(crate) fn extend(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        records: usize,
+        lens: &[usize],
+    ) -> Result<(), AeadError> {
+        let State::Ready {
+            input,
+            keystream,
+            j0s,
+            ..
+        } = &mut self.state
+        else {
+            return Err(AeadError::state("must be in ready state to extend"));
+        };
+
+        for _ in 0..records {
+            let j0 = self.aes.alloc_ctr_block(vm)?;
+            let j0_shared = OneTimePadShared::<[u8; 16]>::new(self.role, j0.output, vm)?;
+
+            j0s.insert(0, (j0, j0_shared));
+        }
+
+        if lens.is_empty() {
+            return Ok(());
+        }
+
+        // Each record is padded to the block size separately.
+        let len: usize = lens.iter().map(|len| 16 * len.div_ceil(16)).sum();
+
+        let extra = vm.alloc_vec::<U8>(len)?;
+        match self.role {
+            Role::Leader => {
+                vm.mark_private(extra)?;
+            }
+            Role::Follower => {
+                vm.mark_blind(extra)?;
+            }
+        }
+
+        *input = extra;
+        keystream.extend(self.aes.alloc_keystream(vm, len)?);
+
+        Ok(())
+    }
+
     /// Returns `len` bytes of input and output text.
     ///
     /// The outer context is responsible for assigning to the input text.
@@ -452,7 +511,7 @@ mod tests {
     use super::*;
     use aes_gcm::{
         aead::{AeadInPlace, NewAead},
-        Aes128Gcm,
+        Aes128Gcm, Aes256Gcm,
     };
     use mpz_common::context::test_st_context;
     use mpz_core::Block;
@@ -468,7 +527,7 @@ mod tests {
 
     #[derive(Clone, Copy)]
     struct Vars {
-        key: Array<U8, 16>,
+        key: AesKey,
         iv: Array<U8, 4>,
     }
 
@@ -478,13 +537,17 @@ mod tests {
     #[case::short_multiple(SHORT_MSG, 3)]
     #[case::long_multiple(LONG_MSG, 3)]
     #[tokio::test]
-    async fn test_aes_gcm_encrypt(#[case] msg: &[u8], #[case] count: usize) {
+    async fn test_aes_gcm_encrypt(
+        #[case] msg: &[u8],
+        #[case] count: usize,
+        #[values(16, 32)] key_len: usize,
+    ) {
         let (mut ctx_0, mut ctx_1) = test_st_context(8);
 
-        let key = [42u8; 16];
+        let key = vec![42u8; key_len];
         let iv = [0u8; 4];
 
-        let ((mut vm_0, vars_0), (mut vm_1, vars_1)) = create_vm(key, iv);
+        let ((mut vm_0, vars_0), (mut vm_1, vars_1)) = create_vm(&key, iv);
         let (mut leader, mut follower) = create_pair(vars_0, vars_1);
 
         leader.alloc(&mut vm_0, count, 256).unwrap();
@@ -528,13 +591,17 @@ mod tests {
     #[case::short_multiple(SHORT_MSG, 3)]
     #[case::long_multiple(LONG_MSG, 3)]
     #[tokio::test]
-    async fn test_aes_gcm_decrypt(#[case] msg: &[u8], #[case] count: usize) {
+    async fn test_aes_gcm_decrypt(
+        #[case] msg: &[u8],
+        #[case] count: usize,
+        #[values(16, 32)] key_len: usize,
+    ) {
         let (mut ctx_0, mut ctx_1) = test_st_context(8);
 
-        let key = [42u8; 16];
+        let key = vec![42u8; key_len];
         let iv = [0u8; 4];
 
-        let ((mut vm_0, vars_0), (mut vm_1, vars_1)) = create_vm(key, iv);
+        let ((mut vm_0, vars_0), (mut vm_1, vars_1)) = create_vm(&key, iv);
         let (mut leader, mut follower) = create_pair(vars_0, vars_1);
 
         leader.alloc(&mut vm_0, count, 256).unwrap();
@@ -573,7 +640,7 @@ mod tests {
         }
     }
 
-    fn create_vm(key: [u8; 16], iv: [u8; 4]) -> ((impl Vm<Binary>, Vars), (impl Vm<Binary>, Vars)) {
+    fn create_vm(key: &[u8], iv: [u8; 4]) -> ((impl Vm<Binary>, Vars), (impl Vm<Binary>, Vars)) {
         let mut rng = StdRng::seed_from_u64(0);
         let block = Block::random(&mut rng);
         let (sender, receiver) = ideal_cot(block);
@@ -582,14 +649,14 @@ mod tests {
         let mut vm_0 = Garbler::new(sender, [0u8; 16], delta);
         let mut vm_1 = Evaluator::new(receiver);
 
-        let key_ref_0 = vm_0.alloc::<Array<U8, 16>>().unwrap();
+        let key_ref_0 = vm_0.alloc_vec::<U8>(key.len()).unwrap();
         vm_0.mark_public(key_ref_0).unwrap();
-        vm_0.assign(key_ref_0, key).unwrap();
+        vm_0.assign(key_ref_0, key.to_vec()).unwrap();
         vm_0.commit(key_ref_0).unwrap();
 
-        let key_ref_1 = vm_1.alloc::<Array<U8, 16>>().unwrap();
+        let key_ref_1 = vm_1.alloc_vec::<U8>(key.len()).unwrap();
         vm_1.mark_public(key_ref_1).unwrap();
-        vm_1.assign(key_ref_1, key).unwrap();
+        vm_1.assign(key_ref_1, key.to_vec()).unwrap();
         vm_1.commit(key_ref_1).unwrap();
 
         let iv_ref_0 = vm_0.alloc::<Array<U8, 4>>().unwrap();
@@ -606,14 +673,14 @@ mod tests {
             (
                 vm_0,
                 Vars {
-                    key: key_ref_0,
+                    key: AesKey::try_from(key_ref_0).unwrap(),
                     iv: iv_ref_0,
                 },
             ),
             (
                 vm_1,
                 Vars {
-                    key: key_ref_1,
+                    key: AesKey::try_from(key_ref_1).unwrap(),
                     iv: iv_ref_1,
                 },
             ),
@@ -658,17 +725,20 @@ mod tests {
         msg: &[u8],
         aad: &[u8],
     ) -> (Vec<u8>, Vec<u8>) {
-        let key: [u8; 16] = key.try_into().unwrap();
-        let aes = Aes128Gcm::new(&key.into());
-
         let mut nonce = [0u8; 12];
         nonce[..4].copy_from_slice(iv);
         nonce[4..].copy_from_slice(explicit_nonce);
 
         let mut payload = msg.to_vec();
-        let tag = aes
-            .encrypt_in_place_detached(&nonce.into(), aad, &mut payload)
-            .unwrap();
+        let tag = match key.len() {
+            16 => Aes128Gcm::new(key.into())
+                .encrypt_in_place_detached(&nonce.into(), aad, &mut payload)
+                .unwrap(),
+            32 => Aes256Gcm::new(key.into())
+                .encrypt_in_place_detached(&nonce.into(), aad, &mut payload)
+                .unwrap(),
+            _ => unreachable!(),
+        };
 
         (payload, tag.to_vec())
     }
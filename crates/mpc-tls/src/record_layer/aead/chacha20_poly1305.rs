@@ -0,0 +1,633 @@
+use std::future::Future;
+
+use cipher::{chacha::ChaCha20, Cipher, CtrBlock, Keystream};
+use mpz_memory_core::{
+    binary::{Binary, U8},
+    Vector,
+};
+use mpz_vm_core::{prelude::*, Vm};
+use tracing::instrument;
+
+use crate::{
+    decode::FollowerOnly,
+    record_layer::{
+        aead::{
+            poly1305::{ComputeTagData, ComputeTags, VerifyTagData, VerifyTags},
+            AeadError, Ctr, Nonce,
+        },
+        TagData,
+    },
+    Role,
+};
+
+/// The first block is used for the Poly1305 key.
+const START_CTR: u32 = 1;
+const BLOCK_LEN: usize = 64;
+
+type Block = Array<U8, 64>;
+
+#[allow(clippy::type_complexity)]
+enum State {
+    Init,
+    Ready {
+        input: Vector<U8>,
+        keystream: Keystream<Nonce, Ctr, Block>,
+        poly_keys: Vec<(CtrBlock<Nonce, Ctr, Block>, FollowerOnly<[u8; 32]>)>,
+    },
+    Error,
+}
+
+impl State {
+    fn take(&mut self) -> Self {
+        std::mem::replace(self, State::Error)
+    }
+}
+
+pub(crate) struct MpcChaCha20Poly1305 {
+    role: Role,
+    chacha: ChaCha20,
+    state: State,
+}
+
+impl MpcChaCha20Poly1305 {
+    /// Creates a new ChaCha20-Poly1305 instance.
+    pub(crate) fn new(role: Role) -> Self {
+        Self {
+            role,
+            chacha: ChaCha20::default(),
+            state: State::Init,
+        }
+    }
+
+    /// Allocates resources.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine to allocate in.
+    /// * `records` - Number of records to allocate.
+    /// * `len` - Length of the input text in bytes.
+    pub(crate) fn alloc(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        records: usize,
+        len: usize,
+    ) -> Result<(), AeadError> {
+        let State::Init = self.state.take() else {
+            return Err(AeadError::state("must be in init state to allocate"));
+        };
+
+        // Allocate the one-time Poly1305 keys, which are only revealed to the
+        // follower.
+        let mut poly_keys = Vec::with_capacity(records);
+        for _ in 0..records {
+            let block = self.chacha.alloc_ctr_block(vm)?;
+
+            let mut key: Vector<U8> = block.output.into();
+            key.truncate(32);
+            let key = Array::<U8, 32>::try_from(key).expect("key is 32 bytes");
+            let key = FollowerOnly::<[u8; 32]>::new(self.role, key, vm)?;
+
+            poly_keys.push((block, key));
+        }
+
+        // Allocate encryption/decryption.
+
+        // Round up the length to the nearest multiple of the block size. Each
+        // record starts a new block, so we also allocate padding for every
+        // record.
+        let len = BLOCK_LEN * (len.div_ceil(BLOCK_LEN) + records);
+
+        let input = vm.alloc_vec::<U8>(len)?;
+        match self.role {
+            Role::Leader => {
+                vm.mark_private(input)?;
+            }
+            Role::Follower => {
+                vm.mark_blind(input)?;
+            }
+        }
+
+        let keystream = self.chacha.alloc_keystream(vm, len)?;
+
+        self.state = State::Ready {
+            input,
+            keystream,
+            poly_keys,
+        };
+
+        Ok(())
+    }
+
+    /// Allocates additional resources.
+    ///
+    /// If `lens` is not empty, the remaining input text is replaced with
+    /// enough input text and keystream for records of the provided lengths.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine to allocate in.
+    /// * `records` - Number of additional records to allocate.
+    /// * `lens` - Lengths of the records to allocate input text for.
+    pub(crate) fn extend(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        records: usize,
+        lens: &[usize],
+    ) -> Result<(), AeadError> {
+        let State::Ready {
+            input,
+            keystream,
+            poly_keys,
+        } = &mut self.state
+        else {
+            return Err(AeadError::state("must be in ready state to extend"));
+        };
+
+        for _ in 0..records {
+            let block = self.chacha.alloc_ctr_block(vm)?;
+
+            let mut key: Vector<U8> = block.output.into();
+            key.truncate(32);
+            let key = Array::<U8, 32>::try_from(key).expect("key is 32 bytes");
+            let key = FollowerOnly::<[u8; 32]>::new(self.role, key, vm)?;
+
+            poly_keys.insert(0, (block, key));
+        }
+
+        if lens.is_empty() {
+            return Ok(());
+        }
+
+        // Each record is padded to the block size separately.
+        let len: usize = lens
+            .iter()
+            .map(|len| BLOCK_LEN * len.div_ceil(BLOCK_LEN))
+            .sum();
+
+        let extra = vm.alloc_vec::<U8>(len)?;
+        match self.role {
+            Role::Leader => {
+                vm.mark_private(extra)?;
+            }
+            Role::Follower => {
+                vm.mark_blind(extra)?;
+            }
+        }
+
+        *input = extra;
+        keystream.extend(self.chacha.alloc_keystream(vm, len)?);
+
+        Ok(())
+    }
+
+    pub(crate) fn set_key(&mut self, key: Array<U8, 32>) {
+        self.chacha.set_key(key);
+    }
+
+    pub(crate) fn set_iv(&mut self, iv: Array<U8, 12>) {
+        self.chacha.set_iv(iv);
+    }
+
+    /// Returns `len` bytes of input and output text.
+    ///
+    /// The outer context is responsible for assigning to the input text.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `explicit_nonce` - Explicit nonce.
+    /// * `len` - Number of bytes to take.
+    #[instrument(level = "debug", skip_all, err)]
+    pub(crate) fn apply_keystream(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        explicit_nonce: Vec<u8>,
+        len: usize,
+    ) -> Result<(Vector<U8>, Vector<U8>), AeadError> {
+        let State::Ready {
+            input, keystream, ..
+        } = &mut self.state
+        else {
+            return Err(AeadError::state(
+                "must be in ready state to apply keystream",
+            ));
+        };
+
+        let explicit_nonce = explicit_nonce_array(explicit_nonce)?;
+
+        let padded_len = BLOCK_LEN * len.div_ceil(BLOCK_LEN);
+        let padding_len = padded_len - len;
+
+        if padded_len > input.len() {
+            return Err(AeadError::cipher(format!(
+                "input length exceeds allocated: {} > {}",
+                padded_len,
+                input.len()
+            )));
+        }
+
+        let mut input = input.split_off(input.len() - padded_len);
+        let keystream = keystream.consume(padded_len)?;
+        let mut output = keystream.apply(vm, input)?;
+
+        // Assign counter block inputs.
+        let mut ctr = START_CTR..;
+        keystream.assign(vm, explicit_nonce, move || {
+            ctr.next().expect("range is unbounded").to_le_bytes()
+        })?;
+
+        // Assign zeroes to the padding.
+        if padding_len > 0 {
+            let padding = input.split_off(input.len() - padding_len);
+            // To simplify the impl, we don't mark the padding as public, that's why only
+            // the prover assigns it.
+            if let Role::Leader = self.role {
+                vm.assign(padding, vec![0; padding_len])?;
+            }
+            vm.commit(padding)?;
+            output.truncate(len);
+        }
+
+        Ok((input, output))
+    }
+
+    /// Returns `len` bytes of keystream.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `explicit_nonce` - Explicit nonce.
+    /// * `len` - Number of bytes to take.
+    #[instrument(level = "debug", skip_all, err)]
+    pub(crate) fn take_keystream(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        explicit_nonce: Vec<u8>,
+        len: usize,
+    ) -> Result<Vector<U8>, AeadError> {
+        let State::Ready {
+            input, keystream, ..
+        } = &mut self.state
+        else {
+            return Err(AeadError::state("must be in ready state to take keystream"));
+        };
+
+        let explicit_nonce = explicit_nonce_array(explicit_nonce)?;
+
+        let padded_len = BLOCK_LEN * len.div_ceil(BLOCK_LEN);
+
+        if padded_len > input.len() {
+            return Err(AeadError::cipher(format!(
+                "input length exceeds allocated: {} > {}",
+                padded_len,
+                input.len()
+            )));
+        }
+
+        let keystream = keystream.consume(len)?;
+
+        // Assign counter block inputs.
+        let mut ctr = START_CTR..;
+        keystream.assign(vm, explicit_nonce, move || {
+            ctr.next().expect("range is unbounded").to_le_bytes()
+        })?;
+
+        Ok(keystream.to_vector(vm, len)?)
+    }
+
+    /// Computes tags for the provided ciphertext. See
+    /// [`verify_tags`](MpcChaCha20Poly1305::verify_tags) for a method that
+    /// verifies tags instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `ciphertexts` - Ciphertexts to compute the tags for.
+    /// * `data` - Tag data associated with `ciphertexts`.
+    #[instrument(level = "debug", skip_all, err)]
+    pub(crate) fn compute_tags<C>(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        ciphertexts: Vec<C>,
+        data: Vec<TagData>,
+    ) -> Result<ComputeTags, AeadError>
+    where
+        C: Future<Output = Result<Vec<u8>, AeadError>> + Send + Sync + 'static,
+    {
+        let State::Ready { poly_keys, .. } = &mut self.state else {
+            return Err(AeadError::state("must be in ready state to compute tags"));
+        };
+
+        if ciphertexts.len() != data.len() {
+            return Err(AeadError::tag("ciphertext and data length mismatch"));
+        } else if ciphertexts.len() > poly_keys.len() {
+            return Err(AeadError::tag("ciphertext length exceeds allocated"));
+        }
+
+        let mut tag_data = Vec::with_capacity(ciphertexts.len());
+        for (ciphertext, data) in ciphertexts.into_iter().zip(data) {
+            let explicit_nonce = explicit_nonce_array(data.explicit_nonce)?;
+            let (block, key) = poly_keys.pop().expect("poly key length was checked");
+
+            assign_poly_key_block(vm, block, explicit_nonce)?;
+
+            tag_data.push(ComputeTagData {
+                key,
+                ciphertext: Box::pin(ciphertext),
+                aad: data.aad,
+            });
+        }
+
+        Ok(ComputeTags::new(self.role, tag_data))
+    }
+
+    /// Verifies the tags for the provided ciphertexts.
+    ///
+    /// Ciphertexts are only authenticated from the leader's perspective.
+    ///
+    /// # Arguments
+    ///
+    /// * `vm` - Virtual machine.
+    /// * `data` - Tag data associated with `tags`.
+    /// * `ciphertexts` - Ciphertexts to verify the tags for.
+    /// * `tags` - Tags to verify.
+    pub(crate) fn verify_tags(
+        &mut self,
+        vm: &mut dyn Vm<Binary>,
+        data: Vec<TagData>,
+        ciphertexts: Vec<Vec<u8>>,
+        tags: Vec<Vec<u8>>,
+    ) -> Result<VerifyTags, AeadError> {
+        let State::Ready { poly_keys, .. } = &mut self.state else {
+            return Err(AeadError::state("must be in ready state to verify tags"));
+        };
+
+        if ciphertexts.len() != data.len() {
+            return Err(AeadError::tag("ciphertext and data length mismatch"));
+        } else if ciphertexts.len() != tags.len() {
+            return Err(AeadError::tag("ciphertext and tag length mismatch"));
+        } else if ciphertexts.len() > poly_keys.len() {
+            return Err(AeadError::tag("ciphertext length exceeds allocated"));
+        }
+
+        let mut tag_data = Vec::with_capacity(ciphertexts.len());
+        for ((ciphertext, data), tag) in ciphertexts.into_iter().zip(data).zip(tags) {
+            let explicit_nonce = explicit_nonce_array(data.explicit_nonce)?;
+            let (block, key) = poly_keys.pop().expect("poly key length was checked");
+
+            assign_poly_key_block(vm, block, explicit_nonce)?;
+
+            tag_data.push(VerifyTagData {
+                key,
+                ciphertext,
+                aad: data.aad,
+                tag,
+            });
+        }
+
+        Ok(VerifyTags::new(self.role, tag_data))
+    }
+}
+
+fn explicit_nonce_array(explicit_nonce: Vec<u8>) -> Result<[u8; 8], AeadError> {
+    explicit_nonce.try_into().map_err(|nonce: Vec<_>| {
+        AeadError::cipher(format!(
+            "explicit nonce length: expected {}, got {}",
+            8,
+            nonce.len()
+        ))
+    })
+}
+
+fn assign_poly_key_block(
+    vm: &mut dyn Vm<Binary>,
+    block: CtrBlock<Nonce, Ctr, Block>,
+    explicit_nonce: [u8; 8],
+) -> Result<(), AeadError> {
+    vm.assign(block.explicit_nonce, explicit_nonce)?;
+    vm.commit(block.explicit_nonce)?;
+    vm.assign(block.counter, 0u32.to_le_bytes())?;
+    vm.commit(block.counter)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chacha20poly1305::{aead::AeadInPlace, ChaCha20Poly1305, NewAead};
+    use mpz_common::{context::test_st_context, Context, Task};
+    use mpz_core::Block;
+    use mpz_garble::protocol::semihonest::{Evaluator, Garbler};
+    use mpz_memory_core::correlated::Delta;
+    use mpz_ot::ideal::cot::ideal_cot;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rstest::*;
+
+    static SHORT_MSG: &[u8] = b"hello world";
+    static LONG_MSG: &[u8] =
+        b"this message exceeds one chacha20 block of sixty-four bytes in length";
+
+    #[rstest]
+    #[case::short(SHORT_MSG, 1)]
+    #[case::long(LONG_MSG, 1)]
+    #[case::short_multiple(SHORT_MSG, 3)]
+    #[case::long_multiple(LONG_MSG, 3)]
+    #[tokio::test]
+    async fn test_chacha20_poly1305_encrypt(#[case] msg: &[u8], #[case] count: usize) {
+        let (mut ctx_0, mut ctx_1) = test_st_context(8);
+
+        let key = [42u8; 32];
+        let iv = [3u8; 12];
+        let aad = [33u8; 13];
+
+        let (mut vm_0, mut vm_1) = create_vms();
+        let mut leader = create(&mut vm_0, Role::Leader, key, iv);
+        let mut follower = create(&mut vm_1, Role::Follower, key, iv);
+
+        leader.alloc(&mut vm_0, count, 256).unwrap();
+        follower.alloc(&mut vm_1, count, 256).unwrap();
+
+        for i in 0u64..count as u64 {
+            let explicit_nonce = i.to_be_bytes().to_vec();
+            let (msg_0, ct_0) = leader
+                .apply_keystream(&mut vm_0, explicit_nonce.clone(), msg.len())
+                .unwrap();
+            let (msg_1, ct_1) = follower
+                .apply_keystream(&mut vm_1, explicit_nonce.clone(), msg.len())
+                .unwrap();
+
+            vm_0.assign(msg_0, msg.to_vec()).unwrap();
+            vm_0.commit(msg_0).unwrap();
+
+            vm_1.commit(msg_1).unwrap();
+
+            let ct_0 = vm_0.decode(ct_0).unwrap();
+            let ct_1 = vm_1.decode(ct_1).unwrap();
+
+            let (expected_ct, expected_tag) = expected(&key, &iv, &explicit_nonce, msg, &aad);
+            let data = vec![TagData {
+                explicit_nonce: explicit_nonce.clone(),
+                aad: aad.to_vec(),
+            }];
+            let tags_0 = leader
+                .compute_tags(
+                    &mut vm_0,
+                    vec![std::future::ready(Ok(expected_ct.clone()))],
+                    data.clone(),
+                )
+                .unwrap();
+            let tags_1 = follower
+                .compute_tags(
+                    &mut vm_1,
+                    vec![std::future::ready(Ok(expected_ct.clone()))],
+                    data,
+                )
+                .unwrap();
+
+            run_vms(&mut vm_0, &mut ctx_0, &mut vm_1, &mut ctx_1).await;
+
+            let (tags_0, tags_1) = tokio::join!(tags_0.run(&mut ctx_0), tags_1.run(&mut ctx_1));
+
+            assert_eq!(ct_0.await.unwrap(), expected_ct);
+            assert_eq!(ct_1.await.unwrap(), expected_ct);
+            assert_eq!(tags_0.unwrap().unwrap(), vec![expected_tag]);
+            assert!(tags_1.unwrap().is_none());
+        }
+    }
+
+    #[rstest]
+    #[case::short(SHORT_MSG, 1)]
+    #[case::long(LONG_MSG, 1)]
+    #[case::short_multiple(SHORT_MSG, 3)]
+    #[case::long_multiple(LONG_MSG, 3)]
+    #[tokio::test]
+    async fn test_chacha20_poly1305_decrypt(#[case] msg: &[u8], #[case] count: usize) {
+        let (mut ctx_0, mut ctx_1) = test_st_context(8);
+
+        let key = [42u8; 32];
+        let iv = [3u8; 12];
+        let aad = [33u8; 13];
+
+        let (mut vm_0, mut vm_1) = create_vms();
+        let mut leader = create(&mut vm_0, Role::Leader, key, iv);
+        let mut follower = create(&mut vm_1, Role::Follower, key, iv);
+
+        leader.alloc(&mut vm_0, count, 256).unwrap();
+        follower.alloc(&mut vm_1, count, 256).unwrap();
+
+        for i in 0u64..count as u64 {
+            let explicit_nonce = i.to_be_bytes().to_vec();
+            let (ct, tag) = expected(&key, &iv, &explicit_nonce, msg, &aad);
+
+            let (ct_0, msg_0) = leader
+                .apply_keystream(&mut vm_0, explicit_nonce.clone(), ct.len())
+                .unwrap();
+            let (ct_1, msg_1) = follower
+                .apply_keystream(&mut vm_1, explicit_nonce.clone(), ct.len())
+                .unwrap();
+
+            vm_0.assign(ct_0, ct.clone()).unwrap();
+            vm_0.commit(ct_0).unwrap();
+
+            vm_1.commit(ct_1).unwrap();
+
+            let msg_0 = vm_0.decode(msg_0).unwrap();
+            let msg_1 = vm_1.decode(msg_1).unwrap();
+
+            let data = vec![TagData {
+                explicit_nonce: explicit_nonce.clone(),
+                aad: aad.to_vec(),
+            }];
+            let verify_0 = leader
+                .verify_tags(&mut vm_0, data.clone(), vec![ct.clone()], vec![tag.clone()])
+                .unwrap();
+            let verify_1 = follower
+                .verify_tags(&mut vm_1, data, vec![ct.clone()], vec![tag.clone()])
+                .unwrap();
+
+            run_vms(&mut vm_0, &mut ctx_0, &mut vm_1, &mut ctx_1).await;
+
+            let (verify_0, verify_1) =
+                tokio::join!(verify_0.run(&mut ctx_0), verify_1.run(&mut ctx_1));
+
+            verify_0.unwrap();
+            verify_1.unwrap();
+            assert_eq!(msg_0.await.unwrap(), msg);
+            assert_eq!(msg_1.await.unwrap(), msg);
+        }
+    }
+
+    fn create_vms() -> (impl Vm<Binary> + Send, impl Vm<Binary> + Send) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let block = Block::random(&mut rng);
+        let (sender, receiver) = ideal_cot(block);
+
+        let delta = Delta::new(block);
+        let vm_0 = Garbler::new(sender, [0u8; 16], delta);
+        let vm_1 = Evaluator::new(receiver);
+
+        (vm_0, vm_1)
+    }
+
+    fn create(
+        vm: &mut dyn Vm<Binary>,
+        role: Role,
+        key: [u8; 32],
+        iv: [u8; 12],
+    ) -> MpcChaCha20Poly1305 {
+        let key_ref: Array<U8, 32> = vm.alloc().unwrap();
+        vm.mark_public(key_ref).unwrap();
+        vm.assign(key_ref, key).unwrap();
+        vm.commit(key_ref).unwrap();
+
+        let iv_ref: Array<U8, 12> = vm.alloc().unwrap();
+        vm.mark_public(iv_ref).unwrap();
+        vm.assign(iv_ref, iv).unwrap();
+        vm.commit(iv_ref).unwrap();
+
+        let mut aead = MpcChaCha20Poly1305::new(role);
+        aead.set_key(key_ref);
+        aead.set_iv(iv_ref);
+
+        aead
+    }
+
+    async fn run_vms(
+        vm_0: &mut (dyn Vm<Binary> + Send),
+        ctx_0: &mut Context,
+        vm_1: &mut (dyn Vm<Binary> + Send),
+        ctx_1: &mut Context,
+    ) {
+        tokio::join!(
+            async {
+                vm_0.execute_all(ctx_0).await.unwrap();
+            },
+            async {
+                vm_1.execute_all(ctx_1).await.unwrap();
+            }
+        );
+    }
+
+    fn expected(
+        key: &[u8],
+        iv: &[u8],
+        explicit_nonce: &[u8],
+        msg: &[u8],
+        aad: &[u8],
+    ) -> (Vec<u8>, Vec<u8>) {
+        let mut nonce = [0u8; 12];
+        nonce.copy_from_slice(iv);
+        nonce[4..]
+            .iter_mut()
+            .zip(explicit_nonce)
+            .for_each(|(a, b)| *a ^= b);
+
+        let mut payload = msg.to_vec();
+        let tag = ChaCha20Poly1305::new(key.into())
+            .encrypt_in_place_detached(&nonce.into(), aad, &mut payload)
+            .unwrap();
+
+        (payload, tag.to_vec())
+    }
+}
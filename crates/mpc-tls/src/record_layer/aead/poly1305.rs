@@ -0,0 +1,79 @@
+//! Poly1305 tags of ChaCha20-Poly1305, see RFC 8439.
+//!
+//! The one-time Poly1305 key of each record is only revealed to the
+//! follower. The leader must not learn the key of a record before the
+//! ciphertext is committed, as it could otherwise forge tags.
+
+mod compute;
+mod verify;
+
+pub(crate) use compute::{ComputeTagData, ComputeTags};
+pub(crate) use verify::{VerifyTagData, VerifyTags};
+
+use poly1305_rc::{universal_hash::KeyInit, Key, Poly1305};
+
+/// Computes the Poly1305 tag of a record.
+///
+/// # Arguments
+///
+/// * `key` - One-time Poly1305 key.
+/// * `aad` - Additional data.
+/// * `ciphertext` - Ciphertext.
+pub(crate) fn compute_tag(key: &[u8; 32], aad: &[u8], ciphertext: &[u8]) -> [u8; 16] {
+    let tag =
+        Poly1305::new(Key::from_slice(key)).compute_unpadded(&build_poly1305_data(aad, ciphertext));
+
+    let mut output = [0u8; 16];
+    output.copy_from_slice(&tag);
+    output
+}
+
+/// Builds padded data for Poly1305.
+fn build_poly1305_data(aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let aad_padded_len = 16 * aad.len().div_ceil(16);
+    let ciphertext_padded_len = 16 * ciphertext.len().div_ceil(16);
+
+    let mut data = Vec::with_capacity(aad_padded_len + ciphertext_padded_len + 16);
+    data.extend_from_slice(aad);
+    data.resize(aad_padded_len, 0);
+    data.extend_from_slice(ciphertext);
+    data.resize(aad_padded_len + ciphertext_padded_len, 0);
+    data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chacha20::{
+        cipher::{KeyIvInit, StreamCipher},
+        ChaCha20,
+    };
+    use chacha20poly1305::{aead::AeadInPlace, ChaCha20Poly1305, NewAead};
+
+    #[test]
+    fn test_poly1305_tag() {
+        let key = [42u8; 32];
+        let nonce = [7u8; 12];
+        let aad = [33u8; 13];
+        let msg = b"this message exceeds one block in length";
+
+        let mut ciphertext = msg.to_vec();
+        let expected = ChaCha20Poly1305::new(&key.into())
+            .encrypt_in_place_detached(&nonce.into(), &aad, &mut ciphertext)
+            .unwrap();
+
+        // The one-time key is the first half of the keystream block with
+        // counter 0.
+        let mut poly_key = [0u8; 64];
+        ChaCha20::new(&key.into(), &nonce.into()).apply_keystream(&mut poly_key);
+        let poly_key: [u8; 32] = poly_key[..32].try_into().unwrap();
+
+        assert_eq!(
+            compute_tag(&poly_key, &aad, &ciphertext),
+            expected.as_slice()
+        );
+    }
+}
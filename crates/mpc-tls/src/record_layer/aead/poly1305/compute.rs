@@ -0,0 +1,94 @@
+use std::{future::Future, pin::Pin};
+
+use async_trait::async_trait;
+use futures::{stream::FuturesOrdered, StreamExt as _};
+use mpz_common::{Context, Task};
+use serio::{stream::IoStreamExt, SinkExt};
+
+use crate::{
+    decode::FollowerOnly,
+    record_layer::aead::{poly1305::compute_tag, AeadError},
+    Role,
+};
+
+pub(crate) struct ComputeTagData {
+    pub(crate) key: FollowerOnly<[u8; 32]>,
+    pub(crate) ciphertext: Pin<Box<dyn Future<Output = Result<Vec<u8>, AeadError>> + Send + Sync>>,
+    pub(crate) aad: Vec<u8>,
+}
+
+#[must_use = "compute tags operation must be awaited"]
+pub(crate) struct ComputeTags {
+    role: Role,
+    data: Vec<ComputeTagData>,
+}
+
+impl ComputeTags {
+    pub(crate) fn new(role: Role, data: Vec<ComputeTagData>) -> Self {
+        Self { role, data }
+    }
+}
+
+#[async_trait]
+impl Task for ComputeTags {
+    type Output = Result<Option<Vec<Vec<u8>>>, AeadError>;
+
+    async fn run(self, ctx: &mut Context) -> Self::Output {
+        let Self { role, mut data } = self;
+
+        if data.is_empty() {
+            return Ok(None);
+        }
+
+        let mut keys = Vec::with_capacity(data.len());
+        {
+            let mut futs = FuturesOrdered::from_iter(data.iter_mut().map(|data| &mut data.key));
+            while let Some(key) = futs.next().await.transpose().map_err(AeadError::tag)? {
+                keys.push(key);
+            }
+        }
+
+        let mut ciphertexts = Vec::with_capacity(data.len());
+        {
+            let mut futs =
+                FuturesOrdered::from_iter(data.iter_mut().map(|data| &mut data.ciphertext));
+            while let Some(ciphertext) = futs.next().await.transpose().map_err(AeadError::tag)? {
+                ciphertexts.push(ciphertext);
+            }
+        }
+
+        let tags = match role {
+            Role::Leader => {
+                let tags: Vec<[u8; 16]> =
+                    ctx.io_mut().expect_next().await.map_err(AeadError::tag)?;
+
+                if tags.len() != ciphertexts.len() {
+                    return Err(AeadError::tag("follower tags length mismatch"));
+                }
+
+                Some(tags.into_iter().map(|tag| tag.to_vec()).collect())
+            }
+            Role::Follower => {
+                let tags = keys
+                    .into_iter()
+                    .zip(ciphertexts)
+                    .zip(data)
+                    .map(|((key, ciphertext), data)| {
+                        let key = key.expect("follower knows key");
+                        compute_tag(&key, &data.aad, &ciphertext)
+                    })
+                    .collect::<Vec<_>>();
+
+                ctx.io_mut().send(tags).await.map_err(AeadError::tag)?;
+
+                None
+            }
+        };
+
+        Ok(tags)
+    }
+
+    async fn run_boxed(self: Box<Self>, ctx: &mut Context) -> Self::Output {
+        self.run(ctx).await
+    }
+}
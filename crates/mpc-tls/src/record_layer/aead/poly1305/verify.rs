@@ -0,0 +1,89 @@
+use async_trait::async_trait;
+use futures::{stream::FuturesOrdered, StreamExt};
+use mpz_common::{Context, Task};
+use serio::{stream::IoStreamExt, SinkExt};
+
+use crate::{
+    decode::FollowerOnly,
+    record_layer::aead::{poly1305::compute_tag, AeadError},
+    Role,
+};
+
+pub(crate) struct VerifyTagData {
+    pub(crate) key: FollowerOnly<[u8; 32]>,
+    pub(crate) ciphertext: Vec<u8>,
+    pub(crate) aad: Vec<u8>,
+    pub(crate) tag: Vec<u8>,
+}
+
+#[must_use = "verify tags operation must be awaited"]
+pub(crate) struct VerifyTags {
+    role: Role,
+    data: Vec<VerifyTagData>,
+}
+
+impl VerifyTags {
+    pub(crate) fn new(role: Role, data: Vec<VerifyTagData>) -> Self {
+        Self { role, data }
+    }
+}
+
+#[async_trait]
+impl Task for VerifyTags {
+    type Output = Result<(), AeadError>;
+
+    async fn run(self, ctx: &mut Context) -> Self::Output {
+        let Self { role, mut data } = self;
+
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut keys = Vec::with_capacity(data.len());
+        {
+            let mut futs = FuturesOrdered::from_iter(data.iter_mut().map(|data| &mut data.key));
+            while let Some(key) = futs.next().await.transpose().map_err(AeadError::tag)? {
+                keys.push(key);
+            }
+        }
+
+        let io = ctx.io_mut();
+        match role {
+            Role::Leader => {
+                let expected_tags: Vec<[u8; 16]> =
+                    io.expect_next().await.map_err(AeadError::tag)?;
+
+                if expected_tags.len() != data.len() {
+                    return Err(AeadError::tag("follower tags length mismatch"));
+                }
+
+                if data
+                    .iter()
+                    .zip(expected_tags)
+                    .any(|(data, expected)| data.tag != expected)
+                {
+                    return Err(AeadError::tag("failed to verify tags"));
+                }
+            }
+            Role::Follower => {
+                let tags = keys
+                    .into_iter()
+                    .zip(data)
+                    .map(|(key, data)| {
+                        let key = key.expect("follower knows key");
+                        compute_tag(&key, &data.aad, &data.ciphertext)
+                    })
+                    .collect::<Vec<_>>();
+
+                // Send expected tags to leader.
+                io.send(tags).await.map_err(AeadError::tag)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_boxed(self: Box<Self>, ctx: &mut Context) -> Self::Output {
+        self.run(ctx).await
+    }
+}
@@ -1,13 +1,16 @@
+use std::ops::Range;
+
 use mpz_core::bitvec::BitVec;
 use mpz_memory_core::{binary::Binary, DecodeFutureTyped};
 use mpz_vm_core::{prelude::*, Vm};
+use rangeset::RangeSet;
 use serde::{Deserialize, Serialize};
 use tls_core::msgs::enums::{ContentType, ProtocolVersion};
 
 use crate::{
     record_layer::{
-        aead::{MpcAesGcm, VerifyTags},
-        aes_gcm::AesGcm,
+        aead::{MpcAead, VerifyTags},
+        local::LocalAead,
         TagData,
     },
     MpcTlsError, Role,
@@ -15,7 +18,7 @@ use crate::{
 
 pub(crate) fn private_mpc(
     vm: &mut dyn Vm<Binary>,
-    decrypter: &mut MpcAesGcm,
+    decrypter: &mut MpcAead,
     otp: Option<&mut Vec<u8>>,
     op: &DecryptOp,
 ) -> Result<DecryptOutput, MpcTlsError> {
@@ -53,11 +56,12 @@ pub(crate) fn private_mpc(
 
 pub(crate) fn public(
     vm: &mut dyn Vm<Binary>,
-    decrypter: &mut MpcAesGcm,
+    decrypter: &mut MpcAead,
     op: &DecryptOp,
 ) -> Result<DecryptOutput, MpcTlsError> {
-    // Instead of computing the plaintext in MPC, we only compute the keystream and
-    // decode it for both parties. Each party then locally computes the plaintext.
+    // Instead of computing the plaintext in MPC, we only compute the keystream
+    // and decode it for both parties. Each party then locally computes the
+    // plaintext.
 
     let keystream = decrypter
         .take_keystream(vm, op.explicit_nonce.clone(), op.ciphertext.len())
@@ -71,7 +75,7 @@ pub(crate) fn public(
 
 pub(crate) fn decrypt_mpc(
     vm: &mut dyn Vm<Binary>,
-    decrypter: &mut MpcAesGcm,
+    decrypter: &mut MpcAead,
     mut otp: Option<&mut Vec<u8>>,
     ops: &[DecryptOp],
 ) -> Result<Vec<PendingDecrypt>, MpcTlsError> {
@@ -97,8 +101,8 @@ pub(crate) fn decrypt_mpc(
 pub(crate) fn decrypt_local(
     role: Role,
     vm: &mut dyn Vm<Binary>,
-    mpc_decrypter: &mut MpcAesGcm,
-    local_decrypter: &mut AesGcm,
+    mpc_decrypter: &mut MpcAead,
+    local_decrypter: &mut LocalAead,
     ops: &[DecryptOp],
 ) -> Result<Vec<PendingDecrypt>, MpcTlsError> {
     let mut pending_decrypt = Vec::with_capacity(ops.len());
@@ -136,7 +140,7 @@ pub(crate) fn decrypt_local(
 
 pub(crate) fn verify_tags(
     vm: &mut dyn Vm<Binary>,
-    decrypter: &mut MpcAesGcm,
+    decrypter: &mut MpcAead,
     ops: &[DecryptOp],
 ) -> Result<VerifyTags, MpcTlsError> {
     let mut ciphertexts = Vec::with_capacity(ops.len());
@@ -172,6 +176,9 @@ pub(crate) struct DecryptOp {
     pub(crate) aad: Vec<u8>,
     pub(crate) tag: Vec<u8>,
     pub(crate) mode: DecryptMode,
+    /// Position of the record in the received application data, if it is an
+    /// application data record.
+    pub(crate) position: Option<RecordPosition>,
 }
 
 impl DecryptOp {
@@ -195,10 +202,20 @@ impl DecryptOp {
             aad,
             tag,
             mode,
+            position: None,
         }
     }
 }
 
+/// Position of a record in the received application data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RecordPosition {
+    /// Index of the record.
+    pub(crate) index: usize,
+    /// Range of the record in the received application data.
+    pub(crate) range: Range<usize>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum DecryptMode {
     /// The plaintext is private.
@@ -207,6 +224,69 @@ pub(crate) enum DecryptMode {
     Public,
 }
 
+/// Selection of received application data records which are decrypted online,
+/// i.e. while the TLS connection is active.
+///
+/// Selected records are decrypted according to their [`DecryptMode`], the
+/// remaining records are decrypted after the connection is closed. Plaintext
+/// is returned in the order it was received, so a selected record is only
+/// returned once all preceding records are decrypted.
+///
+/// Selecting [`Records`](OnlineDecryption::Records) or
+/// [`Bytes`](OnlineDecryption::Bytes) is only supported in TLS 1.2. In TLS 1.3
+/// every record is sent as application data, so post-handshake messages such
+/// as `NewSessionTicket` can not be told apart from application data until
+/// the record is decrypted, and their positions can not be determined.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OnlineDecryption {
+    /// All records are decrypted online.
+    All,
+    /// No records are decrypted online.
+    None,
+    /// Records at the provided indices are decrypted online.
+    ///
+    /// Records are indexed in the order they are received, starting from the
+    /// first application data record.
+    Records(RangeSet<usize>),
+    /// Records overlapping the provided byte ranges are decrypted online.
+    ///
+    /// Ranges are relative to the received application data records,
+    /// measured in ciphertext bytes.
+    Bytes(RangeSet<usize>),
+}
+
+impl OnlineDecryption {
+    /// Returns whether no records are decrypted online.
+    pub(crate) fn is_none(&self) -> bool {
+        match self {
+            OnlineDecryption::All => false,
+            OnlineDecryption::None => true,
+            OnlineDecryption::Records(records) => records.is_empty(),
+            OnlineDecryption::Bytes(bytes) => bytes.is_empty(),
+        }
+    }
+
+    /// Returns whether records are selected by their position.
+    pub(crate) fn is_positional(&self) -> bool {
+        matches!(
+            self,
+            OnlineDecryption::Records(_) | OnlineDecryption::Bytes(_)
+        )
+    }
+
+    /// Returns whether the record at the provided position is selected.
+    pub(crate) fn contains(&self, position: &RecordPosition) -> bool {
+        match self {
+            OnlineDecryption::All => true,
+            OnlineDecryption::None => false,
+            OnlineDecryption::Records(records) => records.contains(&position.index),
+            OnlineDecryption::Bytes(bytes) => bytes
+                .iter_ranges()
+                .any(|range| range.start < position.range.end && position.range.start < range.end),
+        }
+    }
+}
+
 pub(crate) enum DecryptOutput {
     Private(DecryptPrivate),
     Public(DecryptPublic),
@@ -245,8 +325,8 @@ impl DecryptPrivate {
             return Ok(None);
         };
 
-        // Recover the plaintext by removing the OTP from the masked keystream and
-        // applying the ciphertext.
+        // Recover the plaintext by removing the OTP from the masked keystream
+        // and applying the ciphertext.
         let mut plaintext = self.ciphertext;
         plaintext
             .iter_mut()
@@ -300,3 +380,35 @@ impl DecryptLocal {
         Ok(self.plaintext)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(index: usize, range: Range<usize>) -> RecordPosition {
+        RecordPosition { index, range }
+    }
+
+    #[test]
+    fn test_online_decryption_contains() {
+        let records = OnlineDecryption::Records(RangeSet::from([1..3]));
+        assert!(!records.contains(&position(0, 0..10)));
+        assert!(records.contains(&position(1, 10..20)));
+        assert!(records.contains(&position(2, 20..30)));
+        assert!(!records.contains(&position(3, 30..40)));
+
+        let bytes = OnlineDecryption::Bytes(RangeSet::from([15..21]));
+        assert!(!bytes.contains(&position(0, 0..10)));
+        assert!(bytes.contains(&position(1, 10..20)));
+        assert!(bytes.contains(&position(2, 20..30)));
+        assert!(!bytes.contains(&position(3, 30..40)));
+
+        assert!(OnlineDecryption::Records(RangeSet::default()).is_none());
+        assert!(!OnlineDecryption::All.is_none());
+
+        assert!(records.is_positional());
+        assert!(bytes.is_positional());
+        assert!(!OnlineDecryption::All.is_positional());
+        assert!(!OnlineDecryption::None.is_positional());
+    }
+}
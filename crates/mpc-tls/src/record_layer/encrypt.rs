@@ -7,7 +7,7 @@ use tls_core::msgs::enums::{ContentType, ProtocolVersion};
 
 use crate::{
     record_layer::{
-        aead::{AeadError, ComputeTags, MpcAesGcm},
+        aead::{AeadError, ComputeTags, MpcAead},
         TagData,
     },
     BoxFut, MpcTlsError,
@@ -16,7 +16,7 @@ use crate::{
 #[allow(clippy::type_complexity)]
 fn private(
     vm: &mut dyn Vm<Binary>,
-    encrypter: &mut MpcAesGcm,
+    encrypter: &mut MpcAead,
     op: &EncryptOp,
 ) -> Result<(EncryptOutput, BoxFut<Result<Vec<u8>, AeadError>>), MpcTlsError> {
     let (plaintext, ciphertext) = encrypter
@@ -46,7 +46,7 @@ fn private(
 #[allow(clippy::type_complexity)]
 fn public(
     vm: &mut dyn Vm<Binary>,
-    encrypter: &mut MpcAesGcm,
+    encrypter: &mut MpcAead,
     op: &EncryptOp,
 ) -> Result<(EncryptOutput, BoxFut<Result<Vec<u8>, AeadError>>), MpcTlsError> {
     // Instead of computing the ciphertext in MPC, we only compute the keystream and
@@ -87,7 +87,7 @@ fn public(
 
 pub(crate) fn encrypt(
     vm: &mut dyn Vm<Binary>,
-    encrypter: &mut MpcAesGcm,
+    encrypter: &mut MpcAead,
     ops: &[EncryptOp],
 ) -> Result<(Vec<PendingEncrypt>, ComputeTags), MpcTlsError> {
     let mut outputs = Vec::new();
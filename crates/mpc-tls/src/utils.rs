@@ -1,4 +1,11 @@
+use key_exchange as ke;
+use tls_core::{
+    key::PublicKey,
+    msgs::enums::{ContentType, NamedGroup},
+};
+
 use crate::MpcTlsError;
+use tlsn_core::connection::KeyType;
 
 /// Split an opaque message into its constituent parts.
 ///
@@ -17,3 +24,78 @@ pub(crate) fn opaque_into_parts(
 
     Ok((explicit_nonce, ciphertext, tag))
 }
+
+/// Split a TLS 1.3 opaque message into its constituent parts.
+///
+/// Returns the ciphertext and tag, respectively. In TLS 1.3 the nonce is not
+/// transmitted with the record.
+pub(crate) fn opaque_into_parts_tls13(mut msg: Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), MpcTlsError> {
+    if msg.len() < 16 {
+        return Err(MpcTlsError::other("record is shorter than the tag"));
+    }
+
+    let tag = msg.split_off(msg.len() - 16);
+
+    Ok((msg, tag))
+}
+
+/// Removes the padding and content type from a TLS 1.3 inner plaintext.
+///
+/// Returns the content type.
+pub(crate) fn unpad_tls13(plaintext: &mut Vec<u8>) -> Result<ContentType, MpcTlsError> {
+    while let Some(byte) = plaintext.pop() {
+        if byte != 0 {
+            return Ok(ContentType::from(byte));
+        }
+    }
+
+    Err(MpcTlsError::other("record is missing the content type"))
+}
+
+/// Decodes a TLS key share into a key exchange public key.
+pub(crate) fn decode_key_share(key: &PublicKey) -> Result<ke::PublicKey, MpcTlsError> {
+    match key.group {
+        NamedGroup::secp256r1 => p256::PublicKey::from_sec1_bytes(&key.key)
+            .map(ke::PublicKey::Secp256r1)
+            .map_err(|_| MpcTlsError::hs("failed to parse server key")),
+        NamedGroup::X25519 => <[u8; 32]>::try_from(key.key.as_slice())
+            .map(ke::PublicKey::X25519)
+            .map_err(|_| MpcTlsError::hs("x25519 server key is not 32 bytes")),
+        NamedGroup::secp384r1 => p384::PublicKey::from_sec1_bytes(&key.key)
+            .map(ke::PublicKey::Secp384r1)
+            .map_err(|_| MpcTlsError::hs("failed to parse server key")),
+        _ => Err(MpcTlsError::hs("unsupported server key group")),
+    }
+}
+
+/// Encodes a key exchange public key as a TLS key share.
+pub(crate) fn encode_key_share(key: ke::PublicKey) -> PublicKey {
+    match key {
+        ke::PublicKey::Secp256r1(key) => PublicKey::new(
+            NamedGroup::secp256r1,
+            &p256::EncodedPoint::from(key).to_bytes(),
+        ),
+        ke::PublicKey::X25519(key) => PublicKey::new(NamedGroup::X25519, &key),
+        ke::PublicKey::Secp384r1(key) => PublicKey::new(
+            NamedGroup::secp384r1,
+            &p384::EncodedPoint::from(key).to_bytes(),
+        ),
+    }
+}
+
+/// Creates an ideal key exchange which computes the PMS in plaintext.
+///
+/// This is insecure and only intended for testing, see the `tlsn_insecure_ideal` cfg.
+pub(crate) fn ideal_key_exchange(
+    role: ke::Role,
+    key_type: KeyType,
+) -> Box<dyn ke::KeyExchange + Send + Sync> {
+    use ke::mock::IdealKeyExchange;
+
+    match key_type {
+        KeyType::SECP256R1 => Box::new(IdealKeyExchange::<ke::Secp256r1>::new(role)),
+        KeyType::X25519 => Box::new(IdealKeyExchange::<ke::X25519>::new(role)),
+        KeyType::SECP384R1 => Box::new(IdealKeyExchange::<ke::Secp384r1>::new(role)),
+        _ => unreachable!("key type is validated by the config builder"),
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use futures::{AsyncReadExt, AsyncWriteExt};
-use mpc_tls::{Config, MpcTlsFollower, MpcTlsLeader};
+use mpc_tls::{Config, MpcTlsFollower, MpcTlsLeader, OnlineDecryption};
 use mpz_common::context::test_mt_context;
 use mpz_core::Block;
 use mpz_garble::protocol::semihonest::{Evaluator, Garbler};
@@ -12,10 +12,15 @@ use mpz_ot::{
     rcot::shared::{SharedRCOTReceiver, SharedRCOTSender},
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
+use rangeset::RangeSet;
 use rustls_pki_types::CertificateDer;
-use tls_client::RootCertStore;
+use tls_client::{RootCertStore, SupportedKxGroup};
 use tls_client_async::bind_client;
-use tls_server_fixture::{bind_test_server_hyper, CA_CERT_DER, SERVER_DOMAIN};
+use tls_core::suites::SupportedCipherSuite;
+use tls_server_fixture::{
+    bind_test_server_hyper, bind_test_server_hyper_ems, CA_CERT_DER, SERVER_DOMAIN,
+};
+use tlsn_core::connection::{AeadAlgorithm, KeyType, TlsVersion};
 use tokio::sync::Mutex;
 use tokio_util::compat::TokioAsyncReadCompatExt;
 use webpki::anchor_from_trusted_cert;
@@ -27,7 +32,152 @@ const CA_CERT: CertificateDer = CertificateDer::from_slice(CA_CERT_DER);
 async fn mpc_tls_test() {
     tracing_subscriber::fmt::init();
 
+    run_test(
+        TlsVersion::V1_2,
+        false,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::SECP256R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls13_test() {
+    run_test(
+        TlsVersion::V1_3,
+        false,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::SECP256R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_ems_test() {
+    run_test(
+        TlsVersion::V1_2,
+        true,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::SECP256R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_aes_256_test() {
+    run_test(
+        TlsVersion::V1_2,
+        false,
+        AeadAlgorithm::Aes256Gcm,
+        KeyType::SECP256R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_chacha20_poly1305_test() {
+    run_test(
+        TlsVersion::V1_2,
+        false,
+        AeadAlgorithm::ChaCha20Poly1305,
+        KeyType::SECP256R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_x25519_test() {
+    run_test(
+        TlsVersion::V1_2,
+        false,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::X25519,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls13_x25519_test() {
+    run_test(
+        TlsVersion::V1_3,
+        false,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::X25519,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_secp384r1_test() {
+    run_test(
+        TlsVersion::V1_2,
+        false,
+        AeadAlgorithm::Aes128Gcm,
+        KeyType::SECP384R1,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[ignore = "expensive"]
+async fn mpc_tls_online_records_test() {
+    let config = Config::builder()
+        .defer_decryption(false)
+        .max_sent(1 << 13)
+        .max_recv_online(1 << 13)
+        .max_recv(1 << 13)
+        .build()
+        .unwrap();
+
+    let suites = config.cipher_suites();
+    let (leader, follower) = build_pair(config);
+
+    tokio::try_join!(
+        tokio::spawn(online_records_leader_task(leader, suites)),
+        tokio::spawn(follower_task(follower))
+    )
+    .unwrap();
+}
+
+#[tokio::test]
+async fn mpc_tls13_online_records_rejected_test() {
+    let config = Config::builder()
+        .version(TlsVersion::V1_3)
+        .max_sent(1 << 13)
+        .max_recv_online(1 << 13)
+        .max_recv(1 << 13)
+        .build()
+        .unwrap();
+
+    let (mut leader, _follower) = build_pair(config);
+
+    assert!(leader
+        .set_online_decryption(OnlineDecryption::Records(RangeSet::from([0..1])))
+        .await
+        .is_err());
+    assert!(leader
+        .set_online_decryption(OnlineDecryption::Bytes(RangeSet::from([0..10])))
+        .await
+        .is_err());
+    leader
+        .set_online_decryption(OnlineDecryption::All)
+        .await
+        .unwrap();
+}
+
+async fn run_test(version: TlsVersion, ems: bool, aead: AeadAlgorithm, key_type: KeyType) {
     let config = Config::builder()
+        .version(version)
+        .extended_master_secret(ems)
+        .aead(aead)
+        .key_type(key_type)
         .defer_decryption(false)
         .max_sent(1 << 13)
         .max_recv_online(1 << 13)
@@ -35,16 +185,28 @@ async fn mpc_tls_test() {
         .build()
         .unwrap();
 
+    let suites = config.cipher_suites();
+    let kx_group: &'static SupportedKxGroup = match key_type {
+        KeyType::X25519 => &tls_client::kx_group::X25519,
+        KeyType::SECP384R1 => &tls_client::kx_group::SECP384R1,
+        _ => &tls_client::kx_group::SECP256R1,
+    };
     let (leader, follower) = build_pair(config);
 
     tokio::try_join!(
-        tokio::spawn(leader_task(leader)),
+        tokio::spawn(leader_task(leader, version, ems, suites, kx_group)),
         tokio::spawn(follower_task(follower))
     )
     .unwrap();
 }
 
-async fn leader_task(mut leader: MpcTlsLeader) {
+async fn leader_task(
+    mut leader: MpcTlsLeader,
+    version: TlsVersion,
+    ems: bool,
+    suites: Vec<SupportedCipherSuite>,
+    kx_group: &'static SupportedKxGroup,
+) {
     leader.alloc().unwrap();
 
     leader.preprocess().await.unwrap();
@@ -52,12 +214,21 @@ async fn leader_task(mut leader: MpcTlsLeader) {
     let (leader_ctrl, leader_fut) = leader.run();
     tokio::spawn(async { leader_fut.await.unwrap() });
 
-    let config = tls_client::ClientConfig::builder()
-        .with_safe_defaults()
+    let protocol_version = match version {
+        TlsVersion::V1_2 => &tls_client::version::TLS12,
+        TlsVersion::V1_3 => &tls_client::version::TLS13,
+    };
+
+    let mut config = tls_client::ClientConfig::builder()
+        .with_cipher_suites(&suites)
+        .with_kx_groups(&[kx_group])
+        .with_protocol_versions(&[protocol_version])
+        .unwrap()
         .with_root_certificates(RootCertStore {
             roots: vec![anchor_from_trusted_cert(&CA_CERT).unwrap().to_owned()],
         })
         .with_no_client_auth();
+    config.require_ems = ems;
 
     let server_name = SERVER_DOMAIN.try_into().unwrap();
 
@@ -69,7 +240,11 @@ async fn leader_task(mut leader: MpcTlsLeader) {
     .unwrap();
 
     let (client_socket, server_socket) = tokio::io::duplex(1 << 16);
-    tokio::spawn(bind_test_server_hyper(server_socket.compat()));
+    if ems {
+        tokio::spawn(bind_test_server_hyper_ems(server_socket.compat()));
+    } else {
+        tokio::spawn(bind_test_server_hyper(server_socket.compat()));
+    }
 
     let (mut conn, conn_fut) = bind_client(client_socket.compat(), client);
     let handle = tokio::spawn(async { conn_fut.await.unwrap() });
@@ -114,6 +289,91 @@ async fn leader_task(mut leader: MpcTlsLeader) {
     handle.await.unwrap();
 }
 
+/// Decrypts only the first received record online and checks that the
+/// deferred records are returned to the client in the order they were
+/// received.
+async fn online_records_leader_task(mut leader: MpcTlsLeader, suites: Vec<SupportedCipherSuite>) {
+    leader.alloc().unwrap();
+
+    leader.preprocess().await.unwrap();
+
+    let (leader_ctrl, leader_fut) = leader.run();
+    tokio::spawn(async { leader_fut.await.unwrap() });
+
+    leader_ctrl
+        .set_online_decryption(OnlineDecryption::Records(RangeSet::from([0..1])))
+        .await
+        .unwrap();
+
+    let config = tls_client::ClientConfig::builder()
+        .with_cipher_suites(&suites)
+        .with_kx_groups(&[&tls_client::kx_group::SECP256R1])
+        .with_protocol_versions(&[&tls_client::version::TLS12])
+        .unwrap()
+        .with_root_certificates(RootCertStore {
+            roots: vec![anchor_from_trusted_cert(&CA_CERT).unwrap().to_owned()],
+        })
+        .with_no_client_auth();
+
+    let server_name = SERVER_DOMAIN.try_into().unwrap();
+
+    let client = tls_client::ClientConnection::new(
+        Arc::new(config),
+        Box::new(leader_ctrl.clone()),
+        server_name,
+    )
+    .unwrap();
+
+    let (client_socket, server_socket) = tokio::io::duplex(1 << 16);
+    tokio::spawn(bind_test_server_hyper(server_socket.compat()));
+
+    let (mut conn, conn_fut) = bind_client(client_socket.compat(), client);
+    let handle = tokio::spawn(async { conn_fut.await.unwrap() });
+
+    let request = |body: &str, connection: &str| {
+        format!(
+            "POST /echo HTTP/1.1\r\nHost: test-server.io\r\nConnection: {connection}\r\nAccept-Encoding: identity\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    };
+
+    conn.write_all(request("first", "keep-alive").as_bytes())
+        .await
+        .unwrap();
+
+    // The first record contains the status line of the first response and is
+    // available while the connection is open.
+    let mut status = vec![0u8; 15];
+    conn.read_exact(&mut status).await.unwrap();
+    assert_eq!(status, b"HTTP/1.1 200 OK");
+
+    conn.write_all(request("second", "keep-alive").as_bytes())
+        .await
+        .unwrap();
+    conn.write_all(request("third", "close").as_bytes())
+        .await
+        .unwrap();
+    conn.close().await.unwrap();
+
+    let mut rest = Vec::new();
+    conn.read_to_end(&mut rest).await.unwrap();
+
+    leader_ctrl.stop().await.unwrap();
+
+    handle.await.unwrap();
+
+    let find = |needle: &[u8]| {
+        rest.windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap()
+    };
+
+    let first = find(b"first");
+    let second = find(b"second");
+    let third = find(b"third");
+    assert!(first < second && second < third);
+}
+
 async fn follower_task(mut follower: MpcTlsFollower) {
     follower.alloc().unwrap();
     follower.preprocess().await.unwrap();
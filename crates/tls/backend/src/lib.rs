@@ -16,7 +16,7 @@ use tls_core::{
     key::PublicKey,
     msgs::{
         enums::{CipherSuite, NamedGroup, ProtocolVersion},
-        handshake::Random,
+        handshake::{DigitallySignedStruct, Random},
         message::{OpaqueMessage, PlainMessage},
     },
     suites::SupportedCipherSuite,
@@ -104,6 +104,22 @@ pub trait Backend: Send {
     async fn set_hs_hash_client_key_exchange(&mut self, hash: Vec<u8>) -> Result<(), BackendError>;
     /// Sets handshake hash at ServerHello.
     async fn set_hs_hash_server_hello(&mut self, hash: Vec<u8>) -> Result<(), BackendError>;
+    /// Sets the TLS 1.3 server signature from the CertificateVerify message.
+    ///
+    /// `handshake_messages` are the encoded handshake messages from
+    /// ClientHello up to and including Certificate, which are covered by the
+    /// signature.
+    async fn set_server_signature_tls13(
+        &mut self,
+        _handshake_messages: Vec<u8>,
+        _sig: DigitallySignedStruct,
+    ) -> Result<(), BackendError> {
+        Ok(())
+    }
+    /// Sets handshake hash at server Finished (TLS 1.3).
+    async fn set_hs_hash_server_finished(&mut self, _hash: Vec<u8>) -> Result<(), BackendError> {
+        Ok(())
+    }
     /// Returns expected ServerFinished verify_data.
     async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError>;
     /// Returns ClientFinished verify_data.
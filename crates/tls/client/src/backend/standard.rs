@@ -2,14 +2,17 @@ use super::{Backend, BackendError};
 use crate::{DecryptMode, EncryptMode, Error};
 use aes_gcm::{
     aead::{generic_array::GenericArray, Aead, NewAead, Payload},
-    Aes128Gcm,
+    Aes128Gcm, Aes256Gcm,
 };
 use async_trait::async_trait;
+use chacha20poly1305::ChaCha20Poly1305;
 use p256::{ecdh::EphemeralSecret, EncodedPoint, PublicKey as ECDHPublicKey};
 use rand::{rng, rngs::OsRng, Rng};
 
 use digest::Digest;
+use hmac::{Hmac, Mac};
 use rand06_compat::Rand0_6CompatExt;
+use sha2::Sha256;
 use std::{any::Any, collections::VecDeque, convert::TryInto, mem::take};
 use tls_core::{
     cert::ServerCertDetails,
@@ -21,8 +24,8 @@ use tls_core::{
         handshake::Random,
         message::{OpaqueMessage, PlainMessage},
     },
-    prf::prf,
-    suites::{self, SupportedCipherSuite},
+    prf::{prf, prf_sha384, InvalidKeyLength},
+    suites::{self, HashAlgorithm, SupportedCipherSuite},
 };
 
 /// Implementation of TLS backend using RustCrypto primitives
@@ -37,10 +40,17 @@ pub struct RustCryptoBackend {
     ecdh_secret: Option<EphemeralSecret>,
     // session_keys size can vary depending on the ciphersuite
     session_keys: Option<Vec<u8>>,
+    // TLS 1.3 key schedule
+    ecdhe_secret: Option<Vec<u8>>,
+    handshake_secret: Option<[u8; 32]>,
+    client_hs_traffic_secret: Option<[u8; 32]>,
+    server_hs_traffic_secret: Option<[u8; 32]>,
+    client_ap_traffic_secret: Option<[u8; 32]>,
+    server_ap_traffic_secret: Option<[u8; 32]>,
     protocol_version: Option<ProtocolVersion>,
     cipher_suite: Option<SupportedCipherSuite>,
     curve: Option<NamedGroup>,
-    implemented_suites: [CipherSuite; 2],
+    implemented_suites: [CipherSuite; 7],
     encrypter: Option<Encrypter>,
     decrypter: Option<Decrypter>,
 
@@ -63,12 +73,23 @@ impl RustCryptoBackend {
             master_secret: None,
             ems_seed: None,
             session_keys: None,
+            ecdhe_secret: None,
+            handshake_secret: None,
+            client_hs_traffic_secret: None,
+            server_hs_traffic_secret: None,
+            client_ap_traffic_secret: None,
+            server_ap_traffic_secret: None,
             protocol_version: None,
             cipher_suite: None,
             curve: Some(NamedGroup::secp256r1),
             implemented_suites: [
                 CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
                 CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+                CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+                CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+                CipherSuite::TLS13_AES_128_GCM_SHA256,
             ],
             encrypter: None,
             decrypter: None,
@@ -81,11 +102,26 @@ impl RustCryptoBackend {
         }
     }
 
+    /// Computes the TLS 1.2 PRF with the hash function of the cipher suite.
+    fn tls12_prf(
+        &self,
+        out: &mut [u8],
+        secret: &[u8],
+        label: &[u8],
+        seed: &[u8],
+    ) -> Result<(), InvalidKeyLength> {
+        match self.cipher_suite.map(|suite| suite.hash_algorithm()) {
+            Some(HashAlgorithm::SHA384) => prf_sha384(out, secret, label, seed),
+            _ => prf(out, secret, label, seed),
+        }
+    }
+
     /// Expands the handshake hash and master secret into verify_data for
     /// the Server_Finished
     pub fn verify_data_sf_tls12(&self, hs_hash: &[u8], ms: &[u8; 48]) -> [u8; 12] {
         let mut vd = [0u8; 12];
-        prf(&mut vd, ms, b"server finished", hs_hash).expect("key length is valid");
+        self.tls12_prf(&mut vd, ms, b"server finished", hs_hash)
+            .expect("key length is valid");
         vd
     }
 
@@ -93,31 +129,39 @@ impl RustCryptoBackend {
     /// the Client_Finished
     pub fn verify_data_cf_tls12(&self, hs_hash: &[u8], ms: &[u8; 48]) -> [u8; 12] {
         let mut vd = [0u8; 12];
-        prf(&mut vd, ms, b"client finished", hs_hash).expect("key length is valid");
+        self.tls12_prf(&mut vd, ms, b"client finished", hs_hash)
+            .expect("key length is valid");
         vd
     }
 
     /// Expands pre-master secret into session key using TLS 1.2 PRF
     /// Returns master_secret and session keys
+    ///
+    /// Derives the extended master secret if the session hash was set.
     pub fn key_expansion_tls12(
         &mut self,
         client_random: &[u8; 32],
         server_random: &[u8; 32],
         pms: &[u8],
-    ) -> ([u8; 48], [u8; 40]) {
+        key_len: usize,
+        iv_len: usize,
+    ) -> ([u8; 48], Vec<u8>) {
         // first expand pms into ms
         let mut ms = [0u8; 48];
-        prf(
-            &mut ms,
-            pms,
-            b"master secret",
-            &concat::<64>(client_random, server_random),
-        )
+        match &self.ems_seed {
+            Some(ems_seed) => self.tls12_prf(&mut ms, pms, b"extended master secret", ems_seed),
+            None => self.tls12_prf(
+                &mut ms,
+                pms,
+                b"master secret",
+                &concat::<64>(client_random, server_random),
+            ),
+        }
         .expect("key length is valid");
 
-        // expand ms into session keys
-        let mut session_keys = [0u8; 40];
-        prf(
+        // expand ms into session keys, i.e. 2 write keys and 2 implicit IVs
+        let mut session_keys = vec![0u8; 2 * key_len + 2 * iv_len];
+        self.tls12_prf(
             &mut session_keys,
             &ms,
             b"key expansion",
@@ -133,25 +177,21 @@ impl RustCryptoBackend {
             "can not set enccrypter, ciphersuite not set".to_string(),
         ))?;
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract client_write_key and client_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set encrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[0..16]);
-                write_iv.copy_from_slice(&session_keys[32..36]);
-                self.encrypter = Some(Encrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
+        let (key_len, iv_len) = key_iv_len(cipher_suite.suite())
+            .ok_or(BackendError::UnsupportedCiphersuite(cipher_suite.suite()))?;
+
+        // extract client_write_key and client_write_iv. They are at different
+        // offsets depending on the key and IV length of the cipher suite.
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set encrypter, session_keys are not set".to_string(),
+            ))?;
+        let write_key = session_keys[0..key_len].to_vec();
+        let write_iv = session_keys[2 * key_len..2 * key_len + iv_len].to_vec();
+        self.encrypter = Some(Encrypter::new(write_key, write_iv, cipher_suite.suite()));
+
         Ok(())
     }
     fn set_decrypter(&mut self) -> Result<(), BackendError> {
@@ -159,28 +199,46 @@ impl RustCryptoBackend {
             "can not set decrypter, ciphersuite not set".to_string(),
         ))?;
 
-        match cipher_suite.suite() {
-            CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => {
-                // extract server_write_key and server_write_iv. They may be at different
-                // offsets depending on the cipher suite.
-                let mut write_key = [0u8; 16];
-                let mut write_iv = [0u8; 4];
-                let session_keys = self
-                    .session_keys
-                    .as_ref()
-                    .ok_or(BackendError::InvalidState(
-                        "can not set decrypter, session_keys are not set".to_string(),
-                    ))?;
-                write_key.copy_from_slice(&session_keys[16..32]);
-                write_iv.copy_from_slice(&session_keys[36..40]);
-                self.decrypter = Some(Decrypter::new(write_key, write_iv, cipher_suite.suite()));
-            }
-            suite => return Err(BackendError::UnsupportedCiphersuite(suite)),
-        }
+        let (key_len, iv_len) = key_iv_len(cipher_suite.suite())
+            .ok_or(BackendError::UnsupportedCiphersuite(cipher_suite.suite()))?;
+
+        // extract server_write_key and server_write_iv. They are at different
+        // offsets depending on the key and IV length of the cipher suite.
+        let session_keys = self
+            .session_keys
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "can not set decrypter, session_keys are not set".to_string(),
+            ))?;
+        let write_key = session_keys[key_len..2 * key_len].to_vec();
+        let write_iv = session_keys[2 * key_len + iv_len..2 * key_len + 2 * iv_len].to_vec();
+        self.decrypter = Some(Decrypter::new(write_key, write_iv, cipher_suite.suite()));
+
         Ok(())
     }
 
+    /// Expands a TLS 1.3 traffic secret into the write key and IV of the
+    /// cipher suite.
+    fn tls13_traffic_keys(
+        &self,
+        secret: &[u8; 32],
+    ) -> Result<(Vec<u8>, Vec<u8>, CipherSuite), BackendError> {
+        let suite = self
+            .cipher_suite
+            .ok_or(BackendError::InvalidState(
+                "can not derive traffic keys, ciphersuite not set".to_string(),
+            ))?
+            .suite();
+        let (key_len, iv_len) =
+            key_iv_len(suite).ok_or(BackendError::UnsupportedCiphersuite(suite))?;
+
+        Ok((
+            hkdf_expand_label(secret, b"key", &[], key_len),
+            hkdf_expand_label(secret, b"iv", &[], iv_len),
+            suite,
+        ))
+    }
+
     fn encrypt(&mut self, msg: PlainMessage, seq: u64) -> Result<OpaqueMessage, BackendError> {
         let enc = self
             .encrypter
@@ -191,14 +249,28 @@ impl RustCryptoBackend {
 
         match enc.cipher_suite {
             CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => match msg.version {
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256
+            | CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384 => match msg.version {
                 ProtocolVersion::TLSv1_2 => {
-                    return enc.encrypt_aes128gcm(&msg, seq, &seq.to_be_bytes());
+                    return enc.encrypt_aes_gcm(&msg, seq, &seq.to_be_bytes());
                 }
                 version => {
                     return Err(BackendError::UnsupportedProtocolVersion(version));
                 }
             },
+            CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => match msg.version {
+                ProtocolVersion::TLSv1_2 => {
+                    return enc.encrypt_chacha20_poly1305(&msg, seq);
+                }
+                version => {
+                    return Err(BackendError::UnsupportedProtocolVersion(version));
+                }
+            },
+            CipherSuite::TLS13_AES_128_GCM_SHA256 => {
+                return enc.encrypt_tls13(&msg, seq);
+            }
             suite => {
                 return Err(BackendError::UnsupportedCiphersuite(suite));
             }
@@ -215,14 +287,28 @@ impl RustCryptoBackend {
 
         match dec.cipher_suite {
             CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
-            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => match msg.version {
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256
+            | CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384 => match msg.version {
+                ProtocolVersion::TLSv1_2 => {
+                    return dec.decrypt_aes_gcm(&msg, seq);
+                }
+                version => {
+                    return Err(BackendError::UnsupportedProtocolVersion(version));
+                }
+            },
+            CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+            | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => match msg.version {
                 ProtocolVersion::TLSv1_2 => {
-                    return dec.decrypt_aes128gcm(&msg, seq);
+                    return dec.decrypt_chacha20_poly1305(&msg, seq);
                 }
                 version => {
                     return Err(BackendError::UnsupportedProtocolVersion(version));
                 }
             },
+            CipherSuite::TLS13_AES_128_GCM_SHA256 => {
+                return dec.decrypt_tls13(&msg, seq);
+            }
             suite => {
                 return Err(BackendError::UnsupportedCiphersuite(suite));
             }
@@ -234,7 +320,7 @@ impl RustCryptoBackend {
 impl Backend for RustCryptoBackend {
     async fn set_protocol_version(&mut self, version: ProtocolVersion) -> Result<(), BackendError> {
         match version {
-            ProtocolVersion::TLSv1_2 => {
+            ProtocolVersion::TLSv1_2 | ProtocolVersion::TLSv1_3 => {
                 self.protocol_version = Some(version);
                 Ok(())
             }
@@ -269,12 +355,49 @@ impl Backend for RustCryptoBackend {
         Ok(suites::tls12::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256)
     }
 
-    async fn set_encrypt(&mut self, _mode: EncryptMode) -> Result<(), BackendError> {
-        todo!()
+    async fn set_encrypt(&mut self, mode: EncryptMode) -> Result<(), BackendError> {
+        let secret = match mode {
+            EncryptMode::Handshake => self.client_hs_traffic_secret,
+            EncryptMode::Application => self.client_ap_traffic_secret,
+            EncryptMode::EarlyData => {
+                return Err(BackendError::InvalidState(
+                    "early data is not supported".to_string(),
+                ))
+            }
+        }
+        .ok_or(BackendError::InvalidState(
+            "can not set encrypter, traffic secret not set".to_string(),
+        ))?;
+
+        // Messages buffered before the key change are protected with the
+        // previous keys.
+        self.flush().await?;
+
+        let (write_key, write_iv, suite) = self.tls13_traffic_keys(&secret)?;
+        self.encrypter = Some(Encrypter::new(write_key, write_iv, suite));
+        self.write_seq = 0;
+
+        Ok(())
     }
 
-    async fn set_decrypt(&mut self, _mode: DecryptMode) -> Result<(), BackendError> {
-        todo!()
+    async fn set_decrypt(&mut self, mode: DecryptMode) -> Result<(), BackendError> {
+        let secret = match mode {
+            DecryptMode::Handshake => self.server_hs_traffic_secret,
+            DecryptMode::Application => self.server_ap_traffic_secret,
+        }
+        .ok_or(BackendError::InvalidState(
+            "can not set decrypter, traffic secret not set".to_string(),
+        ))?;
+
+        // Messages buffered before the key change are protected with the
+        // previous keys.
+        self.flush().await?;
+
+        let (write_key, write_iv, suite) = self.tls13_traffic_keys(&secret)?;
+        self.decrypter = Some(Decrypter::new(write_key, write_iv, suite));
+        self.read_seq = 0;
+
+        Ok(())
     }
 
     async fn get_client_random(&mut self) -> Result<Random, BackendError> {
@@ -339,18 +462,31 @@ impl Backend for RustCryptoBackend {
             }
         };
 
-        (self.master_secret, self.session_keys) = match self.protocol_version.ok_or(
-            BackendError::InvalidState("Protocol version not set".to_string()),
-        )? {
+        match self.protocol_version.ok_or(BackendError::InvalidState(
+            "Protocol version not set".to_string(),
+        ))? {
             ProtocolVersion::TLSv1_2 => {
-                let (ms, ek) = self.key_expansion_tls12(&client_random, &server_random, &pms);
-                (Some(ms), Some(ek.to_vec()))
+                let suite = self
+                    .cipher_suite
+                    .ok_or(BackendError::InvalidState(
+                        "Ciphersuite not set".to_string(),
+                    ))?
+                    .suite();
+                let (key_len, iv_len) =
+                    key_iv_len(suite).ok_or(BackendError::UnsupportedCiphersuite(suite))?;
+                let (ms, ek) =
+                    self.key_expansion_tls12(&client_random, &server_random, &pms, key_len, iv_len);
+                self.master_secret = Some(ms);
+                self.session_keys = Some(ek);
+
+                self.set_encrypter()?;
+                self.set_decrypter()?;
             }
+            // The traffic secrets are derived once the handshake hash is
+            // known.
+            ProtocolVersion::TLSv1_3 => self.ecdhe_secret = Some(pms),
             version => return Err(BackendError::UnsupportedProtocolVersion(version)),
-        };
-
-        self.set_encrypter()?;
-        self.set_decrypter()?;
+        }
 
         Ok(())
     }
@@ -374,36 +510,89 @@ impl Backend for RustCryptoBackend {
         Ok(())
     }
 
-    async fn set_hs_hash_server_hello(&mut self, _hash: Vec<u8>) -> Result<(), BackendError> {
+    async fn set_hs_hash_server_hello(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        if self.protocol_version != Some(ProtocolVersion::TLSv1_3) {
+            return Ok(());
+        }
+
+        let ecdhe_secret = self
+            .ecdhe_secret
+            .as_ref()
+            .ok_or(BackendError::InvalidState(
+                "ECDHE shared secret not set".to_string(),
+            ))?;
+
+        let early_secret = hkdf_extract(&[0u8; 32], &[0u8; 32]);
+        let salt = derive_secret(&early_secret, b"derived", &Sha256::digest(b""));
+        let handshake_secret = hkdf_extract(&salt, ecdhe_secret);
+
+        self.client_hs_traffic_secret =
+            Some(derive_secret(&handshake_secret, b"c hs traffic", &hash));
+        self.server_hs_traffic_secret =
+            Some(derive_secret(&handshake_secret, b"s hs traffic", &hash));
+        self.handshake_secret = Some(handshake_secret);
+
         Ok(())
     }
 
-    async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
-        let ms = self.master_secret.ok_or(BackendError::InvalidState(
-            "Master secret not set".to_string(),
+    async fn set_hs_hash_server_finished(&mut self, hash: Vec<u8>) -> Result<(), BackendError> {
+        let handshake_secret = self.handshake_secret.ok_or(BackendError::InvalidState(
+            "Handshake secret not set".to_string(),
         ))?;
 
+        let salt = derive_secret(&handshake_secret, b"derived", &Sha256::digest(b""));
+        let master_secret = hkdf_extract(&salt, &[0u8; 32]);
+
+        self.client_ap_traffic_secret = Some(derive_secret(&master_secret, b"c ap traffic", &hash));
+        self.server_ap_traffic_secret = Some(derive_secret(&master_secret, b"s ap traffic", &hash));
+
+        Ok(())
+    }
+
+    async fn get_server_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
         let verify_data = match self.protocol_version.ok_or(BackendError::InvalidState(
             "Protocol version not set".to_string(),
         ))? {
-            ProtocolVersion::TLSv1_2 => self.verify_data_sf_tls12(&hash, &ms),
+            ProtocolVersion::TLSv1_2 => {
+                let ms = self.master_secret.ok_or(BackendError::InvalidState(
+                    "Master secret not set".to_string(),
+                ))?;
+                self.verify_data_sf_tls12(&hash, &ms).to_vec()
+            }
+            ProtocolVersion::TLSv1_3 => {
+                let secret = self
+                    .server_hs_traffic_secret
+                    .ok_or(BackendError::InvalidState(
+                        "Server handshake traffic secret not set".to_string(),
+                    ))?;
+                verify_data_tls13(&secret, &hash).to_vec()
+            }
             _ => unreachable!(),
         };
-        Ok(verify_data.to_vec())
+        Ok(verify_data)
     }
 
     async fn get_client_finished_vd(&mut self, hash: Vec<u8>) -> Result<Vec<u8>, BackendError> {
-        let ms = self.master_secret.ok_or(BackendError::InvalidState(
-            "Master secret not set".to_string(),
-        ))?;
-
         let verify_data = match self.protocol_version.ok_or(BackendError::InvalidState(
             "Protocol version not set".to_string(),
         ))? {
-            ProtocolVersion::TLSv1_2 => self.verify_data_cf_tls12(&hash, &ms),
+            ProtocolVersion::TLSv1_2 => {
+                let ms = self.master_secret.ok_or(BackendError::InvalidState(
+                    "Master secret not set".to_string(),
+                ))?;
+                self.verify_data_cf_tls12(&hash, &ms).to_vec()
+            }
+            ProtocolVersion::TLSv1_3 => {
+                let secret = self
+                    .client_hs_traffic_secret
+                    .ok_or(BackendError::InvalidState(
+                        "Client handshake traffic secret not set".to_string(),
+                    ))?;
+                verify_data_tls13(&secret, &hash).to_vec()
+            }
             _ => unreachable!(),
         };
-        Ok(verify_data.to_vec())
+        Ok(verify_data)
     }
 
     async fn prepare_encryption(&mut self) -> Result<(), BackendError> {
@@ -472,14 +661,158 @@ fn concat<const O: usize>(left: &[u8], right: &[u8]) -> [u8; O] {
     out
 }
 
+/// Returns the write key and IV length of an implemented cipher suite.
+fn key_iv_len(suite: CipherSuite) -> Option<(usize, usize)> {
+    match suite {
+        CipherSuite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
+        | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256 => Some((16, 4)),
+        CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+        | CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384 => Some((32, 4)),
+        CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+        | CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256 => Some((32, 12)),
+        CipherSuite::TLS13_AES_128_GCM_SHA256 => Some((16, 12)),
+        _ => None,
+    }
+}
+
+/// Returns the TLS 1.2 additional data of a record.
+fn make_aad(typ: ContentType, version: ProtocolVersion, seq: u64, len: usize) -> [u8; 13] {
+    let mut aad = [0u8; 13];
+    aad[..8].copy_from_slice(&seq.to_be_bytes());
+    aad[8] = typ.get_u8();
+    aad[9..11].copy_from_slice(&version.get_u16().to_be_bytes());
+    aad[11..13].copy_from_slice(&(len as u16).to_be_bytes());
+    aad
+}
+
+/// Returns the per-record nonce, which is the IV XORed with the left-padded
+/// sequence number. ChaCha20-Poly1305 uses it in TLS 1.2, see RFC 7905, and
+/// all cipher suites use it in TLS 1.3, see section 5.3 of RFC 8446.
+fn xor_nonce(iv: &[u8], seq: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(iv);
+    nonce[4..]
+        .iter_mut()
+        .zip(seq.to_be_bytes())
+        .for_each(|(a, b)| *a ^= b);
+    nonce
+}
+
+/// Returns the TLS 1.3 additional data of a record, which is the record
+/// header.
+fn make_tls13_aad(len: usize) -> [u8; 5] {
+    let mut aad = [0u8; 5];
+    aad[0] = ContentType::ApplicationData.get_u8();
+    aad[1..3].copy_from_slice(&ProtocolVersion::TLSv1_2.get_u16().to_be_bytes());
+    aad[3..5].copy_from_slice(&(len as u16).to_be_bytes());
+    aad
+}
+
+/// HKDF-Extract with SHA-256, see RFC 5869.
+fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; 32] {
+    hmac_sha256(salt, ikm)
+}
+
+/// HKDF-Expand-Label with SHA-256, see section 7.1 of RFC 8446.
+///
+/// Only supports outputs up to 32 bytes.
+fn hkdf_expand_label(secret: &[u8; 32], label: &[u8], context: &[u8], len: usize) -> Vec<u8> {
+    const LABEL_PREFIX: &[u8] = b"tls13 ";
+
+    let mut info = Vec::new();
+    info.extend_from_slice(&(len as u16).to_be_bytes());
+    info.push((LABEL_PREFIX.len() + label.len()) as u8);
+    info.extend_from_slice(LABEL_PREFIX);
+    info.extend_from_slice(label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+    // HKDF-Expand block counter.
+    info.push(1);
+
+    let mut output = hmac_sha256(secret, &info).to_vec();
+    output.truncate(len);
+    output
+}
+
+/// Derive-Secret with SHA-256, see section 7.1 of RFC 8446.
+fn derive_secret(secret: &[u8; 32], label: &[u8], hash: &[u8]) -> [u8; 32] {
+    hkdf_expand_label(secret, label, hash, 32)
+        .try_into()
+        .expect("secret is 32 bytes")
+}
+
+/// Computes the TLS 1.3 Finished verify_data from a handshake traffic
+/// secret, see section 4.4.4 of RFC 8446.
+fn verify_data_tls13(secret: &[u8; 32], hs_hash: &[u8]) -> [u8; 32] {
+    let finished_key: [u8; 32] = hkdf_expand_label(secret, b"finished", &[], 32)
+        .try_into()
+        .expect("finished key is 32 bytes");
+    hmac_sha256(&finished_key, hs_hash)
+}
+
+fn hmac_sha256(key: &[u8], msg: &[u8]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(msg);
+    mac.finalize().into_bytes().into()
+}
+
+/// Encrypts `payload` with AES-GCM, selecting AES-128 or AES-256 by the key
+/// length.
+fn aes_gcm_encrypt(
+    key: &[u8],
+    nonce: &[u8; 12],
+    payload: Payload,
+) -> Result<Vec<u8>, BackendError> {
+    let nonce = GenericArray::from_slice(nonce);
+    match key.len() {
+        16 => Aes128Gcm::new_from_slice(key)
+            .unwrap()
+            .encrypt(nonce, payload),
+        32 => Aes256Gcm::new_from_slice(key)
+            .unwrap()
+            .encrypt(nonce, payload),
+        len => {
+            return Err(BackendError::EncryptionError(format!(
+                "invalid key length: {len}"
+            )))
+        }
+    }
+    .map_err(|e| BackendError::EncryptionError(e.to_string()))
+}
+
+/// Decrypts `payload` with AES-GCM, selecting AES-128 or AES-256 by the key
+/// length.
+fn aes_gcm_decrypt(
+    key: &[u8],
+    nonce: &[u8; 12],
+    payload: Payload,
+) -> Result<Vec<u8>, BackendError> {
+    let nonce = GenericArray::from_slice(nonce);
+    match key.len() {
+        16 => Aes128Gcm::new_from_slice(key)
+            .unwrap()
+            .decrypt(nonce, payload),
+        32 => Aes256Gcm::new_from_slice(key)
+            .unwrap()
+            .decrypt(nonce, payload),
+        len => {
+            return Err(BackendError::DecryptionError(format!(
+                "invalid key length: {len}"
+            )))
+        }
+    }
+    .map_err(|e| BackendError::DecryptionError(e.to_string()))
+}
+
 pub struct Encrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
+    write_key: Vec<u8>,
+    write_iv: Vec<u8>,
     cipher_suite: CipherSuite,
 }
 
 impl Encrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+    pub fn new(write_key: Vec<u8>, write_iv: Vec<u8>, cipher_suite: CipherSuite) -> Self {
         Self {
             write_key,
             write_iv,
@@ -487,8 +820,8 @@ impl Encrypter {
         }
     }
 
-    /// Encrypt with AES128GCM using TLS-specific AAD.
-    fn encrypt_aes128gcm(
+    /// Encrypt with AES-GCM using TLS-specific AAD.
+    fn encrypt_aes_gcm(
         &self,
         m: &PlainMessage,
         seq: u64,
@@ -507,12 +840,8 @@ impl Encrypter {
         let mut nonce = [0u8; 12];
         nonce[..4].copy_from_slice(&self.write_iv);
         nonce[4..].copy_from_slice(explicit_nonce);
-        let nonce = GenericArray::from_slice(&nonce);
-        let cipher = Aes128Gcm::new_from_slice(&self.write_key).unwrap();
         // ciphertext will have the MAC appended
-        let ciphertext = cipher
-            .encrypt(nonce, payload)
-            .map_err(|e| BackendError::EncryptionError(e.to_string()))?;
+        let ciphertext = aes_gcm_encrypt(&self.write_key, &nonce, payload)?;
 
         // prepend the explicit nonce
         let mut nonce_ct_mac = vec![0u8; 0];
@@ -526,16 +855,69 @@ impl Encrypter {
 
         Ok(om)
     }
+
+    /// Encrypt with ChaCha20-Poly1305 using TLS-specific AAD.
+    ///
+    /// No explicit nonce is transmitted, see RFC 7905.
+    fn encrypt_chacha20_poly1305(
+        &self,
+        m: &PlainMessage,
+        seq: u64,
+    ) -> Result<OpaqueMessage, BackendError> {
+        let aad = make_aad(m.typ, m.version, seq, m.payload.0.len());
+        let payload = Payload {
+            msg: &m.payload.0,
+            aad: &aad,
+        };
+
+        let nonce = xor_nonce(&self.write_iv, seq);
+        // ciphertext will have the MAC appended
+        let ciphertext = ChaCha20Poly1305::new_from_slice(&self.write_key)
+            .map_err(|e| BackendError::EncryptionError(e.to_string()))?
+            .encrypt(GenericArray::from_slice(&nonce), payload)
+            .map_err(|e| BackendError::EncryptionError(e.to_string()))?;
+
+        Ok(OpaqueMessage {
+            typ: m.typ,
+            version: m.version,
+            payload: TLSPayload::new(ciphertext),
+        })
+    }
+
+    /// Encrypt a TLS 1.3 record, which hides the content type in the
+    /// ciphertext.
+    fn encrypt_tls13(&self, m: &PlainMessage, seq: u64) -> Result<OpaqueMessage, BackendError> {
+        let mut inner_plaintext = Vec::with_capacity(m.payload.0.len() + 1);
+        inner_plaintext.extend_from_slice(&m.payload.0);
+        inner_plaintext.push(m.typ.get_u8());
+
+        // The 16-byte MAC is counted towards the record length.
+        let aad = make_tls13_aad(inner_plaintext.len() + 16);
+        let payload = Payload {
+            msg: &inner_plaintext,
+            aad: &aad,
+        };
+
+        let nonce = xor_nonce(&self.write_iv, seq);
+        // ciphertext will have the MAC appended
+        let ciphertext = aes_gcm_encrypt(&self.write_key, &nonce, payload)?;
+
+        Ok(OpaqueMessage {
+            typ: ContentType::ApplicationData,
+            version: ProtocolVersion::TLSv1_2,
+            payload: TLSPayload::new(ciphertext),
+        })
+    }
 }
 
 pub struct Decrypter {
-    write_key: [u8; 16],
-    write_iv: [u8; 4],
+    write_key: Vec<u8>,
+    write_iv: Vec<u8>,
     cipher_suite: CipherSuite,
 }
 
 impl Decrypter {
-    pub fn new(write_key: [u8; 16], write_iv: [u8; 4], cipher_suite: CipherSuite) -> Self {
+    pub fn new(write_key: Vec<u8>, write_iv: Vec<u8>, cipher_suite: CipherSuite) -> Self {
         Self {
             write_key,
             write_iv,
@@ -543,7 +925,7 @@ impl Decrypter {
         }
     }
 
-    fn decrypt_aes128gcm(&self, m: &OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
+    fn decrypt_aes_gcm(&self, m: &OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
         // TODO tls-client shouldnt call decrypt with CCS
         if m.typ == ContentType::ChangeCipherSpec {
             return Ok(PlainMessage {
@@ -564,13 +946,45 @@ impl Decrypter {
             aad: &aad,
         };
 
-        let cipher = Aes128Gcm::new_from_slice(&self.write_key).unwrap();
         let mut nonce = [0u8; 12];
         nonce[..4].copy_from_slice(&self.write_iv);
         nonce[4..].copy_from_slice(&m.payload.0[0..8]);
-        let nonce = GenericArray::from_slice(&nonce);
-        let plaintext = cipher
-            .decrypt(nonce, aes_payload)
+        let plaintext = aes_gcm_decrypt(&self.write_key, &nonce, aes_payload)?;
+
+        Ok(PlainMessage {
+            typ: m.typ,
+            version: m.version,
+            payload: TLSPayload(plaintext),
+        })
+    }
+
+    fn decrypt_chacha20_poly1305(
+        &self,
+        m: &OpaqueMessage,
+        seq: u64,
+    ) -> Result<PlainMessage, BackendError> {
+        // TODO tls-client shouldnt call decrypt with CCS
+        if m.typ == ContentType::ChangeCipherSpec {
+            return Ok(PlainMessage {
+                typ: m.typ,
+                version: m.version,
+                payload: TLSPayload(m.payload.0.clone()),
+            });
+        }
+        // The 16-byte MAC is not counted towards plaintext size.
+        let len = m.payload.0.len().checked_sub(16).ok_or_else(|| {
+            BackendError::DecryptionError("record is shorter than the tag".to_string())
+        })?;
+        let aad = make_aad(m.typ, m.version, seq, len);
+        let payload = Payload {
+            msg: &m.payload.0,
+            aad: &aad,
+        };
+
+        let nonce = xor_nonce(&self.write_iv, seq);
+        let plaintext = ChaCha20Poly1305::new_from_slice(&self.write_key)
+            .map_err(|e| BackendError::DecryptionError(e.to_string()))?
+            .decrypt(GenericArray::from_slice(&nonce), payload)
             .map_err(|e| BackendError::DecryptionError(e.to_string()))?;
 
         Ok(PlainMessage {
@@ -579,4 +993,29 @@ impl Decrypter {
             payload: TLSPayload(plaintext),
         })
     }
+    /// Decrypt a TLS 1.3 record, recovering the content type from the end of
+    /// the plaintext.
+    fn decrypt_tls13(&self, m: &OpaqueMessage, seq: u64) -> Result<PlainMessage, BackendError> {
+        let aad = make_tls13_aad(m.payload.0.len());
+        let payload = Payload {
+            msg: &m.payload.0,
+            aad: &aad,
+        };
+
+        let nonce = xor_nonce(&self.write_iv, seq);
+        let mut plaintext = aes_gcm_decrypt(&self.write_key, &nonce, payload)?;
+
+        // Strip the padding, the last non-zero byte is the content type.
+        let typ_pos = plaintext.iter().rposition(|b| *b != 0).ok_or_else(|| {
+            BackendError::DecryptionError("record has no content type".to_string())
+        })?;
+        let typ = ContentType::from(plaintext[typ_pos]);
+        plaintext.truncate(typ_pos);
+
+        Ok(PlainMessage {
+            typ,
+            version: ProtocolVersion::TLSv1_3,
+            payload: TLSPayload(plaintext),
+        })
+    }
 }
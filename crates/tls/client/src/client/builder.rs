@@ -157,6 +157,7 @@ impl ConfigBuilder<WantsClientCert> {
             enable_tickets: true,
             versions: self.state.versions,
             enable_sni: true,
+            require_ems: false,
             verifier: self.state.verifier,
             key_log: Arc::new(NoKeyLog {}),
             enable_early_data: false,
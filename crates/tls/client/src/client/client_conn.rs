@@ -135,6 +135,12 @@ pub struct ClientConfig {
     /// The default is true.
     pub enable_sni: bool,
 
+    /// Whether to send the Extended Master Secret (RFC 7627) extension and
+    /// require the server to negotiate it for TLS 1.2.
+    ///
+    /// The default is false.
+    pub require_ems: bool,
+
     /// How to verify the server certificate chain.
     pub(super) verifier: Arc<dyn verify::ServerCertVerifier>,
 
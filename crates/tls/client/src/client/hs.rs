@@ -163,10 +163,13 @@ async fn emit_client_hello_for_retry(
         ClientExtension::ECPointFormats(ECPointFormatList::supported()),
         ClientExtension::NamedGroups(config.kx_groups.iter().map(|skxg| skxg.name).collect()),
         ClientExtension::SignatureAlgorithms(config.verifier.supported_verify_schemes()),
-        //ClientExtension::ExtendedMasterSecretRequest,
         ClientExtension::CertificateStatusRequest(CertificateStatusRequest::build_ocsp()),
     ];
 
+    if config.require_ems {
+        exts.push(ClientExtension::ExtendedMasterSecretRequest);
+    }
+
     if let (Some(sni_name), true) = (server_name.for_sni(), config.enable_sni) {
         exts.push(ClientExtension::make_sni(sni_name));
     }
@@ -77,6 +77,14 @@ mod server_hello {
 
             // Doing EMS?
             self.using_ems = server_hello.ems_support_acked();
+            if self.config.require_ems && !self.using_ems {
+                cx.common
+                    .send_fatal_alert(AlertDescription::HandshakeFailure)
+                    .await?;
+                return Err(Error::PeerIncompatibleError(
+                    "server does not support extended master secret".to_string(),
+                ));
+            }
 
             // Might the server send a ticket?
             let must_issue_new_ticket = if server_hello
@@ -758,12 +766,14 @@ impl State<ClientConnectionData> for ExpectServerDone {
         let mut transcript = st.transcript;
         emit_clientkx(&mut transcript, cx.common, &key_share).await?;
         // nb. EMS handshake hash only runs up to ClientKeyExchange.
-        let ems_seed = transcript.get_current_hash();
+        if st.using_ems {
+            let ems_seed = transcript.get_current_hash();
 
-        cx.common
-            .backend
-            .set_hs_hash_client_key_exchange(ems_seed.as_ref().to_vec())
-            .await?;
+            cx.common
+                .backend
+                .set_hs_hash_client_key_exchange(ems_seed.as_ref().to_vec())
+                .await?;
+        }
 
         // 5c.
         if let Some(ClientAuthDetails::Verify { signer, .. }) = &st.client_auth {
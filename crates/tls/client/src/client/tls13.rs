@@ -128,6 +128,7 @@ pub(super) async fn handle_server_hello(
         .backend
         .set_decrypt(DecryptMode::Handshake)
         .await?;
+    cx.common.record_layer.set_message_decrypter();
 
     if !cx.data.early_data.is_enabled() {
         // Set the client encryption key for handshakes if early data is not used
@@ -135,6 +136,7 @@ pub(super) async fn handle_server_hello(
             .backend
             .set_encrypt(EncryptMode::Handshake)
             .await?;
+        cx.common.record_layer.set_message_encrypter();
     }
 
     emit_fake_ccs(&mut sent_tls13_fake_ccs, cx.common).await?;
@@ -536,6 +538,15 @@ impl State<ClientConnectionData> for ExpectCertificateVerify {
             Err(e) => return Err(hs::send_cert_error_alert(cx.common, Error::CoreError(e)).await?),
         };
 
+        cx.common
+            .backend
+            .set_server_cert_details(self.server_cert.clone())
+            .await?;
+        cx.common
+            .backend
+            .set_server_signature_tls13(self.transcript.messages().to_vec(), cert_verify.clone())
+            .await?;
+
         cx.common.peer_certificates = Some(self.server_cert.cert_chain().to_vec());
         self.transcript.add_message(&m);
 
@@ -687,6 +698,11 @@ impl State<ClientConnectionData> for ExpectFinished {
 
         st.transcript.add_message(&m);
 
+        cx.common
+            .backend
+            .set_hs_hash_server_finished(st.transcript.get_current_hash().as_ref().to_vec())
+            .await?;
+
         /* The EndOfEarlyData message to server is still encrypted with early data
          * keys, but appears in the transcript after the server Finished. */
         if cx.common.early_traffic {
@@ -741,10 +757,12 @@ impl State<ClientConnectionData> for ExpectFinished {
             .backend
             .set_encrypt(EncryptMode::Application)
             .await?;
+        cx.common.record_layer.set_message_encrypter();
         cx.common
             .backend
             .set_decrypt(DecryptMode::Application)
             .await?;
+        cx.common.record_layer.set_message_decrypter();
 
         cx.common.start_traffic().await?;
 
@@ -858,7 +876,9 @@ impl State<ClientConnectionData> for ExpectTraffic {
     }
 
     async fn perhaps_write_key_update(&mut self, _common: &mut CommonState) {
-        unimplemented!()
+        // Key updates are not supported, so there is never one pending. This
+        // is called before every write of application data.
+        //
         // if self.want_write_key_update {
         //     self.want_write_key_update = false;
         //     common
@@ -74,6 +74,7 @@ impl HandshakeHashBuffer {
         ctx.update(&self.buffer);
         HandshakeHash {
             ctx,
+            messages: self.buffer.clone(),
             client_auth: match self.client_auth_enabled {
                 true => Some(self.buffer),
                 false => None,
@@ -93,6 +94,9 @@ pub(crate) struct HandshakeHash {
     /// None before we know what hash function we're using
     ctx: digest::Context,
 
+    /// log of all the hashed handshake messages.
+    messages: Vec<u8>,
+
     /// buffer for client-auth.
     client_auth: Option<Vec<u8>>,
 }
@@ -116,6 +120,7 @@ impl HandshakeHash {
     /// Hash or buffer a byte slice.
     fn update_raw(&mut self, buf: &[u8]) -> &mut Self {
         self.ctx.update(buf);
+        self.messages.extend_from_slice(buf);
 
         if let Some(buffer) = &mut self.client_auth {
             buffer.extend_from_slice(buf);
@@ -154,6 +159,7 @@ impl HandshakeHash {
         let old_handshake_hash_msg =
             HandshakeMessagePayload::build_handshake_hash(old_hash.as_ref());
 
+        self.messages.clear();
         self.update_raw(&old_handshake_hash_msg.get_encoding());
     }
 
@@ -162,6 +168,11 @@ impl HandshakeHash {
         self.ctx.clone().finish()
     }
 
+    /// Returns the encoded handshake messages which have been hashed so far.
+    pub(crate) fn messages(&self) -> &[u8] {
+        &self.messages
+    }
+
     /// Takes this object's buffer containing all handshake messages
     /// so far.  This method only works once; it resets the buffer
     /// to empty.
@@ -189,6 +200,7 @@ mod test {
         let mut hh = hhb.start_hash(&HashAlgorithm::SHA256);
         assert!(hh.client_auth.is_none());
         hh.update_raw(b"world");
+        assert_eq!(hh.messages(), b"helloworld");
         let h = hh.get_current_hash();
         let h = h.as_ref();
         assert_eq!(h[0], 0x93);
@@ -1627,41 +1627,41 @@ static TEST_CIPHERSUITES: &[(&tls_client::SupportedProtocolVersion, KeyType, Cip
     //     KeyType::Rsa,
     //     CipherSuite::TLS13_AES_256_GCM_SHA384,
     // ),
-    // (
-    //     &tls_client::version::TLS13,
-    //     KeyType::Rsa,
-    //     CipherSuite::TLS13_AES_128_GCM_SHA256,
-    // ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Ecdsa,
-    //     CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
-    // ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Rsa,
-    //     CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
-    // ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Ecdsa,
-    //     CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
-    // ),
+    (
+        &tls_client::version::TLS13,
+        KeyType::Rsa,
+        CipherSuite::TLS13_AES_128_GCM_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Ecdsa,
+        CipherSuite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Rsa,
+        CipherSuite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Ecdsa,
+        CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+    ),
     #[cfg(feature = "tls12")]
     (
         &tls_client::version::TLS12,
         KeyType::Ecdsa,
         CipherSuite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
     ),
-    // #[cfg(feature = "tls12")]
-    // (
-    //     &tls_client::version::TLS12,
-    //     KeyType::Rsa,
-    //     CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
-    // ),
+    #[cfg(feature = "tls12")]
+    (
+        &tls_client::version::TLS12,
+        KeyType::Rsa,
+        CipherSuite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+    ),
     #[cfg(feature = "tls12")]
     (
         &tls_client::version::TLS12,
@@ -1,14 +1,18 @@
 //! Implementation of the TLS Pseudo-Random Function (PRF) as defined in RFC 5246.
 
-use hmac::Mac;
+use hmac::{
+    digest::{KeyInit, OutputSizeUser},
+    Mac,
+};
 
-type Hmac = hmac::Hmac<sha2::Sha256>;
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+type HmacSha384 = hmac::Hmac<sha2::Sha384>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
 #[error("invalid prf key length")]
 pub struct InvalidKeyLength;
 
-/// Fills the given buffer with the output of the TLS PRF.
+/// Fills the given buffer with the output of the TLS PRF using SHA-256.
 ///
 /// # Arguments
 ///
@@ -22,13 +26,41 @@ pub fn prf(
     label: &[u8],
     seed: &[u8],
 ) -> Result<(), InvalidKeyLength> {
-    let keyed_hmac = Hmac::new_from_slice(secret).map_err(|_| InvalidKeyLength)?;
+    p_hash::<HmacSha256>(out, secret, label, seed)
+}
+
+/// Fills the given buffer with the output of the TLS PRF using SHA-384.
+///
+/// This PRF is used by the `*_SHA384` cipher suites.
+///
+/// # Arguments
+///
+/// * `out` - The buffer to fill with the output.
+/// * `secret` - prf secret.
+/// * `label` - prf label, e.g. "master secret".
+/// * `seed` - prf seed, e.g. client_random + server_random.
+pub fn prf_sha384(
+    out: &mut [u8],
+    secret: &[u8],
+    label: &[u8],
+    seed: &[u8],
+) -> Result<(), InvalidKeyLength> {
+    p_hash::<HmacSha384>(out, secret, label, seed)
+}
+
+fn p_hash<M: Mac + KeyInit + Clone>(
+    out: &mut [u8],
+    secret: &[u8],
+    label: &[u8],
+    seed: &[u8],
+) -> Result<(), InvalidKeyLength> {
+    let keyed_hmac = <M as Mac>::new_from_slice(secret).map_err(|_| InvalidKeyLength)?;
 
     let mut current_a = keyed_hmac.clone();
     current_a.update(label);
     current_a.update(seed);
 
-    for chunk in out.chunks_mut(32) {
+    for chunk in out.chunks_mut(M::output_size()) {
         let a = std::mem::replace(&mut current_a, keyed_hmac.clone())
             .finalize()
             .into_bytes();
@@ -67,10 +99,28 @@ mod tests {
         assert_eq!(out, ring_out);
     }
 
+    #[test]
+    fn test_prf_sha384() {
+        let secret = [42u8; 48];
+        let seed = [69u8; 64];
+        let label = b"key expansion";
+
+        let mut out = [0u8; 136];
+        prf_sha384(&mut out, &secret, label, &seed).unwrap();
+
+        let mut ring_out = [0u8; 136];
+        ring_prf::prf_sha384(&mut ring_out, &secret, label, &seed);
+
+        assert_eq!(out, ring_out);
+    }
+
     // Borrowed from Rustls for testing
     // https://github.com/rustls/rustls/blob/main/rustls/src/tls12/prf.rs
     mod ring_prf {
-        use ring::{hmac, hmac::HMAC_SHA256};
+        use ring::{
+            hmac,
+            hmac::{HMAC_SHA256, HMAC_SHA384},
+        };
 
         fn concat_sign(key: &hmac::Key, a: &[u8], b: &[u8]) -> hmac::Tag {
             let mut ctx = hmac::Context::with_key(key);
@@ -79,12 +129,12 @@ mod tests {
             ctx.sign()
         }
 
-        fn p(out: &mut [u8], secret: &[u8], seed: &[u8]) {
-            let hmac_key = hmac::Key::new(HMAC_SHA256, secret);
+        fn p(alg: hmac::Algorithm, out: &mut [u8], secret: &[u8], seed: &[u8]) {
+            let hmac_key = hmac::Key::new(alg, secret);
 
             // A(1)
             let mut current_a = hmac::sign(&hmac_key, seed);
-            let chunk_size = HMAC_SHA256.digest_algorithm().output_len();
+            let chunk_size = alg.digest_algorithm().output_len();
             for chunk in out.chunks_mut(chunk_size) {
                 // P_hash[i] = HMAC_hash(secret, A(i) + seed)
                 let p_term = concat_sign(&hmac_key, current_a.as_ref(), seed);
@@ -104,7 +154,12 @@ mod tests {
 
         pub(crate) fn prf(out: &mut [u8], secret: &[u8], label: &[u8], seed: &[u8]) {
             let joined_seed = concat(label, seed);
-            p(out, secret, &joined_seed);
+            p(HMAC_SHA256, out, secret, &joined_seed);
+        }
+
+        pub(crate) fn prf_sha384(out: &mut [u8], secret: &[u8], label: &[u8], seed: &[u8]) {
+            let joined_seed = concat(label, seed);
+            p(HMAC_SHA384, out, secret, &joined_seed);
         }
     }
 }
@@ -118,6 +118,11 @@ impl SupportedCipherSuite {
         self.common().suite
     }
 
+    /// The AEAD algorithm of the suite.
+    pub fn aead_algorithm(&self) -> &'static AEADAlgorithm {
+        self.common().aead_algorithm
+    }
+
     pub(crate) fn common(&self) -> &CipherSuiteCommon {
         match self {
             #[cfg(feature = "tls12")]
@@ -158,23 +163,23 @@ impl SupportedCipherSuite {
 
 /// A list of all the cipher suites supported by rustls.
 pub static ALL_CIPHER_SUITES: &[SupportedCipherSuite] = &[
-    // // TLS1.3 suites
+    // TLS1.3 suites
     // TLS13_AES_256_GCM_SHA384,
-    // TLS13_AES_128_GCM_SHA256,
+    TLS13_AES_128_GCM_SHA256,
     // TLS13_CHACHA20_POLY1305_SHA256,
     // TLS1.2 suites
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
     #[cfg(feature = "tls12")]
     TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
     #[cfg(feature = "tls12")]
     TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
-    // #[cfg(feature = "tls12")]
-    // TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+    #[cfg(feature = "tls12")]
+    TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
 ];
 
 /// The cipher suite configuration that an application should use by default.
@@ -19,7 +19,12 @@ use hyper::{
     Method, Request, Response, StatusCode,
 };
 use hyper_util::rt::TokioIo;
-use std::{io::Write, sync::Arc};
+use std::{
+    io::Write,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 use tokio_util::{
     compat::{Compat, FuturesAsyncReadCompatExt},
     io::SyncIoBridge,
@@ -39,6 +44,11 @@ pub static APP_RECORD_LENGTH: usize = 1024;
 /// How many ms to delay before closing the socket
 pub static CLOSE_DELAY: u64 = 1000;
 
+/// The extension type of the Extended Master Secret extension (RFC 7627).
+const EXTENDED_MASTER_SECRET: u16 = 0x0017;
+/// A fatal `handshake_failure` alert record.
+const HANDSHAKE_FAILURE_ALERT: [u8; 7] = [0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x28];
+
 /// Binds a `hyper::server` test server to the provided socket.
 #[tracing::instrument(skip(socket))]
 pub async fn bind_test_server_hyper<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
@@ -67,6 +77,41 @@ pub async fn bind_test_server_hyper<T: AsyncRead + AsyncWrite + Send + Unpin + '
         .await
 }
 
+/// Binds a `hyper::server` test server to the provided socket which requires
+/// the client to negotiate the Extended Master Secret (RFC 7627).
+///
+/// If the ClientHello does not offer the extension, the server aborts the
+/// handshake with a `handshake_failure` alert.
+#[tracing::instrument(skip(socket))]
+pub async fn bind_test_server_hyper_ems<T: AsyncRead + AsyncWrite + Send + Unpin + 'static>(
+    mut socket: T,
+) -> Result<(), hyper::Error> {
+    let mut header = [0u8; 5];
+    socket.read_exact(&mut header).await.unwrap();
+
+    let len = u16::from_be_bytes([header[3], header[4]]) as usize;
+    let mut client_hello = header.to_vec();
+    client_hello.resize(header.len() + len, 0);
+    socket
+        .read_exact(&mut client_hello[header.len()..])
+        .await
+        .unwrap();
+
+    if !offers_extension(&client_hello[header.len()..], EXTENDED_MASTER_SECRET) {
+        tracing::debug!("client did not offer the extended master secret");
+        socket.write_all(&HANDSHAKE_FAILURE_ALERT).await.unwrap();
+        socket.close().await.unwrap();
+        return Ok(());
+    }
+
+    bind_test_server_hyper(Prefixed {
+        prefix: client_hello,
+        pos: 0,
+        inner: socket,
+    })
+    .await
+}
+
 /// Binds a raw TLS test server to the provided socket.
 #[tracing::instrument(skip(socket))]
 pub async fn bind_test_server<
@@ -300,3 +345,91 @@ fn empty() -> BoxBody<Bytes, hyper::Error> {
         .map_err(|never| match never {})
         .boxed()
 }
+
+/// Returns whether the ClientHello in the provided handshake record payload
+/// offers the extension.
+fn offers_extension(payload: &[u8], ext_type: u16) -> bool {
+    fn skip(buf: &[u8], len: usize) -> Option<&[u8]> {
+        buf.get(len..)
+    }
+
+    fn read_len(buf: &[u8], size: usize) -> Option<(usize, &[u8])> {
+        let len = buf
+            .get(..size)?
+            .iter()
+            .fold(0, |len, byte| (len << 8) | *byte as usize);
+        Some((len, skip(buf, size)?))
+    }
+
+    let parse = || -> Option<bool> {
+        // Handshake type, length, client version and random.
+        let buf = skip(payload, 4 + 2 + 32)?;
+        // Session id, cipher suites and compression methods.
+        let (len, buf) = read_len(buf, 1)?;
+        let buf = skip(buf, len)?;
+        let (len, buf) = read_len(buf, 2)?;
+        let buf = skip(buf, len)?;
+        let (len, buf) = read_len(buf, 1)?;
+        let buf = skip(buf, len)?;
+
+        let (len, buf) = read_len(buf, 2)?;
+        let mut exts = buf.get(..len)?;
+        while !exts.is_empty() {
+            let (typ, buf) = read_len(exts, 2)?;
+            if typ == ext_type as usize {
+                return Some(true);
+            }
+            let (len, buf) = read_len(buf, 2)?;
+            exts = skip(buf, len)?;
+        }
+
+        Some(false)
+    };
+
+    parse().unwrap_or(false)
+}
+
+/// A socket which replays a prefix before reading from the inner socket.
+struct Prefixed<T> {
+    prefix: Vec<u8>,
+    pos: usize,
+    inner: T,
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for Prefixed<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+
+        if this.pos < this.prefix.len() {
+            let n = buf.len().min(this.prefix.len() - this.pos);
+            buf[..n].copy_from_slice(&this.prefix[this.pos..this.pos + n]);
+            this.pos += n;
+
+            return Poll::Ready(Ok(n));
+        }
+
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for Prefixed<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
@@ -3,9 +3,16 @@ use core::fmt;
 use once_cell::sync::Lazy;
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+use std::{error::Error, time::Duration};
 
-pub use tlsn_core::webpki::{CertificateDer, PrivateKeyDer, RootCertStore};
+mod cost;
+
+pub use cost::CostEstimate;
+
+pub use tlsn_core::{
+    connection::{AeadAlgorithm, KeyType, TlsVersion},
+    webpki::{CertificateDer, PrivateKeyDer, RootCertStore},
+};
 
 // Default is 32 bytes to decrypt the TLS protocol messages.
 const DEFAULT_MAX_RECV_ONLINE: usize = 32;
@@ -13,6 +20,8 @@ const DEFAULT_MAX_RECV_ONLINE: usize = 32;
 //
 // This would allow for up to 50Mb upload from prover to verifier.
 const DEFAULT_RECORDS_LIMIT: usize = 256;
+// Default maximum number of TLS connections per session.
+const DEFAULT_CONNECTIONS_LIMIT: usize = 1;
 
 // Current version that is running.
 static VERSION: Lazy<Version> = Lazy::new(|| {
@@ -40,6 +49,24 @@ pub struct ProtocolConfig {
     /// decrypted online, i.e. while the MPC-TLS connection is active.
     #[builder(setter(strip_option), default)]
     max_recv_records_online: Option<usize>,
+    /// Hard ceiling on the number of bytes that can be sent.
+    ///
+    /// If set, capacity beyond `max_sent_data` is allocated on demand during
+    /// the MPC-TLS connection up to this ceiling.
+    #[builder(setter(strip_option), default)]
+    sent_data_ceiling: Option<usize>,
+    /// Hard ceiling on the number of bytes that can be received.
+    ///
+    /// If set, capacity beyond `max_recv_data_online` and `max_recv_data` is
+    /// allocated on demand during the MPC-TLS connection up to this ceiling.
+    #[builder(setter(strip_option), default)]
+    recv_data_ceiling: Option<usize>,
+    /// Maximum number of sequential TLS connections of the session.
+    ///
+    /// MPC-TLS is preprocessed for each connection during setup, the data and
+    /// record limits apply to each connection.
+    #[builder(default = "DEFAULT_CONNECTIONS_LIMIT")]
+    max_connections: usize,
     /// Whether the `deferred decryption` feature is toggled on from the start
     /// of the MPC-TLS connection.
     #[builder(default = "true")]
@@ -47,6 +74,19 @@ pub struct ProtocolConfig {
     /// Network settings.
     #[builder(default)]
     network: NetworkSetting,
+    /// TLS protocol version used to connect to the server.
+    #[builder(default = "TlsVersion::V1_2")]
+    tls_version: TlsVersion,
+    /// Whether to require the extended master secret (RFC 7627) for TLS 1.2
+    /// connections.
+    #[builder(default)]
+    extended_master_secret: bool,
+    /// AEAD algorithm of the cipher suites offered to the server.
+    #[builder(default)]
+    aead: AeadAlgorithm,
+    /// Key exchange group offered to the server.
+    #[builder(default)]
+    key_type: KeyType,
     /// Version that is being run by prover/verifier.
     #[builder(setter(skip), default = "VERSION.clone()")]
     version: Version,
@@ -59,6 +99,33 @@ impl ProtocolConfigBuilder {
                 "max_recv_data_online must be smaller or equal to max_recv_data".to_string(),
             );
         }
+
+        if let Some(0) = self.max_connections {
+            return Err("max_connections must be at least 1".to_string());
+        }
+
+        if let (Some(Some(ceiling)), Some(max)) = (self.sent_data_ceiling, self.max_sent_data)
+            && ceiling < max
+        {
+            return Err("sent_data_ceiling must be greater or equal to max_sent_data".to_string());
+        }
+
+        if let (Some(Some(ceiling)), Some(max)) = (self.recv_data_ceiling, self.max_recv_data)
+            && ceiling < max
+        {
+            return Err("recv_data_ceiling must be greater or equal to max_recv_data".to_string());
+        }
+
+        match (self.tls_version, self.aead) {
+            (Some(TlsVersion::V1_3), Some(AeadAlgorithm::Aes256Gcm)) => {
+                return Err("AES-256-GCM is not supported with TLS 1.3".to_string());
+            }
+            (Some(TlsVersion::V1_3), Some(AeadAlgorithm::ChaCha20Poly1305)) => {
+                return Err("ChaCha20-Poly1305 is not supported with TLS 1.3".to_string());
+            }
+            _ => {}
+        }
+
         Ok(())
     }
 }
@@ -96,6 +163,22 @@ impl ProtocolConfig {
         self.max_recv_records_online
     }
 
+    /// Returns the hard ceiling on the number of bytes that can be sent.
+    pub fn sent_data_ceiling(&self) -> Option<usize> {
+        self.sent_data_ceiling
+    }
+
+    /// Returns the hard ceiling on the number of bytes that can be received.
+    pub fn recv_data_ceiling(&self) -> Option<usize> {
+        self.recv_data_ceiling
+    }
+
+    /// Returns the maximum number of sequential TLS connections of the
+    /// session.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
     /// Returns whether the `deferred decryption` feature is toggled on from the
     /// start of the MPC-TLS connection.
     pub fn defer_decryption_from_start(&self) -> bool {
@@ -106,6 +189,82 @@ impl ProtocolConfig {
     pub fn network(&self) -> NetworkSetting {
         self.network
     }
+
+    pub(crate) fn set_network(&mut self, network: NetworkSetting) {
+        self.network = network;
+    }
+
+    /// Builds the MPC-TLS configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is not accepted by MPC-TLS, which
+    /// can only happen if it was not created with [`ProtocolConfigBuilder`].
+    pub(crate) fn build_mpc_tls_config(&self) -> Result<mpc_tls::Config, ProtocolConfigError> {
+        let mut builder = mpc_tls::Config::builder();
+
+        builder
+            .defer_decryption(self.defer_decryption_from_start)
+            .max_sent(self.max_sent_data)
+            .max_recv_online(self.max_recv_data_online)
+            .max_recv(self.max_recv_data)
+            .version(self.tls_version)
+            .extended_master_secret(self.extended_master_secret)
+            .aead(self.aead)
+            .key_type(self.key_type);
+
+        if let Some(max_sent_records) = self.max_sent_records {
+            builder.max_sent_records(max_sent_records);
+        }
+
+        if let Some(max_recv_records_online) = self.max_recv_records_online {
+            builder.max_recv_records_online(max_recv_records_online);
+        }
+
+        self.set_ceilings(&mut builder);
+
+        if let NetworkSetting::Latency = self.network {
+            builder.low_bandwidth();
+        }
+
+        builder
+            .build()
+            .map_err(|err| ProtocolConfigError::new(ErrorKind::Invalid, err))
+    }
+
+    /// Sets the hard ceilings of the MPC-TLS configuration.
+    pub(crate) fn set_ceilings(&self, builder: &mut mpc_tls::ConfigBuilder) {
+        if let Some(sent_data_ceiling) = self.sent_data_ceiling {
+            builder.sent_ceiling(sent_data_ceiling);
+        }
+
+        if let Some(recv_data_ceiling) = self.recv_data_ceiling {
+            builder
+                .recv_online_ceiling(recv_data_ceiling)
+                .recv_ceiling(recv_data_ceiling);
+        }
+    }
+
+    /// Returns the TLS protocol version used to connect to the server.
+    pub fn tls_version(&self) -> TlsVersion {
+        self.tls_version
+    }
+
+    /// Returns whether the extended master secret is required for TLS 1.2
+    /// connections.
+    pub fn extended_master_secret(&self) -> bool {
+        self.extended_master_secret
+    }
+
+    /// Returns the AEAD algorithm of the cipher suites offered to the server.
+    pub fn aead(&self) -> AeadAlgorithm {
+        self.aead
+    }
+
+    /// Returns the key exchange group offered to the server.
+    pub fn key_type(&self) -> KeyType {
+        self.key_type
+    }
 }
 
 /// Protocol configuration validator used by checker (i.e. verifier) to perform
@@ -122,6 +281,9 @@ pub struct ProtocolConfigValidator {
     /// Maximum number of application data records that can be received online.
     #[builder(default = "DEFAULT_RECORDS_LIMIT")]
     max_recv_records_online: usize,
+    /// Maximum number of sequential TLS connections of a session.
+    #[builder(default = "DEFAULT_CONNECTIONS_LIMIT")]
+    max_connections: usize,
     /// Version that is being run by checker.
     #[builder(setter(skip), default = "VERSION.clone()")]
     version: Version,
@@ -155,15 +317,125 @@ impl ProtocolConfigValidator {
         self.max_recv_records_online
     }
 
+    /// Returns the maximum number of sequential TLS connections of a session.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Returns the limits which are advertised to the prover.
+    pub fn limits(&self) -> ProtocolLimits {
+        ProtocolLimits {
+            max_sent_data: self.max_sent_data,
+            max_sent_records: self.max_sent_records,
+            max_recv_data: self.max_recv_data,
+            max_recv_records_online: self.max_recv_records_online,
+            max_connections: self.max_connections,
+            versions: vec![self.version.clone()],
+        }
+    }
+
     /// Performs compatibility check of the protocol configuration between
     /// prover and verifier.
+    pub fn validate(&self, config: &ProtocolConfig) -> Result<(), ProtocolConfigError> {
+        self.limits().validate(config)
+    }
+}
+
+/// Protocol limits advertised by the verifier to the prover during setup.
+///
+/// The prover uses these to check its [`ProtocolConfig`], or to clamp it
+/// to the limits, before sending it to the verifier.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolLimits {
+    max_sent_data: usize,
+    max_sent_records: usize,
+    max_recv_data: usize,
+    max_recv_records_online: usize,
+    max_connections: usize,
+    versions: Vec<Version>,
+}
+
+impl ProtocolLimits {
+    /// Returns the maximum number of bytes that can be sent.
+    pub fn max_sent_data(&self) -> usize {
+        self.max_sent_data
+    }
+
+    /// Returns the maximum number of application data records that can
+    /// be sent.
+    pub fn max_sent_records(&self) -> usize {
+        self.max_sent_records
+    }
+
+    /// Returns the maximum number of bytes that can be received.
+    pub fn max_recv_data(&self) -> usize {
+        self.max_recv_data
+    }
+
+    /// Returns the maximum number of application data records that can
+    /// be received online.
+    pub fn max_recv_records_online(&self) -> usize {
+        self.max_recv_records_online
+    }
+
+    /// Returns the maximum number of sequential TLS connections of a session.
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    /// Returns the protocol versions supported by the verifier.
+    pub fn versions(&self) -> &[Version] {
+        &self.versions
+    }
+
+    /// Checks whether the protocol configuration is within the limits.
     pub fn validate(&self, config: &ProtocolConfig) -> Result<(), ProtocolConfigError> {
         self.check_max_transcript_size(config.max_sent_data, config.max_recv_data)?;
+        self.check_ceilings(
+            config.max_sent_data,
+            config.max_recv_data,
+            config.sent_data_ceiling,
+            config.recv_data_ceiling,
+        )?;
         self.check_max_records(config.max_sent_records, config.max_recv_records_online)?;
+        self.check_max_connections(config.max_connections)?;
+        self.check_cipher_suite(config.tls_version, config.aead)?;
         self.check_version(&config.version)?;
         Ok(())
     }
 
+    /// Returns the protocol configuration clamped to the limits.
+    ///
+    /// Data, record and connection limits which exceed the limits are lowered
+    /// to them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version of the configuration is not supported.
+    pub fn clamp(&self, config: &ProtocolConfig) -> Result<ProtocolConfig, ProtocolConfigError> {
+        self.check_version(&config.version)?;
+
+        let mut config = config.clone();
+        config.max_sent_data = config.max_sent_data.min(self.max_sent_data);
+        config.max_recv_data = config.max_recv_data.min(self.max_recv_data);
+        config.max_recv_data_online = config.max_recv_data_online.min(config.max_recv_data);
+        config.sent_data_ceiling = config
+            .sent_data_ceiling
+            .map(|ceiling| ceiling.min(self.max_sent_data));
+        config.recv_data_ceiling = config
+            .recv_data_ceiling
+            .map(|ceiling| ceiling.min(self.max_recv_data));
+        config.max_sent_records = config
+            .max_sent_records
+            .map(|records| records.min(self.max_sent_records));
+        config.max_recv_records_online = config
+            .max_recv_records_online
+            .map(|records| records.min(self.max_recv_records_online));
+        config.max_connections = config.max_connections.min(self.max_connections);
+
+        Ok(config)
+    }
+
     // Checks if both the sent and recv data are within limits.
     fn check_max_transcript_size(
         &self,
@@ -187,6 +459,54 @@ impl ProtocolConfigValidator {
         Ok(())
     }
 
+    // Checks if the ceilings up to which capacity is allocated on demand are
+    // within limits, and not below the data limits of the configuration.
+    fn check_ceilings(
+        &self,
+        max_sent_data: usize,
+        max_recv_data: usize,
+        sent_data_ceiling: Option<usize>,
+        recv_data_ceiling: Option<usize>,
+    ) -> Result<(), ProtocolConfigError> {
+        if let Some(sent_data_ceiling) = sent_data_ceiling
+            && sent_data_ceiling < max_sent_data
+        {
+            return Err(ProtocolConfigError::invalid(format!(
+                "sent_data_ceiling {} is smaller than max_sent_data {}",
+                sent_data_ceiling, max_sent_data,
+            )));
+        }
+
+        if let Some(recv_data_ceiling) = recv_data_ceiling
+            && recv_data_ceiling < max_recv_data
+        {
+            return Err(ProtocolConfigError::invalid(format!(
+                "recv_data_ceiling {} is smaller than max_recv_data {}",
+                recv_data_ceiling, max_recv_data,
+            )));
+        }
+
+        if let Some(sent_data_ceiling) = sent_data_ceiling
+            && sent_data_ceiling > self.max_sent_data
+        {
+            return Err(ProtocolConfigError::max_transcript_size(format!(
+                "sent_data_ceiling {} is greater than the configured limit {}",
+                sent_data_ceiling, self.max_sent_data,
+            )));
+        }
+
+        if let Some(recv_data_ceiling) = recv_data_ceiling
+            && recv_data_ceiling > self.max_recv_data
+        {
+            return Err(ProtocolConfigError::max_transcript_size(format!(
+                "recv_data_ceiling {} is greater than the configured limit {}",
+                recv_data_ceiling, self.max_recv_data,
+            )));
+        }
+
+        Ok(())
+    }
+
     fn check_max_records(
         &self,
         max_sent_records: Option<usize>,
@@ -213,13 +533,41 @@ impl ProtocolConfigValidator {
         Ok(())
     }
 
-    // Checks if both versions are the same (might support check for different but
+    fn check_max_connections(&self, max_connections: usize) -> Result<(), ProtocolConfigError> {
+        if max_connections > self.max_connections {
+            return Err(ProtocolConfigError::max_connection_count(format!(
+                "max_connections {} is greater than the configured limit {}",
+                max_connections, self.max_connections,
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Checks if the AEAD algorithm is supported with the TLS version.
+    fn check_cipher_suite(
+        &self,
+        tls_version: TlsVersion,
+        aead: AeadAlgorithm,
+    ) -> Result<(), ProtocolConfigError> {
+        match (tls_version, aead) {
+            (TlsVersion::V1_3, AeadAlgorithm::Aes256Gcm) => Err(ProtocolConfigError::invalid(
+                "AES-256-GCM is not supported with TLS 1.3",
+            )),
+            (TlsVersion::V1_3, AeadAlgorithm::ChaCha20Poly1305) => Err(
+                ProtocolConfigError::invalid("ChaCha20-Poly1305 is not supported with TLS 1.3"),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    // Checks if the version is supported (might support check for different but
     // compatible versions in the future).
     fn check_version(&self, peer_version: &Version) -> Result<(), ProtocolConfigError> {
-        if *peer_version != self.version {
+        if !self.versions.contains(peer_version) {
             return Err(ProtocolConfigError::version(format!(
-                "prover's version {:?} is different from verifier's version {:?}",
-                peer_version, self.version
+                "prover's version {:?} is not supported by the verifier, supported versions: {:?}",
+                peer_version, self.versions
             )));
         }
 
@@ -231,7 +579,7 @@ impl ProtocolConfigValidator {
 ///
 /// Provides optimization options to adapt the protocol to different network
 /// situations.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NetworkSetting {
     /// Reduces network round-trips at the expense of consuming more network
     /// bandwidth.
@@ -239,6 +587,10 @@ pub enum NetworkSetting {
     /// Reduces network bandwidth utilization at the expense of more network
     /// round-trips.
     Latency,
+    /// Measures the link between the prover and the verifier during setup and
+    /// selects [`Bandwidth`](Self::Bandwidth) or [`Latency`](Self::Latency)
+    /// accordingly.
+    Auto,
 }
 
 impl Default for NetworkSetting {
@@ -247,6 +599,87 @@ impl Default for NetworkSetting {
     }
 }
 
+/// Approximate number of additional round-trips with
+/// [`NetworkSetting::Latency`].
+const LATENCY_EXTRA_ROUND_TRIPS: u32 = 8;
+/// Approximate number of bytes saved with [`NetworkSetting::Latency`].
+const LATENCY_SAVED_BYTES: u64 = 7 << 20;
+
+/// Measurement of the link between the prover and the verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkMeasurement {
+    rtt: Duration,
+    throughput: u64,
+}
+
+impl LinkMeasurement {
+    /// Creates a new link measurement.
+    ///
+    /// # Arguments
+    ///
+    /// * `rtt` - The round-trip time.
+    /// * `throughput` - The throughput in bytes per second.
+    pub fn new(rtt: Duration, throughput: u64) -> Self {
+        Self { rtt, throughput }
+    }
+
+    /// Returns the round-trip time.
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+
+    /// Returns the throughput in bytes per second.
+    pub fn throughput(&self) -> u64 {
+        self.throughput
+    }
+
+    /// Returns the network setting which is expected to be faster on this
+    /// link.
+    pub fn network_setting(&self) -> NetworkSetting {
+        if self.throughput == 0 {
+            return NetworkSetting::Latency;
+        }
+
+        let round_trip_time = self.rtt * LATENCY_EXTRA_ROUND_TRIPS;
+        let transfer_time =
+            Duration::from_secs_f64(LATENCY_SAVED_BYTES as f64 / self.throughput as f64);
+
+        if round_trip_time < transfer_time {
+            NetworkSetting::Latency
+        } else {
+            NetworkSetting::Bandwidth
+        }
+    }
+}
+
+/// The network setting used for a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    /// The network setting, which is never [`NetworkSetting::Auto`].
+    pub setting: NetworkSetting,
+    /// The measured link, if the setting was selected automatically.
+    ///
+    /// The link is measured by the prover, which sends the measurement to the
+    /// verifier. Both parties select the setting from the same measurement.
+    pub link: Option<LinkMeasurement>,
+}
+
+/// How the prover handles a [`ProtocolConfig`] which exceeds the limits
+/// advertised by the verifier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitMode {
+    /// Fails the setup before the configuration is sent to the verifier.
+    ///
+    /// The error carries an [`Abort`](crate::verifier::Abort) of kind
+    /// [`Config`](crate::verifier::AbortKind::Config), the same as if the
+    /// verifier had rejected the configuration.
+    #[default]
+    Strict,
+    /// Lowers the data and record limits of the configuration to the
+    /// verifier's limits.
+    Clamp,
+}
+
 /// A ProtocolConfig error.
 #[derive(thiserror::Error, Debug)]
 pub struct ProtocolConfigError {
@@ -280,12 +713,26 @@ impl ProtocolConfigError {
         }
     }
 
+    fn max_connection_count(msg: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::MaxConnectionCount,
+            source: Some(msg.into().into()),
+        }
+    }
+
     fn version(msg: impl Into<String>) -> Self {
         Self {
             kind: ErrorKind::Version,
             source: Some(msg.into().into()),
         }
     }
+
+    fn invalid(msg: impl Into<String>) -> Self {
+        Self {
+            kind: ErrorKind::Invalid,
+            source: Some(msg.into().into()),
+        }
+    }
 }
 
 impl fmt::Display for ProtocolConfigError {
@@ -293,7 +740,9 @@ impl fmt::Display for ProtocolConfigError {
         match self.kind {
             ErrorKind::MaxTranscriptSize => write!(f, "max transcript size exceeded")?,
             ErrorKind::MaxRecordCount => write!(f, "max record count exceeded")?,
+            ErrorKind::MaxConnectionCount => write!(f, "max connection count exceeded")?,
             ErrorKind::Version => write!(f, "version error")?,
+            ErrorKind::Invalid => write!(f, "invalid configuration")?,
         }
 
         if let Some(ref source) = self.source {
@@ -308,7 +757,9 @@ impl fmt::Display for ProtocolConfigError {
 enum ErrorKind {
     MaxTranscriptSize,
     MaxRecordCount,
+    MaxConnectionCount,
     Version,
+    Invalid,
 }
 
 #[cfg(test)]
@@ -365,4 +816,167 @@ mod test {
 
         assert!(config_validator.validate(&peer_config).is_err())
     }
+
+    #[rstest]
+    #[case::within_limits(1 << 11, 1 << 13, 1 << 11, 1 << 13)]
+    #[case::bigger_max_sent_data(1 << 13, 1 << 13, TEST_MAX_SENT_LIMIT, 1 << 13)]
+    #[case::bigger_max_sent_recv_data(1 << 14, 1 << 21, TEST_MAX_SENT_LIMIT, TEST_MAX_RECV_LIMIT)]
+    fn test_clamp(
+        config_validator: &ProtocolConfigValidator,
+        #[case] max_sent_data: usize,
+        #[case] max_recv_data: usize,
+        #[case] expected_sent_data: usize,
+        #[case] expected_recv_data: usize,
+    ) {
+        let peer_config = ProtocolConfig::builder()
+            .max_sent_data(max_sent_data)
+            .max_recv_data(max_recv_data)
+            .max_recv_data_online(max_recv_data)
+            .max_sent_records(DEFAULT_RECORDS_LIMIT * 2)
+            .build()
+            .unwrap();
+
+        let limits = config_validator.limits();
+        let clamped = limits.clamp(&peer_config).unwrap();
+
+        assert_eq!(clamped.max_sent_data(), expected_sent_data);
+        assert_eq!(clamped.max_recv_data(), expected_recv_data);
+        assert_eq!(clamped.max_recv_data_online(), expected_recv_data);
+        assert_eq!(clamped.max_sent_records(), Some(DEFAULT_RECORDS_LIMIT));
+        assert!(config_validator.validate(&clamped).is_ok())
+    }
+
+    #[rstest]
+    #[case::within_limits(1 << 12, 1 << 14, true)]
+    #[case::bigger_sent_data_ceiling(1 << 13, 1 << 14, false)]
+    #[case::bigger_recv_data_ceiling(1 << 12, 1 << 16, false)]
+    fn test_check_ceilings(
+        config_validator: &ProtocolConfigValidator,
+        #[case] sent_data_ceiling: usize,
+        #[case] recv_data_ceiling: usize,
+        #[case] expected_ok: bool,
+    ) {
+        let peer_config = ProtocolConfig::builder()
+            .max_sent_data(1 << 10)
+            .max_recv_data(1 << 10)
+            .sent_data_ceiling(sent_data_ceiling)
+            .recv_data_ceiling(recv_data_ceiling)
+            .build()
+            .unwrap();
+
+        assert_eq!(config_validator.validate(&peer_config).is_ok(), expected_ok);
+
+        let clamped = config_validator.limits().clamp(&peer_config).unwrap();
+
+        assert_eq!(
+            clamped.sent_data_ceiling(),
+            Some(sent_data_ceiling.min(TEST_MAX_SENT_LIMIT))
+        );
+        assert_eq!(
+            clamped.recv_data_ceiling(),
+            Some(recv_data_ceiling.min(TEST_MAX_RECV_LIMIT))
+        );
+        assert!(config_validator.validate(&clamped).is_ok());
+        assert!(clamped.build_mpc_tls_config().unwrap().is_resizable());
+    }
+
+    #[rstest]
+    fn test_ceiling_below_max(config_validator: &ProtocolConfigValidator) {
+        let result = ProtocolConfig::builder()
+            .max_sent_data(1 << 10)
+            .max_recv_data(1 << 10)
+            .recv_data_ceiling(1 << 9)
+            .build();
+
+        assert!(result.is_err());
+
+        // A configuration which bypasses the builder, e.g. one received from
+        // the prover, is rejected by the verifier instead.
+        let mut peer_config = ProtocolConfig::builder()
+            .max_sent_data(1 << 10)
+            .max_recv_data(1 << 10)
+            .build()
+            .unwrap();
+        peer_config.recv_data_ceiling = Some(1 << 9);
+
+        assert!(config_validator.validate(&peer_config).is_err());
+        assert!(peer_config.build_mpc_tls_config().is_err());
+    }
+
+    #[rstest]
+    fn test_check_max_connections(config_validator: &ProtocolConfigValidator) {
+        let peer_config = ProtocolConfig::builder()
+            .max_sent_data(1 << 10)
+            .max_recv_data(1 << 10)
+            .max_connections(3)
+            .build()
+            .unwrap();
+
+        assert!(config_validator.validate(&peer_config).is_err());
+
+        let clamped = config_validator.limits().clamp(&peer_config).unwrap();
+
+        assert_eq!(clamped.max_connections(), DEFAULT_CONNECTIONS_LIMIT);
+        assert!(config_validator.validate(&clamped).is_ok());
+        assert!(
+            ProtocolConfig::builder()
+                .max_sent_data(1 << 10)
+                .max_recv_data(1 << 10)
+                .max_connections(0)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[rstest]
+    #[case::tls12_aes256(TlsVersion::V1_2, AeadAlgorithm::Aes256Gcm, true)]
+    #[case::tls13_aes128(TlsVersion::V1_3, AeadAlgorithm::Aes128Gcm, true)]
+    #[case::tls13_aes256(TlsVersion::V1_3, AeadAlgorithm::Aes256Gcm, false)]
+    #[case::tls12_chacha20(TlsVersion::V1_2, AeadAlgorithm::ChaCha20Poly1305, true)]
+    #[case::tls13_chacha20(TlsVersion::V1_3, AeadAlgorithm::ChaCha20Poly1305, false)]
+    fn test_check_cipher_suite(
+        config_validator: &ProtocolConfigValidator,
+        #[case] tls_version: TlsVersion,
+        #[case] aead: AeadAlgorithm,
+        #[case] expected_ok: bool,
+    ) {
+        let mut peer_config = ProtocolConfig::builder()
+            .max_sent_data(1 << 10)
+            .max_recv_data(1 << 10)
+            .build()
+            .unwrap();
+        // Sets the fields directly as the builder rejects unsupported pairs.
+        peer_config.tls_version = tls_version;
+        peer_config.aead = aead;
+
+        assert_eq!(config_validator.validate(&peer_config).is_ok(), expected_ok);
+        assert_eq!(peer_config.build_mpc_tls_config().is_ok(), expected_ok);
+    }
+
+    #[rstest]
+    fn test_clamp_unsupported_version(config_validator: &ProtocolConfigValidator) {
+        let mut peer_config = ProtocolConfig::builder()
+            .max_sent_data(TEST_MAX_SENT_LIMIT)
+            .max_recv_data(TEST_MAX_RECV_LIMIT)
+            .build()
+            .unwrap();
+        peer_config.version = Version::new(0, 0, 0);
+
+        assert!(config_validator.limits().clamp(&peer_config).is_err())
+    }
+
+    #[rstest]
+    #[case::fast_link(Duration::from_millis(1), 100 << 20, NetworkSetting::Latency)]
+    #[case::slow_link(Duration::from_millis(20), 1 << 20, NetworkSetting::Latency)]
+    #[case::high_latency(Duration::from_millis(200), 100 << 20, NetworkSetting::Bandwidth)]
+    #[case::no_throughput(Duration::from_millis(200), 0, NetworkSetting::Latency)]
+    fn test_link_network_setting(
+        #[case] rtt: Duration,
+        #[case] throughput: u64,
+        #[case] expected: NetworkSetting,
+    ) {
+        let link = LinkMeasurement::new(rtt, throughput);
+
+        assert_eq!(link.network_setting(), expected);
+    }
 }
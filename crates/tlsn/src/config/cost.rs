@@ -0,0 +1,286 @@
+//! Estimation of the cost of the protocol.
+//!
+//! The estimates are based on an approximate model of the MPC-TLS protocol
+//! and are intended for informing users ahead of a session, e.g. "this will
+//! upload ~40 MB". The actual cost depends on the amount of data which is
+//! transmitted and on how it is split into records.
+//!
+//! The size of the garbled circuits is derived from the circuits which are
+//! used by MPC-TLS. The remaining parameters of the model are compared with
+//! the measured traffic by the harness benches, which report the estimate next
+//! to each measurement.
+
+use std::time::Duration;
+
+use mpz_circuits::circuits::{AES128, SHA256_COMPRESS};
+use tlsn_cipher::{aes::AES256, chacha::CHACHA20};
+use tlsn_core::connection::AeadAlgorithm;
+
+use crate::{
+    config::{
+        LATENCY_EXTRA_ROUND_TRIPS, LATENCY_SAVED_BYTES, LinkMeasurement, NetworkSetting,
+        ProtocolConfig,
+    },
+    network::PING_COUNT,
+};
+
+/// Size of a cipher block in bytes.
+const BLOCK_LEN: usize = 16;
+/// Number of bytes of a garbled AND gate, i.e. two 16 byte ciphertexts with
+/// half-gates.
+const GC_BYTES_PER_AND: u64 = 32;
+/// Number of SHA-256 compressions of the PRF in MPC.
+///
+/// Each of the two PRF keys, the pre-master secret and the master secret, is
+/// hashed with the inner and outer pad once (4). Deriving the master secret
+/// and the key block takes two iterations of P_hash each (2 * 11), the
+/// finished messages take one iteration each (2 * 5).
+const PRF_COMPRESSIONS: u64 = 36;
+/// Number of bytes downloaded from the verifier during preprocessing.
+const PREPROCESS_DOWNLOAD_BYTES: u64 = 1 << 20;
+/// Number of bytes exchanged in each direction per record which is processed
+/// online.
+const ONLINE_BYTES_PER_RECORD: u64 = 1 << 10;
+/// Number of round-trips of the setup.
+const SETUP_ROUND_TRIPS: u64 = 6;
+/// Number of round-trips of the handshake.
+const HANDSHAKE_ROUND_TRIPS: u64 = 8;
+/// Number of round-trips after the connection is closed, i.e. finalization,
+/// tag verification and proving.
+const FINALIZE_ROUND_TRIPS: u64 = 4;
+
+/// Returns the number of AND gates to encrypt a 16 byte block.
+fn block_and_gates(aead: AeadAlgorithm) -> u64 {
+    match aead {
+        AeadAlgorithm::Aes128Gcm => AES128.and_count() as u64,
+        AeadAlgorithm::Aes256Gcm => AES256.and_count() as u64,
+        // A ChaCha20 block is 64 bytes.
+        AeadAlgorithm::ChaCha20Poly1305 => CHACHA20.and_count().div_ceil(4) as u64,
+    }
+}
+
+/// Returns the number of AND gates of the handshake, i.e. the PRF and the
+/// authentication keys of both directions.
+fn handshake_and_gates(aead: AeadAlgorithm) -> u64 {
+    PRF_COMPRESSIONS * SHA256_COMPRESS.and_count() as u64 + 2 * block_and_gates(aead)
+}
+
+/// Estimated cost of a session.
+///
+/// Upload refers to data sent from the prover to the verifier, download to
+/// data sent from the verifier to the prover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Bytes uploaded during preprocessing.
+    pub preprocess_upload: u64,
+    /// Bytes downloaded during preprocessing.
+    pub preprocess_download: u64,
+    /// Bytes uploaded during the online phase.
+    pub online_upload: u64,
+    /// Bytes downloaded during the online phase.
+    pub online_download: u64,
+    /// Number of round-trips between the prover and the verifier.
+    pub round_trips: u64,
+    /// Expected duration of the session, if the link was provided.
+    pub duration: Option<Duration>,
+}
+
+impl CostEstimate {
+    /// Estimates the cost of a session.
+    ///
+    /// If `network` is [`NetworkSetting::Auto`] the setting is selected from
+    /// the link. Without a link the more expensive setting is assumed.
+    ///
+    /// Only the capacity which is allocated up front is accounted for, any
+    /// capacity allocated on demand up to the ceilings of the configuration is
+    /// additional online cost. The estimate assumes that all connections of
+    /// the configuration are made.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The protocol configuration.
+    /// * `network` - The network setting.
+    /// * `link` - The measured link between the prover and the verifier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the configuration is not accepted by MPC-TLS, which can only
+    /// happen if it was not created with
+    /// [`ProtocolConfigBuilder`](crate::config::ProtocolConfigBuilder).
+    pub fn new(
+        config: &ProtocolConfig,
+        network: NetworkSetting,
+        link: Option<&LinkMeasurement>,
+    ) -> Self {
+        let connections = config.max_connections() as u64;
+        let mut round_trips =
+            SETUP_ROUND_TRIPS + HANDSHAKE_ROUND_TRIPS * connections + FINALIZE_ROUND_TRIPS;
+        let network = match (network, link) {
+            (NetworkSetting::Auto, Some(link)) => {
+                round_trips += PING_COUNT as u64 + 1;
+                link.network_setting()
+            }
+            (NetworkSetting::Auto, None) => NetworkSetting::Bandwidth,
+            (network, _) => network,
+        };
+
+        let mut config = config.clone();
+        config.set_network(network);
+        // Uses the same record counts as MPC-TLS.
+        let mpc_tls_config = config
+            .build_mpc_tls_config()
+            .expect("protocol config should be valid");
+
+        let block_and_gates = block_and_gates(config.aead());
+        let blocks = |len: usize, records: usize| (len.div_ceil(BLOCK_LEN) + records) as u64;
+
+        // Sent data and the received data which is decrypted online are
+        // encrypted with garbled circuits, one additional block per record is
+        // needed for the tag.
+        let sent_blocks = blocks(mpc_tls_config.max_sent(), mpc_tls_config.max_sent_records());
+        let online_blocks = if mpc_tls_config.defer_decryption() {
+            0
+        } else {
+            blocks(
+                mpc_tls_config.max_recv_online(),
+                mpc_tls_config.max_recv_records_online(),
+            )
+        };
+
+        let mut preprocess_upload = (handshake_and_gates(config.aead())
+            + (sent_blocks + online_blocks) * block_and_gates)
+            * GC_BYTES_PER_AND
+            * connections;
+        if let NetworkSetting::Latency = network {
+            preprocess_upload = preprocess_upload.saturating_sub(LATENCY_SAVED_BYTES);
+            round_trips += LATENCY_EXTRA_ROUND_TRIPS as u64;
+        }
+
+        // The whole transcript is proven in ZK, which costs about one bit per
+        // AND gate.
+        let zk_blocks =
+            blocks(mpc_tls_config.max_sent() + mpc_tls_config.max_recv(), 0) * connections;
+        let online_records = (mpc_tls_config.max_sent_records()
+            + mpc_tls_config.max_recv_records_online()) as u64
+            * connections;
+
+        let online_upload =
+            zk_blocks * block_and_gates / 8 + online_records * ONLINE_BYTES_PER_RECORD;
+        let online_download = online_records * ONLINE_BYTES_PER_RECORD;
+        round_trips += online_records;
+
+        let mut estimate = Self {
+            preprocess_upload,
+            preprocess_download: PREPROCESS_DOWNLOAD_BYTES * connections,
+            online_upload,
+            online_download,
+            round_trips,
+            duration: None,
+        };
+        estimate.duration = link.map(|link| estimate.duration(link));
+
+        estimate
+    }
+
+    /// Returns the total number of bytes uploaded.
+    pub fn total_upload(&self) -> u64 {
+        self.preprocess_upload + self.online_upload
+    }
+
+    /// Returns the total number of bytes downloaded.
+    pub fn total_download(&self) -> u64 {
+        self.preprocess_download + self.online_download
+    }
+
+    /// Returns the expected duration of the session on the given link.
+    pub fn duration(&self, link: &LinkMeasurement) -> Duration {
+        let latency = link.rtt() * self.round_trips as u32;
+        if link.throughput() == 0 {
+            return latency;
+        }
+
+        let bytes = self.total_upload() + self.total_download();
+
+        latency + Duration::from_secs_f64(bytes as f64 / link.throughput() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_sent_data: usize, defer_decryption: bool) -> ProtocolConfig {
+        ProtocolConfig::builder()
+            .max_sent_data(max_sent_data)
+            .max_recv_data(1 << 14)
+            .max_recv_data_online(1 << 12)
+            .defer_decryption_from_start(defer_decryption)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_block_and_gates() {
+        let aes128 = block_and_gates(AeadAlgorithm::Aes128Gcm);
+        let aes256 = block_and_gates(AeadAlgorithm::Aes256Gcm);
+
+        assert!(aes128 > 0);
+        assert!(aes256 > aes128);
+        assert!(handshake_and_gates(AeadAlgorithm::Aes128Gcm) > PRF_COMPRESSIONS * aes128);
+    }
+
+    #[test]
+    fn test_estimate_grows_with_data() {
+        let small = CostEstimate::new(&config(1 << 10, true), NetworkSetting::Bandwidth, None);
+        let large = CostEstimate::new(&config(1 << 12, true), NetworkSetting::Bandwidth, None);
+
+        assert!(large.preprocess_upload > small.preprocess_upload);
+        assert!(large.online_upload > small.online_upload);
+        assert!(large.round_trips >= small.round_trips);
+    }
+
+    #[test]
+    fn test_estimate_online_decryption() {
+        let deferred = CostEstimate::new(&config(1 << 10, true), NetworkSetting::Bandwidth, None);
+        let online = CostEstimate::new(&config(1 << 10, false), NetworkSetting::Bandwidth, None);
+
+        assert!(online.preprocess_upload > deferred.preprocess_upload);
+    }
+
+    #[test]
+    fn test_estimate_network_setting() {
+        let config = config(1 << 10, true);
+        let bandwidth = CostEstimate::new(&config, NetworkSetting::Bandwidth, None);
+        let latency = CostEstimate::new(&config, NetworkSetting::Latency, None);
+
+        assert!(latency.preprocess_upload < bandwidth.preprocess_upload);
+        assert!(latency.round_trips > bandwidth.round_trips);
+
+        // Without a link the more expensive setting is assumed.
+        assert_eq!(
+            CostEstimate::new(&config, NetworkSetting::Auto, None),
+            bandwidth
+        );
+    }
+
+    #[test]
+    fn test_estimate_connections() {
+        let single = CostEstimate::new(&config(1 << 10, true), NetworkSetting::Bandwidth, None);
+        let mut config = config(1 << 10, true);
+        config.max_connections = 2;
+        let double = CostEstimate::new(&config, NetworkSetting::Bandwidth, None);
+
+        assert_eq!(double.preprocess_upload, single.preprocess_upload * 2);
+        assert!(double.round_trips > single.round_trips);
+    }
+
+    #[test]
+    fn test_estimate_duration() {
+        let link = LinkMeasurement::new(Duration::from_millis(50), 1 << 20);
+        let estimate = CostEstimate::new(&config(1 << 10, true), NetworkSetting::Auto, Some(&link));
+
+        let duration = estimate.duration.unwrap();
+        assert!(duration >= link.rtt() * estimate.round_trips as u32);
+        assert_eq!(duration, estimate.duration(&link));
+    }
+}
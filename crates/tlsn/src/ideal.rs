@@ -0,0 +1,237 @@
+//! Insecure plaintext stand-ins for the MPC protocols.
+//!
+//! Enabled by building with `RUSTFLAGS="--cfg tlsn_insecure_ideal"`, which
+//! replaces the garbled circuit VM of DEAP with [`PlaintextVm`] and the key
+//! exchange with a plaintext computation of the PMS. The ZK VM is retained, so
+//! the outputs and commitments are the same as with the secure protocols.
+//!
+//! The parties reveal all of their private inputs to each other, so this must
+//! only be used for testing. It is a `--cfg` flag rather than a Cargo feature
+//! so that it is never enabled by feature unification or `--all-features`.
+
+use std::{mem, ops::Range};
+
+use async_trait::async_trait;
+use mpz_common::Context;
+use mpz_core::bitvec::BitVec;
+use mpz_ideal_vm::IdealVm;
+use mpz_vm_core::{
+    Call, Callable, Execute, VmError,
+    memory::{DecodeFuture, Memory, Slice, View, binary::Binary},
+};
+use rangeset::{Difference, RangeSet, UnionMut};
+use serio::{SinkExt, stream::IoStreamExt};
+
+/// A plaintext stand-in for a two-party VM.
+///
+/// Private inputs are sent to the other party in the clear when they are
+/// flushed, after which both parties evaluate all calls locally. Both parties
+/// must allocate and commit to memory in the same order, as with the VMs it
+/// replaces.
+pub(crate) struct PlaintextVm {
+    vm: IdealVm,
+    /// Ranges of this party's private inputs.
+    private: RangeSet<usize>,
+    /// Ranges of the other party's private inputs.
+    blind: RangeSet<usize>,
+    /// Private inputs which have been assigned but not committed.
+    assigned: Vec<(Slice, BitVec)>,
+    /// Committed private inputs to send on the next flush.
+    outbox: Vec<(Range<usize>, BitVec)>,
+    /// Committed inputs of the other party which have not been received.
+    pending: Vec<Slice>,
+    /// Inputs received from the other party.
+    inbox: Vec<(Range<usize>, BitVec)>,
+}
+
+opaque_debug::implement!(PlaintextVm);
+
+impl PlaintextVm {
+    /// Creates a new plaintext VM.
+    pub(crate) fn new() -> Self {
+        Self {
+            vm: IdealVm::new(),
+            private: RangeSet::default(),
+            blind: RangeSet::default(),
+            assigned: Vec::new(),
+            outbox: Vec::new(),
+            pending: Vec::new(),
+            inbox: Vec::new(),
+        }
+    }
+
+    /// Returns the value of a committed private input.
+    fn take_assigned(&mut self, slice: Slice) -> Result<BitVec, VmError> {
+        let range = slice.to_range();
+        let mut value = BitVec::repeat(false, range.len());
+        let mut unassigned = RangeSet::from(range.clone());
+
+        let mut i = 0;
+        while i < self.assigned.len() {
+            let chunk = self.assigned[i].0.to_range();
+            if chunk.start >= range.start && chunk.end <= range.end {
+                let (_, data) = self.assigned.swap_remove(i);
+                value[chunk.start - range.start..chunk.end - range.start].copy_from_bitslice(&data);
+                unassigned = unassigned.difference(&chunk);
+            } else {
+                i += 1;
+            }
+        }
+
+        if !unassigned.is_empty() {
+            return Err(VmError::memory(format!(
+                "private input is not assigned: {range:?}"
+            )));
+        }
+
+        Ok(value)
+    }
+
+    /// Returns the value of an input of the other party, if it was received.
+    fn take_received(&mut self, slice: Slice) -> Option<BitVec> {
+        let range = slice.to_range();
+        let i = self
+            .inbox
+            .iter()
+            .position(|(chunk, _)| chunk.start <= range.start && range.end <= chunk.end)?;
+
+        let (chunk, data) = &self.inbox[i];
+        let value = data[range.start - chunk.start..range.end - chunk.start].to_bitvec();
+        if *chunk == range {
+            self.inbox.swap_remove(i);
+        }
+
+        Some(value)
+    }
+}
+
+impl Memory<Binary> for PlaintextVm {
+    type Error = VmError;
+
+    fn is_alloc_raw(&self, slice: Slice) -> bool {
+        self.vm.is_alloc_raw(slice)
+    }
+
+    fn alloc_raw(&mut self, size: usize) -> Result<Slice, VmError> {
+        self.vm.alloc_raw(size)
+    }
+
+    fn is_assigned_raw(&self, slice: Slice) -> bool {
+        self.vm.is_assigned_raw(slice)
+    }
+
+    fn assign_raw(&mut self, slice: Slice, data: BitVec) -> Result<(), VmError> {
+        if slice.to_range().difference(&self.private).is_empty() {
+            self.assigned.push((slice, data.clone()));
+        }
+
+        self.vm.assign_raw(slice, data)
+    }
+
+    fn is_committed_raw(&self, slice: Slice) -> bool {
+        self.vm.is_committed_raw(slice) || self.pending.contains(&slice)
+    }
+
+    fn commit_raw(&mut self, slice: Slice) -> Result<(), VmError> {
+        let range = slice.to_range();
+
+        // Inputs of the other party are committed once they are received.
+        if range.difference(&self.blind).is_empty() {
+            self.pending.push(slice);
+            return Ok(());
+        }
+
+        self.vm.commit_raw(slice)?;
+
+        if range.difference(&self.private).is_empty() {
+            let value = self.take_assigned(slice)?;
+            self.outbox.push((range, value));
+        }
+
+        Ok(())
+    }
+
+    fn get_raw(&self, slice: Slice) -> Result<Option<BitVec>, VmError> {
+        self.vm.get_raw(slice)
+    }
+
+    fn decode_raw(&mut self, slice: Slice) -> Result<DecodeFuture<BitVec>, VmError> {
+        self.vm.decode_raw(slice)
+    }
+}
+
+impl View<Binary> for PlaintextVm {
+    type Error = VmError;
+
+    fn mark_public_raw(&mut self, slice: Slice) -> Result<(), VmError> {
+        self.vm.mark_public_raw(slice)
+    }
+
+    fn mark_private_raw(&mut self, slice: Slice) -> Result<(), VmError> {
+        self.private.union_mut(&slice.to_range());
+        self.vm.mark_private_raw(slice)
+    }
+
+    fn mark_blind_raw(&mut self, slice: Slice) -> Result<(), VmError> {
+        // The value is assigned once it is received from the other party.
+        self.blind.union_mut(&slice.to_range());
+        self.vm.mark_private_raw(slice)
+    }
+}
+
+impl Callable<Binary> for PlaintextVm {
+    fn call_raw(&mut self, call: Call) -> Result<Slice, VmError> {
+        self.vm.call_raw(call)
+    }
+}
+
+#[async_trait]
+impl Execute for PlaintextVm {
+    fn wants_flush(&self) -> bool {
+        !self.outbox.is_empty() || !self.pending.is_empty() || self.vm.wants_flush()
+    }
+
+    async fn flush(&mut self, ctx: &mut Context) -> Result<(), VmError> {
+        let outbox = mem::take(&mut self.outbox)
+            .into_iter()
+            .map(|(range, value)| (range, value.iter().by_vals().collect::<Vec<bool>>()))
+            .collect::<Vec<_>>();
+
+        ctx.io_mut().send(outbox).await.map_err(VmError::execute)?;
+        let inbox: Vec<(Range<usize>, Vec<bool>)> =
+            ctx.io_mut().expect_next().await.map_err(VmError::execute)?;
+
+        self.inbox.extend(
+            inbox
+                .into_iter()
+                .map(|(range, value)| (range, BitVec::from_iter(value))),
+        );
+
+        for slice in mem::take(&mut self.pending) {
+            if let Some(value) = self.take_received(slice) {
+                self.vm.assign_raw(slice, value)?;
+                self.vm.commit_raw(slice)?;
+            } else {
+                self.pending.push(slice);
+            }
+        }
+
+        self.vm.flush(ctx).await
+    }
+
+    fn wants_preprocess(&self) -> bool {
+        self.vm.wants_preprocess()
+    }
+
+    async fn preprocess(&mut self, ctx: &mut Context) -> Result<(), VmError> {
+        self.vm.preprocess(ctx).await
+    }
+
+    fn wants_execute(&self) -> bool {
+        self.vm.wants_execute()
+    }
+
+    async fn execute(&mut self, ctx: &mut Context) -> Result<(), VmError> {
+        self.vm.execute(ctx).await
+    }
+}
@@ -0,0 +1,276 @@
+//! Cancellation and timeouts of a session.
+//!
+//! A session can be cancelled with a [`CancelHandle`], and the time spent in
+//! the stages of a session can be bounded with [`Timeouts`]. Either way the
+//! connection to the peer is torn down and an error is returned which carries
+//! the [`Interrupt`].
+
+use std::{fmt, future::Future, pin::pin, sync::Arc, time::Duration};
+
+use futures::{
+    FutureExt,
+    future::{self, Either},
+};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::{
+    meter::Meter,
+    progress::{Phase, Reporter},
+};
+
+/// Timeouts for the stages of a session.
+///
+/// No timeout is applied to a stage unless it is set.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, derive_builder::Builder, Serialize, Deserialize,
+)]
+#[builder(default)]
+pub struct Timeouts {
+    /// Maximum duration of the setup, including the preprocessing.
+    #[builder(setter(strip_option))]
+    setup: Option<Duration>,
+    /// Maximum duration from the start of the connection until the TLS
+    /// handshake with the server is complete.
+    #[builder(setter(strip_option))]
+    handshake: Option<Duration>,
+    /// Maximum duration without any traffic while the connection to the
+    /// server is open.
+    ///
+    /// The prover observes the traffic with the server. The verifier, which has
+    /// no view of the server, observes the traffic with the prover instead.
+    #[builder(setter(strip_option))]
+    server_idle: Option<Duration>,
+    /// Maximum duration of proving, or verifying.
+    #[builder(setter(strip_option))]
+    prove: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Creates a new builder.
+    pub fn builder() -> TimeoutsBuilder {
+        TimeoutsBuilder::default()
+    }
+
+    /// Returns the setup timeout.
+    pub fn setup(&self) -> Option<Duration> {
+        self.setup
+    }
+
+    /// Returns the handshake timeout.
+    pub fn handshake(&self) -> Option<Duration> {
+        self.handshake
+    }
+
+    /// Returns the server idle timeout.
+    pub fn server_idle(&self) -> Option<Duration> {
+        self.server_idle
+    }
+
+    /// Returns the prove timeout.
+    pub fn prove(&self) -> Option<Duration> {
+        self.prove
+    }
+}
+
+/// A stage of a session which can time out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Stage {
+    /// The setup.
+    Setup,
+    /// The TLS handshake.
+    Handshake,
+    /// The connection to the server, while it is idle.
+    ServerIdle,
+    /// Proving, or verifying.
+    Prove,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Setup => f.write_str("setup"),
+            Stage::Handshake => f.write_str("handshake"),
+            Stage::ServerIdle => f.write_str("idle server connection"),
+            Stage::Prove => f.write_str("prove"),
+        }
+    }
+}
+
+/// Reason a session was interrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Interrupt {
+    /// The session was cancelled.
+    #[error("session was cancelled")]
+    Cancelled,
+    /// A stage of the session timed out.
+    #[error("{0} timed out")]
+    Timeout(Stage),
+}
+
+/// A handle which cancels a session.
+///
+/// Cancelling interrupts the current stage of the session, or the next stage
+/// if none is in progress.
+#[derive(Debug, Clone)]
+pub struct CancelHandle(Arc<watch::Sender<bool>>);
+
+impl CancelHandle {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(watch::Sender::new(false)))
+    }
+
+    /// Cancels the session.
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    /// Returns `true` if the session was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the session is cancelled.
+    async fn cancelled(&self) {
+        let mut rx = self.0.subscribe();
+        // The sender is held by `self`, so this can not fail.
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+/// Runs a future until it completes, unless the session is cancelled or the
+/// `interrupt` future resolves first.
+pub(crate) async fn guard<F, T, E>(
+    cancel: &CancelHandle,
+    interrupt: impl Future<Output = Interrupt>,
+    fut: F,
+) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<Interrupt>,
+{
+    if cancel.is_cancelled() {
+        return Err(Interrupt::Cancelled.into());
+    }
+
+    let interrupt = first(cancel.cancelled().map(|_| Interrupt::Cancelled), interrupt);
+
+    match future::select(pin!(fut), pin!(interrupt)).await {
+        Either::Left((output, _)) => output,
+        Either::Right((interrupt, _)) => Err(interrupt.into()),
+    }
+}
+
+/// Resolves with the first of two interrupts.
+pub(crate) async fn first(
+    a: impl Future<Output = Interrupt>,
+    b: impl Future<Output = Interrupt>,
+) -> Interrupt {
+    future::select(pin!(a), pin!(b)).await.factor_first().0
+}
+
+/// Resolves once the timeout of `stage` elapses, if any.
+pub(crate) async fn timeout(stage: Stage, timeout: Option<Duration>) -> Interrupt {
+    match timeout {
+        Some(timeout) => {
+            Delay::new(timeout).await;
+            Interrupt::Timeout(stage)
+        }
+        None => future::pending().await,
+    }
+}
+
+/// Resolves once the handshake timeout elapses, unless the handshake is
+/// complete by then.
+pub(crate) async fn handshake(reporter: &Reporter, timeout: Option<Duration>) -> Interrupt {
+    let interrupt = self::timeout(Stage::Handshake, timeout).await;
+    if reporter.is_finished(Phase::Handshake) {
+        future::pending().await
+    } else {
+        interrupt
+    }
+}
+
+/// Resolves once no traffic was metered for the idle timeout, if any.
+///
+/// Idleness is checked once per timeout period, so an idle connection is
+/// detected after at most twice the timeout.
+pub(crate) async fn idle(meter: &Meter, timeout: Option<Duration>) -> Interrupt {
+    let Some(timeout) = timeout else {
+        return future::pending().await;
+    };
+
+    let mut last = (meter.sent(), meter.recv());
+    loop {
+        Delay::new(timeout).await;
+
+        let current = (meter.sent(), meter.recv());
+        if current == last {
+            return Interrupt::Timeout(Stage::ServerIdle);
+        }
+        last = current;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Error(Interrupt);
+
+    impl From<Interrupt> for Error {
+        fn from(interrupt: Interrupt) -> Self {
+            Self(interrupt)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_guard_completes() {
+        let cancel = CancelHandle::new();
+        let result: Result<u8, Error> = guard(&cancel, future::pending(), async { Ok(1) }).await;
+
+        assert_eq!(result, Ok(1));
+    }
+
+    #[tokio::test]
+    async fn test_guard_cancelled() {
+        let cancel = CancelHandle::new();
+        let result: Result<(), Error> = guard(&cancel, future::pending(), async {
+            cancel.cancel();
+            future::pending().await
+        })
+        .await;
+
+        assert_eq!(result, Err(Error(Interrupt::Cancelled)));
+
+        // Once cancelled, subsequent stages are interrupted immediately.
+        let result: Result<(), Error> = guard(&cancel, future::pending(), async { Ok(()) }).await;
+
+        assert_eq!(result, Err(Error(Interrupt::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_guard_timeout() {
+        let cancel = CancelHandle::new();
+        let result: Result<(), Error> = guard(
+            &cancel,
+            timeout(Stage::Prove, Some(Duration::from_millis(10))),
+            future::pending(),
+        )
+        .await;
+
+        assert_eq!(result, Err(Error(Interrupt::Timeout(Stage::Prove))));
+    }
+
+    #[tokio::test]
+    async fn test_idle() {
+        let meter = Meter::default();
+        let interrupt = idle(&meter, Some(Duration::from_millis(10))).await;
+
+        assert_eq!(interrupt, Interrupt::Timeout(Stage::ServerIdle));
+    }
+}
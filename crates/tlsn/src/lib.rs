@@ -7,15 +7,24 @@
 pub mod config;
 pub(crate) mod context;
 pub(crate) mod ghash;
+#[cfg(tlsn_insecure_ideal)]
+pub(crate) mod ideal;
+pub mod interrupt;
 pub(crate) mod map;
+pub(crate) mod meter;
 pub(crate) mod mux;
+pub(crate) mod network;
+pub(crate) mod poly1305;
+pub mod progress;
 pub mod prover;
 pub(crate) mod tag;
 pub(crate) mod transcript_internal;
 pub mod verifier;
 
 pub use tlsn_attestation as attestation;
-pub use tlsn_core::{connection, hash, transcript};
+pub use tlsn_core::{connection, hash, metrics, transcript};
+
+use serde::{Deserialize, Serialize};
 
 /// The party's role in the TLSN protocol.
 ///
@@ -27,3 +36,12 @@ pub(crate) enum Role {
     /// The verifier.
     Verifier,
 }
+
+/// Sent by the prover once a TLS connection is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Next {
+    /// Another connection follows.
+    Connection,
+    /// No connection follows, MPC-TLS is finalized.
+    Finalize,
+}
@@ -0,0 +1,236 @@
+//! Metering of the IO between the prover and the verifier.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    task::{Context, Poll},
+};
+
+use futures::{AsyncRead, AsyncWrite};
+use tlsn_core::metrics::{PhaseMetrics, SessionMetrics};
+use web_time::Instant;
+
+use crate::progress::Phase;
+
+/// Counts the bytes sent and received over an IO.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Meter {
+    sent: Arc<AtomicU64>,
+    recv: Arc<AtomicU64>,
+}
+
+impl Meter {
+    /// Returns the number of bytes sent.
+    pub(crate) fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of bytes received.
+    pub(crate) fn recv(&self) -> u64 {
+        self.recv.load(Ordering::Relaxed)
+    }
+}
+
+/// Attributes the bytes and wall time of a [`Meter`] to protocol phases.
+#[derive(Debug, Clone)]
+pub(crate) struct PhaseMeter {
+    meter: Meter,
+    start: Instant,
+    state: Arc<Mutex<PhaseMeterState>>,
+}
+
+#[derive(Debug, Default)]
+struct PhaseMeterState {
+    /// Phases which were started, with the snapshot at their start.
+    started: Vec<(Phase, Snapshot)>,
+    /// Phases which were finished at least once.
+    finished: Vec<Phase>,
+    metrics: SessionMetrics,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    time: Instant,
+    sent: u64,
+    recv: u64,
+}
+
+impl PhaseMeter {
+    /// Creates a new phase meter, starting the clock of the session.
+    pub(crate) fn new(meter: Meter) -> Self {
+        Self {
+            meter,
+            start: Instant::now(),
+            state: Arc::new(Mutex::new(PhaseMeterState::default())),
+        }
+    }
+
+    /// Returns the underlying meter.
+    pub(crate) fn meter(&self) -> &Meter {
+        &self.meter
+    }
+
+    /// Marks the start of a phase.
+    pub(crate) fn start(&self, phase: Phase) {
+        let snapshot = self.snapshot();
+        let mut state = self.state.lock().unwrap();
+        state.started.retain(|(started, _)| *started != phase);
+        state.finished.retain(|finished| *finished != phase);
+        state.started.push((phase, snapshot));
+    }
+
+    /// Marks the end of a phase, attributing the bytes and time since its
+    /// start to it.
+    pub(crate) fn finish(&self, phase: Phase) {
+        let now = self.snapshot();
+        let mut state = self.state.lock().unwrap();
+        let Some(idx) = state
+            .started
+            .iter()
+            .position(|(started, _)| *started == phase)
+        else {
+            return;
+        };
+        let (_, start) = state.started.remove(idx);
+
+        if !state.finished.contains(&phase) {
+            state.finished.push(phase);
+        }
+
+        if let Some(metrics) = phase_metrics(&mut state.metrics, phase) {
+            *metrics += PhaseMetrics {
+                sent: now.sent - start.sent,
+                recv: now.recv - start.recv,
+                duration: now.time - start.time,
+            };
+        }
+    }
+
+    /// Returns `true` if the phase was finished since it was last started.
+    pub(crate) fn is_finished(&self, phase: Phase) -> bool {
+        self.state.lock().unwrap().finished.contains(&phase)
+    }
+
+    /// Returns the metrics of the session so far.
+    pub(crate) fn metrics(&self) -> SessionMetrics {
+        let mut metrics = self.state.lock().unwrap().metrics;
+        metrics.total = PhaseMetrics {
+            sent: self.meter.sent(),
+            recv: self.meter.recv(),
+            duration: self.start.elapsed(),
+        };
+        metrics
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            time: Instant::now(),
+            sent: self.meter.sent(),
+            recv: self.meter.recv(),
+        }
+    }
+}
+
+/// Returns the metrics a phase is attributed to.
+fn phase_metrics(metrics: &mut SessionMetrics, phase: Phase) -> Option<&mut PhaseMetrics> {
+    match phase {
+        Phase::Setup => Some(&mut metrics.setup),
+        Phase::Preprocess => Some(&mut metrics.preprocess),
+        Phase::Handshake => Some(&mut metrics.handshake),
+        Phase::Records => Some(&mut metrics.online),
+        Phase::DeferredDecryption | Phase::Finalize => Some(&mut metrics.decryption),
+        Phase::Prove => Some(&mut metrics.prove),
+        // Commitments are made while proving, so they are already accounted
+        // for in the prove phase.
+        Phase::Commit => None,
+    }
+}
+
+/// IO which counts the bytes sent and received.
+pub(crate) struct MeteredIo<T> {
+    io: T,
+    meter: Meter,
+}
+
+impl<T> MeteredIo<T> {
+    /// Creates a new metered IO.
+    pub(crate) fn new(io: T, meter: Meter) -> Self {
+        Self { io, meter }
+    }
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for MeteredIo<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.io).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = &poll {
+            self.meter.recv.fetch_add(*n as u64, Ordering::Relaxed);
+        }
+
+        poll
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for MeteredIo<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.io).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = &poll {
+            self.meter.sent.fetch_add(*n as u64, Ordering::Relaxed);
+        }
+
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.io).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_meter() {
+        let meter = Meter::default();
+        let phases = PhaseMeter::new(meter.clone());
+
+        phases.start(Phase::Setup);
+        meter.sent.fetch_add(10, Ordering::Relaxed);
+        meter.recv.fetch_add(20, Ordering::Relaxed);
+        phases.finish(Phase::Setup);
+
+        // Traffic outside of a phase is only accounted for in the total.
+        meter.sent.fetch_add(1, Ordering::Relaxed);
+
+        phases.start(Phase::Prove);
+        phases.start(Phase::Commit);
+        meter.recv.fetch_add(5, Ordering::Relaxed);
+        phases.finish(Phase::Commit);
+        phases.finish(Phase::Prove);
+
+        // Finishing a phase which was not started is ignored.
+        phases.finish(Phase::Handshake);
+
+        let metrics = phases.metrics();
+        assert_eq!((metrics.setup.sent, metrics.setup.recv), (10, 20));
+        assert_eq!((metrics.prove.sent, metrics.prove.recv), (0, 5));
+        assert_eq!(metrics.handshake, PhaseMetrics::default());
+        assert_eq!((metrics.total.sent, metrics.total.recv), (11, 25));
+    }
+}
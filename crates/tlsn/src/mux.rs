@@ -9,7 +9,10 @@ use futures::{
 use tracing::error;
 use uid_mux::yamux;
 
-use crate::Role;
+use crate::{
+    Role,
+    meter::{Meter, MeteredIo},
+};
 
 /// Multiplexer supporting unique deterministic stream IDs.
 pub(crate) type Mux<Io> = yamux::Yamux<Io>;
@@ -60,8 +63,8 @@ impl Future for MuxFuture {
 
 /// Attaches a multiplexer to the provided socket.
 ///
-/// Returns the multiplexer and a controller for creating streams with a codec
-/// attached.
+/// Returns the multiplexer, a controller for creating streams with a codec
+/// attached and a meter of the bytes sent and received over the socket.
 ///
 /// # Arguments
 ///
@@ -70,7 +73,10 @@ impl Future for MuxFuture {
 pub(crate) fn attach_mux<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
     socket: T,
     role: Role,
-) -> (MuxFuture, MuxControl) {
+) -> (MuxFuture, MuxControl, Meter) {
+    let meter = Meter::default();
+    let socket = MeteredIo::new(socket, meter.clone());
+
     let mut mux_config = yamux::Config::default();
     mux_config.set_max_num_streams(36);
 
@@ -86,5 +92,5 @@ pub(crate) fn attach_mux<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
         ctrl.alloc(32);
     }
 
-    (MuxFuture(Box::new(mux.into_future().fuse())), ctrl)
+    (MuxFuture(Box::new(mux.into_future().fuse())), ctrl, meter)
 }
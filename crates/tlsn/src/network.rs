@@ -0,0 +1,51 @@
+//! Measurement of the link between the prover and the verifier.
+
+use std::time::Duration;
+
+use mpz_common::Context;
+use serio::{SinkExt, stream::IoStreamExt};
+use web_time::Instant;
+
+use crate::config::LinkMeasurement;
+
+/// Number of round trips used to measure the RTT.
+pub(crate) const PING_COUNT: usize = 4;
+/// Size of the payload used to measure the throughput.
+const PROBE_SIZE: usize = 1 << 18;
+
+/// Measures the link to the verifier and sends the measurement to it.
+pub(crate) async fn measure(ctx: &mut Context) -> Result<LinkMeasurement, std::io::Error> {
+    let mut rtt = Duration::MAX;
+    for _ in 0..PING_COUNT {
+        let start = Instant::now();
+        ctx.io_mut().send(Vec::<u8>::new()).await?;
+        let _: Vec<u8> = ctx.io_mut().expect_next().await?;
+        rtt = rtt.min(start.elapsed());
+    }
+
+    let start = Instant::now();
+    ctx.io_mut().send(vec![0u8; PROBE_SIZE]).await?;
+    let _: Vec<u8> = ctx.io_mut().expect_next().await?;
+
+    // Only the time spent transferring the payload is counted.
+    let elapsed = start
+        .elapsed()
+        .saturating_sub(rtt)
+        .max(Duration::from_micros(1));
+    let throughput = (PROBE_SIZE as f64 / elapsed.as_secs_f64()) as u64;
+
+    let link = LinkMeasurement::new(rtt, throughput);
+    ctx.io_mut().send(link).await?;
+
+    Ok(link)
+}
+
+/// Responds to the measurement of the prover and returns the measurement.
+pub(crate) async fn respond(ctx: &mut Context) -> Result<LinkMeasurement, std::io::Error> {
+    for _ in 0..PING_COUNT + 1 {
+        let _: Vec<u8> = ctx.io_mut().expect_next().await?;
+        ctx.io_mut().send(Vec::<u8>::new()).await?;
+    }
+
+    ctx.io_mut().expect_next().await
+}
@@ -0,0 +1,28 @@
+//! Poly1305 methods.
+
+// This module belongs in tls/core. It was moved out here temporarily.
+
+use poly1305::{Key, Poly1305, universal_hash::KeyInit};
+
+/// Computes a Poly1305 tag as used in ChaCha20-Poly1305.
+pub(crate) fn poly1305(aad: &[u8], ciphertext: &[u8], key: &[u8; 32]) -> [u8; 16] {
+    let tag =
+        Poly1305::new(Key::from_slice(key)).compute_unpadded(&build_poly1305_data(aad, ciphertext));
+    tag.into()
+}
+
+/// Builds padded data for Poly1305, see RFC 8439 section 2.8.
+pub(crate) fn build_poly1305_data(aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    let aad_padded_len = 16 * aad.len().div_ceil(16);
+    let ciphertext_padded_len = 16 * ciphertext.len().div_ceil(16);
+
+    let mut data = Vec::with_capacity(aad_padded_len + ciphertext_padded_len + 16);
+    data.extend_from_slice(aad);
+    data.resize(aad_padded_len, 0);
+    data.extend_from_slice(ciphertext);
+    data.resize(aad_padded_len + ciphertext_padded_len, 0);
+    data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+    data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+    data
+}
@@ -0,0 +1,192 @@
+//! Progress reporting.
+//!
+//! A [`ProgressHandler`] can be set on the
+//! [`ProverConfig`](crate::prover::ProverConfig) or the
+//! [`VerifierConfig`](crate::verifier::VerifierConfig) to be notified of the
+//! progress of a session.
+
+use std::sync::Arc;
+
+use tlsn_core::metrics::SessionMetrics;
+
+use crate::meter::{Meter, PhaseMeter};
+
+/// A phase of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Phase {
+    /// Negotiation of the protocol configuration with the peer.
+    Setup,
+    /// Preprocessing, i.e. the oblivious transfer setup and garbling.
+    Preprocess,
+    /// The TLS handshake with the server.
+    Handshake,
+    /// Encryption and decryption of application data records.
+    Records,
+    /// Decryption of the records received while decryption was deferred.
+    DeferredDecryption,
+    /// Finalization of the MPC and verification of the received tags.
+    Finalize,
+    /// Commitment to the transcript.
+    Commit,
+    /// Proving, or verifying, the disclosed data.
+    Prove,
+}
+
+/// Progress of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Progress {
+    /// A phase started.
+    Started(Phase),
+    /// A phase finished.
+    Finished(Phase),
+    /// Records were processed, with the total number of records processed so
+    /// far.
+    Records {
+        /// Number of sent records which were encrypted.
+        sent: usize,
+        /// Number of received records which were decrypted.
+        recv: usize,
+    },
+}
+
+/// An event reporting the progress of a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressEvent {
+    /// The progress.
+    pub progress: Progress,
+    /// Number of bytes sent to the peer so far.
+    pub bytes_sent: u64,
+    /// Number of bytes received from the peer so far.
+    pub bytes_recv: u64,
+}
+
+/// A handler which is notified of the progress of a session.
+///
+/// The handler is called from within the protocol, so it should return
+/// quickly.
+pub trait ProgressHandler: Send + Sync + 'static {
+    /// Handles a progress event.
+    fn on_progress(&self, event: &ProgressEvent);
+}
+
+impl<F> ProgressHandler for F
+where
+    F: Fn(&ProgressEvent) + Send + Sync + 'static,
+{
+    fn on_progress(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// Reports progress to a handler, if any, and records the metrics of each
+/// phase.
+#[derive(Clone)]
+pub(crate) struct Reporter {
+    handler: Option<Arc<dyn ProgressHandler>>,
+    phases: PhaseMeter,
+}
+
+opaque_debug::implement!(Reporter);
+
+impl Reporter {
+    /// Creates a new reporter.
+    pub(crate) fn new(handler: Option<Arc<dyn ProgressHandler>>, meter: Meter) -> Self {
+        Self {
+            handler,
+            phases: PhaseMeter::new(meter),
+        }
+    }
+
+    /// Reports progress.
+    pub(crate) fn report(&self, progress: Progress) {
+        match progress {
+            Progress::Started(phase) => self.phases.start(phase),
+            Progress::Finished(phase) => self.phases.finish(phase),
+            Progress::Records { .. } => {}
+        }
+
+        if let Some(handler) = &self.handler {
+            let meter = self.phases.meter();
+            handler.on_progress(&ProgressEvent {
+                progress,
+                bytes_sent: meter.sent(),
+                bytes_recv: meter.recv(),
+            });
+        }
+    }
+
+    /// Returns the meter of the traffic with the peer.
+    pub(crate) fn meter(&self) -> &Meter {
+        self.phases.meter()
+    }
+
+    /// Returns `true` if the phase was finished since it was last started.
+    pub(crate) fn is_finished(&self, phase: Phase) -> bool {
+        self.phases.is_finished(phase)
+    }
+
+    /// Returns the metrics of the session so far.
+    pub(crate) fn metrics(&self) -> SessionMetrics {
+        self.phases.metrics()
+    }
+
+    /// Returns a callback which reports the progress of MPC-TLS.
+    pub(crate) fn mpc_tls(&self) -> mpc_tls::ProgressFn {
+        let reporter = self.clone();
+        Arc::new(move |progress| match progress {
+            mpc_tls::Progress::HandshakeComplete => {
+                reporter.report(Progress::Finished(Phase::Handshake));
+                reporter.report(Progress::Started(Phase::Records));
+            }
+            mpc_tls::Progress::Records { sent, recv } => {
+                reporter.report(Progress::Records { sent, recv });
+            }
+            mpc_tls::Progress::DeferredDecryption { .. } => {
+                reporter.report(Progress::Finished(Phase::Records));
+                reporter.report(Progress::Started(Phase::DeferredDecryption));
+            }
+            mpc_tls::Progress::Committed => {
+                reporter.report(Progress::Finished(Phase::DeferredDecryption));
+            }
+            _ => {}
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_reporter_mpc_tls() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handler = {
+            let events = events.clone();
+            move |event: &ProgressEvent| events.lock().unwrap().push(event.progress)
+        };
+
+        let reporter = Reporter::new(Some(Arc::new(handler)), Meter::default());
+        let mpc_tls = reporter.mpc_tls();
+
+        mpc_tls(mpc_tls::Progress::HandshakeComplete);
+        mpc_tls(mpc_tls::Progress::Records { sent: 1, recv: 2 });
+        mpc_tls(mpc_tls::Progress::DeferredDecryption { records: 2 });
+        mpc_tls(mpc_tls::Progress::Committed);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                Progress::Finished(Phase::Handshake),
+                Progress::Started(Phase::Records),
+                Progress::Records { sent: 1, recv: 2 },
+                Progress::Finished(Phase::Records),
+                Progress::Started(Phase::DeferredDecryption),
+                Progress::Finished(Phase::DeferredDecryption),
+            ]
+        );
+    }
+}
@@ -9,8 +9,11 @@ pub mod state;
 pub use config::{ProverConfig, ProverConfigBuilder, TlsConfig, TlsConfigBuilder};
 pub use error::ProverError;
 pub use future::ProverFuture;
+pub use mpc_tls::OnlineDecryption;
 use rustls_pki_types::CertificateDer;
-pub use tlsn_core::{ProveConfig, ProveConfigBuilder, ProveConfigBuilderError, ProverOutput};
+pub use tlsn_core::{
+    ConnectionProveConfig, ProveConfig, ProveConfigBuilder, ProveConfigBuilderError, ProverOutput,
+};
 
 use mpz_common::Context;
 use mpz_core::Block;
@@ -19,17 +22,29 @@ use mpz_vm_core::prelude::*;
 use mpz_zk::ProverConfig as ZkProverConfig;
 use webpki::anchor_from_trusted_cert;
 
-use crate::{Role, context::build_mt_context, mux::attach_mux, tag::verify_tags};
+use crate::{
+    Next, Role,
+    config::{LimitMode, NetworkInfo, NetworkSetting, ProtocolLimits},
+    context::build_mt_context,
+    interrupt::{self, CancelHandle, Stage},
+    meter::{Meter, MeteredIo},
+    mux::{MuxControl, MuxFuture, attach_mux},
+    network,
+    progress::{Phase, Progress, Reporter},
+    tag::verify_tags,
+    verifier::abort::{Abort, AbortStream, Status},
+};
 
-use futures::{AsyncRead, AsyncWrite, TryFutureExt};
+use futures::{AsyncRead, AsyncWrite, Future, TryFutureExt, future};
 use mpc_tls::{LeaderCtrl, MpcTlsLeader, SessionKeys};
+#[cfg(not(tlsn_insecure_ideal))]
 use rand::Rng;
-use serio::SinkExt;
-use std::sync::Arc;
+use serio::{SinkExt, stream::IoStreamExt};
+use std::{collections::VecDeque, pin::Pin, sync::Arc};
 use tls_client::{ClientConnection, ServerName as TlsServerName};
 use tls_client_async::{TlsConnection, bind_client};
 use tlsn_core::{
-    connection::ServerName,
+    connection::{KeyType, ServerName, TlsVersion},
     transcript::{TlsTranscript, Transcript},
 };
 use tlsn_deap::Deap;
@@ -46,18 +61,38 @@ pub(crate) type RCOTReceiver = mpz_ot::rcot::shared::SharedRCOTReceiver<
     bool,
     mpz_core::Block,
 >;
+#[cfg(not(tlsn_insecure_ideal))]
 pub(crate) type Mpc =
     mpz_garble::protocol::semihonest::Garbler<mpz_ot::cot::DerandCOTSender<RCOTSender>>;
+#[cfg(tlsn_insecure_ideal)]
+pub(crate) type Mpc = crate::ideal::PlaintextVm;
 pub(crate) type Zk = mpz_zk::Prover<RCOTReceiver>;
 
+/// Future which returns the prover and the context of the MPC-TLS instance
+/// once a connection is closed.
+type ConnectionFuture =
+    Pin<Box<dyn Future<Output = Result<(Prover<state::Setup>, Context), ProverError>> + Send>>;
+
 /// A prover instance.
 #[derive(Debug)]
 pub struct Prover<T: state::ProverState = state::Initialized> {
     config: ProverConfig,
     span: Span,
+    cancel: CancelHandle,
     state: T,
 }
 
+impl<T: state::ProverState> Prover<T> {
+    /// Returns a handle which cancels the session.
+    ///
+    /// Cancelling interrupts the current stage of the prover, closing the
+    /// connection to the verifier, and returns an error carrying
+    /// [`Interrupt::Cancelled`](crate::interrupt::Interrupt::Cancelled).
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+}
+
 impl Prover<state::Initialized> {
     /// Creates a new prover.
     ///
@@ -69,6 +104,7 @@ impl Prover<state::Initialized> {
         Self {
             config,
             span,
+            cancel: CancelHandle::new(),
             state: state::Initialized,
         }
     }
@@ -86,46 +122,149 @@ impl Prover<state::Initialized> {
         self,
         socket: S,
     ) -> Result<Prover<state::Setup>, ProverError> {
-        let (mut mux_fut, mux_ctrl) = attach_mux(socket, Role::Prover);
-        let mut mt = build_mt_context(mux_ctrl.clone());
-        let mut ctx = mux_fut.poll_with(mt.new_context()).await?;
+        let cancel = self.cancel.clone();
+        let interrupt = interrupt::timeout(Stage::Setup, self.config.timeouts().setup());
+
+        interrupt::guard(&cancel, interrupt, async move {
+            let (mut mux_fut, mux_ctrl, meter) = attach_mux(socket, Role::Prover);
+            let reporter = Reporter::new(self.config.progress(), meter);
+            reporter.report(Progress::Started(Phase::Setup));
+
+            let mut mt = build_mt_context(mux_ctrl.clone());
+            let mut ctx = mux_fut.poll_with(mt.new_context()).await?;
+            let mut abort = AbortStream::new(mux_fut.poll_with(mt.new_context()).await?);
+
+            // Receives the verifier's limits and checks the protocol
+            // configuration against them before sending it.
+            let limits: ProtocolLimits = mux_fut.poll_with(ctx.io_mut().expect_next()).await?;
+            let result = match self.config.limit_mode() {
+                LimitMode::Strict => limits
+                    .validate(self.config.protocol_config())
+                    .map(|_| self.config.protocol_config().clone()),
+                LimitMode::Clamp => limits.clamp(self.config.protocol_config()),
+            };
+
+            let mut protocol_config = match result {
+                Ok(protocol_config) => protocol_config,
+                Err(err) => {
+                    // The configuration is never sent, so close the connection.
+                    mux_ctrl.close();
+                    let _ = mux_fut.await;
+
+                    // The verifier's limits reject the configuration, as the
+                    // verifier would have.
+                    return Err(ProverError::aborted(Abort::from(&err)));
+                }
+            };
+
+            let result = async {
+                // Sends protocol configuration to verifier for compatibility
+                // check.
+                mux_fut
+                    .poll_with(ctx.io_mut().send(protocol_config.clone()))
+                    .await?;
+                mux_fut.poll_with(recv_status(&mut ctx)).await?;
 
-        // Sends protocol configuration to verifier for compatibility check.
-        mux_fut
-            .poll_with(ctx.io_mut().send(self.config.protocol_config().clone()))
-            .await?;
+                let network = if let NetworkSetting::Auto = protocol_config.network() {
+                    debug!("measuring link to verifier");
 
-        let (vm, mut mpc_tls) = build_mpc_tls(&self.config, ctx);
+                    let link = mux_fut.poll_with(network::measure(&mut ctx)).await?;
+                    let setting = link.network_setting();
 
-        // Allocate resources for MPC-TLS in the VM.
-        let mut keys = mpc_tls.alloc()?;
-        let vm_lock = vm.try_lock().expect("VM is not locked");
-        translate_keys(&mut keys, &vm_lock)?;
-        drop(vm_lock);
+                    debug!(?link, ?setting, "selected network setting");
 
-        debug!("setting up mpc-tls");
+                    NetworkInfo {
+                        setting,
+                        link: Some(link),
+                    }
+                } else {
+                    NetworkInfo {
+                        setting: protocol_config.network(),
+                        link: None,
+                    }
+                };
+                protocol_config.set_network(network.setting);
 
-        mux_fut.poll_with(mpc_tls.preprocess()).await?;
+                reporter.report(Progress::Finished(Phase::Setup));
 
-        debug!("mpc-tls setup complete");
+                let mut config = self.config;
+                config.set_protocol_config(protocol_config);
 
-        Ok(Prover {
-            config: self.config,
-            span: self.span,
-            state: state::Setup {
-                mux_ctrl,
-                mux_fut,
-                mpc_tls,
-                keys,
-                vm,
-            },
+                // Each connection runs its own MPC-TLS instance on a separate
+                // context.
+                let mut ctxs = vec![ctx];
+                for _ in 1..config.protocol_config().max_connections() {
+                    ctxs.push(mux_fut.poll_with(mt.new_context()).await?);
+                }
+
+                let (vm, leaders) = build_mpc_tls(&config, ctxs)?;
+
+                debug!("setting up mpc-tls");
+                reporter.report(Progress::Started(Phase::Preprocess));
+
+                let mut mpc_tls = VecDeque::with_capacity(leaders.len());
+                for mut leader in leaders {
+                    leader.set_progress(reporter.mpc_tls())?;
+
+                    // Allocate resources for MPC-TLS in the VM.
+                    let mut keys = leader.alloc()?;
+                    let vm_lock = vm.try_lock().expect("VM is not locked");
+                    translate_keys(&mut keys, &vm_lock)?;
+                    drop(vm_lock);
+
+                    mux_fut.poll_with(leader.preprocess()).await?;
+                    mpc_tls.push_back((leader, keys));
+                }
+
+                reporter.report(Progress::Finished(Phase::Preprocess));
+                debug!("mpc-tls setup complete");
+
+                Ok::<_, ProverError>((config, mpc_tls, vm, network))
+            }
+            .await;
+
+            let (config, mpc_tls, vm, network) = match result {
+                Ok(result) => result,
+                Err(err) => return Err(check_abort(&mux_ctrl, &mut mux_fut, &mut abort, err).await),
+            };
+
+            Ok::<_, ProverError>(Prover {
+                config,
+                span: self.span,
+                cancel: self.cancel,
+                state: state::Setup {
+                    mux_ctrl,
+                    mux_fut,
+                    abort,
+                    mpc_tls,
+                    connections: Vec::new(),
+                    vm,
+                    network,
+                    reporter,
+                },
+            })
         })
+        .await
     }
 }
 
 impl Prover<state::Setup> {
+    /// Returns the network setting used for the session.
+    pub fn network_info(&self) -> &NetworkInfo {
+        &self.state.network
+    }
+
+    /// Returns the number of connections which can still be made.
+    pub fn remaining_connections(&self) -> usize {
+        self.state.mpc_tls.len()
+    }
+
     /// Connects to the server using the provided socket.
     ///
+    /// This is the last connection of the session, MPC-TLS is finalized once
+    /// it is closed. Use [`connect_next`](Self::connect_next) if more
+    /// connections follow.
+    ///
     /// Returns a handle to the TLS connection, a future which returns the
     /// prover once the connection is closed.
     ///
@@ -137,20 +276,106 @@ impl Prover<state::Setup> {
         self,
         socket: S,
     ) -> Result<(TlsConnection, ProverFuture), ProverError> {
+        let server_name = self.config.server_name().clone();
+        self.connect_to(server_name, socket).await
+    }
+
+    /// Connects to the server with the provided name using the provided
+    /// socket.
+    ///
+    /// Same as [`connect`](Self::connect), but connects to `server_name`
+    /// instead of the server name of the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_name` - The name of the server.
+    /// * `socket` - The socket to the server.
+    #[instrument(parent = &self.span, level = "debug", skip_all, err)]
+    pub async fn connect_to<S: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
+        self,
+        server_name: ServerName,
+        socket: S,
+    ) -> Result<(TlsConnection, ProverFuture), ProverError> {
+        let span = self.span.clone();
+        let (conn, ctrl, fut) = self.start_connection(server_name, socket, Next::Finalize)?;
+        let fut = Box::pin(
+            async move {
+                let (prover, ctx) = fut.await?;
+                prover.finalize(ctx).await
+            }
+            .instrument(span),
+        );
+
+        Ok((conn, ProverFuture { fut, ctrl }))
+    }
+
+    /// Connects to the server with the provided name using the provided
+    /// socket, keeping the session open for another connection.
+    ///
+    /// Returns a handle to the TLS connection, a future which returns the
+    /// prover once the connection is closed, ready for the next connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_name` - The name of the server.
+    /// * `socket` - The socket to the server.
+    #[instrument(parent = &self.span, level = "debug", skip_all, err)]
+    pub async fn connect_next<S: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
+        self,
+        server_name: ServerName,
+        socket: S,
+    ) -> Result<(TlsConnection, ProverFuture<state::Setup>), ProverError> {
+        if self.remaining_connections() < 2 {
+            return Err(ProverError::config(
+                "no connection remains after this one, increase `max_connections` in the protocol config or use `connect` for the last connection",
+            ));
+        }
+
+        let span = self.span.clone();
+        let (conn, ctrl, fut) = self.start_connection(server_name, socket, Next::Connection)?;
+        let fut = Box::pin(
+            async move {
+                let (prover, _) = fut.await?;
+                Ok(prover)
+            }
+            .instrument(span),
+        );
+
+        Ok((conn, ProverFuture { fut, ctrl }))
+    }
+
+    /// Starts a connection to the server with the next MPC-TLS instance.
+    ///
+    /// Returns a handle to the TLS connection, the controller of the prover
+    /// and a future which returns the prover and the context of the MPC-TLS
+    /// instance once the connection is closed.
+    fn start_connection<S: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
+        self,
+        server_name: ServerName,
+        socket: S,
+        next: Next,
+    ) -> Result<(TlsConnection, ProverControl, ConnectionFuture), ProverError> {
         let state::Setup {
             mux_ctrl,
             mut mux_fut,
-            mpc_tls,
-            keys,
+            mut abort,
+            mut mpc_tls,
+            mut connections,
             vm,
-            ..
+            network,
+            reporter,
         } = self.state;
 
-        let (mpc_ctrl, mpc_fut) = mpc_tls.run();
+        let (mpc_tls_leader, keys) = mpc_tls
+            .pop_front()
+            .ok_or_else(|| ProverError::config("no connections remain"))?;
+
+        reporter.report(Progress::Started(Phase::Handshake));
+        let (mpc_ctrl, mpc_fut) = mpc_tls_leader.run();
 
-        let ServerName::Dns(server_name) = self.config.server_name();
-        let server_name =
-            TlsServerName::try_from(server_name.as_ref()).expect("name was validated");
+        let ServerName::Dns(name) = &server_name;
+        let tls_server_name =
+            TlsServerName::try_from(name.as_ref()).map_err(ProverError::config)?;
 
         let root_store = if let Some(root_store) = self.config.tls_config().root_store() {
             let roots = root_store
@@ -170,11 +395,38 @@ impl Prover<state::Setup> {
             }
         };
 
+        let version = match self.config.protocol_config().tls_version() {
+            TlsVersion::V1_2 => &tls_client::version::TLS12,
+            TlsVersion::V1_3 => &tls_client::version::TLS13,
+        };
+
+        // Only offer the cipher suites which are supported by the MPC-TLS
+        // configuration.
+        let cipher_suites = self
+            .config
+            .build_mpc_tls_config()
+            .map_err(ProverError::config)?
+            .cipher_suites();
+
+        let kx_group = match self.config.protocol_config().key_type() {
+            KeyType::SECP256R1 => &tls_client::kx_group::SECP256R1,
+            KeyType::X25519 => &tls_client::kx_group::X25519,
+            KeyType::SECP384R1 => &tls_client::kx_group::SECP384R1,
+            typ => {
+                return Err(ProverError::config(format!(
+                    "unsupported key type: {typ:?}"
+                )));
+            }
+        };
+
         let config = tls_client::ClientConfig::builder()
-            .with_safe_defaults()
+            .with_cipher_suites(&cipher_suites)
+            .with_kx_groups(&[kx_group])
+            .with_protocol_versions(&[version])
+            .map_err(ProverError::config)?
             .with_root_certificates(root_store);
 
-        let config = if let Some((cert, key)) = self.config.tls_config().client_auth() {
+        let mut config = if let Some((cert, key)) = self.config.tls_config().client_auth() {
             config
                 .with_single_cert(
                     cert.iter()
@@ -186,143 +438,339 @@ impl Prover<state::Setup> {
         } else {
             config.with_no_client_auth()
         };
+        config.require_ems = self.config.protocol_config().extended_master_secret();
+
+        let client = ClientConnection::new(
+            Arc::new(config),
+            Box::new(mpc_ctrl.clone()),
+            tls_server_name,
+        )
+        .map_err(ProverError::config)?;
 
-        let client =
-            ClientConnection::new(Arc::new(config), Box::new(mpc_ctrl.clone()), server_name)
-                .map_err(ProverError::config)?;
+        // Meter the traffic with the server to detect when it is idle.
+        let server_meter = Meter::default();
+        let socket = MeteredIo::new(socket, server_meter.clone());
 
         let (conn, conn_fut) = bind_client(socket, client);
 
+        let ctrl = ProverControl {
+            mpc_ctrl: mpc_ctrl.clone(),
+            cancel: self.cancel.clone(),
+        };
         let fut = Box::pin({
-            let span = self.span.clone();
-            let mpc_ctrl = mpc_ctrl.clone();
+            let cancel = self.cancel.clone();
+            let timeouts = *self.config.timeouts();
+            let handshake = {
+                let reporter = reporter.clone();
+                async move { interrupt::handshake(&reporter, timeouts.handshake()).await }
+            };
             async move {
-                let conn_fut = async {
-                    mux_fut
-                        .poll_with(conn_fut.map_err(ProverError::from))
+                let fut = async {
+                    let result = async {
+                        let conn_fut = async {
+                            mux_fut
+                                .poll_with(conn_fut.map_err(ProverError::from))
+                                .await?;
+
+                            mpc_ctrl.stop().await?;
+
+                            Ok::<_, ProverError>(())
+                        };
+
+                        info!("starting MPC-TLS");
+
+                        // The connection is interrupted if the server is idle
+                        // for too long.
+                        let (_, (mut ctx, tls_transcript)) = interrupt::guard(
+                            &cancel,
+                            interrupt::idle(&server_meter, timeouts.server_idle()),
+                            async {
+                                futures::try_join!(
+                                    conn_fut,
+                                    mpc_fut.in_current_span().map_err(ProverError::from)
+                                )
+                            },
+                        )
                         .await?;
 
-                    mpc_ctrl.stop().await?;
+                        info!("finished MPC-TLS");
 
-                    Ok::<_, ProverError>(())
-                };
+                        // Notify the verifier whether another connection
+                        // follows.
+                        mux_fut
+                            .poll_with(ctx.io_mut().send(next).map_err(ProverError::from))
+                            .await?;
 
-                info!("starting MPC-TLS");
+                        Ok::<_, ProverError>((ctx, tls_transcript))
+                    }
+                    .await;
 
-                let (_, (mut ctx, tls_transcript)) = futures::try_join!(
-                    conn_fut,
-                    mpc_fut.in_current_span().map_err(ProverError::from)
-                )?;
+                    let (ctx, tls_transcript) = match result {
+                        Ok(result) => result,
+                        Err(err) => {
+                            return Err(check_abort(&mux_ctrl, &mut mux_fut, &mut abort, err).await);
+                        }
+                    };
 
-                info!("finished MPC-TLS");
+                    let transcript = tls_transcript
+                        .to_transcript()
+                        .expect("transcript is complete");
+
+                    connections.push(state::Connection {
+                        server_name,
+                        keys,
+                        tls_transcript,
+                        transcript,
+                    });
+
+                    Ok::<_, ProverError>((
+                        Prover {
+                            config: self.config,
+                            span: self.span,
+                            cancel: self.cancel,
+                            state: state::Setup {
+                                mux_ctrl,
+                                mux_fut,
+                                abort,
+                                mpc_tls,
+                                connections,
+                                vm,
+                                network,
+                                reporter,
+                            },
+                        },
+                        ctx,
+                    ))
+                };
 
-                {
-                    let mut vm = vm.try_lock().expect("VM should not be locked");
+                interrupt::guard(&cancel, handshake, fut).await
+            }
+        });
 
-                    debug!("finalizing mpc");
+        Ok((conn, ctrl, fut))
+    }
 
-                    // Finalize DEAP.
-                    mux_fut
-                        .poll_with(vm.finalize(&mut ctx))
-                        .await
-                        .map_err(ProverError::mpc)?;
+    /// Finalizes MPC-TLS after the last connection is closed.
+    async fn finalize(self, mut ctx: Context) -> Result<Prover<state::Committed>, ProverError> {
+        let Prover {
+            config,
+            span,
+            cancel,
+            state:
+                state::Setup {
+                    mux_ctrl,
+                    mut mux_fut,
+                    mut abort,
+                    mpc_tls,
+                    connections,
+                    vm,
+                    network,
+                    reporter,
+                },
+        } = self;
+
+        // The instances of unused connections hold references to the VM.
+        drop(mpc_tls);
+
+        let result = interrupt::guard(&cancel, future::pending(), async {
+            reporter.report(Progress::Started(Phase::Finalize));
+
+            {
+                let mut vm = vm.try_lock().expect("VM should not be locked");
+
+                debug!("finalizing mpc");
+
+                // Finalize DEAP.
+                mux_fut
+                    .poll_with(vm.finalize(&mut ctx))
+                    .await
+                    .map_err(ProverError::mpc)?;
 
-                    debug!("mpc finalized");
-                }
+                debug!("mpc finalized");
+            }
 
-                // Pull out ZK VM.
-                let (_, mut vm) = Arc::into_inner(vm)
-                    .expect("vm should have only 1 reference")
-                    .into_inner()
-                    .into_inner();
+            // Pull out ZK VM.
+            let (_, mut vm) = Arc::into_inner(vm)
+                .expect("vm should have only 1 reference")
+                .into_inner()
+                .into_inner();
 
-                // Prove tag verification of received records.
-                // The prover drops the proof output.
+            // Prove tag verification of received records.
+            // The prover drops the proof output.
+            for connection in &connections {
+                let keys = &connection.keys;
                 let _ = verify_tags(
                     &mut vm,
+                    keys.aead,
                     (keys.server_write_key, keys.server_write_iv),
                     keys.server_write_mac_key,
-                    *tls_transcript.version(),
-                    tls_transcript.recv().to_vec(),
+                    *connection.tls_transcript.version(),
+                    connection.tls_transcript.recv().to_vec(),
                 )
                 .map_err(ProverError::zk)?;
+            }
 
-                mux_fut
-                    .poll_with(vm.execute_all(&mut ctx).map_err(ProverError::zk))
-                    .await?;
+            mux_fut
+                .poll_with(vm.execute_all(&mut ctx).map_err(ProverError::zk))
+                .await?;
+            mux_fut.poll_with(recv_status(&mut ctx)).await?;
+            reporter.report(Progress::Finished(Phase::Finalize));
 
-                let transcript = tls_transcript
-                    .to_transcript()
-                    .expect("transcript is complete");
+            Ok::<_, ProverError>(vm)
+        })
+        .await;
 
-                Ok(Prover {
-                    config: self.config,
-                    span: self.span,
-                    state: state::Committed {
-                        mux_ctrl,
-                        mux_fut,
-                        ctx,
-                        vm,
-                        keys,
-                        tls_transcript,
-                        transcript,
-                    },
-                })
-            }
-            .instrument(span)
-        });
+        let vm = match result {
+            Ok(vm) => vm,
+            Err(err) => return Err(check_abort(&mux_ctrl, &mut mux_fut, &mut abort, err).await),
+        };
 
-        Ok((
-            conn,
-            ProverFuture {
-                fut,
-                ctrl: ProverControl { mpc_ctrl },
+        Ok(Prover {
+            config,
+            span,
+            cancel,
+            state: state::Committed {
+                mux_ctrl,
+                mux_fut,
+                abort,
+                ctx,
+                vm,
+                connections,
+                network,
+                reporter,
             },
-        ))
+        })
     }
 }
 
 impl Prover<state::Committed> {
     /// Returns the TLS transcript.
+    ///
+    /// Use [`tls_transcript_of`](Self::tls_transcript_of) if the session has
+    /// more than one connection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the session has more than one connection.
     pub fn tls_transcript(&self) -> &TlsTranscript {
-        &self.state.tls_transcript
+        assert_eq!(
+            self.connection_count(),
+            1,
+            "session has more than one connection, use `tls_transcript_of`"
+        );
+        &self.state.connections[0].tls_transcript
     }
 
     /// Returns the transcript.
+    ///
+    /// Use [`transcript_of`](Self::transcript_of) if the session has more than
+    /// one connection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the session has more than one connection.
     pub fn transcript(&self) -> &Transcript {
-        &self.state.transcript
+        assert_eq!(
+            self.connection_count(),
+            1,
+            "session has more than one connection, use `transcript_of`"
+        );
+        &self.state.connections[0].transcript
+    }
+
+    /// Returns the number of connections of the session.
+    pub fn connection_count(&self) -> usize {
+        self.state.connections.len()
+    }
+
+    /// Returns the TLS transcript of the connection with the given index, in
+    /// the order the connections were made.
+    pub fn tls_transcript_of(&self, connection: usize) -> Option<&TlsTranscript> {
+        self.state
+            .connections
+            .get(connection)
+            .map(|connection| &connection.tls_transcript)
+    }
+
+    /// Returns the transcript of the connection with the given index, in the
+    /// order the connections were made.
+    pub fn transcript_of(&self, connection: usize) -> Option<&Transcript> {
+        self.state
+            .connections
+            .get(connection)
+            .map(|connection| &connection.transcript)
+    }
+
+    /// Returns the network setting used for the session.
+    pub fn network_info(&self) -> &NetworkInfo {
+        &self.state.network
     }
 
     /// Proves information to the verifier.
     ///
+    /// The connections which are proven, and what is disclosed about each of
+    /// them, are selected by the [`ProveConfig`]. Returns an output for each
+    /// connection, in the order the connections were made.
+    ///
+    /// May be called repeatedly, each call is matched by a call to
+    /// [`Verifier::verify`](crate::verifier::Verifier::verify).
+    ///
     /// # Arguments
     ///
     /// * `config` - The disclosure configuration.
     #[instrument(parent = &self.span, level = "info", skip_all, err)]
-    pub async fn prove(&mut self, config: &ProveConfig) -> Result<ProverOutput, ProverError> {
+    pub async fn prove(&mut self, config: &ProveConfig) -> Result<Vec<ProverOutput>, ProverError> {
         let state::Committed {
+            mux_ctrl,
             mux_fut,
+            abort,
             ctx,
             vm,
-            keys,
-            tls_transcript,
-            transcript,
+            connections,
+            reporter,
             ..
         } = &mut self.state;
 
-        let output = mux_fut
-            .poll_with(prove::prove(
-                ctx,
-                vm,
-                keys,
-                self.config.server_name(),
-                transcript,
-                tls_transcript,
-                config,
-            ))
-            .await?;
+        if let Some(config) = config
+            .connections()
+            .iter()
+            .find(|config| config.connection() >= connections.len())
+        {
+            return Err(ProverError::config(format!(
+                "connection {} does not exist, the session has {} connections",
+                config.connection(),
+                connections.len()
+            )));
+        }
+
+        let interrupt = interrupt::timeout(Stage::Prove, self.config.timeouts().prove());
+
+        let result = interrupt::guard(&self.cancel, interrupt, async {
+            reporter.report(Progress::Started(Phase::Prove));
 
-        Ok(output)
+            let mut outputs = mux_fut
+                .poll_with(prove::prove(ctx, vm, connections, config, reporter))
+                .await?;
+            mux_fut.poll_with(recv_status(ctx)).await?;
+
+            reporter.report(Progress::Finished(Phase::Prove));
+            let metrics = reporter.metrics();
+            for output in &mut outputs {
+                output.metrics = metrics;
+            }
+
+            Ok::<_, ProverError>(outputs)
+        })
+        .await;
+
+        match result {
+            // A rejected request leaves the session intact, so another request
+            // may be sent.
+            Err(err) if err.rejection().is_none() => {
+                Err(check_abort(mux_ctrl, mux_fut, abort, err).await)
+            }
+            result => result,
+        }
     }
 
     /// Closes the connection with the verifier.
@@ -342,7 +790,43 @@ impl Prover<state::Committed> {
     }
 }
 
-fn build_mpc_tls(config: &ProverConfig, ctx: Context) -> (Arc<Mutex<Deap<Mpc, Zk>>>, MpcTlsLeader) {
+/// Replaces the error with the abort of the verifier if the verifier aborted
+/// the protocol, in which case the connection is closed.
+///
+/// Returns the error.
+async fn check_abort(
+    mux_ctrl: &MuxControl,
+    mux_fut: &mut MuxFuture,
+    abort: &mut AbortStream,
+    err: ProverError,
+) -> ProverError {
+    let err = match abort.try_recv() {
+        Some(abort) if err.abort().is_none() && err.interrupt().is_none() => {
+            ProverError::aborted(abort)
+        }
+        _ => err,
+    };
+
+    if err.abort().is_some() && !mux_fut.is_complete() {
+        mux_ctrl.close();
+        let _ = mux_fut.await;
+    }
+
+    err
+}
+
+/// Receives the status of a protocol step from the verifier.
+async fn recv_status(ctx: &mut Context) -> Result<(), ProverError> {
+    let status: Status = ctx.io_mut().expect_next().await?;
+
+    status.map_err(ProverError::aborted)
+}
+
+fn build_mpc_tls(
+    config: &ProverConfig,
+    ctxs: Vec<Context>,
+) -> Result<(Arc<Mutex<Deap<Mpc, Zk>>>, Vec<MpcTlsLeader>), ProverError> {
+    let mpc_tls_config = config.build_mpc_tls_config().map_err(ProverError::config)?;
     let mut rng = rand::rng();
     let delta = Delta::new(Block::random(&mut rng));
 
@@ -367,32 +851,43 @@ fn build_mpc_tls(config: &ProverConfig, ctx: Context) -> (Arc<Mutex<Deap<Mpc, Zk
     let rcot_send = mpz_ot::rcot::shared::SharedRCOTSender::new(rcot_send);
     let rcot_recv = mpz_ot::rcot::shared::SharedRCOTReceiver::new(rcot_recv);
 
+    #[cfg(not(tlsn_insecure_ideal))]
     let mpc = Mpc::new(
         mpz_ot::cot::DerandCOTSender::new(rcot_send.clone()),
         rng.random(),
         delta,
     );
+    #[cfg(tlsn_insecure_ideal)]
+    let mpc = {
+        tracing::warn!("INSECURE: using the ideal mode which reveals all private inputs");
+        Mpc::new()
+    };
 
     let zk = Zk::new(ZkProverConfig::default(), rcot_recv.clone());
 
     let vm = Arc::new(Mutex::new(Deap::new(tlsn_deap::Role::Leader, mpc, zk)));
 
-    (
-        vm.clone(),
-        MpcTlsLeader::new(
-            config.build_mpc_tls_config(),
-            ctx,
-            vm,
-            (rcot_send.clone(), rcot_send.clone(), rcot_send),
-            rcot_recv,
-        ),
-    )
+    let leaders = ctxs
+        .into_iter()
+        .map(|ctx| {
+            MpcTlsLeader::new(
+                mpc_tls_config.clone(),
+                ctx,
+                vm.clone(),
+                (rcot_send.clone(), rcot_send.clone(), rcot_send.clone()),
+                rcot_recv.clone(),
+            )
+        })
+        .collect();
+
+    Ok((vm, leaders))
 }
 
 /// A controller for the prover.
 #[derive(Clone)]
 pub struct ProverControl {
     mpc_ctrl: LeaderCtrl,
+    cancel: CancelHandle,
 }
 
 impl ProverControl {
@@ -406,13 +901,46 @@ impl ProverControl {
     ///
     /// * The prover may need to close the connection to the server in order for
     ///   it to close the connection on its end. If neither the prover or server
-    ///   close the connection this will cause a deadlock.
+    ///   close the connection this will cause a deadlock, unless a server idle
+    ///   timeout is configured or the session is cancelled.
     pub async fn defer_decryption(&self) -> Result<(), ProverError> {
         self.mpc_ctrl
             .defer_decryption()
             .await
             .map_err(ProverError::from)
     }
+
+    /// Sets which data from the server is decrypted while the connection is
+    /// active.
+    ///
+    /// Records which are not selected are decrypted once the server has
+    /// closed the connection. Decrypted data is always returned in the order
+    /// it was received, so data following a deferred record is held back
+    /// until that record is decrypted.
+    ///
+    /// # Notes
+    ///
+    /// * Selecting [`OnlineDecryption::None`] is equivalent to
+    ///   [`defer_decryption`](Self::defer_decryption).
+    /// * Online decryption is limited by
+    ///   [`ProtocolConfig::max_recv_data_online`](crate::config::ProtocolConfig::max_recv_data_online).
+    /// * Selecting [`OnlineDecryption::Records`] or [`OnlineDecryption::Bytes`]
+    ///   is rejected if the session is configured for TLS 1.3.
+    pub async fn decrypt_online(&self, decrypt: OnlineDecryption) -> Result<(), ProverError> {
+        self.mpc_ctrl
+            .set_online_decryption(decrypt)
+            .await
+            .map_err(ProverError::from)
+    }
+
+    /// Cancels the session.
+    ///
+    /// The [`ProverFuture`] returns an error carrying
+    /// [`Interrupt::Cancelled`](crate::interrupt::Interrupt::Cancelled), and
+    /// the connections to the server and the verifier are closed.
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
 }
 
 /// Translates VM references to the ZK address space.
@@ -429,8 +957,10 @@ fn translate_keys<Mpc, Zk>(keys: &mut SessionKeys, vm: &Deap<Mpc, Zk>) -> Result
     keys.server_write_iv = vm
         .translate(keys.server_write_iv)
         .map_err(ProverError::mpc)?;
-    keys.server_write_mac_key = vm
-        .translate(keys.server_write_mac_key)
+    keys.server_write_mac_key = keys
+        .server_write_mac_key
+        .map(|mac_key| vm.translate(mac_key))
+        .transpose()
         .map_err(ProverError::mpc)?;
 
     Ok(())
@@ -1,3 +1,5 @@
+use std::{fmt, sync::Arc};
+
 use mpc_tls::Config;
 use serde::{Deserialize, Serialize};
 use tlsn_core::{
@@ -5,19 +7,54 @@ use tlsn_core::{
     webpki::{CertificateDer, PrivateKeyDer, RootCertStore},
 };
 
-use crate::config::{NetworkSetting, ProtocolConfig};
+use crate::{
+    config::{LimitMode, ProtocolConfig, ProtocolConfigError},
+    interrupt::Timeouts,
+    progress::ProgressHandler,
+};
 
 /// Configuration for the prover.
-#[derive(Debug, Clone, derive_builder::Builder, Serialize, Deserialize)]
+#[derive(Clone, derive_builder::Builder, Serialize, Deserialize)]
 pub struct ProverConfig {
     /// The server DNS name.
     #[builder(setter(into))]
     server_name: ServerName,
     /// Protocol configuration to be checked with the verifier.
     protocol_config: ProtocolConfig,
+    /// How to handle a protocol configuration which exceeds the verifier's
+    /// limits.
+    #[builder(default)]
+    limit_mode: LimitMode,
     /// TLS configuration.
     #[builder(default)]
     tls_config: TlsConfig,
+    /// Timeouts for the stages of the session.
+    #[builder(default)]
+    timeouts: Timeouts,
+    /// Handler which is notified of the progress of the session.
+    #[builder(default, setter(custom))]
+    #[serde(skip)]
+    progress: Option<Arc<dyn ProgressHandler>>,
+}
+
+impl ProverConfigBuilder {
+    /// Sets a handler which is notified of the progress of the session.
+    pub fn progress(&mut self, handler: impl ProgressHandler) -> &mut Self {
+        self.progress = Some(Some(Arc::new(handler)));
+        self
+    }
+}
+
+impl fmt::Debug for ProverConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverConfig")
+            .field("server_name", &self.server_name)
+            .field("protocol_config", &self.protocol_config)
+            .field("limit_mode", &self.limit_mode)
+            .field("tls_config", &self.tls_config)
+            .field("timeouts", &self.timeouts)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ProverConfig {
@@ -36,33 +73,32 @@ impl ProverConfig {
         &self.protocol_config
     }
 
+    /// Returns how to handle a protocol configuration which exceeds the
+    /// verifier's limits.
+    pub fn limit_mode(&self) -> LimitMode {
+        self.limit_mode
+    }
+
     /// Returns the TLS configuration.
     pub fn tls_config(&self) -> &TlsConfig {
         &self.tls_config
     }
 
-    pub(crate) fn build_mpc_tls_config(&self) -> Config {
-        let mut builder = Config::builder();
-
-        builder
-            .defer_decryption(self.protocol_config.defer_decryption_from_start())
-            .max_sent(self.protocol_config.max_sent_data())
-            .max_recv_online(self.protocol_config.max_recv_data_online())
-            .max_recv(self.protocol_config.max_recv_data());
-
-        if let Some(max_sent_records) = self.protocol_config.max_sent_records() {
-            builder.max_sent_records(max_sent_records);
-        }
+    /// Returns the timeouts for the stages of the session.
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
 
-        if let Some(max_recv_records_online) = self.protocol_config.max_recv_records_online() {
-            builder.max_recv_records_online(max_recv_records_online);
-        }
+    pub(crate) fn progress(&self) -> Option<Arc<dyn ProgressHandler>> {
+        self.progress.clone()
+    }
 
-        if let NetworkSetting::Latency = self.protocol_config.network() {
-            builder.low_bandwidth();
-        }
+    pub(crate) fn set_protocol_config(&mut self, protocol_config: ProtocolConfig) {
+        self.protocol_config = protocol_config;
+    }
 
-        builder.build().unwrap()
+    pub(crate) fn build_mpc_tls_config(&self) -> Result<Config, ProtocolConfigError> {
+        self.protocol_config.build_mpc_tls_config()
     }
 }
 
@@ -2,7 +2,11 @@ use std::{error::Error, fmt};
 
 use mpc_tls::MpcTlsError;
 
-use crate::transcript_internal::commit::encoding::EncodingError;
+use crate::{
+    interrupt::Interrupt,
+    transcript_internal::commit::encoding::EncodingError,
+    verifier::{Abort, PolicyRejection},
+};
 
 /// Error for [`Prover`](crate::Prover).
 #[derive(Debug, thiserror::Error)]
@@ -49,6 +53,42 @@ impl ProverError {
     {
         Self::new(ErrorKind::Commit, source)
     }
+
+    pub(crate) fn aborted(abort: Abort) -> Self {
+        Self::new(ErrorKind::Aborted, abort)
+    }
+
+    pub(crate) fn rejected(rejection: PolicyRejection) -> Self {
+        Self::new(ErrorKind::Rejected, rejection)
+    }
+
+    /// Returns the abort if the verifier aborted the protocol.
+    pub fn abort(&self) -> Option<&Abort> {
+        if let ErrorKind::Aborted = self.kind {
+            self.source.as_ref()?.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the interrupt if the session was cancelled or timed out.
+    pub fn interrupt(&self) -> Option<&Interrupt> {
+        if let ErrorKind::Interrupted = self.kind {
+            self.source.as_ref()?.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the rejection if the verifier rejected the request due to its
+    /// disclosure policy.
+    pub fn rejection(&self) -> Option<&PolicyRejection> {
+        if let ErrorKind::Rejected = self.kind {
+            self.source.as_ref()?.downcast_ref()
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -58,6 +98,9 @@ enum ErrorKind {
     Zk,
     Config,
     Commit,
+    Rejected,
+    Aborted,
+    Interrupted,
 }
 
 impl fmt::Display for ProverError {
@@ -70,6 +113,9 @@ impl fmt::Display for ProverError {
             ErrorKind::Zk => f.write_str("zk error")?,
             ErrorKind::Config => f.write_str("config error")?,
             ErrorKind::Commit => f.write_str("commit error")?,
+            ErrorKind::Rejected => f.write_str("rejected by verifier")?,
+            ErrorKind::Aborted => f.write_str("aborted by verifier")?,
+            ErrorKind::Interrupted => f.write_str("interrupted")?,
         }
 
         if let Some(source) = &self.source {
@@ -86,6 +132,12 @@ impl From<std::io::Error> for ProverError {
     }
 }
 
+impl From<Interrupt> for ProverError {
+    fn from(e: Interrupt) -> Self {
+        Self::new(ErrorKind::Interrupted, e)
+    }
+}
+
 impl From<tls_client_async::ConnectionError> for ProverError {
     fn from(e: tls_client_async::ConnectionError) -> Self {
         Self::new(ErrorKind::Io, e)
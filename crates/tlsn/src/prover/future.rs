@@ -5,23 +5,23 @@ use futures::Future;
 use std::pin::Pin;
 
 /// Prover future which must be polled for the TLS connection to make progress.
-pub struct ProverFuture {
+///
+/// Returns the prover in state `T` once the connection is closed.
+pub struct ProverFuture<T: state::ProverState = state::Committed> {
     #[allow(clippy::type_complexity)]
-    pub(crate) fut: Pin<
-        Box<dyn Future<Output = Result<Prover<state::Committed>, ProverError>> + Send + 'static>,
-    >,
+    pub(crate) fut: Pin<Box<dyn Future<Output = Result<Prover<T>, ProverError>> + Send + 'static>>,
     pub(crate) ctrl: ProverControl,
 }
 
-impl ProverFuture {
+impl<T: state::ProverState> ProverFuture<T> {
     /// Returns a controller for the prover for advanced functionality.
     pub fn control(&self) -> ProverControl {
         self.ctrl.clone()
     }
 }
 
-impl Future for ProverFuture {
-    type Output = Result<Prover<state::Committed>, ProverError>;
+impl<T: state::ProverState> Future for ProverFuture<T> {
+    type Output = Result<Prover<T>, ProverError>;
 
     fn poll(
         mut self: Pin<&mut Self>,
@@ -1,44 +1,76 @@
-use mpc_tls::SessionKeys;
 use mpz_common::Context;
 use mpz_memory_core::binary::Binary;
 use mpz_vm_core::Vm;
 use rangeset::{RangeSet, UnionMut};
-use serio::SinkExt;
+use serio::{SinkExt, stream::IoStreamExt};
 use tlsn_core::{
-    ProveConfig, ProveRequest, ProverOutput,
-    connection::{HandshakeData, ServerName},
-    transcript::{
-        ContentType, Direction, TlsTranscript, Transcript, TranscriptCommitment, TranscriptSecret,
-    },
+    ConnectionProveConfig, ProveConfig, ProveRequest, ProverOutput,
+    connection::HandshakeData,
+    metrics::SessionMetrics,
+    transcript::{Direction, TranscriptCommitment, TranscriptSecret},
 };
 
 use crate::{
-    prover::ProverError,
+    progress::{Phase, Progress, Reporter},
+    prover::{ProverError, state::Connection},
     transcript_internal::{
-        TranscriptRefs,
+        TranscriptRefs, app_records,
         auth::prove_plaintext,
         commit::{
             encoding::{self, MacStore},
             hash::prove_hash,
         },
     },
+    verifier::PolicyRejection,
 };
 
 pub(crate) async fn prove<T: Vm<Binary> + MacStore + Send + Sync>(
     ctx: &mut Context,
     vm: &mut T,
-    keys: &SessionKeys,
-    server_name: &ServerName,
-    transcript: &Transcript,
-    tls_transcript: &TlsTranscript,
+    connections: &[Connection],
     config: &ProveConfig,
-) -> Result<ProverOutput, ProverError> {
-    let mut output = ProverOutput {
-        transcript_commitments: Vec::default(),
-        transcript_secrets: Vec::default(),
-    };
+    reporter: &Reporter,
+) -> Result<Vec<ProverOutput>, ProverError> {
+    let requests: Vec<ProveRequest> = config
+        .connections()
+        .iter()
+        .map(|config| request(&connections[config.connection()], config))
+        .collect();
+
+    ctx.io_mut()
+        .send(requests)
+        .await
+        .map_err(ProverError::from)?;
+
+    // Wait for the verifier to check the requests against its policy.
+    let decision: Result<(), PolicyRejection> = ctx
+        .io_mut()
+        .expect_next()
+        .await
+        .map_err(ProverError::from)?;
+    decision.map_err(ProverError::rejected)?;
+
+    // The connections are proven in the order of the requests.
+    let mut outputs = Vec::with_capacity(config.connections().len());
+    for config in config.connections() {
+        outputs.push(
+            prove_connection(ctx, vm, &connections[config.connection()], config, reporter).await?,
+        );
+    }
+
+    Ok(outputs)
+}
 
-    let request = ProveRequest {
+fn request(connection: &Connection, config: &ConnectionProveConfig) -> ProveRequest {
+    let Connection {
+        server_name,
+        tls_transcript,
+        transcript,
+        ..
+    } = connection;
+
+    ProveRequest {
+        connection: config.connection(),
         handshake: config.server_identity().then(|| {
             (
                 server_name.clone(),
@@ -59,12 +91,29 @@ pub(crate) async fn prove<T: Vm<Binary> + MacStore + Send + Sync>(
             .reveal()
             .map(|(sent, recv)| transcript.to_partial(sent.clone(), recv.clone())),
         transcript_commit: config.transcript_commit().map(|config| config.to_request()),
-    };
+    }
+}
 
-    ctx.io_mut()
-        .send(request)
-        .await
-        .map_err(ProverError::from)?;
+async fn prove_connection<T: Vm<Binary> + MacStore + Send + Sync>(
+    ctx: &mut Context,
+    vm: &mut T,
+    connection: &Connection,
+    config: &ConnectionProveConfig,
+    reporter: &Reporter,
+) -> Result<ProverOutput, ProverError> {
+    let Connection {
+        keys,
+        tls_transcript,
+        transcript,
+        ..
+    } = connection;
+
+    let mut output = ProverOutput {
+        connection: config.connection(),
+        transcript_commitments: Vec::default(),
+        transcript_secrets: Vec::default(),
+        metrics: SessionMetrics::default(),
+    };
 
     let (reveal_sent, reveal_recv) = config.reveal().cloned().unwrap_or_default();
     let (mut commit_sent, mut commit_recv) = (RangeSet::default(), RangeSet::default());
@@ -87,26 +136,22 @@ pub(crate) async fn prove<T: Vm<Binary> + MacStore + Send + Sync>(
     let transcript_refs = TranscriptRefs {
         sent: prove_plaintext(
             vm,
+            keys.aead,
             keys.client_write_key,
             keys.client_write_iv,
             transcript.sent(),
-            tls_transcript
-                .sent()
-                .iter()
-                .filter(|record| record.typ == ContentType::ApplicationData),
+            app_records(tls_transcript, Direction::Sent).iter(),
             &reveal_sent,
             &commit_sent,
         )
         .map_err(ProverError::commit)?,
         recv: prove_plaintext(
             vm,
+            keys.aead,
             keys.server_write_key,
             keys.server_write_iv,
             transcript.received(),
-            tls_transcript
-                .recv()
-                .iter()
-                .filter(|record| record.typ == ContentType::ApplicationData),
+            app_records(tls_transcript, Direction::Received).iter(),
             &reveal_recv,
             &commit_recv,
         )
@@ -135,6 +180,8 @@ pub(crate) async fn prove<T: Vm<Binary> + MacStore + Send + Sync>(
     if let Some(commit_config) = config.transcript_commit()
         && commit_config.has_encoding()
     {
+        reporter.report(Progress::Started(Phase::Commit));
+
         let mut sent_ranges = RangeSet::default();
         let mut recv_ranges = RangeSet::default();
         for (dir, idx) in commit_config.iter_encoding() {
@@ -169,6 +216,8 @@ pub(crate) async fn prove<T: Vm<Binary> + MacStore + Send + Sync>(
         output
             .transcript_secrets
             .push(TranscriptSecret::Encoding(tree));
+
+        reporter.report(Progress::Finished(Phase::Commit));
     }
 
     if let Some((hash_fut, hash_secrets)) = hash_commitments {
@@ -1,16 +1,22 @@
 //! TLS prover states.
 
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
 use mpc_tls::{MpcTlsLeader, SessionKeys};
 use mpz_common::Context;
-use tlsn_core::transcript::{TlsTranscript, Transcript};
+use tlsn_core::{
+    connection::ServerName,
+    transcript::{TlsTranscript, Transcript},
+};
 use tlsn_deap::Deap;
 use tokio::sync::Mutex;
 
 use crate::{
+    config::NetworkInfo,
     mux::{MuxControl, MuxFuture},
+    progress::Reporter,
     prover::{Mpc, Zk},
+    verifier::abort::AbortStream,
 };
 
 /// Entry state
@@ -22,26 +28,40 @@ opaque_debug::implement!(Initialized);
 pub struct Setup {
     pub(crate) mux_ctrl: MuxControl,
     pub(crate) mux_fut: MuxFuture,
-    pub(crate) mpc_tls: MpcTlsLeader,
-    pub(crate) keys: SessionKeys,
+    pub(crate) abort: AbortStream,
+    /// MPC-TLS instances of the remaining connections.
+    pub(crate) mpc_tls: VecDeque<(MpcTlsLeader, SessionKeys)>,
+    /// Connections which have been closed.
+    pub(crate) connections: Vec<Connection>,
     pub(crate) vm: Arc<Mutex<Deap<Mpc, Zk>>>,
+    pub(crate) network: NetworkInfo,
+    pub(crate) reporter: Reporter,
 }
 
 opaque_debug::implement!(Setup);
 
-/// State after the TLS connection has been committed and closed.
+/// State after the TLS connections have been committed and closed.
 pub struct Committed {
     pub(crate) mux_ctrl: MuxControl,
     pub(crate) mux_fut: MuxFuture,
+    pub(crate) abort: AbortStream,
     pub(crate) ctx: Context,
     pub(crate) vm: Zk,
+    pub(crate) connections: Vec<Connection>,
+    pub(crate) network: NetworkInfo,
+    pub(crate) reporter: Reporter,
+}
+
+opaque_debug::implement!(Committed);
+
+/// A TLS connection which has been committed and closed.
+pub(crate) struct Connection {
+    pub(crate) server_name: ServerName,
     pub(crate) keys: SessionKeys,
     pub(crate) tls_transcript: TlsTranscript,
     pub(crate) transcript: Transcript,
 }
 
-opaque_debug::implement!(Committed);
-
 #[allow(missing_docs)]
 pub trait ProverState: sealed::Sealed {}
 
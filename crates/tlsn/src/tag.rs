@@ -1,17 +1,27 @@
 //! TLS record tag verification.
 
-use crate::ghash::ghash;
+use crate::{ghash::ghash, poly1305::poly1305};
 
-use cipher::{Cipher, aes::Aes128};
+use cipher::{
+    Cipher,
+    aes::{Aes, AesKey},
+    chacha::ChaCha20,
+};
 use mpz_core::bitvec::BitVec;
 use mpz_memory_core::{
-    DecodeFutureTyped,
+    DecodeFutureTyped, Vector,
     binary::{Binary, U8},
 };
 use mpz_vm_core::{Vm, prelude::*};
 use tls_client::ProtocolVersion;
-use tls_core::cipher::make_tls12_aad;
-use tlsn_core::{connection::TlsVersion, transcript::Record};
+use tls_core::cipher::{make_tls12_aad, make_tls13_aad};
+use tlsn_core::{
+    connection::{AeadAlgorithm, TlsVersion},
+    transcript::Record,
+};
+
+/// AEAD tag length.
+const TAG_LEN: usize = 16;
 
 /// Proves the verification of tags of the given `records`,
 /// returning a proof.
@@ -19,37 +29,55 @@ use tlsn_core::{connection::TlsVersion, transcript::Record};
 /// # Arguments
 ///
 /// * `vm` - Virtual machine.
+/// * `aead` - AEAD algorithm.
 /// * `key_iv` - Cipher key and IV.
-/// * `mac_key` - MAC key.
+/// * `mac_key` - MAC key, only used by AES-GCM.
 /// * `tls_version` - TLS protocol version.
 /// * `records` - Records for which the verification is to be proven.
 pub(crate) fn verify_tags(
     vm: &mut dyn Vm<Binary>,
-    key_iv: (Array<U8, 16>, Array<U8, 4>),
-    mac_key: Array<U8, 16>,
+    aead: AeadAlgorithm,
+    key_iv: (Vector<U8>, Vector<U8>),
+    mac_key: Option<Array<U8, 16>>,
     tls_version: TlsVersion,
     records: Vec<Record>,
 ) -> Result<TagProof, TagProofError> {
-    let mut aes = Aes128::default();
-    aes.set_key(key_iv.0);
-    aes.set_iv(key_iv.1);
+    let keys = match aead {
+        AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => {
+            let mac_key = mac_key.ok_or(ErrorRepr::MissingMacKey)?;
+            TagKeys::Ghash {
+                j0s: alloc_j0s(vm, key_iv, &records)?,
+                mac_key: vm.decode(mac_key).map_err(TagProofError::vm)?,
+            }
+        }
+        AeadAlgorithm::ChaCha20Poly1305 => TagKeys::Poly1305 {
+            blocks: alloc_poly1305_key_blocks(vm, key_iv, &records)?,
+        },
+    };
+
+    Ok(TagProof {
+        tls_version,
+        keys,
+        records,
+    })
+}
 
-    // Compute j0 blocks.
-    let j0s = records
+/// Computes the j0 block of each record for AES-GCM.
+fn alloc_j0s(
+    vm: &mut dyn Vm<Binary>,
+    (key, iv): (Vector<U8>, Vector<U8>),
+    records: &[Record],
+) -> Result<Vec<DecodeFutureTyped<BitVec, [u8; 16]>>, TagProofError> {
+    let mut aes = Aes::default();
+    aes.set_key(AesKey::try_from(key).map_err(TagProofError::vm)?);
+    aes.set_iv(iv.try_into().map_err(|_| ErrorRepr::IvLength)?);
+
+    records
         .iter()
         .map(|rec| {
             let block = aes.alloc_ctr_block(vm).map_err(TagProofError::vm)?;
 
-            let explicit_nonce: [u8; 8] =
-                rec.explicit_nonce
-                    .clone()
-                    .try_into()
-                    .map_err(|explicit_nonce: Vec<_>| ErrorRepr::ExplicitNonceLength {
-                        expected: 8,
-                        actual: explicit_nonce.len(),
-                    })?;
-
-            vm.assign(block.explicit_nonce, explicit_nonce)
+            vm.assign(block.explicit_nonce, explicit_nonce(rec)?)
                 .map_err(TagProofError::vm)?;
             vm.commit(block.explicit_nonce).map_err(TagProofError::vm)?;
 
@@ -62,16 +90,53 @@ pub(crate) fn verify_tags(
 
             Ok(j0)
         })
-        .collect::<Result<Vec<_>, TagProofError>>()?;
+        .collect()
+}
 
-    let mac_key = vm.decode(mac_key).map_err(TagProofError::vm)?;
+/// Computes the keystream block with counter 0 of each record for
+/// ChaCha20-Poly1305, the first 32 bytes of which are the one-time Poly1305
+/// key.
+fn alloc_poly1305_key_blocks(
+    vm: &mut dyn Vm<Binary>,
+    (key, iv): (Vector<U8>, Vector<U8>),
+    records: &[Record],
+) -> Result<Vec<DecodeFutureTyped<BitVec, [u8; 64]>>, TagProofError> {
+    let mut chacha = ChaCha20::default();
+    chacha.set_key(key.try_into().map_err(|_| ErrorRepr::KeyLength)?);
+    chacha.set_iv(iv.try_into().map_err(|_| ErrorRepr::IvLength)?);
 
-    Ok(TagProof {
-        tls_version,
-        j0s,
-        records,
-        mac_key,
-    })
+    records
+        .iter()
+        .map(|rec| {
+            let block = chacha.alloc_ctr_block(vm).map_err(TagProofError::vm)?;
+
+            vm.assign(block.explicit_nonce, explicit_nonce(rec)?)
+                .map_err(TagProofError::vm)?;
+            vm.commit(block.explicit_nonce).map_err(TagProofError::vm)?;
+
+            // The Poly1305 key is derived from the block with counter 0.
+            vm.assign(block.counter, 0u32.to_le_bytes())
+                .map_err(TagProofError::vm)?;
+            vm.commit(block.counter).map_err(TagProofError::vm)?;
+
+            let block = vm.decode(block.output).map_err(TagProofError::vm)?;
+
+            Ok(block)
+        })
+        .collect()
+}
+
+fn explicit_nonce(rec: &Record) -> Result<[u8; 8], TagProofError> {
+    rec.explicit_nonce
+        .clone()
+        .try_into()
+        .map_err(|explicit_nonce: Vec<_>| {
+            ErrorRepr::ExplicitNonceLength {
+                expected: 8,
+                actual: explicit_nonce.len(),
+            }
+            .into()
+        })
 }
 
 /// Proof of tag verification.
@@ -79,11 +144,23 @@ pub(crate) fn verify_tags(
 #[must_use]
 pub(crate) struct TagProof {
     tls_version: TlsVersion,
-    /// The j0 block for each record.
-    j0s: Vec<DecodeFutureTyped<BitVec, [u8; 16]>>,
+    keys: TagKeys,
     records: Vec<Record>,
-    /// The MAC key for tag computation.
-    mac_key: DecodeFutureTyped<BitVec, [u8; 16]>,
+}
+
+/// Decoded values needed to compute the record tags.
+#[derive(Debug)]
+enum TagKeys {
+    Ghash {
+        /// The j0 block for each record.
+        j0s: Vec<DecodeFutureTyped<BitVec, [u8; 16]>>,
+        /// The MAC key for tag computation.
+        mac_key: DecodeFutureTyped<BitVec, [u8; 16]>,
+    },
+    Poly1305 {
+        /// The keystream block with counter 0 for each record.
+        blocks: Vec<DecodeFutureTyped<BitVec, [u8; 64]>>,
+    },
 }
 
 impl TagProof {
@@ -91,31 +168,54 @@ impl TagProof {
     pub(crate) fn verify(self) -> Result<(), TagProofError> {
         let Self {
             tls_version,
-            j0s,
-            mut mac_key,
+            keys,
             records,
         } = self;
 
-        let mac_key = mac_key
-            .try_recv()
-            .map_err(TagProofError::vm)?
-            .ok_or_else(|| ErrorRepr::NotDecoded)?;
+        let tags = match keys {
+            TagKeys::Ghash { j0s, mut mac_key } => {
+                let mac_key = mac_key
+                    .try_recv()
+                    .map_err(TagProofError::vm)?
+                    .ok_or_else(|| ErrorRepr::NotDecoded)?;
 
-        let vers = match tls_version {
-            TlsVersion::V1_2 => ProtocolVersion::TLSv1_2,
-            TlsVersion::V1_3 => ProtocolVersion::TLSv1_3,
-        };
+                j0s.into_iter()
+                    .zip(&records)
+                    .map(|(mut j0, rec)| {
+                        let j0 = j0
+                            .try_recv()
+                            .map_err(TagProofError::vm)?
+                            .ok_or_else(|| ErrorRepr::NotDecoded)?;
 
-        for (mut j0, rec) in j0s.into_iter().zip(records) {
-            let j0 = j0
-                .try_recv()
-                .map_err(TagProofError::vm)?
-                .ok_or_else(|| ErrorRepr::NotDecoded)?;
+                        let aad = make_aad(tls_version, rec);
+                        let ghash_tag = ghash(aad.as_ref(), &rec.ciphertext, &mac_key);
 
-            let aad = make_tls12_aad(rec.seq, rec.typ, vers, rec.ciphertext.len());
+                        Ok(ghash_tag
+                            .into_iter()
+                            .zip(j0)
+                            .map(|(a, b)| a ^ b)
+                            .collect::<Vec<_>>())
+                    })
+                    .collect::<Result<Vec<_>, TagProofError>>()?
+            }
+            TagKeys::Poly1305 { blocks } => blocks
+                .into_iter()
+                .zip(&records)
+                .map(|(mut block, rec)| {
+                    let block = block
+                        .try_recv()
+                        .map_err(TagProofError::vm)?
+                        .ok_or_else(|| ErrorRepr::NotDecoded)?;
 
-            let ghash_tag = ghash(aad.as_ref(), &rec.ciphertext, &mac_key);
+                    let key: [u8; 32] = block[..32].try_into().expect("block is 64 bytes");
+                    let aad = make_aad(tls_version, rec);
 
+                    Ok(poly1305(aad.as_ref(), &rec.ciphertext, &key).to_vec())
+                })
+                .collect::<Result<Vec<_>, TagProofError>>()?,
+        };
+
+        for (tag, rec) in tags.into_iter().zip(records) {
             let record_tag = match rec.tag.as_ref() {
                 Some(tag) => tag,
                 None => {
@@ -125,13 +225,7 @@ impl TagProof {
                 }
             };
 
-            if *record_tag
-                != ghash_tag
-                    .into_iter()
-                    .zip(j0.into_iter())
-                    .map(|(a, b)| a ^ b)
-                    .collect::<Vec<_>>()
-            {
+            if *record_tag != tag {
                 return Err(ErrorRepr::InvalidTag.into());
             }
         }
@@ -140,6 +234,19 @@ impl TagProof {
     }
 }
 
+fn make_aad(tls_version: TlsVersion, rec: &Record) -> Vec<u8> {
+    match tls_version {
+        TlsVersion::V1_2 => make_tls12_aad(
+            rec.seq,
+            rec.typ,
+            ProtocolVersion::TLSv1_2,
+            rec.ciphertext.len(),
+        )
+        .to_vec(),
+        TlsVersion::V1_3 => make_tls13_aad(rec.ciphertext.len() + TAG_LEN).to_vec(),
+    }
+}
+
 /// Error for [`J0Proof`].
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
@@ -165,6 +272,12 @@ enum ErrorRepr {
     InvalidTag,
     #[error("tag is not known")]
     UnknownTag,
+    #[error("MAC key is missing")]
+    MissingMacKey,
+    #[error("invalid key length")]
+    KeyLength,
+    #[error("invalid IV length")]
+    IvLength,
     #[error("invalid explicit nonce length: expected {expected}, got {actual}")]
     ExplicitNonceLength { expected: usize, actual: usize },
 }
@@ -2,6 +2,10 @@ pub(crate) mod auth;
 pub(crate) mod commit;
 
 use mpz_memory_core::{Vector, binary::U8};
+use tlsn_core::{
+    connection::TlsVersion,
+    transcript::{ContentType, Direction, Record, TlsTranscript},
+};
 
 use crate::map::RangeMap;
 
@@ -14,3 +18,31 @@ pub(crate) struct TranscriptRefs {
     pub(crate) sent: ReferenceMap,
     pub(crate) recv: ReferenceMap,
 }
+
+/// Returns the application data records of the transcript in the given
+/// direction.
+///
+/// In TLS 1.3 the content type is encrypted as the last byte of each record,
+/// which is removed so that the records align with the application data
+/// transcript.
+pub(crate) fn app_records(tls_transcript: &TlsTranscript, direction: Direction) -> Vec<Record> {
+    let records = match direction {
+        Direction::Sent => tls_transcript.sent(),
+        Direction::Received => tls_transcript.recv(),
+    };
+
+    records
+        .iter()
+        .filter(|record| record.typ == ContentType::ApplicationData)
+        .cloned()
+        .map(|mut record| {
+            if let TlsVersion::V1_3 = tls_transcript.version() {
+                record.ciphertext.pop();
+                if let Some(plaintext) = record.plaintext.as_mut() {
+                    plaintext.pop();
+                }
+            }
+            record
+        })
+        .collect()
+}
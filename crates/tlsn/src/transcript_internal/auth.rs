@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use aes::Aes128;
+use aes::{Aes128, Aes256};
+use chacha20::ChaCha20;
+use cipher::{aes::AES256, chacha::CHACHA20};
 use ctr::{
     Ctr32BE,
     cipher::{KeyIvInit, StreamCipher, StreamCipherSeek},
@@ -13,14 +15,16 @@ use mpz_memory_core::{
 };
 use mpz_vm_core::{Call, CallableExt, Vm};
 use rangeset::{Difference, RangeSet, Union};
-use tlsn_core::transcript::Record;
+use tlsn_core::{connection::AeadAlgorithm, transcript::Record};
 
 use crate::transcript_internal::ReferenceMap;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn prove_plaintext<'a>(
     vm: &mut dyn Vm<Binary>,
-    key: Array<U8, 16>,
-    iv: Array<U8, 4>,
+    aead: AeadAlgorithm,
+    key: Vector<U8>,
+    iv: Vector<U8>,
     plaintext: &[u8],
     records: impl IntoIterator<Item = &'a Record>,
     reveal: &RangeSet<usize>,
@@ -72,7 +76,7 @@ pub(crate) fn prove_plaintext<'a>(
             vm.commit(*slice).map_err(PlaintextAuthError::vm)?;
         }
 
-        let ciphertext = alloc_ciphertext(vm, key, iv, plaintext_refs.clone(), &records)?;
+        let ciphertext = alloc_ciphertext(vm, aead, key, iv, plaintext_refs.clone(), &records)?;
         for (_, slice) in ciphertext.iter() {
             drop(vm.decode(*slice).map_err(PlaintextAuthError::vm)?);
         }
@@ -84,8 +88,9 @@ pub(crate) fn prove_plaintext<'a>(
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn verify_plaintext<'a>(
     vm: &mut dyn Vm<Binary>,
-    key: Array<U8, 16>,
-    iv: Array<U8, 4>,
+    aead: AeadAlgorithm,
+    key: Vector<U8>,
+    iv: Vector<U8>,
     plaintext: &'a [u8],
     ciphertext: &'a [u8],
     records: impl IntoIterator<Item = &'a Record>,
@@ -116,6 +121,7 @@ pub(crate) fn verify_plaintext<'a>(
         }
 
         PlaintextProof(ProofInner::WithKey {
+            aead,
             key,
             iv,
             records,
@@ -146,7 +152,7 @@ pub(crate) fn verify_plaintext<'a>(
             vm.commit(*slice).map_err(PlaintextAuthError::vm)?;
         }
 
-        let ciphertext_map = alloc_ciphertext(vm, key, iv, plaintext_refs.clone(), &records)?;
+        let ciphertext_map = alloc_ciphertext(vm, aead, key, iv, plaintext_refs.clone(), &records)?;
 
         let mut ciphertexts = Vec::new();
         for (range, chunk) in ciphertext_map.iter() {
@@ -188,8 +194,9 @@ fn alloc_plaintext(
 
 fn alloc_ciphertext<'a>(
     vm: &mut dyn Vm<Binary>,
-    key: Array<U8, 16>,
-    iv: Array<U8, 4>,
+    aead: AeadAlgorithm,
+    key: Vector<U8>,
+    iv: Vector<U8>,
     plaintext: ReferenceMap,
     records: impl IntoIterator<Item = &'a RecordParams>,
 ) -> Result<ReferenceMap, PlaintextAuthError> {
@@ -199,7 +206,7 @@ fn alloc_ciphertext<'a>(
 
     let ranges = RangeSet::from(plaintext.keys().collect::<Vec<_>>());
 
-    let keystream = alloc_keystream(vm, key, iv, &ranges, records)?;
+    let keystream = alloc_keystream(vm, aead, key, iv, &ranges, records)?;
     let mut builder = Call::builder(Arc::new(xor(ranges.len() * 8)));
     for (_, slice) in plaintext.iter() {
         builder = builder.arg(*slice);
@@ -225,8 +232,9 @@ fn alloc_ciphertext<'a>(
 
 fn alloc_keystream<'a>(
     vm: &mut dyn Vm<Binary>,
-    key: Array<U8, 16>,
-    iv: Array<U8, 4>,
+    aead: AeadAlgorithm,
+    key: Vector<U8>,
+    iv: Vector<U8>,
     ranges: &RangeSet<usize>,
     records: impl IntoIterator<Item = &'a RecordParams>,
 ) -> Result<Vec<Vector<U8>>, PlaintextAuthError> {
@@ -260,22 +268,22 @@ fn alloc_keystream<'a>(
                 nonce
             };
 
-            const BLOCK_SIZE: usize = 16;
-            let block_num = offset_range.start / BLOCK_SIZE;
+            let block_size = block_size(aead);
+            let block_num = offset_range.start / block_size;
             let block = if let Some((current_block_num, block)) = current_block.take()
                 && current_block_num == block_num
             {
                 block
             } else {
-                let block = alloc_block(vm, key, iv, explicit_nonce, block_num)?;
+                let block = alloc_block(vm, aead, key, iv, explicit_nonce, block_num)?;
                 current_block = Some((block_num, block));
                 block
             };
 
             // Range within the block.
-            let block_range_start = offset_range.start % BLOCK_SIZE;
+            let block_range_start = offset_range.start % block_size;
             let len =
-                (range.end.min(record_range.end) - range.start).min(BLOCK_SIZE - block_range_start);
+                (range.end.min(record_range.end) - range.start).min(block_size - block_range_start);
             let block_range = block_range_start..block_range_start + len;
 
             keystream.push(block.get(block_range).expect("range is checked"));
@@ -310,21 +318,42 @@ fn alloc_explicit_nonce(
 
 fn alloc_block(
     vm: &mut dyn Vm<Binary>,
-    key: Array<U8, 16>,
-    iv: Array<U8, 4>,
+    aead: AeadAlgorithm,
+    key: Vector<U8>,
+    iv: Vector<U8>,
     explicit_nonce: Vector<U8>,
     block: usize,
 ) -> Result<Vector<U8>, PlaintextAuthError> {
+    if iv.len() != aead.iv_len() {
+        return Err(ErrorRepr::IvLength(iv.len()).into());
+    }
+
     let ctr: Array<U8, 4> = vm.alloc().map_err(PlaintextAuthError::vm)?;
     vm.mark_public(ctr).map_err(PlaintextAuthError::vm)?;
-    const START_CTR: u32 = 2;
-    vm.assign(ctr, (START_CTR + block as u32).to_be_bytes())
-        .map_err(PlaintextAuthError::vm)?;
+    let (circuit, ctr_value) = match aead {
+        AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => {
+            let circuit = match key.len() {
+                16 => AES128.clone(),
+                32 => AES256.clone(),
+                len => return Err(ErrorRepr::KeyLength(len).into()),
+            };
+            const START_CTR: u32 = 2;
+            (circuit, (START_CTR + block as u32).to_be_bytes())
+        }
+        AeadAlgorithm::ChaCha20Poly1305 => {
+            if key.len() != aead.key_len() {
+                return Err(ErrorRepr::KeyLength(key.len()).into());
+            }
+            const START_CTR: u32 = 1;
+            (CHACHA20.clone(), (START_CTR + block as u32).to_le_bytes())
+        }
+    };
+    vm.assign(ctr, ctr_value).map_err(PlaintextAuthError::vm)?;
     vm.commit(ctr).map_err(PlaintextAuthError::vm)?;
 
-    let block: Array<U8, 16> = vm
+    let block: Vector<U8> = vm
         .call(
-            Call::builder(AES128.clone())
+            Call::builder(circuit)
                 .arg(key)
                 .arg(iv)
                 .arg(explicit_nonce)
@@ -334,7 +363,15 @@ fn alloc_block(
         )
         .map_err(PlaintextAuthError::vm)?;
 
-    Ok(Vector::from(block))
+    Ok(block)
+}
+
+/// Returns the keystream block size in bytes.
+fn block_size(aead: AeadAlgorithm) -> usize {
+    match aead {
+        AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => 16,
+        AeadAlgorithm::ChaCha20Poly1305 => 64,
+    }
 }
 
 struct RecordParams {
@@ -358,6 +395,7 @@ impl<'a> PlaintextProof<'a> {
     pub(crate) fn verify(self) -> Result<(), PlaintextAuthError> {
         match self.0 {
             ProofInner::WithKey {
+                aead,
                 mut key,
                 mut iv,
                 records,
@@ -373,7 +411,7 @@ impl<'a> PlaintextProof<'a> {
                     .map_err(PlaintextAuthError::vm)?
                     .ok_or(ErrorRepr::MissingDecoding)?;
 
-                verify_plaintext_with_key(key, iv, &records, plaintext, ciphertext)?;
+                verify_plaintext_with_key(aead, key, iv, &records, plaintext, ciphertext)?;
             }
             ProofInner::WithZk { ciphertexts } => {
                 for (expected, mut actual) in ciphertexts {
@@ -395,8 +433,9 @@ impl<'a> PlaintextProof<'a> {
 
 enum ProofInner<'a> {
     WithKey {
-        key: DecodeFutureTyped<BitVec, [u8; 16]>,
-        iv: DecodeFutureTyped<BitVec, [u8; 4]>,
+        aead: AeadAlgorithm,
+        key: DecodeFutureTyped<BitVec, Vec<u8>>,
+        iv: DecodeFutureTyped<BitVec, Vec<u8>>,
         records: Vec<RecordParams>,
         plaintext: &'a [u8],
         ciphertext: &'a [u8],
@@ -408,22 +447,74 @@ enum ProofInner<'a> {
     },
 }
 
-fn aes_ctr_apply_keystream(key: &[u8; 16], iv: &[u8; 4], explicit_nonce: &[u8], input: &mut [u8]) {
-    let mut full_iv = [0u8; 16];
-    full_iv[0..4].copy_from_slice(iv);
-    full_iv[4..12].copy_from_slice(&explicit_nonce[..8]);
+fn apply_keystream_with_key(
+    aead: AeadAlgorithm,
+    key: &[u8],
+    iv: &[u8],
+    explicit_nonce: &[u8],
+    input: &mut [u8],
+) -> Result<(), PlaintextAuthError> {
+    if iv.len() != aead.iv_len() {
+        return Err(ErrorRepr::IvLength(iv.len()).into());
+    }
+
+    match aead {
+        AeadAlgorithm::Aes128Gcm | AeadAlgorithm::Aes256Gcm => {
+            let mut full_iv = [0u8; 16];
+            full_iv[0..4].copy_from_slice(iv);
+            full_iv[4..12].copy_from_slice(&explicit_nonce[..8]);
+
+            const START_CTR: u64 = 2;
+            match key.len() {
+                16 => apply_keystream(
+                    Ctr32BE::<Aes128>::new(key.into(), &full_iv.into()),
+                    START_CTR * 16,
+                    input,
+                ),
+                32 => apply_keystream(
+                    Ctr32BE::<Aes256>::new(key.into(), &full_iv.into()),
+                    START_CTR * 16,
+                    input,
+                ),
+                len => return Err(ErrorRepr::KeyLength(len).into()),
+            }
+        }
+        AeadAlgorithm::ChaCha20Poly1305 => {
+            if key.len() != aead.key_len() {
+                return Err(ErrorRepr::KeyLength(key.len()).into());
+            }
+
+            // The nonce is the IV XORed with the left-padded explicit nonce.
+            let mut nonce = [0u8; 12];
+            nonce.copy_from_slice(iv);
+            nonce[4..]
+                .iter_mut()
+                .zip(&explicit_nonce[..8])
+                .for_each(|(a, b)| *a ^= b);
+
+            const START_CTR: u64 = 1;
+            apply_keystream(
+                ChaCha20::new(key.into(), &nonce.into()),
+                START_CTR * 64,
+                input,
+            )
+        }
+    }
+
+    Ok(())
+}
 
-    const START_CTR: u32 = 2;
-    let mut cipher = Ctr32BE::<Aes128>::new(key.into(), &full_iv.into());
+fn apply_keystream(mut cipher: impl StreamCipher + StreamCipherSeek, pos: u64, input: &mut [u8]) {
     cipher
-        .try_seek(START_CTR * 16)
-        .expect("start counter is less than keystream length");
+        .try_seek(pos)
+        .expect("start position is less than keystream length");
     cipher.apply_keystream(input);
 }
 
 fn verify_plaintext_with_key<'a>(
-    key: [u8; 16],
-    iv: [u8; 4],
+    aead: AeadAlgorithm,
+    key: Vec<u8>,
+    iv: Vec<u8>,
     records: impl IntoIterator<Item = &'a RecordParams>,
     plaintext: &[u8],
     ciphertext: &[u8],
@@ -434,7 +525,7 @@ fn verify_plaintext_with_key<'a>(
         text.clear();
         text.extend_from_slice(&plaintext[pos..pos + record.len]);
 
-        aes_ctr_apply_keystream(&key, &iv, &record.explicit_nonce, &mut text);
+        apply_keystream_with_key(aead, &key, &iv, &record.explicit_nonce, &mut text)?;
 
         if text != ciphertext[pos..pos + record.len] {
             return Err(PlaintextAuthError(ErrorRepr::InvalidPlaintext));
@@ -469,6 +560,10 @@ enum ErrorRepr {
     MissingDecoding,
     #[error("plaintext does not match ciphertext")]
     InvalidPlaintext,
+    #[error("invalid key length: {0}")]
+    KeyLength(usize),
+    #[error("invalid IV length: {0}")]
+    IvLength(usize),
 }
 
 #[cfg(test)]
@@ -482,24 +577,25 @@ mod tests {
     use rstest::*;
     use std::ops::Range;
 
-    fn build_vm(key: [u8; 16], iv: [u8; 4]) -> (IdealVm, Array<U8, 16>, Array<U8, 4>) {
+    fn build_vm(key: &[u8], iv: &[u8]) -> (IdealVm, Vector<U8>, Vector<U8>) {
         let mut vm = IdealVm::new();
-        let key_ref = vm.alloc::<Array<U8, 16>>().unwrap();
-        let iv_ref = vm.alloc::<Array<U8, 4>>().unwrap();
+        let key_ref = vm.alloc_vec::<U8>(key.len()).unwrap();
+        let iv_ref = vm.alloc_vec::<U8>(iv.len()).unwrap();
 
         vm.mark_public(key_ref).unwrap();
         vm.mark_public(iv_ref).unwrap();
-        vm.assign(key_ref, key).unwrap();
-        vm.assign(iv_ref, iv).unwrap();
+        vm.assign(key_ref, key.to_vec()).unwrap();
+        vm.assign(iv_ref, iv.to_vec()).unwrap();
         vm.commit(key_ref).unwrap();
         vm.commit(iv_ref).unwrap();
 
         (vm, key_ref, iv_ref)
     }
 
-    fn expected_aes_ctr<'a>(
-        key: [u8; 16],
-        iv: [u8; 4],
+    fn expected_keystream<'a>(
+        aead: AeadAlgorithm,
+        key: &[u8],
+        iv: &[u8],
         records: impl IntoIterator<Item = &'a RecordParams>,
         ranges: &RangeSet<usize>,
     ) -> Vec<u8> {
@@ -507,7 +603,8 @@ mod tests {
         let mut pos = 0;
         for record in records {
             let mut record_keystream = vec![0u8; record.len];
-            aes_ctr_apply_keystream(&key, &iv, &record.explicit_nonce, &mut record_keystream);
+            apply_keystream_with_key(aead, key, iv, &record.explicit_nonce, &mut record_keystream)
+                .unwrap();
             for mut range in ranges.iter_ranges() {
                 range.start = range.start.max(pos);
                 range.end = range.end.min(pos + record.len);
@@ -536,12 +633,18 @@ mod tests {
     async fn test_alloc_keystream(
         #[case] record_lens: impl IntoIterator<Item = usize>,
         #[case] ranges: impl IntoIterator<Item = Range<usize>>,
+        #[values(
+            AeadAlgorithm::Aes128Gcm,
+            AeadAlgorithm::Aes256Gcm,
+            AeadAlgorithm::ChaCha20Poly1305
+        )]
+        aead: AeadAlgorithm,
     ) {
         let mut rng = StdRng::seed_from_u64(0);
-        let mut key = [0u8; 16];
-        let mut iv = [0u8; 4];
-        rng.fill(&mut key);
-        rng.fill(&mut iv);
+        let mut key = vec![0u8; aead.key_len()];
+        let mut iv = vec![0u8; aead.iv_len()];
+        rng.fill(key.as_mut_slice());
+        rng.fill(iv.as_mut_slice());
 
         let mut total_len = 0;
         let records = record_lens
@@ -561,9 +664,9 @@ mod tests {
         let is_out_of_bounds = ranges.end().unwrap_or(0) > total_len;
 
         let (mut ctx, _) = test_st_context(1024);
-        let (mut vm, key_ref, iv_ref) = build_vm(key, iv);
+        let (mut vm, key_ref, iv_ref) = build_vm(&key, &iv);
 
-        let keystream = match alloc_keystream(&mut vm, key_ref, iv_ref, &ranges, &records) {
+        let keystream = match alloc_keystream(&mut vm, aead, key_ref, iv_ref, &ranges, &records) {
             Ok(_) if is_out_of_bounds => panic!("should be out of bounds"),
             Ok(keystream) => keystream,
             Err(PlaintextAuthError(ErrorRepr::OutOfBounds)) if is_out_of_bounds => {
@@ -581,7 +684,7 @@ mod tests {
 
         assert_eq!(keystream.len(), ranges.len());
 
-        let expected = expected_aes_ctr(key, iv, &records, &ranges);
+        let expected = expected_keystream(aead, &key, &iv, &records, &ranges);
 
         assert_eq!(keystream, expected);
     }
@@ -594,12 +697,18 @@ mod tests {
     fn test_verify_plaintext_with_key(
         #[case] record_lens: impl IntoIterator<Item = usize>,
         #[values(false, true)] tamper: bool,
+        #[values(
+            AeadAlgorithm::Aes128Gcm,
+            AeadAlgorithm::Aes256Gcm,
+            AeadAlgorithm::ChaCha20Poly1305
+        )]
+        aead: AeadAlgorithm,
     ) {
         let mut rng = StdRng::seed_from_u64(0);
-        let mut key = [0u8; 16];
-        let mut iv = [0u8; 4];
-        rng.fill(&mut key);
-        rng.fill(&mut iv);
+        let mut key = vec![0u8; aead.key_len()];
+        let mut iv = vec![0u8; aead.iv_len()];
+        rng.fill(key.as_mut_slice());
+        rng.fill(iv.as_mut_slice());
 
         let mut total_len = 0;
         let records = record_lens
@@ -619,7 +728,7 @@ mod tests {
         rng.fill(plaintext.as_mut_slice());
 
         let mut ciphertext = plaintext.clone();
-        expected_aes_ctr(key, iv, &records, &(0..total_len).into())
+        expected_keystream(aead, &key, &iv, &records, &(0..total_len).into())
             .iter()
             .zip(ciphertext.iter_mut())
             .for_each(|(key, pt)| {
@@ -630,7 +739,7 @@ mod tests {
             plaintext.first_mut().map(|pt| *pt ^= 1);
         }
 
-        match verify_plaintext_with_key(key, iv, &records, &plaintext, &ciphertext) {
+        match verify_plaintext_with_key(aead, key, iv, &records, &plaintext, &ciphertext) {
             Ok(_) if tamper && !plaintext.is_empty() => panic!("should be invalid"),
             Err(e) if !tamper => panic!("unexpected error: {:?}", e),
             _ => {}
@@ -1,31 +1,47 @@
 //! Verifier.
 
+pub(crate) mod abort;
 pub(crate) mod config;
 mod error;
+mod policy;
 pub mod state;
 mod verify;
 
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
+pub use abort::{Abort, AbortKind};
 pub use config::{VerifierConfig, VerifierConfigBuilder, VerifierConfigBuilderError};
 pub use error::VerifierError;
+pub use policy::{
+    BasicPolicy, BasicPolicyBuilder, BasicPolicyBuilderError, DisclosurePolicy, PolicyRejection,
+    RejectionKind,
+};
 pub use tlsn_core::{
     VerifierOutput, VerifyConfig, VerifyConfigBuilder, VerifyConfigBuilderError,
     webpki::ServerCertVerifier,
 };
 
 use crate::{
-    Role, config::ProtocolConfig, context::build_mt_context, mux::attach_mux, tag::verify_tags,
+    Next, Role,
+    config::{NetworkInfo, NetworkSetting, ProtocolConfig},
+    context::build_mt_context,
+    interrupt::{self, CancelHandle, Stage},
+    mux::{MuxControl, MuxFuture, attach_mux},
+    network,
+    progress::{Phase, Progress, Reporter},
+    tag::verify_tags,
+    verifier::abort::{AbortStream, Status},
 };
-use futures::{AsyncRead, AsyncWrite, TryFutureExt};
+use futures::{AsyncRead, AsyncWrite, TryFutureExt, future};
 use mpc_tls::{MpcTlsFollower, SessionKeys};
 use mpz_common::Context;
 use mpz_core::Block;
 use mpz_garble_core::Delta;
 use mpz_vm_core::prelude::*;
 use mpz_zk::VerifierConfig as ZkVerifierConfig;
-use serio::stream::IoStreamExt;
+use serio::{SinkExt, stream::IoStreamExt};
 use tlsn_core::{
+    ProveRequest,
     connection::{ConnectionInfo, ServerName},
     transcript::TlsTranscript,
 };
@@ -43,8 +59,11 @@ pub(crate) type RCOTReceiver = mpz_ot::rcot::shared::SharedRCOTReceiver<
     bool,
     mpz_core::Block,
 >;
+#[cfg(not(tlsn_insecure_ideal))]
 pub(crate) type Mpc =
     mpz_garble::protocol::semihonest::Evaluator<mpz_ot::cot::DerandCOTReceiver<RCOTReceiver>>;
+#[cfg(tlsn_insecure_ideal)]
+pub(crate) type Mpc = crate::ideal::PlaintextVm;
 pub(crate) type Zk = mpz_zk::Verifier<RCOTSender>;
 
 /// Information about the TLS session.
@@ -54,15 +73,32 @@ pub struct SessionInfo {
     pub server_name: ServerName,
     /// Connection information.
     pub connection_info: ConnectionInfo,
+    /// Network setting used for the session.
+    ///
+    /// If the setting was selected automatically, it was picked by the prover
+    /// from its measurement of the link.
+    pub network: NetworkInfo,
 }
 
 /// A Verifier instance.
 pub struct Verifier<T: state::VerifierState = state::Initialized> {
     config: VerifierConfig,
     span: Span,
+    cancel: CancelHandle,
     state: T,
 }
 
+impl<T: state::VerifierState> Verifier<T> {
+    /// Returns a handle which cancels the session.
+    ///
+    /// Cancelling interrupts the current stage of the verifier, closing the
+    /// connection to the prover, and returns an error carrying
+    /// [`Interrupt::Cancelled`](crate::interrupt::Interrupt::Cancelled).
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+}
+
 impl Verifier<state::Initialized> {
     /// Creates a new verifier.
     pub fn new(config: VerifierConfig) -> Self {
@@ -70,6 +106,7 @@ impl Verifier<state::Initialized> {
         Self {
             config,
             span,
+            cancel: CancelHandle::new(),
             state: state::Initialized,
         }
     }
@@ -86,47 +123,128 @@ impl Verifier<state::Initialized> {
         self,
         socket: S,
     ) -> Result<Verifier<state::Setup>, VerifierError> {
-        let (mut mux_fut, mux_ctrl) = attach_mux(socket, Role::Verifier);
-        let mut mt = build_mt_context(mux_ctrl.clone());
-        let mut ctx = mux_fut.poll_with(mt.new_context()).await?;
-
-        // Receives protocol configuration from prover to perform compatibility check.
-        let protocol_config = mux_fut
-            .poll_with(async {
-                let peer_configuration: ProtocolConfig = ctx.io_mut().expect_next().await?;
-                self.config
-                    .protocol_config_validator()
-                    .validate(&peer_configuration)?;
-
-                Ok::<_, VerifierError>(peer_configuration)
+        let cancel = self.cancel.clone();
+        let interrupt = interrupt::timeout(Stage::Setup, self.config.timeouts().setup());
+
+        interrupt::guard(&cancel, interrupt, async move {
+            let (mut mux_fut, mux_ctrl, meter) = attach_mux(socket, Role::Verifier);
+            let reporter = Reporter::new(self.config.progress(), meter);
+            reporter.report(Progress::Started(Phase::Setup));
+
+            let mut mt = build_mt_context(mux_ctrl.clone());
+            let mut ctx = mux_fut.poll_with(mt.new_context()).await?;
+            let mut abort = AbortStream::new(mux_fut.poll_with(mt.new_context()).await?);
+
+            let result = async {
+                // Advertises the limits to the prover, then receives the
+                // protocol configuration from the prover to perform
+                // compatibility check.
+                let (mut protocol_config, result) = mux_fut
+                    .poll_with(async {
+                        let limits = self.config.protocol_config_validator().limits();
+                        ctx.io_mut().send(limits).await?;
+
+                        let peer_configuration: ProtocolConfig = ctx.io_mut().expect_next().await?;
+                        // The configuration is deserialized without the
+                        // checks of its builder, so it is also checked by
+                        // building the MPC-TLS configuration.
+                        let result = self
+                            .config
+                            .protocol_config_validator()
+                            .validate(&peer_configuration)
+                            .and_then(|()| {
+                                self.config
+                                    .build_mpc_tls_config(&peer_configuration)
+                                    .map(|_| ())
+                            });
+
+                        // Notify the prover whether the configuration is
+                        // accepted.
+                        let status: Status = result.as_ref().map_err(Abort::from).copied();
+                        ctx.io_mut().send(status).await?;
+
+                        Ok::<_, VerifierError>((peer_configuration, result))
+                    })
+                    .await?;
+
+                result?;
+
+                let network = if let NetworkSetting::Auto = protocol_config.network() {
+                    debug!("responding to link measurement");
+
+                    let link = mux_fut.poll_with(network::respond(&mut ctx)).await?;
+                    let setting = link.network_setting();
+
+                    debug!(?link, ?setting, "selected network setting");
+
+                    NetworkInfo {
+                        setting,
+                        link: Some(link),
+                    }
+                } else {
+                    NetworkInfo {
+                        setting: protocol_config.network(),
+                        link: None,
+                    }
+                };
+                protocol_config.set_network(network.setting);
+
+                reporter.report(Progress::Finished(Phase::Setup));
+
+                // Each connection runs its own MPC-TLS instance on a separate
+                // context.
+                let mut ctxs = vec![ctx];
+                for _ in 1..protocol_config.max_connections() {
+                    ctxs.push(mux_fut.poll_with(mt.new_context()).await?);
+                }
+
+                let (vm, followers) = build_mpc_tls(&self.config, &protocol_config, ctxs)?;
+
+                debug!("setting up mpc-tls");
+                reporter.report(Progress::Started(Phase::Preprocess));
+
+                let mut mpc_tls = VecDeque::with_capacity(followers.len());
+                for mut follower in followers {
+                    follower.set_progress(reporter.mpc_tls())?;
+
+                    // Allocate resources for MPC-TLS in the VM.
+                    let mut keys = follower.alloc()?;
+                    let vm_lock = vm.try_lock().expect("VM is not locked");
+                    translate_keys(&mut keys, &vm_lock)?;
+                    drop(vm_lock);
+
+                    mux_fut.poll_with(follower.preprocess()).await?;
+                    mpc_tls.push_back((follower, keys));
+                }
+
+                reporter.report(Progress::Finished(Phase::Preprocess));
+                debug!("mpc-tls setup complete");
+
+                Ok::<_, VerifierError>((mpc_tls, vm, network))
+            }
+            .await;
+
+            let (mpc_tls, vm, network) = match result {
+                Ok(result) => result,
+                Err(err) => return Err(abort_with(&mux_ctrl, &mut mux_fut, &mut abort, err).await),
+            };
+
+            Ok::<_, VerifierError>(Verifier {
+                config: self.config,
+                span: self.span,
+                cancel: self.cancel,
+                state: state::Setup {
+                    mux_ctrl,
+                    mux_fut,
+                    abort,
+                    mpc_tls,
+                    vm,
+                    network,
+                    reporter,
+                },
             })
-            .await?;
-
-        let (vm, mut mpc_tls) = build_mpc_tls(&self.config, &protocol_config, ctx);
-
-        // Allocate resources for MPC-TLS in the VM.
-        let mut keys = mpc_tls.alloc()?;
-        let vm_lock = vm.try_lock().expect("VM is not locked");
-        translate_keys(&mut keys, &vm_lock)?;
-        drop(vm_lock);
-
-        debug!("setting up mpc-tls");
-
-        mux_fut.poll_with(mpc_tls.preprocess()).await?;
-
-        debug!("mpc-tls setup complete");
-
-        Ok(Verifier {
-            config: self.config,
-            span: self.span,
-            state: state::Setup {
-                mux_ctrl,
-                mux_fut,
-                mpc_tls,
-                keys,
-                vm,
-            },
         })
+        .await
     }
 
     /// Runs the TLS verifier to completion, verifying the TLS session.
@@ -142,84 +260,177 @@ impl Verifier<state::Initialized> {
         self,
         socket: S,
         config: &VerifyConfig,
-    ) -> Result<VerifierOutput, VerifierError> {
+    ) -> Result<Vec<VerifierOutput>, VerifierError> {
         let mut verifier = self.setup(socket).await?.run().await?;
 
-        let output = verifier.verify(config).await?;
+        let outputs = verifier.verify(config).await?;
 
         verifier.close().await?;
 
-        Ok(output)
+        Ok(outputs)
     }
 }
 
 impl Verifier<state::Setup> {
-    /// Runs the verifier until the TLS connection is closed.
+    /// Returns the network setting used for the session.
+    ///
+    /// If the setting was selected automatically, it was picked by the prover
+    /// from its measurement of the link.
+    pub fn network_info(&self) -> &NetworkInfo {
+        &self.state.network
+    }
+
+    /// Runs the verifier until the TLS connections are closed.
+    ///
+    /// MPC-TLS is run for each connection of the prover and finalized once
+    /// the prover closes its last connection.
     #[instrument(parent = &self.span, level = "info", skip_all, err)]
     pub async fn run(self) -> Result<Verifier<state::Committed>, VerifierError> {
         let state::Setup {
             mux_ctrl,
             mut mux_fut,
-            mpc_tls,
+            mut abort,
+            mut mpc_tls,
             vm,
-            keys,
+            network,
+            reporter,
         } = self.state;
-
-        info!("starting MPC-TLS");
-
-        let (mut ctx, tls_transcript) = mux_fut.poll_with(mpc_tls.run()).await?;
-
-        info!("finished MPC-TLS");
-
-        {
-            let mut vm = vm.try_lock().expect("VM should not be locked");
-
-            debug!("finalizing mpc");
-
-            mux_fut
-                .poll_with(vm.finalize(&mut ctx))
-                .await
-                .map_err(VerifierError::mpc)?;
-
-            debug!("mpc finalized");
+        let cancel = self.cancel.clone();
+        let timeouts = *self.config.timeouts();
+
+        let result = async {
+            let mut connections = Vec::new();
+            let mut ctx = loop {
+                let (follower, keys) = mpc_tls
+                    .pop_front()
+                    .expect("at least one connection remains");
+                let handshake = {
+                    let reporter = reporter.clone();
+                    async move { interrupt::handshake(&reporter, timeouts.handshake()).await }
+                };
+
+                let (ctx, next) = interrupt::guard(&cancel, handshake, async {
+                    info!("starting MPC-TLS");
+                    reporter.report(Progress::Started(Phase::Handshake));
+
+                    // The connection is interrupted if the prover is idle for
+                    // too long.
+                    let (mut ctx, tls_transcript) = interrupt::guard(
+                        &cancel,
+                        interrupt::idle(reporter.meter(), timeouts.server_idle()),
+                        mux_fut.poll_with(follower.run().map_err(VerifierError::from)),
+                    )
+                    .await?;
+
+                    info!("finished MPC-TLS");
+
+                    connections.push(state::Connection {
+                        keys,
+                        tls_transcript,
+                    });
+
+                    let next: Next = mux_fut
+                        .poll_with(ctx.io_mut().expect_next().map_err(VerifierError::from))
+                        .await?;
+
+                    Ok::<_, VerifierError>((ctx, next))
+                })
+                .await?;
+
+                match next {
+                    Next::Connection if !mpc_tls.is_empty() => {}
+                    Next::Connection => {
+                        return Err(VerifierError::config(
+                            "prover attempted more connections than were set up",
+                        ));
+                    }
+                    Next::Finalize => break ctx,
+                }
+            };
+
+            // The instances of unused connections hold references to the VM.
+            drop(mpc_tls);
+
+            interrupt::guard(&cancel, future::pending(), async {
+                reporter.report(Progress::Started(Phase::Finalize));
+
+                {
+                    let mut vm = vm.try_lock().expect("VM should not be locked");
+
+                    debug!("finalizing mpc");
+
+                    mux_fut
+                        .poll_with(vm.finalize(&mut ctx))
+                        .await
+                        .map_err(VerifierError::mpc)?;
+
+                    debug!("mpc finalized");
+                }
+
+                // Pull out ZK VM.
+                let (_, mut vm) = Arc::into_inner(vm)
+                    .expect("vm should have only 1 reference")
+                    .into_inner()
+                    .into_inner();
+
+                // Prepare for the prover to prove tag verification of the
+                // received records.
+                let tag_proofs = connections
+                    .iter()
+                    .map(|connection| {
+                        let keys = &connection.keys;
+                        verify_tags(
+                            &mut vm,
+                            keys.aead,
+                            (keys.server_write_key, keys.server_write_iv),
+                            keys.server_write_mac_key,
+                            *connection.tls_transcript.version(),
+                            connection.tls_transcript.recv().to_vec(),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(VerifierError::zk)?;
+
+                mux_fut
+                    .poll_with(vm.execute_all(&mut ctx).map_err(VerifierError::zk))
+                    .await?;
+
+                // Verify the tags.
+                // After the verification, the entire TLS trancripts become
+                // authenticated from the verifier's perspective.
+                let result = tag_proofs
+                    .into_iter()
+                    .try_for_each(|tag_proof| tag_proof.verify())
+                    .map_err(VerifierError::zk);
+                mux_fut.poll_with(send_status(&mut ctx, &result)).await?;
+                result?;
+
+                reporter.report(Progress::Finished(Phase::Finalize));
+
+                Ok::<_, VerifierError>((ctx, vm, connections))
+            })
+            .await
         }
+        .await;
 
-        // Pull out ZK VM.
-        let (_, mut vm) = Arc::into_inner(vm)
-            .expect("vm should have only 1 reference")
-            .into_inner()
-            .into_inner();
-
-        // Prepare for the prover to prove tag verification of the received
-        // records.
-        let tag_proof = verify_tags(
-            &mut vm,
-            (keys.server_write_key, keys.server_write_iv),
-            keys.server_write_mac_key,
-            *tls_transcript.version(),
-            tls_transcript.recv().to_vec(),
-        )
-        .map_err(VerifierError::zk)?;
-
-        mux_fut
-            .poll_with(vm.execute_all(&mut ctx).map_err(VerifierError::zk))
-            .await?;
-
-        // Verify the tags.
-        // After the verification, the entire TLS trancript becomes
-        // authenticated from the verifier's perspective.
-        tag_proof.verify().map_err(VerifierError::zk)?;
+        let (ctx, vm, connections) = match result {
+            Ok(result) => result,
+            Err(err) => return Err(abort_with(&mux_ctrl, &mut mux_fut, &mut abort, err).await),
+        };
 
         Ok(Verifier {
             config: self.config,
             span: self.span,
+            cancel: self.cancel,
             state: state::Committed {
                 mux_ctrl,
                 mux_fut,
+                abort,
                 ctx,
                 vm,
-                keys,
-                tls_transcript,
+                connections,
+                network,
+                reporter,
             },
         })
     }
@@ -227,12 +438,55 @@ impl Verifier<state::Setup> {
 
 impl Verifier<state::Committed> {
     /// Returns the TLS transcript.
+    ///
+    /// Use [`tls_transcript_of`](Self::tls_transcript_of) if the session has
+    /// more than one connection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the session has more than one connection.
     pub fn tls_transcript(&self) -> &TlsTranscript {
-        &self.state.tls_transcript
+        assert_eq!(
+            self.connection_count(),
+            1,
+            "session has more than one connection, use `tls_transcript_of`"
+        );
+        &self.state.connections[0].tls_transcript
+    }
+
+    /// Returns the number of connections of the session.
+    pub fn connection_count(&self) -> usize {
+        self.state.connections.len()
+    }
+
+    /// Returns the TLS transcript of the connection with the given index, in
+    /// the order the connections were made.
+    pub fn tls_transcript_of(&self, connection: usize) -> Option<&TlsTranscript> {
+        self.state
+            .connections
+            .get(connection)
+            .map(|connection| &connection.tls_transcript)
+    }
+
+    /// Returns the network setting used for the session.
+    ///
+    /// If the setting was selected automatically, it was picked by the prover
+    /// from its measurement of the link.
+    pub fn network_info(&self) -> &NetworkInfo {
+        &self.state.network
     }
 
     /// Verifies information from the prover.
     ///
+    /// If a [`DisclosurePolicy`] is configured, the request of the prover for
+    /// each connection is checked against it first and a rejection is sent to
+    /// the prover.
+    ///
+    /// Must be called once for each call to
+    /// [`Prover::prove`](crate::prover::Prover::prove). Returns an output for
+    /// each connection which was proven, in the order the connections were
+    /// made. At least one connection is proven.
+    ///
     /// # Arguments
     ///
     /// * `config` - Verification configuration.
@@ -240,38 +494,93 @@ impl Verifier<state::Committed> {
     pub async fn verify(
         &mut self,
         #[allow(unused_variables)] config: &VerifyConfig,
-    ) -> Result<VerifierOutput, VerifierError> {
+    ) -> Result<Vec<VerifierOutput>, VerifierError> {
         let state::Committed {
+            mux_ctrl,
             mux_fut,
+            abort,
             ctx,
             vm,
-            keys,
-            tls_transcript,
+            connections,
+            reporter,
             ..
         } = &mut self.state;
+        let root_store = self.config.root_store();
+        let policy = self.config.policy();
+        let interrupt = interrupt::timeout(Stage::Prove, self.config.timeouts().prove());
+
+        let result = interrupt::guard(&self.cancel, interrupt, async {
+            let cert_verifier = if let Some(root_store) = root_store {
+                ServerCertVerifier::new(root_store).map_err(VerifierError::config)?
+            } else {
+                ServerCertVerifier::mozilla()
+            };
+
+            reporter.report(Progress::Started(Phase::Prove));
+
+            let requests: Vec<ProveRequest> = mux_fut
+                .poll_with(ctx.io_mut().expect_next().map_err(VerifierError::from))
+                .await?;
+
+            // Check the requests against the policy before doing any work, and
+            // notify the prover of the decision.
+            let decision = policy.map_or(Ok(()), |policy| {
+                requests
+                    .iter()
+                    .try_for_each(|request| policy.check(request))
+            });
+            mux_fut
+                .poll_with(
+                    ctx.io_mut()
+                        .send(decision.clone())
+                        .map_err(VerifierError::from),
+                )
+                .await?;
+            decision.map_err(VerifierError::policy)?;
+
+            let mut result = async {
+                check_requests(&requests, connections.len())?;
+
+                let mut outputs = Vec::with_capacity(requests.len());
+                for request in requests {
+                    let connection = &connections[request.connection];
+                    let output = mux_fut
+                        .poll_with(verify::verify(
+                            ctx,
+                            vm,
+                            &connection.keys,
+                            &cert_verifier,
+                            &connection.tls_transcript,
+                            request,
+                            reporter,
+                        ))
+                        .await?;
+                    outputs.push(output);
+                }
+
+                Ok::<_, VerifierError>(outputs)
+            }
+            .await;
+            mux_fut.poll_with(send_status(ctx, &result)).await?;
+
+            if let Ok(outputs) = &mut result {
+                reporter.report(Progress::Finished(Phase::Prove));
+                let metrics = reporter.metrics();
+                for output in outputs {
+                    output.metrics = metrics;
+                }
+            }
+
+            result
+        })
+        .await;
 
-        let cert_verifier = if let Some(root_store) = self.config.root_store() {
-            ServerCertVerifier::new(root_store).map_err(VerifierError::config)?
-        } else {
-            ServerCertVerifier::mozilla()
-        };
-
-        let request = mux_fut
-            .poll_with(ctx.io_mut().expect_next().map_err(VerifierError::from))
-            .await?;
-
-        let output = mux_fut
-            .poll_with(verify::verify(
-                ctx,
-                vm,
-                keys,
-                &cert_verifier,
-                tls_transcript,
-                request,
-            ))
-            .await?;
-
-        Ok(output)
+        match result {
+            // A rejected request leaves the session intact, so the prover may
+            // send another one.
+            Err(err) if !err.is_policy() => Err(abort_with(mux_ctrl, mux_fut, abort, err).await),
+            result => result,
+        }
     }
 
     /// Closes the connection with the prover.
@@ -291,11 +600,70 @@ impl Verifier<state::Committed> {
     }
 }
 
+/// Reports the error to the prover on the abort stream and closes the
+/// connection.
+///
+/// Returns the error.
+async fn abort_with(
+    mux_ctrl: &MuxControl,
+    mux_fut: &mut MuxFuture,
+    abort: &mut AbortStream,
+    err: VerifierError,
+) -> VerifierError {
+    if mux_fut.is_complete() {
+        return err;
+    }
+
+    // The prover may have closed the connection already.
+    let _ = mux_fut.poll_with(abort.send(err.to_abort())).await;
+
+    // Wait for the prover to receive the abort and close the connection.
+    mux_ctrl.close();
+    let _ = mux_fut.await;
+
+    err
+}
+
+/// Checks that the prover requested at least one connection, and each
+/// connection of the session at most once and in order.
+fn check_requests(requests: &[ProveRequest], connections: usize) -> Result<(), VerifierError> {
+    if requests.is_empty() {
+        return Err(VerifierError::verify("prover requested no connections"));
+    }
+
+    for (idx, request) in requests.iter().enumerate() {
+        if request.connection >= connections {
+            return Err(VerifierError::verify(format!(
+                "prover requested connection {} but the session has {} connections",
+                request.connection, connections
+            )));
+        } else if idx > 0 && request.connection <= requests[idx - 1].connection {
+            return Err(VerifierError::verify(
+                "prover requested connections out of order or more than once",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends the status of a protocol step to the prover.
+async fn send_status<T>(
+    ctx: &mut Context,
+    result: &Result<T, VerifierError>,
+) -> Result<(), VerifierError> {
+    let status: Status = result.as_ref().map(|_| ()).map_err(VerifierError::to_abort);
+    ctx.io_mut().send(status).await?;
+
+    Ok(())
+}
+
 fn build_mpc_tls(
     config: &VerifierConfig,
     protocol_config: &ProtocolConfig,
-    ctx: Context,
-) -> (Arc<Mutex<Deap<Mpc, Zk>>>, MpcTlsFollower) {
+    ctxs: Vec<Context>,
+) -> Result<(Arc<Mutex<Deap<Mpc, Zk>>>, Vec<MpcTlsFollower>), VerifierError> {
+    let mpc_tls_config = config.build_mpc_tls_config(protocol_config)?;
     let mut rng = rand::rng();
 
     let delta = Delta::random(&mut rng);
@@ -320,22 +688,32 @@ fn build_mpc_tls(
     let rcot_send = mpz_ot::rcot::shared::SharedRCOTSender::new(rcot_send);
     let rcot_recv = mpz_ot::rcot::shared::SharedRCOTReceiver::new(rcot_recv);
 
+    #[cfg(not(tlsn_insecure_ideal))]
     let mpc = Mpc::new(mpz_ot::cot::DerandCOTReceiver::new(rcot_recv.clone()));
+    #[cfg(tlsn_insecure_ideal)]
+    let mpc = {
+        tracing::warn!("INSECURE: using the ideal mode which reveals all private inputs");
+        Mpc::new()
+    };
 
     let zk = Zk::new(ZkVerifierConfig::default(), delta, rcot_send.clone());
 
     let vm = Arc::new(Mutex::new(Deap::new(tlsn_deap::Role::Follower, mpc, zk)));
 
-    (
-        vm.clone(),
-        MpcTlsFollower::new(
-            config.build_mpc_tls_config(protocol_config),
-            ctx,
-            vm,
-            rcot_send,
-            (rcot_recv.clone(), rcot_recv.clone(), rcot_recv),
-        ),
-    )
+    let followers = ctxs
+        .into_iter()
+        .map(|ctx| {
+            MpcTlsFollower::new(
+                mpc_tls_config.clone(),
+                ctx,
+                vm.clone(),
+                rcot_send.clone(),
+                (rcot_recv.clone(), rcot_recv.clone(), rcot_recv.clone()),
+            )
+        })
+        .collect();
+
+    Ok((vm, followers))
 }
 
 /// Translates VM references to the ZK address space.
@@ -355,8 +733,10 @@ fn translate_keys<Mpc, Zk>(
     keys.server_write_iv = vm
         .translate(keys.server_write_iv)
         .map_err(VerifierError::mpc)?;
-    keys.server_write_mac_key = vm
-        .translate(keys.server_write_mac_key)
+    keys.server_write_mac_key = keys
+        .server_write_mac_key
+        .map(|mac_key| vm.translate(mac_key))
+        .transpose()
         .map_err(VerifierError::mpc)?;
 
     Ok(())
@@ -0,0 +1,120 @@
+//! Abort messages sent to the prover.
+//!
+//! Steps which the prover waits on report their [`Status`] inline. Any failure
+//! of the verifier, including those in the middle of a step, is additionally
+//! reported on a dedicated [`AbortStream`] before the verifier closes the
+//! connection, so the prover learns why the protocol was aborted instead of
+//! observing a closed connection or an unexpected message.
+
+use std::fmt;
+
+use futures::FutureExt;
+use mpz_common::Context;
+use serde::{Deserialize, Serialize};
+use serio::{SinkExt, stream::IoStreamExt};
+
+/// Status of a protocol step which the verifier sends to the prover.
+pub(crate) type Status = Result<(), Abort>;
+
+/// The kind of an [`Abort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum AbortKind {
+    /// The protocol configuration was rejected.
+    Config,
+    /// MPC failed.
+    Mpc,
+    /// ZK failed.
+    Zk,
+    /// Commitment failed.
+    Commit,
+    /// Verification failed.
+    Verify,
+    /// Any other reason.
+    Other,
+}
+
+impl fmt::Display for AbortKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbortKind::Config => f.write_str("config"),
+            AbortKind::Mpc => f.write_str("mpc"),
+            AbortKind::Zk => f.write_str("zk"),
+            AbortKind::Commit => f.write_str("commit"),
+            AbortKind::Verify => f.write_str("verification"),
+            AbortKind::Other => f.write_str("other"),
+        }
+    }
+}
+
+/// An abort of the protocol by the verifier.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("verifier aborted, {kind}: {reason}")]
+pub struct Abort {
+    kind: AbortKind,
+    reason: String,
+}
+
+impl Abort {
+    /// Creates a new abort.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of abort.
+    /// * `reason` - A human-readable reason.
+    pub fn new(kind: AbortKind, reason: impl Into<String>) -> Self {
+        Self {
+            kind,
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the kind of abort.
+    pub fn kind(&self) -> AbortKind {
+        self.kind
+    }
+
+    /// Returns the reason for the abort.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl From<&crate::config::ProtocolConfigError> for Abort {
+    fn from(err: &crate::config::ProtocolConfigError) -> Self {
+        Self::new(AbortKind::Config, err.to_string())
+    }
+}
+
+/// Stream on which the verifier sends an [`Abort`] to the prover.
+///
+/// It is opened by both parties during setup on its own context, so it is
+/// never interleaved with the messages of the protocol.
+pub(crate) struct AbortStream(Context);
+
+opaque_debug::implement!(AbortStream);
+
+impl AbortStream {
+    /// Creates a new abort stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context dedicated to the abort stream.
+    pub(crate) fn new(ctx: Context) -> Self {
+        Self(ctx)
+    }
+
+    /// Sends an abort to the prover.
+    pub(crate) async fn send(&mut self, abort: Abort) -> Result<(), std::io::Error> {
+        self.0.io_mut().send(abort).await
+    }
+
+    /// Returns the abort of the verifier if it has been received, without
+    /// waiting.
+    ///
+    /// The verifier sends the abort before closing the connection, so it has
+    /// been received once the prover observes an error caused by the abort.
+    pub(crate) fn try_recv(&mut self) -> Option<Abort> {
+        self.0.io_mut().expect_next().now_or_never()?.ok()
+    }
+}
@@ -1,10 +1,18 @@
-use std::fmt::{Debug, Formatter, Result};
+use std::{
+    fmt::{Debug, Formatter, Result},
+    sync::Arc,
+};
 
 use mpc_tls::Config;
 use serde::{Deserialize, Serialize};
 use tlsn_core::webpki::RootCertStore;
 
-use crate::config::{NetworkSetting, ProtocolConfig, ProtocolConfigValidator};
+use crate::{
+    config::{ProtocolConfig, ProtocolConfigError, ProtocolConfigValidator},
+    interrupt::Timeouts,
+    progress::ProgressHandler,
+    verifier::DisclosurePolicy,
+};
 
 /// Configuration for the [`Verifier`](crate::tls::Verifier).
 #[allow(missing_docs)]
@@ -14,12 +22,36 @@ pub struct VerifierConfig {
     protocol_config_validator: ProtocolConfigValidator,
     #[builder(default, setter(strip_option))]
     root_store: Option<RootCertStore>,
+    #[builder(default)]
+    timeouts: Timeouts,
+    #[builder(default, setter(custom))]
+    #[serde(skip)]
+    policy: Option<Arc<dyn DisclosurePolicy>>,
+    #[builder(default, setter(custom))]
+    #[serde(skip)]
+    progress: Option<Arc<dyn ProgressHandler>>,
+}
+
+impl VerifierConfigBuilder {
+    /// Sets the disclosure policy which is checked before verifying a request
+    /// from the prover.
+    pub fn policy(mut self, policy: impl DisclosurePolicy) -> Self {
+        self.policy = Some(Some(Arc::new(policy)));
+        self
+    }
+
+    /// Sets a handler which is notified of the progress of the session.
+    pub fn progress(mut self, handler: impl ProgressHandler) -> Self {
+        self.progress = Some(Some(Arc::new(handler)));
+        self
+    }
 }
 
 impl Debug for VerifierConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         f.debug_struct("VerifierConfig")
             .field("protocol_config_validator", &self.protocol_config_validator)
+            .field("timeouts", &self.timeouts)
             .finish_non_exhaustive()
     }
 }
@@ -40,26 +72,24 @@ impl VerifierConfig {
         self.root_store.as_ref()
     }
 
-    pub(crate) fn build_mpc_tls_config(&self, protocol_config: &ProtocolConfig) -> Config {
-        let mut builder = Config::builder();
-
-        builder
-            .max_sent(protocol_config.max_sent_data())
-            .max_recv_online(protocol_config.max_recv_data_online())
-            .max_recv(protocol_config.max_recv_data());
-
-        if let Some(max_sent_records) = protocol_config.max_sent_records() {
-            builder.max_sent_records(max_sent_records);
-        }
+    /// Returns the timeouts for the stages of the session.
+    pub fn timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
 
-        if let Some(max_recv_records_online) = protocol_config.max_recv_records_online() {
-            builder.max_recv_records_online(max_recv_records_online);
-        }
+    /// Returns the disclosure policy.
+    pub fn policy(&self) -> Option<&dyn DisclosurePolicy> {
+        self.policy.as_deref()
+    }
 
-        if let NetworkSetting::Latency = protocol_config.network() {
-            builder.low_bandwidth();
-        }
+    pub(crate) fn progress(&self) -> Option<Arc<dyn ProgressHandler>> {
+        self.progress.clone()
+    }
 
-        builder.build().unwrap()
+    pub(crate) fn build_mpc_tls_config(
+        &self,
+        protocol_config: &ProtocolConfig,
+    ) -> Result<Config, ProtocolConfigError> {
+        protocol_config.build_mpc_tls_config()
     }
 }
@@ -2,7 +2,11 @@ use std::{error::Error, fmt};
 
 use mpc_tls::MpcTlsError;
 
-use crate::transcript_internal::commit::encoding::EncodingError;
+use crate::{
+    interrupt::Interrupt,
+    transcript_internal::commit::encoding::EncodingError,
+    verifier::{Abort, AbortKind, PolicyRejection},
+};
 
 /// Error for [`Verifier`](crate::Verifier).
 #[derive(Debug, thiserror::Error)]
@@ -49,6 +53,42 @@ impl VerifierError {
     {
         Self::new(ErrorKind::Verify, source)
     }
+
+    pub(crate) fn policy(rejection: PolicyRejection) -> Self {
+        Self::new(ErrorKind::Policy, rejection)
+    }
+
+    /// Returns the interrupt if the session was cancelled or timed out.
+    pub fn interrupt(&self) -> Option<&Interrupt> {
+        if let ErrorKind::Interrupted = self.kind {
+            self.source.as_ref()?.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the request of the prover was rejected due to the
+    /// disclosure policy.
+    pub(crate) fn is_policy(&self) -> bool {
+        matches!(self.kind, ErrorKind::Policy)
+    }
+
+    /// Returns the abort which is sent to the prover.
+    pub(crate) fn to_abort(&self) -> Abort {
+        let kind = match self.kind {
+            ErrorKind::Config => AbortKind::Config,
+            ErrorKind::Mpc => AbortKind::Mpc,
+            ErrorKind::Zk => AbortKind::Zk,
+            ErrorKind::Commit => AbortKind::Commit,
+            ErrorKind::Verify | ErrorKind::Policy => AbortKind::Verify,
+            ErrorKind::Io | ErrorKind::Interrupted => AbortKind::Other,
+        };
+
+        match &self.source {
+            Some(source) => Abort::new(kind, source.to_string()),
+            None => Abort::new(kind, kind.to_string()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -59,6 +99,8 @@ enum ErrorKind {
     Zk,
     Commit,
     Verify,
+    Policy,
+    Interrupted,
 }
 
 impl fmt::Display for VerifierError {
@@ -72,6 +114,8 @@ impl fmt::Display for VerifierError {
             ErrorKind::Zk => f.write_str("zk error")?,
             ErrorKind::Commit => f.write_str("commit error")?,
             ErrorKind::Verify => f.write_str("verification error")?,
+            ErrorKind::Policy => f.write_str("policy error")?,
+            ErrorKind::Interrupted => f.write_str("interrupted")?,
         }
 
         if let Some(source) = &self.source {
@@ -88,6 +132,12 @@ impl From<std::io::Error> for VerifierError {
     }
 }
 
+impl From<Interrupt> for VerifierError {
+    fn from(e: Interrupt) -> Self {
+        Self::new(ErrorKind::Interrupted, e)
+    }
+}
+
 impl From<crate::config::ProtocolConfigError> for VerifierError {
     fn from(e: crate::config::ProtocolConfigError) -> Self {
         Self::new(ErrorKind::Config, e)
@@ -0,0 +1,275 @@
+//! Disclosure policies.
+
+use std::fmt;
+
+use rangeset::{Difference, RangeSet};
+use serde::{Deserialize, Serialize};
+use tlsn_core::{ProveRequest, connection::ServerName, hash::HashAlgId, transcript::Direction};
+
+/// A policy which decides whether the verifier accepts a prove request.
+///
+/// The policy is checked before any verification is performed, once for the
+/// request of each connection which is proven. If any request is rejected,
+/// the rejection is sent to the prover.
+pub trait DisclosurePolicy: Send + Sync + 'static {
+    /// Checks the request from the prover.
+    fn check(&self, request: &ProveRequest) -> Result<(), PolicyRejection>;
+}
+
+impl<F> DisclosurePolicy for F
+where
+    F: Fn(&ProveRequest) -> Result<(), PolicyRejection> + Send + Sync + 'static,
+{
+    fn check(&self, request: &ProveRequest) -> Result<(), PolicyRejection> {
+        self(request)
+    }
+}
+
+/// The kind of a [`PolicyRejection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum RejectionKind {
+    /// The server name is not allowed.
+    ServerName,
+    /// The server identity was not disclosed.
+    ServerIdentity,
+    /// Required data was not revealed.
+    Reveal,
+    /// A hash algorithm is not allowed.
+    HashAlgorithm,
+    /// Any other reason.
+    Other,
+}
+
+impl fmt::Display for RejectionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectionKind::ServerName => f.write_str("server name"),
+            RejectionKind::ServerIdentity => f.write_str("server identity"),
+            RejectionKind::Reveal => f.write_str("reveal"),
+            RejectionKind::HashAlgorithm => f.write_str("hash algorithm"),
+            RejectionKind::Other => f.write_str("other"),
+        }
+    }
+}
+
+/// Rejection of a prove request by a [`DisclosurePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+#[error("request rejected by policy, {kind}: {reason}")]
+pub struct PolicyRejection {
+    kind: RejectionKind,
+    reason: String,
+}
+
+impl PolicyRejection {
+    /// Creates a new rejection.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of rejection.
+    /// * `reason` - A human-readable reason.
+    pub fn new(kind: RejectionKind, reason: impl Into<String>) -> Self {
+        Self {
+            kind,
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the kind of rejection.
+    pub fn kind(&self) -> RejectionKind {
+        self.kind
+    }
+
+    /// Returns the reason for the rejection.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// A policy covering common disclosure requirements.
+#[derive(Debug, Clone, Default, derive_builder::Builder)]
+#[builder(pattern = "owned", default)]
+pub struct BasicPolicy {
+    /// Server names which are allowed. Any server is allowed if unset.
+    ///
+    /// Setting this requires the server identity to be disclosed.
+    #[builder(setter(strip_option))]
+    allowed_server_names: Option<Vec<ServerName>>,
+    /// Requires the server identity to be disclosed.
+    require_server_identity: bool,
+    /// Ranges of the sent data which must be revealed.
+    required_sent: RangeSet<usize>,
+    /// Ranges of the received data which must be revealed.
+    required_recv: RangeSet<usize>,
+    /// Hash algorithms which may not be used for commitments.
+    forbidden_hash_algs: Vec<HashAlgId>,
+}
+
+impl BasicPolicy {
+    /// Creates a new policy builder.
+    pub fn builder() -> BasicPolicyBuilder {
+        BasicPolicyBuilder::default()
+    }
+}
+
+impl DisclosurePolicy for BasicPolicy {
+    fn check(&self, request: &ProveRequest) -> Result<(), PolicyRejection> {
+        let server_name = request.handshake.as_ref().map(|(name, _)| name);
+
+        if let Some(allowed) = &self.allowed_server_names {
+            let Some(server_name) = server_name else {
+                return Err(PolicyRejection::new(
+                    RejectionKind::ServerIdentity,
+                    "server identity must be disclosed to check the server name",
+                ));
+            };
+
+            if !allowed.contains(server_name) {
+                return Err(PolicyRejection::new(
+                    RejectionKind::ServerName,
+                    format!("server name is not allowed: {server_name}"),
+                ));
+            }
+        } else if self.require_server_identity && server_name.is_none() {
+            return Err(PolicyRejection::new(
+                RejectionKind::ServerIdentity,
+                "server identity must be disclosed",
+            ));
+        }
+
+        let empty = RangeSet::default();
+        let (sent_authed, recv_authed) = request
+            .transcript
+            .as_ref()
+            .map(|transcript| (transcript.sent_authed(), transcript.received_authed()))
+            .unwrap_or((&empty, &empty));
+
+        for (direction, required, authed) in [
+            (Direction::Sent, &self.required_sent, sent_authed),
+            (Direction::Received, &self.required_recv, recv_authed),
+        ] {
+            let missing = required.difference(authed);
+            if !missing.is_empty() {
+                return Err(PolicyRejection::new(
+                    RejectionKind::Reveal,
+                    format!("{direction} data must be revealed: {missing:?}"),
+                ));
+            }
+        }
+
+        if let Some(commit) = &request.transcript_commit
+            && let Some((_, _, alg)) = commit
+                .iter_hash()
+                .find(|(_, _, alg)| self.forbidden_hash_algs.contains(alg))
+        {
+            return Err(PolicyRejection::new(
+                RejectionKind::HashAlgorithm,
+                format!("hash algorithm is not allowed: {alg}"),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tlsn_core::transcript::{Transcript, TranscriptCommitConfig, TranscriptCommitmentKind};
+
+    fn request(reveal: bool, alg: Option<HashAlgId>) -> ProveRequest {
+        let transcript = Transcript::new(b"GET / HTTP/1.1", b"HTTP/1.1 200 OK");
+
+        let transcript_commit = alg.map(|alg| {
+            let mut builder = TranscriptCommitConfig::builder(&transcript);
+            builder
+                .commit_with_kind(
+                    &(0..4),
+                    Direction::Sent,
+                    TranscriptCommitmentKind::Hash { alg },
+                )
+                .unwrap();
+            builder.build().unwrap().to_request()
+        });
+
+        ProveRequest {
+            connection: 0,
+            handshake: None,
+            transcript: reveal
+                .then(|| transcript.to_partial(RangeSet::from(0..4), RangeSet::from(0..8))),
+            transcript_commit,
+        }
+    }
+
+    #[test]
+    fn test_default_policy_accepts() {
+        let policy = BasicPolicy::default();
+
+        assert!(policy.check(&request(false, None)).is_ok());
+        assert!(
+            policy
+                .check(&request(true, Some(HashAlgId::SHA256)))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_policy_requires_server_identity() {
+        let policy = BasicPolicy::builder()
+            .require_server_identity(true)
+            .build()
+            .unwrap();
+
+        let err = policy.check(&request(true, None)).unwrap_err();
+        assert_eq!(err.kind(), RejectionKind::ServerIdentity);
+
+        let policy = BasicPolicy::builder()
+            .allowed_server_names(vec![])
+            .build()
+            .unwrap();
+
+        let err = policy.check(&request(true, None)).unwrap_err();
+        assert_eq!(err.kind(), RejectionKind::ServerIdentity);
+    }
+
+    #[test]
+    fn test_policy_requires_reveal() {
+        let policy = BasicPolicy::builder()
+            .required_sent(RangeSet::from(0..4))
+            .required_recv(RangeSet::from(0..8))
+            .build()
+            .unwrap();
+
+        assert!(policy.check(&request(true, None)).is_ok());
+
+        let err = policy.check(&request(false, None)).unwrap_err();
+        assert_eq!(err.kind(), RejectionKind::Reveal);
+
+        let policy = BasicPolicy::builder()
+            .required_recv(RangeSet::from(0..9))
+            .build()
+            .unwrap();
+
+        let err = policy.check(&request(true, None)).unwrap_err();
+        assert_eq!(err.kind(), RejectionKind::Reveal);
+    }
+
+    #[test]
+    fn test_policy_forbids_hash_alg() {
+        let policy = BasicPolicy::builder()
+            .forbidden_hash_algs(vec![HashAlgId::KECCAK256])
+            .build()
+            .unwrap();
+
+        assert!(
+            policy
+                .check(&request(false, Some(HashAlgId::SHA256)))
+                .is_ok()
+        );
+
+        let err = policy
+            .check(&request(false, Some(HashAlgId::KECCAK256)))
+            .unwrap_err();
+        assert_eq!(err.kind(), RejectionKind::HashAlgorithm);
+    }
+}
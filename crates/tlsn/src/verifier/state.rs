@@ -1,8 +1,13 @@
 //! TLS Verifier state.
 
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
-use crate::mux::{MuxControl, MuxFuture};
+use crate::{
+    config::NetworkInfo,
+    mux::{MuxControl, MuxFuture},
+    progress::Reporter,
+    verifier::abort::AbortStream,
+};
 use mpc_tls::{MpcTlsFollower, SessionKeys};
 use mpz_common::Context;
 use tlsn_core::transcript::TlsTranscript;
@@ -23,23 +28,34 @@ opaque_debug::implement!(Initialized);
 pub struct Setup {
     pub(crate) mux_ctrl: MuxControl,
     pub(crate) mux_fut: MuxFuture,
-    pub(crate) mpc_tls: MpcTlsFollower,
-    pub(crate) keys: SessionKeys,
+    pub(crate) abort: AbortStream,
+    /// MPC-TLS instances of the remaining connections.
+    pub(crate) mpc_tls: VecDeque<(MpcTlsFollower, SessionKeys)>,
     pub(crate) vm: Arc<Mutex<Deap<Mpc, Zk>>>,
+    pub(crate) network: NetworkInfo,
+    pub(crate) reporter: Reporter,
 }
 
-/// State after the TLS connection has been closed.
+/// State after the TLS connections have been closed.
 pub struct Committed {
     pub(crate) mux_ctrl: MuxControl,
     pub(crate) mux_fut: MuxFuture,
+    pub(crate) abort: AbortStream,
     pub(crate) ctx: Context,
     pub(crate) vm: Zk,
-    pub(crate) keys: SessionKeys,
-    pub(crate) tls_transcript: TlsTranscript,
+    pub(crate) connections: Vec<Connection>,
+    pub(crate) network: NetworkInfo,
+    pub(crate) reporter: Reporter,
 }
 
 opaque_debug::implement!(Committed);
 
+/// A TLS connection which has been closed.
+pub(crate) struct Connection {
+    pub(crate) keys: SessionKeys,
+    pub(crate) tls_transcript: TlsTranscript,
+}
+
 impl VerifierState for Initialized {}
 impl VerifierState for Setup {}
 impl VerifierState for Committed {}
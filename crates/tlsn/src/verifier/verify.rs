@@ -5,15 +5,16 @@ use mpz_vm_core::Vm;
 use rangeset::{RangeSet, UnionMut};
 use tlsn_core::{
     ProveRequest, VerifierOutput,
-    transcript::{
-        ContentType, Direction, PartialTranscript, Record, TlsTranscript, TranscriptCommitment,
-    },
+    connection::{ConnectionInfo, TranscriptLength},
+    metrics::SessionMetrics,
+    transcript::{Direction, PartialTranscript, Record, TlsTranscript, TranscriptCommitment},
     webpki::ServerCertVerifier,
 };
 
 use crate::{
+    progress::{Phase, Progress, Reporter},
     transcript_internal::{
-        TranscriptRefs,
+        TranscriptRefs, app_records,
         auth::verify_plaintext,
         commit::{
             encoding::{self, KeyStore},
@@ -30,15 +31,19 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
     cert_verifier: &ServerCertVerifier,
     tls_transcript: &TlsTranscript,
     request: ProveRequest,
+    reporter: &Reporter,
 ) -> Result<VerifierOutput, VerifierError> {
     let ProveRequest {
+        connection,
         handshake,
         transcript,
         transcript_commit,
     } = request;
 
-    let ciphertext_sent = collect_ciphertext(tls_transcript.sent());
-    let ciphertext_recv = collect_ciphertext(tls_transcript.recv());
+    let records_sent = app_records(tls_transcript, Direction::Sent);
+    let records_recv = app_records(tls_transcript, Direction::Received);
+    let ciphertext_sent = collect_ciphertext(&records_sent);
+    let ciphertext_recv = collect_ciphertext(&records_recv);
 
     let has_reveal = transcript.is_some();
     let transcript = if let Some(transcript) = transcript {
@@ -55,6 +60,15 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
         PartialTranscript::new(ciphertext_sent.len(), ciphertext_recv.len())
     };
 
+    let connection_info = ConnectionInfo {
+        time: tls_transcript.time(),
+        version: *tls_transcript.version(),
+        transcript_length: TranscriptLength {
+            sent: transcript.len_sent() as u32,
+            received: transcript.len_received() as u32,
+        },
+    };
+
     let server_name = if let Some((name, cert_data)) = handshake {
         cert_data
             .verify(
@@ -87,28 +101,24 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
 
     let (sent_refs, sent_proof) = verify_plaintext(
         vm,
+        keys.aead,
         keys.client_write_key,
         keys.client_write_iv,
         transcript.sent_unsafe(),
         &ciphertext_sent,
-        tls_transcript
-            .sent()
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData),
+        &records_sent,
         transcript.sent_authed(),
         &commit_sent,
     )
     .map_err(VerifierError::zk)?;
     let (recv_refs, recv_proof) = verify_plaintext(
         vm,
+        keys.aead,
         keys.server_write_key,
         keys.server_write_iv,
         transcript.received_unsafe(),
         &ciphertext_recv,
-        tls_transcript
-            .recv()
-            .iter()
-            .filter(|record| record.typ == ContentType::ApplicationData),
+        &records_recv,
         transcript.received_authed(),
         &commit_recv,
     )
@@ -139,6 +149,8 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
     if let Some(commit_config) = transcript_commit
         && let Some((sent, recv)) = commit_config.encoding()
     {
+        reporter.report(Progress::Started(Phase::Commit));
+
         let sent_map = transcript_refs
             .sent
             .index(sent)
@@ -151,6 +163,8 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
         let (secret, commitment) = encoding::transfer(ctx, vm, &sent_map, &recv_map).await?;
         encoder_secret = Some(secret);
         transcript_commitments.push(TranscriptCommitment::Encoding(commitment));
+
+        reporter.report(Progress::Finished(Phase::Commit));
     }
 
     if let Some(hash_commitments) = hash_commitments {
@@ -160,20 +174,20 @@ pub(crate) async fn verify<T: Vm<Binary> + KeyStore + Send + Sync>(
     }
 
     Ok(VerifierOutput {
+        connection,
+        connection_info,
         server_name,
         transcript: has_reveal.then_some(transcript),
         encoder_secret,
         transcript_commitments,
+        metrics: SessionMetrics::default(),
     })
 }
 
 fn collect_ciphertext<'a>(records: impl IntoIterator<Item = &'a Record>) -> Vec<u8> {
     let mut ciphertext = Vec::new();
-    records
-        .into_iter()
-        .filter(|record| record.typ == ContentType::ApplicationData)
-        .for_each(|record| {
-            ciphertext.extend_from_slice(&record.ciphertext);
-        });
+    records.into_iter().for_each(|record| {
+        ciphertext.extend_from_slice(&record.ciphertext);
+    });
     ciphertext
 }
@@ -1,15 +1,18 @@
+use std::time::Duration;
+
 use futures::{AsyncReadExt, AsyncWriteExt};
 use rangeset::RangeSet;
 use tlsn::{
-    config::{CertificateDer, ProtocolConfig, ProtocolConfigValidator, RootCertStore},
-    connection::ServerName,
+    config::{CertificateDer, LimitMode, ProtocolConfig, ProtocolConfigValidator, RootCertStore},
+    connection::{CertBinding, ServerName, TlsVersion},
     hash::{HashAlgId, HashProvider},
+    interrupt::{Interrupt, Stage, Timeouts},
     prover::{ProveConfig, Prover, ProverConfig, TlsConfig},
     transcript::{
         Direction, Transcript, TranscriptCommitConfig, TranscriptCommitment,
         TranscriptCommitmentKind, TranscriptSecret,
     },
-    verifier::{Verifier, VerifierConfig, VerifierOutput, VerifyConfig},
+    verifier::{AbortKind, Verifier, VerifierConfig, VerifierOutput, VerifyConfig},
 };
 use tlsn_core::ProverOutput;
 use tlsn_server_fixture::bind;
@@ -29,7 +32,7 @@ const MAX_RECV_DATA: usize = 1 << 14;
 const MAX_RECV_RECORDS: usize = 6;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-#[ignore]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
 async fn test() {
     tracing_subscriber::fmt::init();
 
@@ -103,6 +106,743 @@ async fn test() {
 
     assert_eq!(auth_sent, prove_sent);
     assert_eq!(auth_recv, prove_recv);
+
+    let (prover_metrics, verifier_metrics) = (prover_output.metrics, verifier_output.metrics);
+    assert!(prover_metrics.preprocess.sent > 0);
+    assert!(prover_metrics.prove.sent > 0);
+    assert!(prover_metrics.total.sent >= prover_metrics.preprocess.sent);
+    assert!(verifier_metrics.prove.recv > 0);
+}
+
+#[tokio::test]
+async fn test_setup_abort() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 16);
+
+    let prover = Prover::new(
+        ProverConfig::builder()
+            .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+            .tls_config(TlsConfig::builder().build().unwrap())
+            .protocol_config(
+                ProtocolConfig::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA / 2)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.setup(socket_0.compat()),
+        verifier.setup(socket_1.compat())
+    );
+
+    assert!(verifier_result.is_err());
+
+    let err = prover_result.unwrap_err();
+    let abort = err.abort().expect("verifier should abort");
+    assert_eq!(abort.kind(), AbortKind::Config);
+    assert!(abort.reason().contains("max_recv_data"));
+}
+
+#[tokio::test]
+async fn test_setup_limits() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 16);
+
+    let prover = Prover::new(
+        ProverConfig::builder()
+            .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+            .tls_config(TlsConfig::builder().build().unwrap())
+            .protocol_config(
+                ProtocolConfig::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA / 2)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.setup(socket_0.compat()),
+        verifier.setup(socket_1.compat())
+    );
+
+    // The prover fails before sending its configuration.
+    let err = verifier_result.unwrap_err();
+    assert!(err.to_string().contains("io error"));
+
+    let err = prover_result.unwrap_err();
+    let abort = err.abort().expect("limits should reject the configuration");
+    assert_eq!(abort.kind(), AbortKind::Config);
+    assert!(abort.reason().contains("max_recv_data"));
+}
+
+#[tokio::test]
+async fn test_setup_malformed_config() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 16);
+
+    // A configuration which was not created with the builder, with a ceiling
+    // below the data limit.
+    let mut protocol_config = serde_json::to_value(
+        ProtocolConfig::builder()
+            .max_sent_data(MAX_SENT_DATA)
+            .max_recv_data(MAX_RECV_DATA)
+            .build()
+            .unwrap(),
+    )
+    .unwrap();
+    protocol_config["recv_data_ceiling"] = (MAX_RECV_DATA / 2).into();
+    let protocol_config: ProtocolConfig = serde_json::from_value(protocol_config).unwrap();
+
+    // Clamping sends the configuration without checking it.
+    let prover = Prover::new(
+        ProverConfig::builder()
+            .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+            .tls_config(TlsConfig::builder().build().unwrap())
+            .protocol_config(protocol_config)
+            .limit_mode(LimitMode::Clamp)
+            .build()
+            .unwrap(),
+    );
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.setup(socket_0.compat()),
+        verifier.setup(socket_1.compat())
+    );
+
+    assert!(verifier_result.is_err());
+
+    let err = prover_result.unwrap_err();
+    let abort = err.abort().expect("verifier should abort");
+    assert_eq!(abort.kind(), AbortKind::Config);
+    assert!(abort.reason().contains("recv_data_ceiling"));
+}
+
+#[tokio::test]
+async fn test_setup_timeout() {
+    // The prover never responds.
+    let (_socket_0, socket_1) = tokio::io::duplex(2 << 16);
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .timeouts(
+                Timeouts::builder()
+                    .setup(Duration::from_millis(100))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let err = verifier.setup(socket_1.compat()).await.unwrap_err();
+    assert_eq!(err.interrupt(), Some(&Interrupt::Timeout(Stage::Setup)));
+}
+
+#[tokio::test]
+async fn test_setup_cancel() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 16);
+
+    let prover = Prover::new(
+        ProverConfig::builder()
+            .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+            .tls_config(TlsConfig::builder().build().unwrap())
+            .protocol_config(
+                ProtocolConfig::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let cancel = verifier.cancel_handle();
+    cancel.cancel();
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.setup(socket_0.compat()),
+        verifier.setup(socket_1.compat())
+    );
+
+    assert_eq!(
+        verifier_result.unwrap_err().interrupt(),
+        Some(&Interrupt::Cancelled)
+    );
+
+    // The connection is torn down, so the prover fails as well.
+    assert!(prover_result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
+async fn test_setup_limits_clamp() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let prover = Prover::new(
+        ProverConfig::builder()
+            .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+            .tls_config(TlsConfig::builder().build().unwrap())
+            .protocol_config(
+                ProtocolConfig::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA)
+                    .build()
+                    .unwrap(),
+            )
+            .limit_mode(LimitMode::Clamp)
+            .build()
+            .unwrap(),
+    );
+
+    let verifier = Verifier::new(
+        VerifierConfig::builder()
+            .protocol_config_validator(
+                ProtocolConfigValidator::builder()
+                    .max_sent_data(MAX_SENT_DATA)
+                    .max_recv_data(MAX_RECV_DATA / 2)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap(),
+    );
+
+    let (prover_result, verifier_result) = tokio::join!(
+        prover.setup(socket_0.compat()),
+        verifier.setup(socket_1.compat())
+    );
+
+    assert!(prover_result.is_ok());
+    assert!(verifier_result.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
+async fn test_multiple_connections() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let prover = async {
+        let mut tls_config_builder = TlsConfig::builder();
+        tls_config_builder.root_store(RootCertStore {
+            roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+        });
+
+        let server_name = ServerName::Dns(SERVER_DOMAIN.try_into().unwrap());
+        let mut prover = Prover::new(
+            ProverConfig::builder()
+                .server_name(server_name.clone())
+                .tls_config(tls_config_builder.build().unwrap())
+                .protocol_config(
+                    ProtocolConfig::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_sent_records(MAX_SENT_RECORDS)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .max_recv_records_online(MAX_RECV_RECORDS)
+                        .max_connections(2)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .setup(socket_0.compat())
+        .await
+        .unwrap();
+
+        assert_eq!(prover.remaining_connections(), 2);
+
+        for last in [false, true] {
+            let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+            let server_task = tokio::spawn(bind(server_socket.compat()));
+
+            let request = b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n";
+            let mut response = Vec::new();
+            if last {
+                let (mut tls_connection, prover_fut) =
+                    prover.connect(client_socket.compat()).await.unwrap();
+                let prover_task = tokio::spawn(prover_fut);
+
+                tls_connection.write_all(request).await.unwrap();
+                tls_connection.close().await.unwrap();
+                tls_connection.read_to_end(&mut response).await.unwrap();
+                let _ = server_task.await.unwrap();
+
+                let mut prover = prover_task.await.unwrap().unwrap();
+                assert_eq!(prover.connection_count(), 2);
+
+                let transcripts: Vec<_> = (0..prover.connection_count())
+                    .map(|connection| prover.transcript_of(connection).unwrap().clone())
+                    .collect();
+
+                // The connections are disclosed differently in a single proof.
+                let mut builder = ProveConfig::builder(&transcripts[0]);
+                builder.server_identity();
+                builder.reveal_sent_all().unwrap();
+                builder.connection(1, &transcripts[1]);
+                builder.reveal_recv_all().unwrap();
+                let config = builder.build().unwrap();
+
+                let outputs = prover.prove(&config).await.unwrap();
+                assert_eq!(outputs.len(), 2);
+                assert_eq!(outputs[1].connection, 1);
+
+                prover.close().await.unwrap();
+
+                return;
+            }
+
+            let (mut tls_connection, prover_fut) = prover
+                .connect_next(server_name.clone(), client_socket.compat())
+                .await
+                .unwrap();
+            let prover_task = tokio::spawn(prover_fut);
+
+            tls_connection.write_all(request).await.unwrap();
+            tls_connection.close().await.unwrap();
+            tls_connection.read_to_end(&mut response).await.unwrap();
+            let _ = server_task.await.unwrap();
+
+            prover = prover_task.await.unwrap().unwrap();
+            assert_eq!(prover.remaining_connections(), 1);
+        }
+    };
+
+    let verifier = async {
+        let verifier = Verifier::new(
+            VerifierConfig::builder()
+                .root_store(RootCertStore {
+                    roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+                })
+                .protocol_config_validator(
+                    ProtocolConfigValidator::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .max_connections(2)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        );
+
+        let mut verifier = verifier
+            .setup(socket_1.compat())
+            .await
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(verifier.connection_count(), 2);
+
+        let outputs = verifier.verify(&VerifyConfig::default()).await.unwrap();
+        verifier.close().await.unwrap();
+
+        outputs
+    };
+
+    let (_, outputs) = tokio::join!(prover, verifier);
+
+    assert_eq!(outputs.len(), 2);
+    for (idx, output) in outputs.iter().enumerate() {
+        assert_eq!(output.connection, idx);
+        assert!(output.connection_info.transcript_length.sent > 0);
+    }
+
+    let (first, second) = (&outputs[0], &outputs[1]);
+    assert!(first.server_name.is_some());
+    assert!(
+        first
+            .transcript
+            .as_ref()
+            .unwrap()
+            .received_authed()
+            .is_empty()
+    );
+    assert!(second.server_name.is_none());
+    assert!(second.transcript.as_ref().unwrap().sent_authed().is_empty());
+    assert!(
+        !second
+            .transcript
+            .as_ref()
+            .unwrap()
+            .received_authed()
+            .is_empty()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
+async fn test_tls13() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let prover = async {
+        let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+        let server_task = tokio::spawn(bind(server_socket.compat()));
+
+        let mut tls_config_builder = TlsConfig::builder();
+        tls_config_builder.root_store(RootCertStore {
+            roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+        });
+
+        let prover = Prover::new(
+            ProverConfig::builder()
+                .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+                .tls_config(tls_config_builder.build().unwrap())
+                .protocol_config(
+                    ProtocolConfig::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_sent_records(MAX_SENT_RECORDS)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .max_recv_records_online(MAX_RECV_RECORDS)
+                        .tls_version(TlsVersion::V1_3)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .setup(socket_0.compat())
+        .await
+        .unwrap();
+
+        let (mut tls_connection, prover_fut) =
+            prover.connect(client_socket.compat()).await.unwrap();
+        let prover_task = tokio::spawn(prover_fut);
+
+        tls_connection
+            .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        tls_connection.close().await.unwrap();
+
+        let mut response = Vec::new();
+        tls_connection.read_to_end(&mut response).await.unwrap();
+        let _ = server_task.await.unwrap();
+
+        let mut prover = prover_task.await.unwrap().unwrap();
+
+        let tls_transcript = prover.tls_transcript_of(0).unwrap();
+        assert_eq!(tls_transcript.version(), &TlsVersion::V1_3);
+        assert!(matches!(
+            tls_transcript.certificate_binding(),
+            CertBinding::V1_3(_)
+        ));
+
+        let transcript = prover.transcript_of(0).unwrap().clone();
+        let mut builder = ProveConfig::builder(&transcript);
+        builder.server_identity();
+        builder.reveal_sent_all().unwrap();
+        builder.reveal_recv(&(0..10)).unwrap();
+        let config = builder.build().unwrap();
+
+        prover.prove(&config).await.unwrap();
+        prover.close().await.unwrap();
+    };
+
+    let verifier = async {
+        let verifier = Verifier::new(
+            VerifierConfig::builder()
+                .root_store(RootCertStore {
+                    roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+                })
+                .protocol_config_validator(
+                    ProtocolConfigValidator::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        );
+
+        let mut verifier = verifier
+            .setup(socket_1.compat())
+            .await
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            verifier.tls_transcript_of(0).unwrap().certificate_binding(),
+            CertBinding::V1_3(_)
+        ));
+
+        let mut outputs = verifier.verify(&VerifyConfig::default()).await.unwrap();
+        verifier.close().await.unwrap();
+
+        assert_eq!(outputs.len(), 1);
+        outputs.remove(0)
+    };
+
+    let (_, output) = tokio::join!(prover, verifier);
+
+    let ServerName::Dns(server_name) = output.server_name.unwrap();
+    assert_eq!(server_name.as_str(), SERVER_DOMAIN);
+    assert_eq!(output.connection_info.version, TlsVersion::V1_3);
+
+    let transcript = output.transcript.unwrap();
+    assert!(transcript.sent_unauthed().is_empty());
+    assert_eq!(
+        transcript.received_authed().iter_ranges().next().unwrap(),
+        0..10
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
+async fn test_recv_ceiling() {
+    // The response is larger than the capacity which is allocated up front, so
+    // the rest is allocated on demand up to the ceiling.
+    const RESPONSE_SIZE: usize = 1 << 13;
+    const RECV_CEILING: usize = 1 << 15;
+
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let prover = async {
+        let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+        let server_task = tokio::spawn(bind(server_socket.compat()));
+
+        let mut tls_config_builder = TlsConfig::builder();
+        tls_config_builder.root_store(RootCertStore {
+            roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+        });
+
+        let prover = Prover::new(
+            ProverConfig::builder()
+                .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+                .tls_config(tls_config_builder.build().unwrap())
+                .protocol_config(
+                    ProtocolConfig::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_recv_data(1 << 10)
+                        .max_recv_data_online(1 << 10)
+                        .defer_decryption_from_start(false)
+                        .recv_data_ceiling(RECV_CEILING)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .setup(socket_0.compat())
+        .await
+        .unwrap();
+
+        let (mut tls_connection, prover_fut) =
+            prover.connect(client_socket.compat()).await.unwrap();
+        let prover_task = tokio::spawn(prover_fut);
+
+        tls_connection
+            .write_all(
+                format!("GET /bytes?size={RESPONSE_SIZE} HTTP/1.1\r\nConnection: close\r\n\r\n")
+                    .as_bytes(),
+            )
+            .await
+            .unwrap();
+        tls_connection.close().await.unwrap();
+
+        let mut response = Vec::new();
+        tls_connection.read_to_end(&mut response).await.unwrap();
+        let _ = server_task.await.unwrap();
+
+        assert!(response.ends_with(&[0x42; RESPONSE_SIZE]));
+
+        let mut prover = prover_task.await.unwrap().unwrap();
+
+        let mut builder = ProveConfig::builder(prover.transcript());
+        builder.server_identity();
+        builder.reveal_sent_all().unwrap();
+        builder.reveal_recv_all().unwrap();
+        let config = builder.build().unwrap();
+
+        prover.prove(&config).await.unwrap();
+        let transcript = prover.transcript().clone();
+        prover.close().await.unwrap();
+
+        transcript
+    };
+
+    let verifier = async {
+        Verifier::new(
+            VerifierConfig::builder()
+                .root_store(RootCertStore {
+                    roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+                })
+                .protocol_config_validator(
+                    ProtocolConfigValidator::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_recv_data(RECV_CEILING)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .verify(socket_1.compat(), &VerifyConfig::default())
+        .await
+        .unwrap()
+    };
+
+    let (transcript, mut outputs) = tokio::join!(prover, verifier);
+    let output = outputs.remove(0);
+
+    let partial_transcript = output.transcript.unwrap();
+    assert!(partial_transcript.is_complete());
+    assert!(transcript.received().len() > RESPONSE_SIZE);
+    assert_eq!(partial_transcript.received_unsafe(), transcript.received());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[cfg_attr(not(tlsn_insecure_ideal), ignore)]
+async fn test_prove_abort() {
+    let (socket_0, socket_1) = tokio::io::duplex(2 << 23);
+
+    let prover = async {
+        let (client_socket, server_socket) = tokio::io::duplex(2 << 16);
+        let server_task = tokio::spawn(bind(server_socket.compat()));
+
+        let mut tls_config_builder = TlsConfig::builder();
+        tls_config_builder.root_store(RootCertStore {
+            roots: vec![CertificateDer(CA_CERT_DER.to_vec())],
+        });
+
+        let prover = Prover::new(
+            ProverConfig::builder()
+                .server_name(ServerName::Dns(SERVER_DOMAIN.try_into().unwrap()))
+                .tls_config(tls_config_builder.build().unwrap())
+                .protocol_config(
+                    ProtocolConfig::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_sent_records(MAX_SENT_RECORDS)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .max_recv_records_online(MAX_RECV_RECORDS)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .setup(socket_0.compat())
+        .await
+        .unwrap();
+
+        let (mut tls_connection, prover_fut) =
+            prover.connect(client_socket.compat()).await.unwrap();
+        let prover_task = tokio::spawn(prover_fut);
+
+        tls_connection
+            .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        tls_connection.close().await.unwrap();
+
+        let mut response = Vec::new();
+        tls_connection.read_to_end(&mut response).await.unwrap();
+        let _ = server_task.await.unwrap();
+
+        let mut prover = prover_task.await.unwrap().unwrap();
+
+        let mut builder = ProveConfig::builder(prover.transcript());
+        builder.server_identity();
+        builder.reveal_sent_all().unwrap();
+        let config = builder.build().unwrap();
+
+        prover.prove(&config).await.unwrap_err()
+    };
+
+    // The verifier does not trust the certificate of the server, so
+    // verification fails while the prover is proving.
+    let verifier = async {
+        Verifier::new(
+            VerifierConfig::builder()
+                .protocol_config_validator(
+                    ProtocolConfigValidator::builder()
+                        .max_sent_data(MAX_SENT_DATA)
+                        .max_recv_data(MAX_RECV_DATA)
+                        .build()
+                        .unwrap(),
+                )
+                .build()
+                .unwrap(),
+        )
+        .verify(socket_1.compat(), &VerifyConfig::default())
+        .await
+        .unwrap_err()
+    };
+
+    let (prover_err, verifier_err) = tokio::join!(prover, verifier);
+
+    assert!(verifier_err.to_string().contains("verification error"));
+
+    let abort = prover_err.abort().expect("verifier should abort");
+    assert_eq!(abort.kind(), AbortKind::Verify);
 }
 
 #[instrument(skip(verifier_socket))]
@@ -194,10 +934,11 @@ async fn prover<T: AsyncWrite + AsyncRead + Send + Unpin + 'static>(
 
     let config = builder.build().unwrap();
     let transcript = prover.transcript().clone();
-    let output = prover.prove(&config).await.unwrap();
+    let mut outputs = prover.prove(&config).await.unwrap();
     prover.close().await.unwrap();
 
-    (transcript, output)
+    assert_eq!(outputs.len(), 1);
+    (transcript, outputs.remove(0))
 }
 
 #[instrument(skip(socket))]
@@ -228,8 +969,9 @@ async fn verifier<T: AsyncWrite + AsyncRead + Send + Sync + Unpin + 'static>(
         .await
         .unwrap();
 
-    let output = verifier.verify(&VerifyConfig::default()).await.unwrap();
+    let mut outputs = verifier.verify(&VerifyConfig::default()).await.unwrap();
     verifier.close().await.unwrap();
 
-    output
+    assert_eq!(outputs.len(), 1);
+    outputs.remove(0)
 }
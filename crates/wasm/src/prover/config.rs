@@ -1,8 +1,9 @@
 use crate::types::NetworkSetting;
 use serde::Deserialize;
 use tlsn::{
-    config::{CertificateDer, PrivateKeyDer, ProtocolConfig},
+    config::{CertificateDer, LimitMode, PrivateKeyDer, ProtocolConfig},
     connection::ServerName,
+    progress::{ProgressEvent, ProgressHandler},
 };
 use tsify_next::Tsify;
 use wasm_bindgen::JsError;
@@ -16,8 +17,11 @@ pub struct ProverConfig {
     pub max_recv_data_online: Option<usize>,
     pub max_recv_data: usize,
     pub max_recv_records_online: Option<usize>,
+    pub sent_data_ceiling: Option<usize>,
+    pub recv_data_ceiling: Option<usize>,
     pub defer_decryption_from_start: Option<bool>,
     pub network: NetworkSetting,
+    pub clamp_to_verifier_limits: Option<bool>,
     pub client_auth: Option<(Vec<Vec<u8>>, Vec<u8>)>,
 }
 
@@ -25,32 +29,51 @@ impl TryFrom<ProverConfig> for tlsn::prover::ProverConfig {
     type Error = JsError;
 
     fn try_from(value: ProverConfig) -> Result<Self, Self::Error> {
+        value.into_config(None::<fn(&ProgressEvent)>)
+    }
+}
+
+impl ProverConfig {
+    /// Converts into a prover config, setting the progress handler if
+    /// provided.
+    pub(crate) fn into_config(
+        self,
+        progress: Option<impl ProgressHandler>,
+    ) -> Result<tlsn::prover::ProverConfig, JsError> {
         let mut builder = ProtocolConfig::builder();
 
-        builder.max_sent_data(value.max_sent_data);
-        builder.max_recv_data(value.max_recv_data);
+        builder.max_sent_data(self.max_sent_data);
+        builder.max_recv_data(self.max_recv_data);
 
-        if let Some(value) = value.max_recv_data_online {
+        if let Some(value) = self.max_recv_data_online {
             builder.max_recv_data_online(value);
         }
 
-        if let Some(value) = value.max_sent_records {
+        if let Some(value) = self.max_sent_records {
             builder.max_sent_records(value);
         }
 
-        if let Some(value) = value.max_recv_records_online {
+        if let Some(value) = self.max_recv_records_online {
             builder.max_recv_records_online(value);
         }
 
-        if let Some(value) = value.defer_decryption_from_start {
+        if let Some(value) = self.sent_data_ceiling {
+            builder.sent_data_ceiling(value);
+        }
+
+        if let Some(value) = self.recv_data_ceiling {
+            builder.recv_data_ceiling(value);
+        }
+
+        if let Some(value) = self.defer_decryption_from_start {
             builder.defer_decryption_from_start(value);
         }
 
-        builder.network(value.network.into());
+        builder.network(self.network.into());
         let protocol_config = builder.build().unwrap();
 
         let mut builder = tlsn::prover::TlsConfig::builder();
-        if let Some((certs, key)) = value.client_auth {
+        if let Some((certs, key)) = self.client_auth {
             let certs = certs
                 .into_iter()
                 .map(|cert| {
@@ -80,6 +103,14 @@ impl TryFrom<ProverConfig> for tlsn::prover::ProverConfig {
             .protocol_config(protocol_config)
             .tls_config(tls_config);
 
+        if self.clamp_to_verifier_limits.unwrap_or_default() {
+            builder.limit_mode(LimitMode::Clamp);
+        }
+
+        if let Some(progress) = progress {
+            builder.progress(progress);
+        }
+
         Ok(builder.build().unwrap())
     }
 }
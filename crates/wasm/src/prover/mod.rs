@@ -3,12 +3,13 @@ mod config;
 pub use config::ProverConfig;
 
 use enum_try_as_inner::EnumTryAsInner;
-use futures::TryFutureExt;
+use futures::{channel::mpsc, StreamExt, TryFutureExt};
 use http_body_util::{BodyExt, Full};
 use hyper::body::Bytes;
 use tls_client_async::TlsConnection;
 use tlsn::prover::{state, ProveConfig, Prover};
-use tracing::info;
+use tracing::{info, warn};
+use tsify_next::Tsify;
 use wasm_bindgen::{prelude::*, JsError};
 use wasm_bindgen_futures::spawn_local;
 use ws_stream_wasm::WsMeta;
@@ -40,10 +41,37 @@ impl State {
 
 #[wasm_bindgen(js_class = Prover)]
 impl JsProver {
+    /// Creates a new prover.
+    ///
+    /// If `on_progress` is provided, it is called with a `ProgressEvent` each
+    /// time the prover makes progress.
     #[wasm_bindgen(constructor)]
-    pub fn new(config: ProverConfig) -> Result<JsProver> {
+    pub fn new(config: ProverConfig, on_progress: Option<js_sys::Function>) -> Result<JsProver> {
+        let progress = on_progress.map(|callback| {
+            // The callback can not be shared with other threads, so events are
+            // forwarded to it from a local task.
+            let (sender, mut receiver) = mpsc::unbounded::<ProgressEvent>();
+            spawn_local(async move {
+                while let Some(event) = receiver.next().await {
+                    let Ok(event) = event.into_js() else {
+                        continue;
+                    };
+
+                    if let Err(err) = callback.call1(&JsValue::NULL, &event) {
+                        warn!("progress callback failed: {:?}", err);
+                    }
+                }
+            });
+
+            move |event: &tlsn::progress::ProgressEvent| {
+                if let Ok(event) = ProgressEvent::try_from(event) {
+                    let _ = sender.unbounded_send(event);
+                }
+            }
+        });
+
         Ok(JsProver {
-            state: State::Initialized(Prover::new(config.try_into()?)),
+            state: State::Initialized(Prover::new(config.into_config(progress)?)),
         })
     }
 
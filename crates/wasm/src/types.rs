@@ -188,6 +188,8 @@ pub enum NetworkSetting {
     Bandwidth,
     /// Prefers a latency-heavy protocol.
     Latency,
+    /// Selects a protocol from the measured link.
+    Auto,
 }
 
 impl From<NetworkSetting> for tlsn::config::NetworkSetting {
@@ -195,6 +197,88 @@ impl From<NetworkSetting> for tlsn::config::NetworkSetting {
         match value {
             NetworkSetting::Bandwidth => Self::Bandwidth,
             NetworkSetting::Latency => Self::Latency,
+            NetworkSetting::Auto => Self::Auto,
         }
     }
 }
+
+#[derive(Debug, Tsify, Serialize)]
+#[tsify(into_wasm_abi)]
+pub enum Phase {
+    Setup,
+    Preprocess,
+    Handshake,
+    Records,
+    DeferredDecryption,
+    Finalize,
+    Commit,
+    Prove,
+}
+
+impl TryFrom<tlsn::progress::Phase> for Phase {
+    type Error = ();
+
+    fn try_from(value: tlsn::progress::Phase) -> Result<Self, Self::Error> {
+        use tlsn::progress::Phase as P;
+
+        Ok(match value {
+            P::Setup => Self::Setup,
+            P::Preprocess => Self::Preprocess,
+            P::Handshake => Self::Handshake,
+            P::Records => Self::Records,
+            P::DeferredDecryption => Self::DeferredDecryption,
+            P::Finalize => Self::Finalize,
+            P::Commit => Self::Commit,
+            P::Prove => Self::Prove,
+            _ => return Err(()),
+        })
+    }
+}
+
+#[derive(Debug, Tsify, Serialize)]
+#[tsify(into_wasm_abi)]
+#[serde(tag = "type")]
+pub enum Progress {
+    Started { phase: Phase },
+    Finished { phase: Phase },
+    Records { sent: usize, recv: usize },
+}
+
+impl TryFrom<tlsn::progress::Progress> for Progress {
+    type Error = ();
+
+    fn try_from(value: tlsn::progress::Progress) -> Result<Self, Self::Error> {
+        use tlsn::progress::Progress as P;
+
+        Ok(match value {
+            P::Started(phase) => Self::Started {
+                phase: phase.try_into()?,
+            },
+            P::Finished(phase) => Self::Finished {
+                phase: phase.try_into()?,
+            },
+            P::Records { sent, recv } => Self::Records { sent, recv },
+            _ => return Err(()),
+        })
+    }
+}
+
+#[derive(Debug, Tsify, Serialize)]
+#[tsify(into_wasm_abi)]
+pub struct ProgressEvent {
+    pub progress: Progress,
+    pub bytes_sent: u64,
+    pub bytes_recv: u64,
+}
+
+impl TryFrom<&tlsn::progress::ProgressEvent> for ProgressEvent {
+    type Error = ();
+
+    fn try_from(value: &tlsn::progress::ProgressEvent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            progress: value.progress.try_into()?,
+            bytes_sent: value.bytes_sent,
+            bytes_recv: value.bytes_recv,
+        })
+    }
+}
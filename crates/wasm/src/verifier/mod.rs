@@ -100,7 +100,8 @@ impl JsVerifier {
             },
         };
 
-        let output = verifier.verify(&VerifyConfig::default()).await?;
+        // The session has a single connection.
+        let output = verifier.verify(&VerifyConfig::default()).await?.remove(0);
         verifier.close().await?;
 
         self.state = State::Complete;